@@ -0,0 +1,37 @@
+//! `gateway audit <command>`: offline maintenance of the audit log in
+//! `AUDIT_LOG_DIR`. Rotate only while the gateway is stopped, as a running
+//! gateway keeps appending to the segment it opened.
+
+use anyhow::bail;
+use compliance::audit::{export_dir, verify_dir};
+use compliance::AuditLog;
+use std::time::{SystemTime, UNIX_EPOCH};
+
+const USAGE: &str = "usage: gateway audit <verify | export [from] [to] | rotate>";
+
+pub fn run(args: &[String], dir: &str) -> anyhow::Result<()> {
+    match args.first().map(String::as_str) {
+        Some("verify") => {
+            let report = verify_dir(dir)?;
+            println!("{}", serde_json::to_string_pretty(&report)?);
+            if !report.valid {
+                bail!("audit log integrity check failed");
+            }
+        }
+        Some("export") => {
+            let from = args.get(1).map(|s| s.parse()).transpose()?.unwrap_or(0);
+            let to = args.get(2).map(|s| s.parse()).transpose()?;
+            // One entry per line, in the same format as the segment files
+            for entry in export_dir(dir, from, to)? {
+                println!("{}", serde_json::to_string(&entry)?);
+            }
+        }
+        Some("rotate") => {
+            let now = SystemTime::now().duration_since(UNIX_EPOCH)?.as_secs();
+            let entry = AuditLog::open(dir)?.rotate(now)?;
+            println!("{}", serde_json::to_string_pretty(&entry)?);
+        }
+        _ => bail!(USAGE),
+    }
+    Ok(())
+}
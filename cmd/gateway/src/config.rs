@@ -5,8 +5,53 @@ pub struct Config {
     pub bitcoin_rpc_user: String,
     pub bitcoin_rpc_pass: String,
     pub stacks_rpc_url: String,
+    pub stacks_network: String,
+    pub stacks_api_key: Option<String>,
+    pub stacks_rpc_timeout_secs: u64,
+    pub stacks_rpc_max_retries: u32,
+    pub stacks_tx_max_fee: u64,
+    pub stacks_tx_max_transfer: Option<u64>,
+    pub stacks_tx_allow_deploy: bool,
+    pub stacks_tx_blocked_principals: Vec<String>,
+    pub stacks_tracked_principals: Vec<String>,
+    pub stacks_signer_warn_margin: f64,
+    pub stacks_bns_contract: Option<String>,
+    pub stacks_bns_zonefile_contract: Option<String>,
+    pub stacks_bns_ttl_blocks: u64,
+    pub stacks_observer_port: Option<u16>,
+    pub attestation_audience: String,
+    pub attestation_nonce_store: String,
+    pub attestation_max_validity_secs: u64,
+    pub attestation_legacy_payloads: bool,
+    pub device_registry_path: String,
+    pub attestation_android_roots: Option<String>,
+    pub attestation_apple_roots: Option<String>,
+    pub attestation_apple_app_id: Option<String>,
+    pub webauthn_rp_id: String,
+    pub webauthn_origins: Vec<String>,
+    pub webauthn_require_uv: bool,
+    pub passkey_registry_path: String,
+    pub receipt_key: Option<String>,
+    pub audit_log_dir: String,
+    pub audit_segment_max_entries: usize,
+    pub auth_failure_window_secs: u64,
+    pub commitment_mode: Option<String>,
+    pub commitment_interval_secs: u64,
+    pub commitment_state_path: String,
+    pub screening_lists: Vec<String>,
+    pub screening_reload_secs: u64,
+    pub risk_max_hops: u32,
+    pub risk_decay: f64,
+    pub risk_medium_threshold: f64,
+    pub risk_high_threshold: f64,
+    pub risk_max_transactions: usize,
+    pub travel_rule_store_path: String,
+    pub travel_rule_threshold: Option<u64>,
+    pub policy_file: Option<String>,
+    pub policy_reload_secs: u64,
     pub api_port: u16,
     pub api_token: String,
+    pub admin_api_token: Option<String>,
 }
 
 impl Config {
@@ -18,12 +63,139 @@ impl Config {
             bitcoin_rpc_pass: env::var("BITCOIN_RPC_PASS").unwrap_or_else(|_| "pass".to_string()),
             stacks_rpc_url: env::var("STACKS_RPC_URL")
                 .unwrap_or_else(|_| "https://api.mainnet.hiro.so".to_string()),
+            stacks_network: env::var("STACKS_NETWORK").unwrap_or_else(|_| "mainnet".to_string()),
+            stacks_api_key: env::var("STACKS_API_KEY").ok(),
+            stacks_rpc_timeout_secs: env::var("STACKS_RPC_TIMEOUT_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(10),
+            stacks_rpc_max_retries: env::var("STACKS_RPC_MAX_RETRIES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(3),
+            stacks_tx_max_fee: env::var("STACKS_TX_MAX_FEE")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(1_000_000),
+            stacks_tx_max_transfer: env::var("STACKS_TX_MAX_TRANSFER")
+                .ok()
+                .and_then(|v| v.parse().ok()),
+            stacks_tx_allow_deploy: env::var("STACKS_TX_ALLOW_DEPLOY")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            stacks_tx_blocked_principals: env::var("STACKS_TX_BLOCKED_PRINCIPALS")
+                .map(|v| list(&v))
+                .unwrap_or_default(),
+            stacks_tracked_principals: env::var("STACKS_TRACKED_PRINCIPALS")
+                .map(|v| list(&v))
+                .unwrap_or_default(),
+            stacks_signer_warn_margin: env::var("STACKS_SIGNER_WARN_MARGIN")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(5.0),
+            stacks_bns_contract: env::var("STACKS_BNS_CONTRACT").ok(),
+            stacks_bns_zonefile_contract: env::var("STACKS_BNS_ZONEFILE_CONTRACT").ok(),
+            stacks_bns_ttl_blocks: env::var("STACKS_BNS_TTL_BLOCKS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(6),
+            stacks_observer_port: env::var("STACKS_OBSERVER_PORT")
+                .ok()
+                .and_then(|v| v.parse().ok()),
+            attestation_audience: env::var("ATTESTATION_AUDIENCE")
+                .unwrap_or_else(|_| "conxian-gateway".to_string()),
+            attestation_nonce_store: env::var("ATTESTATION_NONCE_STORE")
+                .unwrap_or_else(|_| "attestation_nonces.json".to_string()),
+            attestation_max_validity_secs: env::var("ATTESTATION_MAX_VALIDITY_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(600),
+            attestation_legacy_payloads: env::var("ATTESTATION_LEGACY_PAYLOADS")
+                .map(|v| v == "true")
+                .unwrap_or(false),
+            device_registry_path: env::var("DEVICE_REGISTRY_PATH")
+                .unwrap_or_else(|_| "device_registry.json".to_string()),
+            attestation_android_roots: env::var("ATTESTATION_ANDROID_ROOTS").ok(),
+            attestation_apple_roots: env::var("ATTESTATION_APPLE_ROOTS").ok(),
+            attestation_apple_app_id: env::var("ATTESTATION_APPLE_APP_ID").ok(),
+            webauthn_rp_id: env::var("WEBAUTHN_RP_ID").unwrap_or_else(|_| "localhost".to_string()),
+            webauthn_origins: env::var("WEBAUTHN_ORIGINS")
+                .map(|v| list(&v))
+                .unwrap_or_default(),
+            webauthn_require_uv: env::var("WEBAUTHN_REQUIRE_UV")
+                .map(|v| v != "false")
+                .unwrap_or(true),
+            passkey_registry_path: env::var("PASSKEY_REGISTRY_PATH")
+                .unwrap_or_else(|_| "passkey_registry.json".to_string()),
+            receipt_key: env::var("GATEWAY_RECEIPT_KEY").ok(),
+            audit_log_dir: env::var("AUDIT_LOG_DIR").unwrap_or_else(|_| "audit_log".to_string()),
+            audit_segment_max_entries: env::var("AUDIT_SEGMENT_MAX_ENTRIES")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(100_000),
+            auth_failure_window_secs: env::var("AUTH_FAILURE_AUDIT_WINDOW_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(60),
+            commitment_mode: env::var("COMMITMENT_MODE").ok(),
+            commitment_interval_secs: env::var("COMMITMENT_INTERVAL_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(3600),
+            commitment_state_path: env::var("COMMITMENT_STATE_PATH")
+                .unwrap_or_else(|_| "commitments.json".to_string()),
+            screening_lists: env::var("SCREENING_LISTS")
+                .map(|v| list(&v))
+                .unwrap_or_default(),
+            screening_reload_secs: env::var("SCREENING_RELOAD_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(60),
+            risk_max_hops: env::var("RISK_MAX_HOPS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(3),
+            risk_decay: env::var("RISK_DECAY")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0.5),
+            risk_medium_threshold: env::var("RISK_MEDIUM_THRESHOLD")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0.1),
+            risk_high_threshold: env::var("RISK_HIGH_THRESHOLD")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(0.5),
+            risk_max_transactions: env::var("RISK_MAX_TRANSACTIONS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(500),
+            travel_rule_store_path: env::var("TRAVEL_RULE_STORE_PATH")
+                .unwrap_or_else(|_| "travel_rule.json".to_string()),
+            travel_rule_threshold: env::var("TRAVEL_RULE_THRESHOLD")
+                .ok()
+                .and_then(|v| v.parse().ok()),
+            policy_file: env::var("POLICY_FILE").ok(),
+            policy_reload_secs: env::var("POLICY_RELOAD_SECS")
+                .ok()
+                .and_then(|v| v.parse().ok())
+                .unwrap_or(60),
             api_port: env::var("API_PORT")
                 .unwrap_or_else(|_| "3000".to_string())
                 .parse()
                 .unwrap_or(3000),
             api_token: env::var("API_TOKEN")
                 .unwrap_or_else(|_| "institutional-default-token".to_string()),
+            admin_api_token: env::var("ADMIN_API_TOKEN").ok(),
         }
     }
 }
+
+fn list(value: &str) -> Vec<String> {
+    value
+        .split(',')
+        .map(|s| s.trim().to_string())
+        .filter(|s| !s.is_empty())
+        .collect()
+}
@@ -1,17 +1,36 @@
+mod audit;
 mod config;
 
-use api::configure_routes;
+use api::{configure_routes, observer_routes, AppState};
+use compliance::hardware::parse_pem_certificates;
+use compliance::{
+    AuditLog, DeviceRegistry, HardwareVerifier, NonceStore, PasskeyRegistry, PolicyEngine,
+    ReplayGuard, ReplayPolicy, Screener, TravelRuleStore, WebAuthnPolicy, ZkcVerifier,
+};
 use config::Config;
 use conxian_core::persistence::FilePersistence;
+use conxian_core::receipt::ReceiptSigner;
 use conxian_core::{GatewayState, Persistence, SharedState};
-use engine::{BitcoinListener, BitcoinRpcClient, StacksListener, StacksRpcClient};
+use engine::stacks::tx::{CHAIN_ID_MAINNET, CHAIN_ID_TESTNET};
+use engine::{
+    AnchorMonitor, BitcoinListener, BitcoinRpc, BitcoinRpcClient, BnsResolver, CommitmentMode,
+    MempoolMonitor, MempoolTracker, Notary, NotaryService, RiskPolicy, RiskScorer, SignerMonitor,
+    StacksClientConfig, StacksListener, StacksRpc, StacksRpcClient, StacksTxPolicy,
+};
 use std::net::SocketAddr;
 use std::sync::{Arc, RwLock};
+use std::time::Duration;
 use tokio::signal;
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 #[tokio::main]
 async fn main() -> anyhow::Result<()> {
+    // `gateway audit <command>` works on the audit log and exits
+    let args: Vec<String> = std::env::args().skip(1).collect();
+    if args.first().map(String::as_str) == Some("audit") {
+        return audit::run(&args[1..], &Config::from_env().audit_log_dir);
+    }
+
     // Initialize tracing
     tracing_subscriber::fmt::init();
 
@@ -43,11 +62,54 @@ async fn main() -> anyhow::Result<()> {
         &config.bitcoin_rpc_pass,
     )?;
 
-    let mut btc_listener = BitcoinListener::new(btc_rpc, state.clone(), persistence.clone());
+    let mut btc_listener =
+        BitcoinListener::new(btc_rpc.clone(), state.clone(), persistence.clone());
+
+    // Audit entries and receipts are committed to Bitcoin when a mode is set
+    let commitments = match config.commitment_mode.as_deref() {
+        None => None,
+        Some(mode) => {
+            let mode = match mode {
+                "psbt" => CommitmentMode::Psbt,
+                "wallet" => CommitmentMode::Wallet,
+                other => anyhow::bail!("Unknown COMMITMENT_MODE: {}", other),
+            };
+            let notary = Arc::new(Notary::open(&config.commitment_state_path)?);
+            info!("Loaded {} Bitcoin commitments", notary.batches().len());
+            btc_listener = btc_listener.with_notary(notary.clone());
+            Some((notary, mode))
+        }
+    };
 
     // Initialize Stacks listener
-    let stx_rpc = StacksRpcClient::new(&config.stacks_rpc_url);
-    let mut stx_listener = StacksListener::new(stx_rpc, state.clone(), persistence);
+    let stx_rpc = StacksRpcClient::with_config(
+        &config.stacks_rpc_url,
+        StacksClientConfig {
+            timeout: Duration::from_secs(config.stacks_rpc_timeout_secs),
+            max_retries: config.stacks_rpc_max_retries,
+            api_key: config.stacks_api_key.clone(),
+            ..StacksClientConfig::default()
+        },
+    )?;
+    let mut stx_listener = StacksListener::new(stx_rpc.clone(), state.clone(), persistence)
+        .with_tracked_principals(config.stacks_tracked_principals.clone());
+
+    // Initialize cross-chain anchor monitor over the Bitcoin header index
+    let mut anchor_monitor =
+        AnchorMonitor::new(stx_rpc.clone(), state.clone(), btc_listener.header_index());
+
+    // Initialize Nakamoto signer participation monitor
+    let mut signer_monitor = SignerMonitor::new(stx_rpc.clone(), state.clone())
+        .with_warn_margin(config.stacks_signer_warn_margin);
+
+    // Track the mempool of watched principals, fed by the node's event
+    // observer when one is configured and by API polling otherwise
+    let mempool_tracker =
+        MempoolTracker::new(state.clone(), config.stacks_tracked_principals.clone())?;
+    let mut mempool_monitor = MempoolMonitor::new(stx_rpc.clone(), mempool_tracker.clone());
+    if config.stacks_observer_port.is_some() {
+        mempool_monitor = mempool_monitor.with_observer_feed();
+    }
 
     // Create a cancellation token for graceful shutdown of listeners
     let (shutdown_tx, _) = tokio::sync::broadcast::channel::<()>(1);
@@ -80,17 +142,321 @@ async fn main() -> anyhow::Result<()> {
         }
     });
 
+    let mut anchor_shutdown_rx = shutdown_tx.subscribe();
+    tokio::spawn(async move {
+        tokio::select! {
+            res = anchor_monitor.run() => {
+                if let Err(e) = res {
+                    error!("Anchor monitor failed: {}", e);
+                }
+            }
+            _ = anchor_shutdown_rx.recv() => {
+                info!("Anchor monitor stopping...");
+            }
+        }
+    });
+
+    let mut signer_shutdown_rx = shutdown_tx.subscribe();
+    tokio::spawn(async move {
+        tokio::select! {
+            res = signer_monitor.run() => {
+                if let Err(e) = res {
+                    error!("Signer monitor failed: {}", e);
+                }
+            }
+            _ = signer_shutdown_rx.recv() => {
+                info!("Signer monitor stopping...");
+            }
+        }
+    });
+
+    let mut mempool_shutdown_rx = shutdown_tx.subscribe();
+    tokio::spawn(async move {
+        tokio::select! {
+            res = mempool_monitor.run() => {
+                if let Err(e) = res {
+                    error!("Mempool monitor failed: {}", e);
+                }
+            }
+            _ = mempool_shutdown_rx.recv() => {
+                info!("Mempool monitor stopping...");
+            }
+        }
+    });
+
+    if let Some(port) = config.stacks_observer_port {
+        let addr = SocketAddr::from(([0, 0, 0, 0], port));
+        let observer = tokio::net::TcpListener::bind(addr).await?;
+        info!("Stacks event observer listening on {}", addr);
+
+        let mut observer_shutdown_rx = shutdown_tx.subscribe();
+        tokio::spawn(async move {
+            let res = axum::serve(observer, observer_routes(mempool_tracker))
+                .with_graceful_shutdown(async move {
+                    let _ = observer_shutdown_rx.recv().await;
+                })
+                .await;
+            if let Err(e) = res {
+                error!("Stacks event observer failed: {}", e);
+            }
+        });
+    }
+
+    // Denylists screen transaction principals and attesters, reloaded on change
+    let screener = if config.screening_lists.is_empty() {
+        None
+    } else {
+        let screener = Arc::new(Screener::open(&config.screening_lists)?);
+        for list in screener.lists() {
+            info!(
+                "Loaded denylist {} with {} entries",
+                list.name, list.entries
+            );
+        }
+        let reloading = screener.clone();
+        let interval = Duration::from_secs(config.screening_reload_secs.max(1));
+        let mut screening_shutdown_rx = shutdown_tx.subscribe();
+        tokio::spawn(async move {
+            loop {
+                tokio::select! {
+                    _ = tokio::time::sleep(interval) => {
+                        match reloading.reload_if_changed() {
+                            Ok(true) => info!("Reloaded {} denylists", reloading.lists().len()),
+                            Ok(false) => {}
+                            Err(e) => warn!("Failed to reload denylists: {}", e),
+                        }
+                    }
+                    _ = screening_shutdown_rx.recv() => break,
+                }
+            }
+        });
+        Some(screener)
+    };
+
+    // Bitcoin transactions are scored by their ancestors' exposure to the denylists
+    let risk_scorer = match &screener {
+        Some(screener) => {
+            let policy = RiskPolicy {
+                max_hops: config.risk_max_hops,
+                decay: config.risk_decay,
+                medium_threshold: config.risk_medium_threshold,
+                high_threshold: config.risk_high_threshold,
+                max_transactions: config.risk_max_transactions,
+            };
+            // Addresses are derived for the node's own chain, e.g. signet or regtest
+            let chain = btc_rpc.get_network_info().await?;
+            let network: bitcoin::Network = chain
+                .parse()
+                .map_err(|e| anyhow::anyhow!("Unknown Bitcoin chain {}: {}", chain, e))?;
+            Some(
+                RiskScorer::new(Arc::new(btc_rpc.clone()), screener.clone(), policy)
+                    .with_network(network),
+            )
+        }
+        None => None,
+    };
+
+    // Stacks transaction submission
+    let stacks_policy = StacksTxPolicy {
+        chain_id: if config.stacks_network == "mainnet" {
+            CHAIN_ID_MAINNET
+        } else {
+            CHAIN_ID_TESTNET
+        },
+        max_fee: config.stacks_tx_max_fee,
+        max_transfer: config.stacks_tx_max_transfer,
+        allow_contract_deploy: config.stacks_tx_allow_deploy,
+        blocked_principals: config.stacks_tx_blocked_principals.into_iter().collect(),
+        screener: screener.clone(),
+    };
+    let stx_rpc: Arc<dyn StacksRpc> = Arc::new(stx_rpc);
+
+    // BNS resolution defaults to the mainnet BNS-V2 contracts
+    let mut bns = BnsResolver::new(stx_rpc.clone(), state.clone())
+        .with_ttl_blocks(config.stacks_bns_ttl_blocks);
+    if let Some(registry) = config.stacks_bns_contract {
+        bns = bns.with_contracts(registry, config.stacks_bns_zonefile_contract);
+    }
+
+    // Attestation replay protection, with nonces persisted across restarts
+    let replay_policy = ReplayPolicy {
+        audience: config.attestation_audience,
+        max_validity: config.attestation_max_validity_secs,
+        allow_legacy_payloads: config.attestation_legacy_payloads,
+        ..ReplayPolicy::default()
+    };
+    if replay_policy.allow_legacy_payloads {
+        warn!("Legacy attestation payloads are accepted without replay protection");
+    }
+    let nonces = NonceStore::open(&config.attestation_nonce_store)?;
+
+    // Attestation keys must be bound to a device enrolled through the admin API
+    let devices = DeviceRegistry::open(&config.device_registry_path)?;
+    info!("Loaded {} enrolled devices", devices.list().len());
+
+    // Operators approve actions with registered passkeys
+    let passkeys = PasskeyRegistry::open(&config.passkey_registry_path)?;
+    info!("Loaded {} operator passkeys", passkeys.list().len());
+    let webauthn_policy = WebAuthnPolicy {
+        rp_id: config.webauthn_rp_id,
+        origins: config.webauthn_origins,
+        require_user_verification: config.webauthn_require_uv,
+    };
+
+    let verifier = ZkcVerifier::new()
+        .with_replay_guard(ReplayGuard::new(replay_policy, nonces))
+        .with_device_registry(Arc::new(devices))
+        .with_passkeys(Arc::new(passkeys), webauthn_policy);
+
+    // Travel Rule payloads are sealed to counterparties and kept with their
+    // acknowledgements
+    let mut travel_rule = TravelRuleStore::open(&config.travel_rule_store_path)?;
+    if let Some(threshold) = config.travel_rule_threshold {
+        travel_rule = travel_rule.with_threshold(threshold);
+    }
+    info!("Loaded {} Travel Rule records", travel_rule.list().len());
+
+    // Attestations, transactions and account lookups are evaluated against
+    // the compliance policy, reloaded on change
+    let policy = match &config.policy_file {
+        Some(path) => {
+            let policy = Arc::new(PolicyEngine::open(path)?);
+            info!(
+                "Loaded compliance policy {} with {} rules",
+                path,
+                policy.policy().rules.len()
+            );
+            let reloading = policy.clone();
+            let interval = Duration::from_secs(config.policy_reload_secs.max(1));
+            let mut policy_shutdown_rx = shutdown_tx.subscribe();
+            tokio::spawn(async move {
+                loop {
+                    tokio::select! {
+                        _ = tokio::time::sleep(interval) => {
+                            match reloading.reload_if_changed() {
+                                Ok(true) => info!("Reloaded compliance policy {}", reloading.hash()),
+                                Ok(false) => {}
+                                Err(e) => warn!("Failed to reload compliance policy: {}", e),
+                            }
+                        }
+                        _ = policy_shutdown_rx.recv() => break,
+                    }
+                }
+            });
+            policy
+        }
+        None => Arc::new(PolicyEngine::default()),
+    };
+
+    let mut app_state = AppState::new(state)
+        .with_stacks(stx_rpc, stacks_policy)
+        .with_bns(bns)
+        .with_verifier(verifier)
+        .with_policy(policy)
+        .with_travel_rule(travel_rule);
+
+    // Hardware attestation is enabled for each platform with configured roots
+    let mut hardware = HardwareVerifier::new();
+    let mut hardware_enabled = false;
+    if let Some(path) = &config.attestation_android_roots {
+        let roots = parse_pem_certificates(&std::fs::read_to_string(path)?)?;
+        info!("Loaded {} Android attestation roots", roots.len());
+        hardware = hardware.with_android_roots(roots);
+        hardware_enabled = true;
+    }
+    if let Some(path) = &config.attestation_apple_roots {
+        let app_id = config
+            .attestation_apple_app_id
+            .clone()
+            .ok_or_else(|| anyhow::anyhow!("ATTESTATION_APPLE_APP_ID is required"))?;
+        let roots = parse_pem_certificates(&std::fs::read_to_string(path)?)?;
+        info!("Loaded {} Apple attestation roots", roots.len());
+        hardware = hardware.with_apple_roots(roots, app_id);
+        hardware_enabled = true;
+    }
+    if hardware_enabled {
+        app_state = app_state.with_hardware_verifier(hardware);
+    }
+    if let Some(screener) = screener {
+        app_state = app_state.with_screener(screener);
+    }
+    if let Some(scorer) = risk_scorer {
+        app_state = app_state.with_risk_scorer(scorer);
+    }
+
+    // Verifications, auth failures and admin actions are recorded in the audit log
+    let audit_log = AuditLog::open(&config.audit_log_dir)?
+        .with_max_segment_entries(config.audit_segment_max_entries);
+    let report = audit_log.verify()?;
+    if !report.valid {
+        warn!(
+            "Audit log failed its integrity check: {}",
+            report.error.unwrap_or_default()
+        );
+    }
+    info!(
+        "Audit log has {} entries in {} segments",
+        report.entries, report.segments
+    );
+    app_state = app_state
+        .with_audit_log(audit_log)
+        .with_auth_failure_window(config.auth_failure_window_secs);
+
+    if let Some((notary, mode)) = commitments {
+        let audit = app_state.audit.clone();
+        let mut service = NotaryService::new(btc_rpc, notary.clone(), mode)
+            .with_interval(Duration::from_secs(config.commitment_interval_secs))
+            .with_audit_source(Box::new(move |from| audit.export(from, None)));
+        if let Some(scorer) = &app_state.risk {
+            service = service.with_risk_scorer(scorer.clone());
+        }
+        let mut notary_shutdown_rx = shutdown_tx.subscribe();
+        tokio::spawn(async move {
+            tokio::select! {
+                res = service.run() => {
+                    if let Err(e) = res {
+                        error!("Bitcoin commitment service failed: {}", e);
+                    }
+                }
+                _ = notary_shutdown_rx.recv() => {
+                    info!("Bitcoin commitment service stopping...");
+                }
+            }
+        });
+        app_state = app_state.with_notary(notary);
+    }
+
+    // Verdicts are countersigned when a receipt key is configured
+    if let Some(key) = &config.receipt_key {
+        let signer = ReceiptSigner::from_secret_hex(key)?;
+        info!("Issuing verification receipts as {}", signer.public_key());
+        app_state = app_state.with_receipt_signer(signer);
+    }
+
+    // Admin routes stay disabled unless given a token of their own
+    match config.admin_api_token {
+        Some(token) if token == config.api_token => {
+            anyhow::bail!("ADMIN_API_TOKEN must differ from API_TOKEN")
+        }
+        Some(token) => app_state = app_state.with_admin_token(token),
+        None => warn!("ADMIN_API_TOKEN is not set; admin routes are disabled"),
+    }
+
     // Configure and start API server
-    let app = configure_routes(state, config.api_token);
+    let app = configure_routes(app_state, config.api_token);
     let addr = SocketAddr::from(([0, 0, 0, 0], config.api_port));
     info!("API server listening on {}", addr);
 
     let listener = tokio::net::TcpListener::bind(addr).await?;
 
-    // Axum graceful shutdown
-    axum::serve(listener, app)
-        .with_graceful_shutdown(shutdown_signal(shutdown_tx))
-        .await?;
+    // Axum graceful shutdown; peer addresses key the aggregation of
+    // authentication failures in the audit log
+    axum::serve(
+        listener,
+        app.into_make_service_with_connect_info::<SocketAddr>(),
+    )
+    .with_graceful_shutdown(shutdown_signal(shutdown_tx))
+    .await?;
 
     info!("Conxian Gateway shut down successfully.");
     Ok(())
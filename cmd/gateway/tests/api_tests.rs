@@ -1,14 +1,57 @@
-use api::configure_routes;
+use api::{configure_routes, AppState};
 use axum::{
     body::Body,
     http::{Request, StatusCode},
 };
-use conxian_core::{GatewayState, SharedState};
+use conxian_core::{GatewayState, SharedState, TransactionInfo};
+use engine::{SimulatedStacksRpc, StacksTxPolicy};
 use serde_json::Value;
 use std::sync::{Arc, RwLock};
 use tower::ServiceExt; // for `oneshot` and `ready`
 
 const TEST_TOKEN: &str = "test-token";
+const ADMIN_TOKEN: &str = "admin-token";
+
+/// Routes with the admin API enabled under [`ADMIN_TOKEN`].
+fn admin_routes(state: impl Into<AppState>) -> axum::Router {
+    configure_routes(
+        state.into().with_admin_token(ADMIN_TOKEN.to_string()),
+        TEST_TOKEN.to_string(),
+    )
+}
+
+/// Authorization header for `uri`; the admin routes take their own token.
+fn bearer(uri: &str) -> String {
+    let token = if uri.starts_with("/api/v1/admin/") {
+        ADMIN_TOKEN
+    } else {
+        TEST_TOKEN
+    };
+    format!("Bearer {}", token)
+}
+
+/// Verifier accepting raw payloads, as signed by the BIP-322 reference
+/// vectors, with the vectors' key enrolled as `conxius-btc-1`.
+fn legacy_verifier() -> compliance::ZkcVerifier {
+    use compliance::{NonceStore, ReplayGuard, ReplayPolicy};
+
+    let verifier = compliance::ZkcVerifier::new().with_replay_guard(ReplayGuard::new(
+        ReplayPolicy {
+            allow_legacy_payloads: true,
+            ..ReplayPolicy::default()
+        },
+        NonceStore::in_memory(),
+    ));
+    verifier
+        .device_registry()
+        .enroll(
+            "conxius-btc-1",
+            "02c7f12003196442943d8588e01aee840423cc54fc1521526a3b85c2b0cbd58872",
+            0,
+        )
+        .unwrap();
+    verifier
+}
 
 #[tokio::test]
 async fn test_health_check() {
@@ -163,3 +206,1627 @@ async fn test_metrics_endpoint() {
     assert!(body_str.contains("gateway_total_requests"));
     assert!(body_str.contains("bitcoin_block_height"));
 }
+
+#[tokio::test]
+async fn test_broadcast_stacks_tx_without_rpc() {
+    let state: SharedState = Arc::new(RwLock::new(GatewayState::default()));
+    let app = configure_routes(state, TEST_TOKEN.to_string());
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/stacks/tx")
+                .method("POST")
+                .header("Authorization", format!("Bearer {}", TEST_TOKEN))
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{"tx": "00"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+}
+
+#[tokio::test]
+async fn test_broadcast_stacks_tx_malformed() {
+    let state: SharedState = Arc::new(RwLock::new(GatewayState::default()));
+    let app_state = AppState::new(state.clone()).with_stacks(
+        Arc::new(SimulatedStacksRpc {
+            initial_height: 100,
+        }),
+        StacksTxPolicy::default(),
+    );
+    let app = configure_routes(app_state, TEST_TOKEN.to_string());
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/stacks/tx")
+                .method("POST")
+                .header("Authorization", format!("Bearer {}", TEST_TOKEN))
+                .header("Content-Type", "application/json")
+                .body(Body::from(r#"{"tx": "0x000000000104"}"#))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    assert_eq!(state.read().unwrap().metrics.transactions_rejected, 1);
+    assert!(state.read().unwrap().transactions.is_empty());
+}
+
+#[tokio::test]
+async fn test_get_stacks_tx_tracked() {
+    let state: SharedState = Arc::new(RwLock::new(GatewayState::default()));
+    state.write().unwrap().transactions.insert(
+        "abcd".to_string(),
+        TransactionInfo::pending("stacks", "abcd"),
+    );
+    let app = configure_routes(state, TEST_TOKEN.to_string());
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/stacks/tx/0xabcd")
+                .header("Authorization", format!("Bearer {}", TEST_TOKEN))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let json: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(json["status"], "pending");
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/stacks/tx/ffff")
+                .header("Authorization", format!("Bearer {}", TEST_TOKEN))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_observer_drop_mempool_tx() {
+    use api::observer_routes;
+    use conxian_core::MempoolTx;
+    use engine::MempoolTracker;
+
+    let state: SharedState = Arc::new(RwLock::new(GatewayState::default()));
+    let tracker =
+        MempoolTracker::new(state.clone(), vec!["ST000000000000000000002AMW42H".into()]).unwrap();
+    tracker.record(MempoolTx {
+        txid: "aa".to_string(),
+        sender: "ST000000000000000000002AMW42H".to_string(),
+        nonce: 1,
+        fee: 180,
+        received_at: 0,
+    });
+    let app = observer_routes(tracker);
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .method("POST")
+                .uri("/drop_mempool_tx")
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    r#"{"dropped_txids":["0xaa"],"reason":"TooExpensive","new_txid":null}"#,
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Malformed transactions and unsubscribed events are still acknowledged
+    for (uri, body) in [("/new_mempool_tx", r#"["0x00"]"#), ("/new_block", "{}")] {
+        let response = app
+            .clone()
+            .oneshot(
+                Request::builder()
+                    .method("POST")
+                    .uri(uri)
+                    .header("Content-Type", "application/json")
+                    .body(Body::from(body))
+                    .unwrap(),
+            )
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::OK);
+    }
+
+    let s = state.read().unwrap();
+    assert!(s.mempool.pending.is_empty());
+    assert_eq!(s.mempool.dropped, 1);
+}
+
+#[tokio::test]
+async fn test_verify_sip018_attestation_mismatched_signer() {
+    let state: SharedState = Arc::new(RwLock::new(GatewayState::default()));
+    let app = configure_routes(state, TEST_TOKEN.to_string());
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/verify")
+                .method("POST")
+                .header("Authorization", format!("Bearer {}", TEST_TOKEN))
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    serde_json::to_string(&serde_json::json!({
+                        "type": "Sip018",
+                        "data": {
+                            "device_id": "conxius-stx-1",
+                            "domain": {
+                                "type": "tuple",
+                                "value": {
+                                    "name": { "type": "string_ascii", "value": "Conxian" },
+                                    "version": { "type": "string_ascii", "value": "1.0.0" },
+                                    "chain-id": { "type": "uint", "value": 1 }
+                                }
+                            },
+                            "message": { "type": "string_ascii", "value": "attest" },
+                            // r is the generator's x coordinate, so recovery succeeds
+                        "signature": format!(
+                            "79be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798{}00",
+                            "11".repeat(32)
+                        ),
+                            "expected_address": "SP000000000000000000002Q6VF78"
+                        }
+                    }))
+                    .unwrap(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert!(body["error"].as_str().unwrap().contains("does not match"));
+}
+
+#[tokio::test]
+async fn test_verify_bip322_attestation() {
+    let state: SharedState = Arc::new(RwLock::new(GatewayState::default()));
+    let app = configure_routes(
+        AppState::new(state).with_verifier(legacy_verifier()),
+        TEST_TOKEN.to_string(),
+    );
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/verify")
+                .method("POST")
+                .header("Authorization", format!("Bearer {}", TEST_TOKEN))
+                .header("Content-Type", "application/json")
+                .body(Body::from(
+                    serde_json::to_string(&serde_json::json!({
+                        "type": "Bip322",
+                        "data": {
+                            "device_id": "conxius-btc-1",
+                            "address": "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l",
+                            "message": "Hello World",
+                            "signature": "AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI="
+                        }
+                    }))
+                    .unwrap(),
+                ))
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["valid"], true);
+}
+
+#[tokio::test]
+async fn test_device_admin_lifecycle() {
+    let state: SharedState = Arc::new(RwLock::new(GatewayState::default()));
+    let app = admin_routes(state);
+    let key = "0250863ad64a87ad8a2bf2bb8ae16617bc25e101c70628d01f0599a4f7bb4d602f";
+
+    let post = |uri: &str, body: Value| {
+        Request::builder()
+            .uri(uri)
+            .method("POST")
+            .header("Authorization", bearer(uri))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let enroll = serde_json::json!({ "device_id": "conxius-1", "public_key": key });
+    let response = app
+        .clone()
+        .oneshot(post("/api/v1/admin/devices", enroll.clone()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let response = app
+        .clone()
+        .oneshot(post("/api/v1/admin/devices", enroll))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    let response = app
+        .clone()
+        .oneshot(post(
+            "/api/v1/admin/devices/conxius-1/suspend",
+            serde_json::json!({ "reason": "reported lost" }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    // Rotation requires an active device
+    let response = app
+        .clone()
+        .oneshot(post(
+            "/api/v1/admin/devices/conxius-1/rotate",
+            serde_json::json!({ "public_key": key }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+    let response = app
+        .clone()
+        .oneshot(post(
+            "/api/v1/admin/devices/conxius-2/revoke",
+            serde_json::json!({}),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/admin/devices/conxius-1")
+                .header("Authorization", format!("Bearer {}", ADMIN_TOKEN))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let record: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(record["status"], "suspended");
+    assert_eq!(record["history"][1]["action"], "suspended");
+    assert_eq!(record["history"][1]["reason"], "reported lost");
+}
+
+#[tokio::test]
+async fn test_device_hardware_attestation() {
+    use compliance::hardware::parse_pem_certificates;
+    use compliance::HardwareVerifier;
+
+    let fixture = |name: &str| {
+        std::fs::read_to_string(format!(
+            "{}/../../internal/compliance/tests/fixtures/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        ))
+        .unwrap()
+    };
+    // PEM bodies are the base64 DER the API expects
+    let certificates: Vec<String> = fixture("android_chain.pem")
+        .split("-----")
+        .map(|block| block.split_whitespace().collect::<String>())
+        .filter(|block| !block.is_empty() && !block.contains("CERTIFICATE"))
+        .collect();
+    assert_eq!(certificates.len(), 3);
+
+    let post = |uri: &str, body: Value| {
+        Request::builder()
+            .uri(uri)
+            .method("POST")
+            .header("Authorization", bearer(uri))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+    let enroll = serde_json::json!({
+        "device_id": "conxius-android-1",
+        "public_key": "0250863ad64a87ad8a2bf2bb8ae16617bc25e101c70628d01f0599a4f7bb4d602f",
+    });
+    let attest = serde_json::json!({ "platform": "android", "certificates": certificates });
+
+    // Without configured roots, attestation is unavailable
+    let state: SharedState = Arc::new(RwLock::new(GatewayState::default()));
+    let app = admin_routes(AppState::new(state.clone()));
+    app.clone()
+        .oneshot(post("/api/v1/admin/devices", enroll.clone()))
+        .await
+        .unwrap();
+    let response = app
+        .oneshot(post(
+            "/api/v1/admin/devices/conxius-android-1/attestation",
+            attest.clone(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let hardware = HardwareVerifier::new()
+        .with_android_roots(parse_pem_certificates(&fixture("android_root.pem")).unwrap());
+    let app = admin_routes(AppState::new(state).with_hardware_verifier(hardware));
+    app.clone()
+        .oneshot(post("/api/v1/admin/devices", enroll))
+        .await
+        .unwrap();
+
+    // The chain's challenge is bound to a different device
+    app.clone()
+        .oneshot(post(
+            "/api/v1/admin/devices",
+            serde_json::json!({
+                "device_id": "conxius-android-2",
+                "public_key": "0250863ad64a87ad8a2bf2bb8ae16617bc25e101c70628d01f0599a4f7bb4d602f",
+            }),
+        ))
+        .await
+        .unwrap();
+    let response = app
+        .clone()
+        .oneshot(post(
+            "/api/v1/admin/devices/conxius-android-2/attestation",
+            attest.clone(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = app
+        .oneshot(post(
+            "/api/v1/admin/devices/conxius-android-1/attestation",
+            attest,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let record: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(record["hardware"]["platform"], "android");
+    assert_eq!(record["hardware"]["security_level"], "strongbox");
+    assert_eq!(record["history"][1]["action"], "attested");
+}
+
+#[tokio::test]
+async fn test_passkey_admin_and_unregistered_assertion() {
+    let state: SharedState = Arc::new(RwLock::new(GatewayState::default()));
+    let app = admin_routes(state);
+    let request = |method: &str, uri: &str, body: Value| {
+        Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("Authorization", bearer(uri))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let register = serde_json::json!({
+        "credential_id": "Y3JlZC0x",
+        "operator_id": "operator-1",
+        "algorithm": "ES256",
+        "public_key": "036b17d1f2e12c4247f8bce6e563a440f277037d812deb33a0f4a13945d898c296",
+    });
+    let response = app
+        .clone()
+        .oneshot(request("POST", "/api/v1/admin/passkeys", register.clone()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CREATED);
+    let response = app
+        .clone()
+        .oneshot(request("POST", "/api/v1/admin/passkeys", register))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+
+    // Assertions from credentials that are not registered are rejected
+    let assertion = serde_json::json!({
+        "type": "WebAuthn",
+        "data": {
+            "credential_id": "Y3JlZC0y",
+            "authenticator_data": "",
+            "client_data_json": "",
+            "signature": "",
+            "payload": "approve",
+        }
+    });
+    let response = app
+        .clone()
+        .oneshot(request("POST", "/api/v1/verify", assertion))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = app
+        .clone()
+        .oneshot(request(
+            "DELETE",
+            "/api/v1/admin/passkeys/Y3JlZC0x",
+            Value::Null,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let response = app
+        .oneshot(request(
+            "DELETE",
+            "/api/v1/admin/passkeys/Y3JlZC0x",
+            Value::Null,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+}
+
+#[tokio::test]
+async fn test_verify_attestation_batch() {
+    let state: SharedState = Arc::new(RwLock::new(GatewayState::default()));
+    let app = configure_routes(
+        AppState::new(state.clone()).with_verifier(legacy_verifier()),
+        TEST_TOKEN.to_string(),
+    );
+    let bip322 = serde_json::json!({
+        "type": "Bip322",
+        "data": {
+            "device_id": "conxius-btc-1",
+            "address": "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l",
+            "message": "Hello World",
+            "signature": "AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI="
+        }
+    });
+    let schnorr = serde_json::json!({
+        "type": "Schnorr",
+        "data": {
+            "device_id": "conxius-schnorr-123",
+            "signature": "64".repeat(64),
+            "payload": "payload",
+            "x_only_public_key": "32".repeat(32)
+        }
+    });
+    let batch = |attestations: Vec<Value>| {
+        Request::builder()
+            .uri("/api/v1/verify/batch")
+            .method("POST")
+            .header("Authorization", format!("Bearer {}", TEST_TOKEN))
+            .header("Content-Type", "application/json")
+            .body(Body::from(
+                serde_json::json!({ "attestations": attestations }).to_string(),
+            ))
+            .unwrap()
+    };
+
+    let response = app
+        .clone()
+        .oneshot(batch(vec![
+            schnorr.clone(),
+            bip322.clone(),
+            schnorr,
+            bip322.clone(),
+        ]))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["total"], 4);
+    assert_eq!(body["valid"], 2);
+    let results = body["results"].as_array().unwrap();
+    for (i, result) in results.iter().enumerate() {
+        assert_eq!(result["index"], i);
+        assert_eq!(result["valid"], i % 2 == 1);
+    }
+    // The Schnorr key is not bound to an enrolled device
+    assert_eq!(results[0]["code"], "security");
+
+    let metrics = state.read().unwrap().metrics.clone();
+    assert_eq!(metrics.verification_count, 4);
+    assert_eq!(metrics.verifications_valid, 2);
+    assert_eq!(metrics.verifications_failed, 2);
+
+    let oversized = vec![bip322; 1001];
+    let response = app.oneshot(batch(oversized)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::PAYLOAD_TOO_LARGE);
+}
+
+#[tokio::test]
+async fn test_verification_receipts() {
+    use conxian_core::receipt::{attestation_hash, verify_receipt, Receipt, ReceiptSigner};
+
+    let state: SharedState = Arc::new(RwLock::new(GatewayState::default()));
+    {
+        let mut s = state.write().unwrap();
+        s.bitcoin.height = 850_000;
+        s.bitcoin.best_block_hash = "ab".repeat(32);
+        s.stacks.height = 170_000;
+    }
+    let signer = ReceiptSigner::from_secret_hex(&"07".repeat(32)).unwrap();
+    let issuer = signer.public_key();
+    let app_state = AppState::new(state)
+        .with_verifier(legacy_verifier())
+        .with_receipt_signer(signer);
+    let app = configure_routes(app_state, TEST_TOKEN.to_string());
+    let attestation = serde_json::json!({
+        "type": "Bip322",
+        "data": {
+            "device_id": "conxius-btc-1",
+            "address": "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l",
+            "message": "Hello World",
+            "signature": "AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI="
+        }
+    });
+    let post = |uri: &str, body: Value| {
+        Request::builder()
+            .uri(uri)
+            .method("POST")
+            .header("Authorization", format!("Bearer {}", TEST_TOKEN))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let response = app
+        .clone()
+        .oneshot(post("/api/v1/verify", attestation.clone()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    let receipt: Receipt = serde_json::from_value(body["receipt"].clone()).unwrap();
+
+    // Anyone holding the gateway key can check the receipt offline
+    let claims = verify_receipt(&receipt, &issuer).unwrap();
+    assert!(claims.valid);
+    assert_eq!(claims.bitcoin_height, 850_000);
+    assert_eq!(claims.bitcoin_tip, "ab".repeat(32));
+    assert_eq!(claims.stacks_height, 170_000);
+    assert_eq!(
+        claims.attestation_hash,
+        attestation_hash(
+            serde_json::to_string(
+                &serde_json::from_value::<conxian_core::AttestationRequest>(attestation).unwrap()
+            )
+            .unwrap()
+            .as_bytes()
+        )
+    );
+
+    let response = app
+        .clone()
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/receipts/key")
+                .header("Authorization", format!("Bearer {}", TEST_TOKEN))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["issuer"], issuer);
+
+    let response = app
+        .clone()
+        .oneshot(post(
+            "/api/v1/receipts/verify",
+            serde_json::to_value(&receipt).unwrap(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    let mut tampered = receipt;
+    tampered.payload = tampered.payload.replace("850000", "850001");
+    let response = app
+        .oneshot(post(
+            "/api/v1/receipts/verify",
+            serde_json::to_value(&tampered).unwrap(),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
+
+#[tokio::test]
+async fn test_audit_log_records_requests() {
+    let state: SharedState = Arc::new(RwLock::new(GatewayState::default()));
+    let app = admin_routes(state);
+    let key = "0250863ad64a87ad8a2bf2bb8ae16617bc25e101c70628d01f0599a4f7bb4d602f";
+    let request = |method: &str, uri: &str, token: Option<&str>, body: Option<Value>| {
+        let mut builder = Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("Content-Type", "application/json");
+        if let Some(token) = token {
+            builder = builder.header("Authorization", format!("Bearer {}", token));
+        }
+        builder
+            .body(body.map_or_else(Body::empty, |b| Body::from(b.to_string())))
+            .unwrap()
+    };
+    let json = |response: axum::response::Response| async move {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    let response = app
+        .clone()
+        .oneshot(request("GET", "/api/v1/state", Some("wrong-token"), None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let enroll = serde_json::json!({ "device_id": "conxius-1", "public_key": key });
+    for _ in 0..2 {
+        app.clone()
+            .oneshot(request(
+                "POST",
+                "/api/v1/admin/devices",
+                Some(ADMIN_TOKEN),
+                Some(enroll.clone()),
+            ))
+            .await
+            .unwrap();
+    }
+    let schnorr = serde_json::json!({
+        "type": "Schnorr",
+        "data": {
+            "device_id": "conxius-schnorr-123",
+            "signature": "64".repeat(64),
+            "payload": "payload",
+            "x_only_public_key": "32".repeat(32)
+        }
+    });
+    app.clone()
+        .oneshot(request(
+            "POST",
+            "/api/v1/verify",
+            Some(TEST_TOKEN),
+            Some(schnorr),
+        ))
+        .await
+        .unwrap();
+
+    let response = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/api/v1/admin/audit/rotate",
+            Some(ADMIN_TOKEN),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let rotation = json(response).await;
+    assert_eq!(rotation["kind"], "rotation");
+    assert_eq!(rotation["seq"], 4);
+
+    let response = app
+        .clone()
+        .oneshot(request(
+            "GET",
+            "/api/v1/admin/audit/export?from=0&to=3",
+            Some(ADMIN_TOKEN),
+            None,
+        ))
+        .await
+        .unwrap();
+    let entries = json(response).await;
+    let entries = entries.as_array().unwrap();
+    assert_eq!(entries.len(), 4);
+    assert_eq!(entries[0]["kind"], "auth_failure");
+    assert_eq!(entries[0]["subject"], "GET /api/v1/state");
+    assert_eq!(entries[1]["action"], "POST /api/v1/admin/devices");
+    assert_eq!(entries[1]["success"], true);
+    assert_eq!(entries[2]["success"], false);
+    assert_eq!(entries[3]["action"], "verify.schnorr");
+    assert_eq!(entries[3]["success"], false);
+    assert_eq!(entries[3]["prev_hash"], entries[2]["hash"]);
+
+    let response = app
+        .oneshot(request(
+            "GET",
+            "/api/v1/admin/audit/verify",
+            Some(ADMIN_TOKEN),
+            None,
+        ))
+        .await
+        .unwrap();
+    let report = json(response).await;
+    assert_eq!(report["valid"], true);
+    assert_eq!(report["segments"], 2);
+    // The rotate request itself is recorded after the rotation entry
+    assert_eq!(report["entries"], 6);
+}
+
+#[tokio::test]
+async fn test_auth_failures_aggregated_in_audit_log() {
+    let state: SharedState = Arc::new(RwLock::new(GatewayState::default()));
+    let app = admin_routes(AppState::new(state));
+    let request = |token: &str| {
+        Request::builder()
+            .uri("/api/v1/admin/audit/export?from=0&to=100")
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    for _ in 0..20 {
+        let response = app.clone().oneshot(request("wrong-token")).await.unwrap();
+        assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    }
+
+    let response = app.oneshot(request(ADMIN_TOKEN)).await.unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let entries: Value = serde_json::from_slice(&body).unwrap();
+    let failures: Vec<&Value> = entries
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|entry| entry["kind"] == "auth_failure")
+        .collect();
+    assert_eq!(failures.len(), 1);
+    assert_eq!(failures[0]["detail"], "Invalid bearer token from unknown");
+}
+
+#[tokio::test]
+async fn test_admin_routes_require_admin_token() {
+    let state: SharedState = Arc::new(RwLock::new(GatewayState::default()));
+    let request = |token: &str| {
+        Request::builder()
+            .uri("/api/v1/admin/audit/verify")
+            .header("Authorization", format!("Bearer {}", token))
+            .body(Body::empty())
+            .unwrap()
+    };
+
+    // The API token does not reach the admin routes
+    let app = admin_routes(AppState::new(state.clone()));
+    let response = app.clone().oneshot(request(TEST_TOKEN)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+    let response = app.clone().oneshot(request(ADMIN_TOKEN)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    // Nor does the admin token reach the API
+    let response = app
+        .oneshot(
+            Request::builder()
+                .uri("/api/v1/state")
+                .header("Authorization", format!("Bearer {}", ADMIN_TOKEN))
+                .body(Body::empty())
+                .unwrap(),
+        )
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::UNAUTHORIZED);
+
+    // Without an admin token the admin routes are disabled
+    let app = configure_routes(state, TEST_TOKEN.to_string());
+    let response = app.oneshot(request(TEST_TOKEN)).await.unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+}
+
+#[tokio::test]
+async fn test_receipts_committed_to_bitcoin() {
+    use bitcoin::consensus::encode::serialize_hex;
+    use conxian_core::merkle::{commitment_script, leaf_hash, parse_id, verify_merkle_path};
+    use conxian_core::receipt::{receipt_hash, Receipt, ReceiptSigner};
+    use engine::Notary;
+
+    let state: SharedState = Arc::new(RwLock::new(GatewayState::default()));
+    let notary = Arc::new(Notary::in_memory());
+    let app_state = AppState::new(state)
+        .with_verifier(legacy_verifier())
+        .with_receipt_signer(ReceiptSigner::from_secret_hex(&"07".repeat(32)).unwrap())
+        .with_notary(notary.clone());
+    let app = admin_routes(app_state);
+    let request = |method: &str, uri: &str, body: Option<Value>| {
+        Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("Authorization", bearer(uri))
+            .header("Content-Type", "application/json")
+            .body(body.map_or_else(Body::empty, |b| Body::from(b.to_string())))
+            .unwrap()
+    };
+    let json = |response: axum::response::Response| async move {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    let attestation = serde_json::json!({
+        "type": "Bip322",
+        "data": {
+            "device_id": "conxius-btc-1",
+            "address": "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l",
+            "message": "Hello World",
+            "signature": "AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI="
+        }
+    });
+    let response = app
+        .clone()
+        .oneshot(request("POST", "/api/v1/verify", Some(attestation)))
+        .await
+        .unwrap();
+    let body = json(response).await;
+    let receipt: Receipt = serde_json::from_value(body["receipt"].clone()).unwrap();
+    let id = receipt_hash(&receipt);
+    assert_eq!(notary.pending_len(), 1);
+    let batch = notary.seal(100).unwrap().unwrap();
+
+    let response = app
+        .clone()
+        .oneshot(request("GET", &format!("/api/v1/proofs/{}", id), None))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let proof: conxian_core::InclusionProof = serde_json::from_value(json(response).await).unwrap();
+    assert_eq!(proof.batch, batch.id);
+    assert!(verify_merkle_path(
+        leaf_hash(&parse_id(&id).unwrap()),
+        &proof.path,
+        &parse_id(&proof.root).unwrap()
+    ));
+
+    let commitment_tx = |root: &str| bitcoin::Transaction {
+        version: bitcoin::transaction::Version::TWO,
+        lock_time: bitcoin::absolute::LockTime::ZERO,
+        input: vec![Default::default()],
+        output: vec![bitcoin::TxOut {
+            value: bitcoin::Amount::ZERO,
+            script_pubkey: commitment_script(&parse_id(root).unwrap()),
+        }],
+    };
+    let uri = format!("/api/v1/admin/commitments/{}/transaction", batch.id);
+    let wrong = serialize_hex(&commitment_tx(&"00".repeat(32)));
+    let response = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            &uri,
+            Some(serde_json::json!({ "raw_tx": wrong })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let signed = serialize_hex(&commitment_tx(&batch.root));
+    let response = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            &uri,
+            Some(serde_json::json!({ "raw_tx": signed })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(json(response).await["status"], "signed");
+
+    let response = app
+        .oneshot(request("GET", "/api/v1/commitments", None))
+        .await
+        .unwrap();
+    let batches = json(response).await;
+    assert_eq!(batches.as_array().unwrap().len(), 1);
+    assert_eq!(batches[0]["leaves"][0]["kind"], "receipt");
+}
+
+#[tokio::test]
+async fn test_screening_lists() {
+    use bitcoin::address::NetworkUnchecked;
+    use bitcoin::hashes::Hash;
+    use bitcoin::{Address, Network, PubkeyHash};
+    use compliance::Screener;
+
+    const ADDRESS: &str = "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l";
+    let post = |uri: &str, body: Value| {
+        Request::builder()
+            .uri(uri)
+            .method("POST")
+            .header("Authorization", format!("Bearer {}", TEST_TOKEN))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+
+    let state: SharedState = Arc::new(RwLock::new(GatewayState::default()));
+    let response = configure_routes(state.clone(), TEST_TOKEN.to_string())
+        .oneshot(post(
+            "/api/v1/screen",
+            serde_json::json!({ "subjects": [ADDRESS] }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    let path = std::env::temp_dir().join(format!("conxian-sdn-{}.csv", std::process::id()));
+    std::fs::write(
+        &path,
+        format!("address,reason\n{},SDN\n", ADDRESS.to_uppercase()),
+    )
+    .unwrap();
+    let screener = Arc::new(Screener::open(&[&path]).unwrap());
+    let app = configure_routes(
+        AppState::new(state)
+            .with_verifier(legacy_verifier())
+            .with_screener(screener),
+        TEST_TOKEN.to_string(),
+    );
+
+    // The legacy encoding of the same key hash is a derived match
+    let script = ADDRESS
+        .parse::<Address<NetworkUnchecked>>()
+        .unwrap()
+        .assume_checked()
+        .script_pubkey();
+    let hash = PubkeyHash::from_byte_array(script.as_bytes()[2..22].try_into().unwrap());
+    let legacy = Address::p2pkh(hash, Network::Bitcoin).to_string();
+    let response = app
+        .clone()
+        .oneshot(post(
+            "/api/v1/screen",
+            serde_json::json!({ "subjects": [ADDRESS, legacy, "SP000000000000000000002Q6VF78"] }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["screened"], 3);
+    assert_eq!(body["clear"], false);
+    let hits = body["hits"].as_array().unwrap();
+    assert_eq!(hits.len(), 2);
+    assert_eq!(hits[0]["match"], "exact");
+    assert_eq!(hits[0]["reason"], "SDN");
+    assert_eq!(hits[1]["subject"], legacy);
+    assert_eq!(hits[1]["match"], "derived");
+    assert_eq!(body["lists"][0]["entries"], 1);
+
+    // A valid signature from a listed address is not attested
+    let attestation = serde_json::json!({
+        "type": "Bip322",
+        "data": {
+            "device_id": "conxius-btc-1",
+            "address": ADDRESS,
+            "message": "Hello World",
+            "signature": "AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI="
+        }
+    });
+    let response = app
+        .clone()
+        .oneshot(post("/api/v1/verify", attestation.clone()))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert!(body["error"].as_str().unwrap().contains("denylisted"));
+
+    let response = app
+        .oneshot(post(
+            "/api/v1/verify/batch",
+            serde_json::json!({ "attestations": [attestation] }),
+        ))
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["valid"], 0);
+    assert_eq!(body["results"][0]["code"], "compliance");
+    std::fs::remove_file(path).unwrap();
+}
+
+#[tokio::test]
+async fn test_bitcoin_risk_scoring() {
+    use async_trait::async_trait;
+    use bitcoin::consensus::encode::serialize_hex;
+    use bitcoin::{
+        absolute::LockTime, transaction::Version, Address, Amount, OutPoint, Sequence, Transaction,
+        TxIn, TxOut,
+    };
+    use compliance::Screener;
+    use conxian_core::merkle::{commitment_script, parse_id};
+    use conxian_core::{BlockInfo, CommitmentLeafKind, ConxianError, ConxianResult};
+    use engine::{BitcoinRpc, Notary, RiskPolicy, RiskScorer};
+    use std::collections::HashMap;
+
+    /// Node that serves a fixed set of transactions.
+    struct Node(HashMap<String, String>);
+
+    #[async_trait]
+    impl BitcoinRpc for Node {
+        async fn get_block_count(&self) -> ConxianResult<u64> {
+            unimplemented!()
+        }
+        async fn get_block_info(&self, _height: u64) -> ConxianResult<BlockInfo> {
+            unimplemented!()
+        }
+        async fn get_network_info(&self) -> ConxianResult<String> {
+            unimplemented!()
+        }
+        async fn get_block_txids(&self, _block_hash: &str) -> ConxianResult<Vec<String>> {
+            unimplemented!()
+        }
+        async fn get_tx_out_proof(&self, _txid: &str, _block_hash: &str) -> ConxianResult<String> {
+            unimplemented!()
+        }
+        async fn create_funded_psbt(&self, _data: &[u8]) -> ConxianResult<String> {
+            unimplemented!()
+        }
+        async fn sign_psbt(&self, _psbt: &str) -> ConxianResult<String> {
+            unimplemented!()
+        }
+        async fn send_raw_transaction(&self, _tx_hex: &str) -> ConxianResult<String> {
+            unimplemented!()
+        }
+        async fn get_raw_transaction(&self, txid: &str) -> ConxianResult<String> {
+            self.0
+                .get(txid)
+                .cloned()
+                .ok_or_else(|| ConxianError::Bitcoin(format!("No such transaction {}", txid)))
+        }
+    }
+
+    const LISTED: &str = "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l";
+    const CLEAN: &str = "bc1qar0srrr7xfkvy5l643lydnw9re59gtzzwf5mdq";
+    let post = |body: Value| {
+        Request::builder()
+            .uri("/api/v1/bitcoin/risk")
+            .method("POST")
+            .header("Authorization", format!("Bearer {}", TEST_TOKEN))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+    let tx = |inputs: Vec<OutPoint>, address: &str, value: u64| Transaction {
+        version: Version::TWO,
+        lock_time: LockTime::ZERO,
+        input: inputs
+            .into_iter()
+            .map(|previous_output| TxIn {
+                previous_output,
+                sequence: Sequence::MAX,
+                ..TxIn::default()
+            })
+            .collect(),
+        output: vec![TxOut {
+            value: Amount::from_sat(value),
+            script_pubkey: address
+                .parse::<Address<_>>()
+                .unwrap()
+                .assume_checked()
+                .script_pubkey(),
+        }],
+    };
+
+    let state: SharedState = Arc::new(RwLock::new(GatewayState::default()));
+    let response = configure_routes(state.clone(), TEST_TOKEN.to_string())
+        .oneshot(post(serde_json::json!({ "txid": "00".repeat(32) })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::SERVICE_UNAVAILABLE);
+
+    // A deposit two hops downstream of a listed output
+    let source = tx(vec![OutPoint::null()], LISTED, 50_000);
+    let mid = tx(vec![OutPoint::new(source.compute_txid(), 0)], CLEAN, 49_000);
+    let deposit = tx(vec![OutPoint::new(mid.compute_txid(), 0)], CLEAN, 48_000);
+    let funding = tx(vec![OutPoint::null()], CLEAN, 10_000);
+    let node = Node(
+        [&source, &mid, &deposit, &funding]
+            .into_iter()
+            .map(|t| (t.compute_txid().to_string(), serialize_hex(t)))
+            .collect(),
+    );
+    let path = std::env::temp_dir().join(format!("conxian-risk-{}.csv", std::process::id()));
+    std::fs::write(&path, format!("{},SDN\n", LISTED)).unwrap();
+    let screener = Arc::new(Screener::open(&[&path]).unwrap());
+    let scorer = RiskScorer::new(Arc::new(node), screener, RiskPolicy::default());
+    let notary = Arc::new(Notary::in_memory());
+    notary
+        .submit(CommitmentLeafKind::Receipt, &"ab".repeat(32))
+        .unwrap();
+    let batch = notary.seal(100).unwrap().unwrap();
+    let app = admin_routes(
+        AppState::new(state)
+            .with_risk_scorer(scorer)
+            .with_notary(notary),
+    );
+
+    let response = app
+        .clone()
+        .oneshot(post(
+            serde_json::json!({ "txid": deposit.compute_txid().to_string() }),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["score"], 0.5);
+    assert_eq!(body["level"], "high");
+    let exposure = &body["exposures"][0];
+    assert_eq!(exposure["hops"], 2);
+    assert_eq!(exposure["path"][1]["address"], LISTED);
+    assert_eq!(exposure["hits"][0]["reason"], "SDN");
+
+    // A transaction about to be broadcast spending the deposit
+    let spend = tx(
+        vec![OutPoint::new(deposit.compute_txid(), 0)],
+        CLEAN,
+        47_000,
+    );
+    let response = app
+        .clone()
+        .oneshot(post(serde_json::json!({ "raw_tx": serialize_hex(&spend) })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let body: Value = serde_json::from_slice(&body).unwrap();
+    assert_eq!(body["score"], 0.25);
+    assert_eq!(body["level"], "medium");
+
+    let response = app
+        .clone()
+        .oneshot(post(serde_json::json!({ "raw_tx": "zz" })))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+    let response = app
+        .clone()
+        .oneshot(post(serde_json::json!({})))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    // Commitment transactions are not broadcast from high risk inputs
+    let uri = format!("/api/v1/admin/commitments/{}/transaction", batch.id);
+    let commitment = |input: OutPoint| {
+        let mut tx = tx(vec![input], CLEAN, 0);
+        tx.output[0].script_pubkey = commitment_script(&parse_id(&batch.root).unwrap());
+        serde_json::json!({ "raw_tx": serialize_hex(&tx) })
+    };
+    let submit = |body: Value| {
+        Request::builder()
+            .uri(&uri)
+            .method("POST")
+            .header("Authorization", format!("Bearer {}", ADMIN_TOKEN))
+            .header("Content-Type", "application/json")
+            .body(Body::from(body.to_string()))
+            .unwrap()
+    };
+    let response = app
+        .clone()
+        .oneshot(submit(commitment(OutPoint::new(mid.compute_txid(), 0))))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    let response = app
+        .oneshot(submit(commitment(OutPoint::new(funding.compute_txid(), 0))))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    std::fs::remove_file(path).unwrap();
+}
+
+#[tokio::test]
+async fn test_travel_rule_exchange() {
+    use conxian_core::travel_rule::{open, sign_acknowledgement};
+
+    const SECRET: &str = "0909090909090909090909090909090909090909090909090909090909090909";
+    // Public key of SECRET
+    let counterparty = bitcoin::secp256k1::PublicKey::from_secret_key(
+        bitcoin::secp256k1::SECP256K1,
+        &bitcoin::secp256k1::SecretKey::from_slice(&[9; 32]).unwrap(),
+    )
+    .to_string();
+    let request = |method: &str, uri: &str, body: Option<Value>| {
+        Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("Authorization", format!("Bearer {}", TEST_TOKEN))
+            .header("Content-Type", "application/json")
+            .body(body.map_or_else(Body::empty, |b| Body::from(b.to_string())))
+            .unwrap()
+    };
+    let payload = serde_json::json!({
+        "originator": {
+            "originatorPersons": [{
+                "naturalPerson": {
+                    "name": { "nameIdentifier": [{
+                        "primaryIdentifier": "Nakamoto",
+                        "secondaryIdentifier": "Satoshi",
+                        "nameIdentifierType": "LEGL"
+                    }]},
+                    "customerIdentification": "CX-42"
+                }
+            }],
+            "accountNumber": ["SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7"]
+        },
+        "beneficiary": {
+            "beneficiaryPersons": [{
+                "legalPerson": {
+                    "name": { "nameIdentifier": [{
+                        "legalPersonName": "Example Custody AG",
+                        "legalPersonNameIdentifierType": "LEGL"
+                    }]}
+                }
+            }]
+        }
+    });
+
+    let state: SharedState = Arc::new(RwLock::new(GatewayState::default()));
+    state.write().unwrap().transactions.insert(
+        "abcd".to_string(),
+        TransactionInfo::pending("stacks", "abcd"),
+    );
+    let app = configure_routes(state.clone(), TEST_TOKEN.to_string());
+
+    let attach = serde_json::json!({ "counterparty_key": counterparty, "payload": payload });
+    let response = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/api/v1/travel-rule/ffff",
+            Some(attach.clone()),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::NOT_FOUND);
+
+    let mut invalid = attach.clone();
+    invalid["payload"]["beneficiary"]["beneficiaryPersons"] = serde_json::json!([]);
+    let response = app
+        .clone()
+        .oneshot(request("POST", "/api/v1/travel-rule/abcd", Some(invalid)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let response = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/api/v1/travel-rule/0xabcd",
+            Some(attach.clone()),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    assert_eq!(
+        state.read().unwrap().transactions["abcd"].travel_rule,
+        Some(conxian_core::TravelRuleStatus::Sent)
+    );
+
+    // The counterparty opens the sealed payload and acknowledges its hash
+    let response = app
+        .clone()
+        .oneshot(request("GET", "/api/v1/travel-rule/abcd", None))
+        .await
+        .unwrap();
+    let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+        .await
+        .unwrap();
+    let record: conxian_core::TravelRuleRecord = serde_json::from_slice(&body).unwrap();
+    assert_eq!(record.status, conxian_core::TravelRuleStatus::Sent);
+    let (opened, hash) = open(&record.sealed, SECRET).unwrap();
+    assert_eq!(opened, serde_json::from_value(payload).unwrap());
+    assert_eq!(hash, record.payload_hash);
+
+    let forged = sign_acknowledgement(&"0a".repeat(32), "abcd", &hash, true).unwrap();
+    let response = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/api/v1/travel-rule/abcd/ack",
+            Some(serde_json::json!({ "accepted": true, "signature": forged })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+
+    let signature = sign_acknowledgement(SECRET, "abcd", &hash, true).unwrap();
+    let response = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/api/v1/travel-rule/abcd/ack",
+            Some(serde_json::json!({ "accepted": true, "signature": signature })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    {
+        let s = state.read().unwrap();
+        assert_eq!(
+            s.transactions["abcd"].travel_rule,
+            Some(conxian_core::TravelRuleStatus::Acknowledged)
+        );
+        assert!(matches!(
+            s.events.last(),
+            Some(conxian_core::GatewayEvent::TravelRuleAcknowledged { accepted: true, .. })
+        ));
+    }
+
+    let response = app
+        .oneshot(request("POST", "/api/v1/travel-rule/abcd", Some(attach)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::CONFLICT);
+}
+
+#[tokio::test]
+async fn test_compliance_policy_decisions() {
+    use compliance::{PolicyEngine, PolicySet};
+
+    const BLOCKED: &str = "SP000000000000000000002Q6VF78";
+    let request = |method: &str, uri: &str, body: Option<Value>| {
+        Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("Authorization", bearer(uri))
+            .header("Content-Type", "application/json")
+            .body(body.map_or_else(Body::empty, |b| Body::from(b.to_string())))
+            .unwrap()
+    };
+    let json = |response: axum::response::Response| async move {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    let policy = PolicySet::parse(&format!(
+        r#"{{
+            "default": "allow",
+            "tenants": [{{ "name": "acme", "principals": ["{}"] }}],
+            "rules": [
+                {{ "id": "acme-accounts", "applies_to": ["account"], "effect": "deny",
+                   "when": {{ "tenant": ["acme"] }}, "reason": "acme accounts are private" }},
+                {{ "id": "acme-limit", "applies_to": ["transaction"], "effect": "deny",
+                   "when": {{ "all": [{{ "tenant": ["acme"] }}, {{ "amount_above": 1000 }}] }} }}
+            ]
+        }}"#,
+        BLOCKED
+    ))
+    .unwrap();
+    let engine = Arc::new(PolicyEngine::new(policy));
+    let state: SharedState = Arc::new(RwLock::new(GatewayState::default()));
+    let app = admin_routes(
+        AppState::new(state)
+            .with_stacks(
+                Arc::new(SimulatedStacksRpc {
+                    initial_height: 100,
+                }),
+                StacksTxPolicy::default(),
+            )
+            .with_policy(engine.clone()),
+    );
+
+    let response = app
+        .clone()
+        .oneshot(request("GET", "/api/v1/policy", None))
+        .await
+        .unwrap();
+    let body = json(response).await;
+    assert_eq!(body["policy_hash"], engine.hash());
+    assert_eq!(body["policy"]["rules"].as_array().unwrap().len(), 2);
+
+    // Dry runs report every matching rule against the resolved tenant
+    let response = app
+        .clone()
+        .oneshot(request(
+            "POST",
+            "/api/v1/policy/evaluate",
+            Some(serde_json::json!({
+                "target": "transaction",
+                "principals": [BLOCKED],
+                "amount": 1001
+            })),
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let decision = json(response).await;
+    assert_eq!(decision["allowed"], false);
+    assert_eq!(decision["tenant"], "acme");
+    assert_eq!(decision["matched"][0]["id"], "acme-limit");
+
+    let response = app
+        .clone()
+        .oneshot(request(
+            "GET",
+            &format!("/api/v1/stacks/accounts/{}", BLOCKED),
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::FORBIDDEN);
+    assert!(json(response).await["error"]
+        .as_str()
+        .unwrap()
+        .contains("acme-accounts (acme accounts are private)"));
+    let response = app
+        .clone()
+        .oneshot(request(
+            "GET",
+            "/api/v1/stacks/accounts/SP2J6ZY48GV1EZ5V2V5RB9MP66SW86PYKKNRV9EJ7",
+            None,
+        ))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+
+    // Every decision is in the audit log with the rules behind it
+    let response = app
+        .oneshot(request("GET", "/api/v1/admin/audit/export?from=0", None))
+        .await
+        .unwrap();
+    let entries = json(response).await;
+    let decisions: Vec<&Value> = entries
+        .as_array()
+        .unwrap()
+        .iter()
+        .filter(|e| e["kind"] == "policy")
+        .collect();
+    assert_eq!(decisions.len(), 3);
+    assert_eq!(decisions[0]["action"], "policy.evaluate");
+    assert_eq!(decisions[1]["action"], "policy.account");
+    assert_eq!(decisions[1]["subject"], BLOCKED);
+    assert_eq!(decisions[1]["success"], false);
+    let detail: Value = serde_json::from_str(decisions[1]["detail"].as_str().unwrap()).unwrap();
+    assert_eq!(detail["matched"][0]["id"], "acme-accounts");
+    assert_eq!(detail["policy_hash"], engine.hash());
+    assert_eq!(decisions[2]["success"], true);
+}
+
+#[tokio::test]
+async fn test_verify_ring_membership() {
+    use bitcoin::secp256k1::{PublicKey, Secp256k1, SecretKey};
+    use conxian_core::AttestationClaims;
+
+    let request = |method: &str, uri: &str, body: Option<Value>| {
+        Request::builder()
+            .uri(uri)
+            .method(method)
+            .header("Authorization", bearer(uri))
+            .header("Content-Type", "application/json")
+            .body(body.map_or_else(Body::empty, |b| Body::from(b.to_string())))
+            .unwrap()
+    };
+    let json = |response: axum::response::Response| async move {
+        let body = axum::body::to_bytes(response.into_body(), usize::MAX)
+            .await
+            .unwrap();
+        serde_json::from_slice::<Value>(&body).unwrap()
+    };
+
+    let state: SharedState = Arc::new(RwLock::new(GatewayState::default()));
+    let app = admin_routes(state);
+    let response = app
+        .clone()
+        .oneshot(request("GET", "/api/v1/zkc/ring", None))
+        .await
+        .unwrap();
+    let ring = json(response).await;
+    assert_eq!(ring["size"], 0);
+    assert!(ring["root"].is_null());
+
+    let secp = Secp256k1::new();
+    let secrets: Vec<SecretKey> = (1..=4u8)
+        .map(|i| SecretKey::from_slice(&[i; 32]).unwrap())
+        .collect();
+    for (i, secret) in secrets.iter().enumerate() {
+        let enroll = serde_json::json!({
+            "device_id": format!("conxius-ring-{}", i),
+            "public_key": PublicKey::from_secret_key(&secp, secret).to_string(),
+        });
+        let response = app
+            .clone()
+            .oneshot(request("POST", "/api/v1/admin/devices", Some(enroll)))
+            .await
+            .unwrap();
+        assert_eq!(response.status(), StatusCode::CREATED);
+    }
+
+    // The device proves membership of the published ring, not which key it holds
+    let response = app
+        .clone()
+        .oneshot(request("GET", "/api/v1/zkc/ring", None))
+        .await
+        .unwrap();
+    let ring = json(response).await;
+    assert_eq!(ring["size"], 4);
+    let keys: Vec<PublicKey> = ring["keys"]
+        .as_array()
+        .unwrap()
+        .iter()
+        .map(|key| key.as_str().unwrap().parse().unwrap())
+        .collect();
+    let now = std::time::SystemTime::now()
+        .duration_since(std::time::UNIX_EPOCH)
+        .unwrap()
+        .as_secs();
+    let payload = serde_json::to_string(&AttestationClaims {
+        nonce: "ring-nonce".to_string(),
+        issued_at: now,
+        expires_at: now + 300,
+        audience: "conxian-gateway".to_string(),
+    })
+    .unwrap();
+    let attestation = zkc_prover::prove_membership(&keys, &secrets[2], &payload).unwrap();
+    assert_eq!(attestation.root, ring["root"]);
+    let body = serde_json::json!({ "type": "Ring", "data": attestation });
+
+    let response = app
+        .clone()
+        .oneshot(request("POST", "/api/v1/verify", Some(body.clone())))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::OK);
+    let verified = json(response).await;
+    assert_eq!(verified["valid"], true);
+    assert_eq!(verified["ring_size"], 4);
+
+    let response = app
+        .oneshot(request("POST", "/api/v1/verify", Some(body)))
+        .await
+        .unwrap();
+    assert_eq!(response.status(), StatusCode::BAD_REQUEST);
+}
@@ -0,0 +1,55 @@
+use crate::handlers::unix_now;
+use crate::state::AppState;
+use axum::{
+    extract::{MatchedPath, OriginalUri, Request, State},
+    http::Method,
+    middleware::Next,
+    response::Response,
+};
+use compliance::AuditLog;
+use conxian_core::{AuditEvent, AuditKind};
+use tracing::warn;
+
+/// Path of the request as received, before any nesting prefix was stripped.
+pub fn request_path(req: &Request) -> String {
+    req.extensions()
+        .get::<OriginalUri>()
+        .map_or_else(|| req.uri().path(), |uri| uri.path())
+        .to_string()
+}
+
+/// Appends `event` to the audit log. A write failure is logged rather than
+/// failing the request it describes.
+pub fn record(log: &AuditLog, event: AuditEvent) {
+    if let Err(e) = log.append(event, unix_now()) {
+        warn!("Failed to write audit log entry: {}", e);
+    }
+}
+
+/// Records every state-changing admin request with its route and outcome.
+pub async fn audit_middleware(State(app): State<AppState>, req: Request, next: Next) -> Response {
+    if req.method() == Method::GET {
+        return next.run(req).await;
+    }
+    let route = req
+        .extensions()
+        .get::<MatchedPath>()
+        .map(|path| path.as_str().to_string())
+        .unwrap_or_else(|| request_path(&req));
+    let action = format!("{} {}", req.method(), route);
+    let subject = request_path(&req);
+
+    let response = next.run(req).await;
+    let status = response.status();
+    record(
+        &app.audit,
+        AuditEvent {
+            kind: AuditKind::Admin,
+            action,
+            subject: Some(subject),
+            success: status.is_success(),
+            detail: Some(status.to_string()),
+        },
+    );
+    response
+}
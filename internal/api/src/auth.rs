@@ -1,24 +1,248 @@
+use crate::audit::{record, request_path};
+use crate::handlers::unix_now;
 use axum::{
-    extract::Request,
+    extract::{ConnectInfo, Request},
     http::{header, StatusCode},
     middleware::Next,
     response::Response,
 };
+use compliance::AuditLog;
+use conxian_core::{AuditEvent, AuditKind};
+use std::collections::HashMap;
+use std::net::SocketAddr;
+use std::sync::{Arc, Mutex};
+
+/// Sources tracked at once; failures from further sources share one window.
+const MAX_SOURCES: usize = 1024;
+const OVERFLOW_SOURCE: &str = "other";
+
+#[derive(Debug)]
+struct FailureWindow {
+    opened: u64,
+    suppressed: u64,
+}
+
+/// Authentication failures from one source left out of the audit log over
+/// one window.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SuppressedFailures {
+    pub source: String,
+    pub count: u64,
+    pub from: u64,
+    pub to: u64,
+}
+
+/// Bounds the audit entries written for authentication failures: the first
+/// failure from a source in each window is recorded, and the rest are
+/// summarized in one entry once the window has closed. A zero window
+/// records every failure.
+#[derive(Debug)]
+pub struct AuthFailureThrottle {
+    window: u64,
+    sources: Mutex<HashMap<String, FailureWindow>>,
+}
+
+impl Default for AuthFailureThrottle {
+    fn default() -> Self {
+        Self::new(60)
+    }
+}
+
+impl AuthFailureThrottle {
+    pub fn new(window_secs: u64) -> Self {
+        Self {
+            window: window_secs,
+            sources: Mutex::new(HashMap::new()),
+        }
+    }
+
+    /// Counts a failure from `source` at `now`, returning whether it opens a
+    /// window and should be recorded itself.
+    pub fn failure(&self, source: &str, now: u64) -> bool {
+        let mut sources = self.sources.lock().unwrap();
+        let source = if sources.len() >= MAX_SOURCES && !sources.contains_key(source) {
+            OVERFLOW_SOURCE
+        } else {
+            source
+        };
+        match sources.get_mut(source) {
+            Some(window) => {
+                window.suppressed += 1;
+                false
+            }
+            None => {
+                sources.insert(
+                    source.to_string(),
+                    FailureWindow {
+                        opened: now,
+                        suppressed: 0,
+                    },
+                );
+                true
+            }
+        }
+    }
+
+    /// Closes the windows ended by `now`, returning the failures suppressed
+    /// in them.
+    pub fn close_expired(&self, now: u64) -> Vec<SuppressedFailures> {
+        let mut closed = Vec::new();
+        self.sources.lock().unwrap().retain(|source, window| {
+            let to = window.opened + self.window;
+            if now < to {
+                return true;
+            }
+            if window.suppressed > 0 {
+                closed.push(SuppressedFailures {
+                    source: source.clone(),
+                    count: window.suppressed,
+                    from: window.opened,
+                    to,
+                });
+            }
+            false
+        });
+        closed
+    }
+}
+
+/// Peer address of the connection, when the server records it.
+fn request_source(req: &Request) -> String {
+    req.extensions()
+        .get::<ConnectInfo<SocketAddr>>()
+        .map_or_else(|| "unknown".to_string(), |info| info.0.ip().to_string())
+}
 
 pub async fn auth_middleware(
     req: Request,
     next: Next,
     expected_token: String,
+    audit: Arc<AuditLog>,
+    throttle: Arc<AuthFailureThrottle>,
 ) -> Result<Response, StatusCode> {
     let auth_header = req
         .headers()
         .get(header::AUTHORIZATION)
         .and_then(|h| h.to_str().ok());
 
+    let now = unix_now();
+    for summary in throttle.close_expired(now) {
+        record(
+            &audit,
+            AuditEvent {
+                kind: AuditKind::AuthFailure,
+                action: "auth.reject.summary".to_string(),
+                subject: Some(summary.source),
+                success: false,
+                detail: Some(format!(
+                    "{} further failures between {} and {}",
+                    summary.count, summary.from, summary.to
+                )),
+            },
+        );
+    }
+
     match auth_header {
         Some(auth) if auth.starts_with("Bearer ") && auth[7..] == expected_token => {
             Ok(next.run(req).await)
         }
-        _ => Err(StatusCode::UNAUTHORIZED),
+        _ => {
+            let source = request_source(&req);
+            if throttle.failure(&source, now) {
+                // Never record the presented token itself
+                record(
+                    &audit,
+                    AuditEvent {
+                        kind: AuditKind::AuthFailure,
+                        action: "auth.reject".to_string(),
+                        subject: Some(format!("{} {}", req.method(), request_path(&req))),
+                        success: false,
+                        detail: Some(format!(
+                            "{} from {}",
+                            if auth_header.is_some() {
+                                "Invalid bearer token"
+                            } else {
+                                "Missing bearer token"
+                            },
+                            source
+                        )),
+                    },
+                );
+            }
+            Err(StatusCode::UNAUTHORIZED)
+        }
+    }
+}
+
+/// Guards the `/admin` routes with their own token, so that API clients
+/// cannot enroll or bind device keys. Without one the routes are disabled.
+pub async fn admin_auth_middleware(
+    req: Request,
+    next: Next,
+    admin_token: Option<String>,
+    audit: Arc<AuditLog>,
+    throttle: Arc<AuthFailureThrottle>,
+) -> Result<Response, StatusCode> {
+    match admin_token {
+        Some(token) => auth_middleware(req, next, token, audit, throttle).await,
+        None => Err(StatusCode::FORBIDDEN),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_auth_failures_summarized_per_window() {
+        let throttle = AuthFailureThrottle::new(60);
+        assert!(throttle.failure("10.0.0.1", 1000));
+        for t in 1001..1010 {
+            assert!(!throttle.failure("10.0.0.1", t));
+        }
+        // Each source has its own window
+        assert!(throttle.failure("10.0.0.2", 1010));
+        assert!(throttle.close_expired(1059).is_empty());
+
+        let closed = throttle.close_expired(1060);
+        assert_eq!(
+            closed,
+            vec![SuppressedFailures {
+                source: "10.0.0.1".to_string(),
+                count: 9,
+                from: 1000,
+                to: 1060,
+            }]
+        );
+        assert!(throttle.failure("10.0.0.1", 1060));
+        assert!(!throttle.failure("10.0.0.2", 1069));
+        let closed = throttle.close_expired(1070);
+        assert_eq!(closed.len(), 1);
+        assert_eq!(
+            (closed[0].source.as_str(), closed[0].count),
+            ("10.0.0.2", 1)
+        );
+        // A window with a single failure closes without a summary
+        assert!(throttle.close_expired(1120).is_empty());
+        assert!(throttle.failure("10.0.0.1", 1120));
+
+        let unthrottled = AuthFailureThrottle::new(0);
+        assert!(unthrottled.failure("10.0.0.1", 1000));
+        assert!(unthrottled.close_expired(1000).is_empty());
+        assert!(unthrottled.failure("10.0.0.1", 1000));
+    }
+
+    #[test]
+    fn test_auth_failure_sources_are_bounded() {
+        let throttle = AuthFailureThrottle::new(60);
+        for i in 0..MAX_SOURCES {
+            assert!(throttle.failure(&format!("source-{}", i), 0));
+        }
+        assert!(throttle.failure("late-1", 0));
+        assert!(!throttle.failure("late-2", 0));
+        let closed = throttle.close_expired(60);
+        assert_eq!(closed.len(), 1);
+        assert_eq!(closed[0].source, OVERFLOW_SOURCE);
+        assert_eq!(closed[0].count, 1);
     }
 }
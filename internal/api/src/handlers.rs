@@ -1,8 +1,31 @@
-use axum::{extract::State, http::StatusCode, Json};
+use crate::audit;
+use crate::state::AppState;
+use axum::{
+    extract::{Path, Query, State},
+    http::StatusCode,
+    Json,
+};
+use bitcoin::consensus::encode::deserialize_hex;
+use bitcoin::{Transaction, Txid};
 use compliance::ZkcVerifier;
-use conxian_core::{AttestationRequest, SharedState};
+use conxian_core::clarity::PrincipalData;
+use conxian_core::ivms101::IdentityPayload;
+use conxian_core::receipt::{self, Receipt, ReceiptClaims};
+use conxian_core::ring;
+use conxian_core::{
+    AttestationRequest, AuditEntry, AuditEvent, AuditKind, AuditReport, CommitmentBatch,
+    CommitmentLeafKind, ConxianError, ConxianResult, DeviceRecord, GatewayEvent, InclusionProof,
+    PasskeyAlgorithm, PasskeyCredential, PolicyDecision, PolicyInput, PolicyTarget, RiskAssessment,
+    ScreeningHit, ScreeningStatus, SharedState, StacksAccount, TransactionInfo, TravelRuleRecord,
+    TravelRuleStatus,
+};
+use engine::stacks::tx::TransactionPayload;
+use engine::{BnsName, MempoolTracker, Notary, StacksTransaction};
+use serde::Deserialize;
 use serde_json::{json, Value};
+use std::fmt::Write;
 use std::time::{SystemTime, UNIX_EPOCH};
+use tracing::warn;
 
 pub async fn health_check() -> Json<Value> {
     Json(json!({
@@ -27,6 +50,12 @@ pub async fn get_state(State(state): State<SharedState>) -> Json<Value> {
     Json(json!({
         "bitcoin": s.bitcoin,
         "stacks": s.stacks,
+        "transactions": s.transactions,
+        "accounts": s.accounts,
+        "anchor": s.anchor,
+        "signers": s.signers,
+        "mempool": s.mempool,
+        "alerts": s.alerts,
         "metrics": s.metrics,
         "start_time": s.start_time,
         "uptime_seconds": uptime
@@ -41,39 +70,1219 @@ pub async fn get_metrics(State(state): State<SharedState>) -> String {
         .as_secs();
     let uptime = now.saturating_sub(s.start_time);
 
-    format!(
+    let mut out = format!(
         "# HELP gateway_total_requests The total number of API requests received.\n# TYPE gateway_total_requests counter\ngateway_total_requests {}\n# HELP gateway_verification_count The total number of attestation verifications attempted.\n# TYPE gateway_verification_count counter\ngateway_verification_count {}\n# HELP bitcoin_block_height The current block height of the Bitcoin chain.\n# TYPE bitcoin_block_height gauge\nbitcoin_block_height {}\n# HELP stacks_block_height The current block height of the Stacks chain.\n# TYPE stacks_block_height gauge\nstacks_block_height {}\n# HELP gateway_uptime_seconds The total uptime of the gateway in seconds.\n# TYPE gateway_uptime_seconds counter\ngateway_uptime_seconds {}\n",
         s.metrics.total_requests,
         s.metrics.verification_count,
         s.bitcoin.height,
         s.stacks.height,
         uptime
-    )
+    );
+    let _ = write!(
+        out,
+        "# HELP gateway_alerts_total The total number of operational alerts raised.\n# TYPE gateway_alerts_total counter\ngateway_alerts_total {}\n# HELP stacks_anchor_lag_blocks Bitcoin blocks between the Bitcoin tip and the Stacks tip's burn block.\n# TYPE stacks_anchor_lag_blocks gauge\nstacks_anchor_lag_blocks {}\n# HELP stacks_anchor_mismatched_blocks Recent Stacks blocks whose burn block differs from the Bitcoin best chain.\n# TYPE stacks_anchor_mismatched_blocks gauge\nstacks_anchor_mismatched_blocks {}\n# HELP stacks_anchor_orphaned_blocks Recent Stacks blocks anchored to orphaned Bitcoin blocks.\n# TYPE stacks_anchor_orphaned_blocks gauge\nstacks_anchor_orphaned_blocks {}\n",
+        s.metrics.alerts_raised,
+        s.anchor.lag_blocks,
+        s.anchor.mismatched_anchors,
+        s.anchor.orphaned_anchors
+    );
+    let _ = write!(
+        out,
+        "# HELP gateway_verifications_valid Attestations that verified successfully.\n# TYPE gateway_verifications_valid counter\ngateway_verifications_valid {}\n# HELP gateway_verifications_failed Attestations that failed verification.\n# TYPE gateway_verifications_failed counter\ngateway_verifications_failed {}\n",
+        s.metrics.verifications_valid,
+        s.metrics.verifications_failed
+    );
+    let _ = write!(
+        out,
+        "# HELP gateway_transactions_broadcast The total number of transactions broadcast through the gateway.\n# TYPE gateway_transactions_broadcast counter\ngateway_transactions_broadcast {}\n# HELP gateway_transactions_rejected The total number of transactions rejected by validation or policy.\n# TYPE gateway_transactions_rejected counter\ngateway_transactions_rejected {}\n",
+        s.metrics.transactions_broadcast,
+        s.metrics.transactions_rejected
+    );
+    let _ = write!(
+        out,
+        "# HELP stacks_signer_weight_signed Signer weight that signed the latest Stacks block.\n# TYPE stacks_signer_weight_signed gauge\nstacks_signer_weight_signed {}\n# HELP stacks_signer_weight_total Total weight of the current reward cycle signer set.\n# TYPE stacks_signer_weight_total gauge\nstacks_signer_weight_total {}\n# HELP stacks_signer_threshold_margin Percentage points of signer weight above the 70% threshold on the latest block.\n# TYPE stacks_signer_threshold_margin gauge\nstacks_signer_threshold_margin {}\n",
+        s.signers.last_signed_weight,
+        s.signers.total_weight,
+        s.signers.threshold_margin
+    );
+    let _ = write!(
+        out,
+        "# HELP stacks_mempool_pending Pending Stacks transactions of watched principals.\n# TYPE stacks_mempool_pending gauge\nstacks_mempool_pending {}\n# HELP stacks_mempool_seen_total Watched Stacks transactions seen in the mempool.\n# TYPE stacks_mempool_seen_total counter\nstacks_mempool_seen_total {}\n# HELP stacks_mempool_replaced_total Watched Stacks transactions replaced by fee.\n# TYPE stacks_mempool_replaced_total counter\nstacks_mempool_replaced_total {}\n# HELP stacks_mempool_dropped_total Watched Stacks transactions dropped from the mempool.\n# TYPE stacks_mempool_dropped_total counter\nstacks_mempool_dropped_total {}\n# HELP stacks_mempool_confirmed_total Watched Stacks transactions that left the mempool in a block.\n# TYPE stacks_mempool_confirmed_total counter\nstacks_mempool_confirmed_total {}\n",
+        s.mempool.pending.len(),
+        s.mempool.seen,
+        s.mempool.replaced,
+        s.mempool.dropped,
+        s.mempool.confirmed
+    );
+    if !s.signers.signers.is_empty() {
+        out.push_str("# HELP stacks_signer_participation Share of recent blocks in the reward cycle signed by each signer.\n# TYPE stacks_signer_participation gauge\n");
+    }
+    for signer in &s.signers.signers {
+        let _ = writeln!(
+            out,
+            "stacks_signer_participation{{signing_key=\"{}\"}} {}",
+            signer.signing_key, signer.participation
+        );
+    }
+    out
 }
 
-pub async fn verify_attestation(
+fn api_error(status: StatusCode, e: impl ToString) -> (StatusCode, Json<Value>) {
+    (status, Json(json!({ "error": e.to_string() })))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct StacksTxRequest {
+    /// Hex-encoded serialized transaction.
+    pub tx: String,
+}
+
+pub async fn broadcast_stacks_tx(
+    State(app): State<AppState>,
+    Json(request): Json<StacksTxRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    {
+        let mut s = app.state.write().unwrap();
+        s.metrics.total_requests += 1;
+    }
+
+    let rpc = app
+        .stacks
+        .clone()
+        .ok_or_else(|| api_error(StatusCode::SERVICE_UNAVAILABLE, "Stacks RPC not configured"))?;
+
+    let reject = |status: StatusCode, e: ConxianError| {
+        app.state.write().unwrap().metrics.transactions_rejected += 1;
+        api_error(status, e)
+    };
+
+    let raw = hex::decode(request.tx.trim_start_matches("0x")).map_err(|e| {
+        reject(
+            StatusCode::BAD_REQUEST,
+            ConxianError::Stacks(format!("Invalid transaction hex: {}", e)),
+        )
+    })?;
+    let tx = StacksTransaction::decode(&raw).map_err(|e| reject(StatusCode::BAD_REQUEST, e))?;
+    tx.verify_signatures()
+        .map_err(|e| reject(StatusCode::BAD_REQUEST, e))?;
+    app.stacks_policy
+        .check(&tx)
+        .map_err(|e| reject(StatusCode::FORBIDDEN, e))?;
+    let principals: Vec<String> = tx.principals().iter().map(ToString::to_string).collect();
+    let input = PolicyInput {
+        amount: match &tx.payload {
+            TransactionPayload::TokenTransfer { amount, .. } => Some(*amount),
+            _ => None,
+        },
+        screening: screening_status(&app, &principals),
+        principals,
+        ..PolicyInput::new(PolicyTarget::Transaction)
+    };
+    decide(&app, &input, &tx.sender().to_string())
+        .check()
+        .map_err(|e| reject(StatusCode::FORBIDDEN, e))?;
+
+    let txid = rpc
+        .broadcast_transaction(&raw)
+        .await
+        .map_err(|e| api_error(StatusCode::BAD_GATEWAY, e))?;
+
+    {
+        let mut s = app.state.write().unwrap();
+        s.metrics.transactions_broadcast += 1;
+        let mut info = TransactionInfo::pending("stacks", &txid);
+        if let TransactionPayload::TokenTransfer { amount, .. } = &tx.payload {
+            if app.travel_rule.is_required(*amount) {
+                info.travel_rule = Some(TravelRuleStatus::Required);
+            }
+        }
+        s.transactions.insert(txid.clone(), info);
+    }
+
+    Ok(Json(json!({
+        "txid": txid,
+        "status": "pending",
+        "transaction": tx
+    })))
+}
+
+pub async fn get_stacks_tx(
     State(state): State<SharedState>,
+    Path(txid): Path<String>,
+) -> Result<Json<TransactionInfo>, (StatusCode, Json<Value>)> {
+    let s = state.read().unwrap();
+    s.transactions
+        .get(txid.trim_start_matches("0x"))
+        .filter(|tx| tx.chain == "stacks")
+        .cloned()
+        .map(Json)
+        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, "Transaction not tracked"))
+}
+
+pub async fn verify_attestation(
+    State(app): State<AppState>,
     Json(request): Json<AttestationRequest>,
 ) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    let hash = request_hash(&request);
+    let result = verify_request(&app.verifier, &request)
+        .and_then(|body| screen_request(&app, &request).map(|_| body))
+        .and_then(|mut body| {
+            let decision = decide(&app, &attestation_input(&app, &request), &hash);
+            decision.check()?;
+            body["policy"] = json!(decision);
+            Ok(body)
+        });
     {
-        let mut s = state.write().unwrap();
+        let mut s = app.state.write().unwrap();
         s.metrics.total_requests += 1;
         s.metrics.verification_count += 1;
+        if result.is_ok() {
+            s.metrics.verifications_valid += 1;
+        } else {
+            s.metrics.verifications_failed += 1;
+        }
     }
+    audit::record(
+        &app.audit,
+        verification_event(request.kind(), &hash, result.as_ref().err()),
+    );
+    let receipt = issue_receipt(&app, &hash, result.is_ok());
 
-    let verifier = ZkcVerifier::new();
-    let result = match request {
-        AttestationRequest::Ecdsa(a) => verifier.verify(&a),
-        AttestationRequest::Schnorr(a) => verifier.verify_schnorr(&a),
+    let (status, mut body) = match result {
+        Ok(body) => (StatusCode::OK, body),
+        Err(e) => (StatusCode::BAD_REQUEST, json!({ "error": e.to_string() })),
     };
+    if let Some(receipt) = receipt {
+        body["receipt"] = json!(receipt);
+    }
+    if status == StatusCode::OK {
+        Ok(Json(body))
+    } else {
+        Err((status, Json(body)))
+    }
+}
 
-    match result {
-        Ok(valid) => Ok(Json(json!({ "valid": valid }))),
-        Err(e) => Err((
-            StatusCode::BAD_REQUEST,
-            Json(json!({ "error": e.to_string() })),
-        )),
+/// Screens the keys and addresses of an attestation against the denylists.
+fn screen_request(app: &AppState, request: &AttestationRequest) -> ConxianResult<()> {
+    match &app.screener {
+        Some(screener) => screener.check(request.subjects()),
+        None => Ok(()),
+    }
+}
+
+/// Whether any of `subjects` is denylisted; `NotScreened` without denylists.
+fn screening_status(app: &AppState, subjects: &[String]) -> ScreeningStatus {
+    match &app.screener {
+        None => ScreeningStatus::NotScreened,
+        Some(screener) if subjects.iter().any(|s| !screener.screen(s).is_empty()) => {
+            ScreeningStatus::Hit
+        }
+        Some(_) => ScreeningStatus::Clear,
+    }
+}
+
+/// Facts about an attestation for the compliance policy.
+fn attestation_input(app: &AppState, request: &AttestationRequest) -> PolicyInput {
+    let principals = request.subjects();
+    PolicyInput {
+        device_id: request.device_id().map(str::to_string),
+        scheme: Some(request.kind().to_string()),
+        screening: screening_status(app, &principals),
+        principals,
+        ..PolicyInput::new(PolicyTarget::Attestation)
+    }
+}
+
+fn policy_event(decision: &PolicyDecision, subject: &str) -> AuditEvent {
+    let target = match decision.target {
+        PolicyTarget::Attestation => "attestation",
+        PolicyTarget::Transaction => "transaction",
+        PolicyTarget::Account => "account",
+    };
+    AuditEvent {
+        kind: AuditKind::Policy,
+        action: format!("policy.{}", target),
+        subject: Some(subject.to_string()),
+        success: decision.allowed,
+        detail: serde_json::to_string(decision).ok(),
+    }
+}
+
+/// Evaluates the policy over `input` and records the decision about `subject`.
+fn decide(app: &AppState, input: &PolicyInput, subject: &str) -> PolicyDecision {
+    let decision = app.policy.evaluate(input, unix_now());
+    audit::record(&app.audit, policy_event(&decision, subject));
+    decision
+}
+
+/// SHA-256 of the attestation request as serialized by the gateway.
+fn request_hash(request: &AttestationRequest) -> String {
+    receipt::attestation_hash(
+        serde_json::to_string(request)
+            .unwrap_or_default()
+            .as_bytes(),
+    )
+}
+
+fn verification_event(kind: &str, hash: &str, error: Option<&ConxianError>) -> AuditEvent {
+    AuditEvent {
+        kind: AuditKind::Verification,
+        action: format!("verify.{}", kind.to_lowercase()),
+        subject: Some(hash.to_string()),
+        success: error.is_none(),
+        detail: error.map(ToString::to_string),
+    }
+}
+
+/// Signs a receipt for a verdict on `attestation_hash`, at the current chain
+/// tips, if a receipt key is configured.
+fn issue_receipt(app: &AppState, attestation_hash: &str, valid: bool) -> Option<Receipt> {
+    let signer = app.receipts.as_ref()?;
+    let claims = {
+        let s = app.state.read().unwrap();
+        ReceiptClaims {
+            attestation_hash: attestation_hash.to_string(),
+            valid,
+            issued_at: unix_now(),
+            bitcoin_height: s.bitcoin.height,
+            bitcoin_tip: s.bitcoin.best_block_hash.clone(),
+            stacks_height: s.stacks.height,
+            stacks_tip: s.stacks.best_block_hash.clone(),
+            issuer: String::new(),
+        }
+    };
+    let receipt = signer
+        .issue(claims)
+        .map_err(|e| warn!("Failed to issue verification receipt: {}", e))
+        .ok()?;
+    if let Some(notary) = &app.notary {
+        let id = receipt::receipt_hash(&receipt);
+        if let Err(e) = notary.submit(CommitmentLeafKind::Receipt, &id) {
+            warn!("Failed to queue receipt for commitment: {}", e);
+        }
     }
+    Some(receipt)
+}
+
+/// Verifies one attestation, returning the body reported for it.
+fn verify_request(verifier: &ZkcVerifier, request: &AttestationRequest) -> ConxianResult<Value> {
+    match request {
+        AttestationRequest::Ecdsa(a) => verifier.verify(a).map(|valid| json!({ "valid": valid })),
+        AttestationRequest::Schnorr(a) => verifier
+            .verify_schnorr(a)
+            .map(|valid| json!({ "valid": valid })),
+        AttestationRequest::Sip018(a) => verifier
+            .verify_sip018(a)
+            .map(|signer| json!({ "valid": true, "signer": signer })),
+        AttestationRequest::Bip322(a) => verifier
+            .verify_bip322(a)
+            .map(|valid| json!({ "valid": valid })),
+        AttestationRequest::Bip137(a) => verifier
+            .verify_bip137(a)
+            .map(|valid| json!({ "valid": valid })),
+        AttestationRequest::P256(a) => verifier
+            .verify_p256(a)
+            .map(|valid| json!({ "valid": valid })),
+        AttestationRequest::WebAuthn(a) => verifier
+            .verify_webauthn(a)
+            .map(|operator| json!({ "valid": true, "operator": operator })),
+        AttestationRequest::Ring(a) => verifier
+            .verify_ring(a)
+            .map(|ring_size| json!({ "valid": true, "root": a.root, "ring_size": ring_size })),
+        AttestationRequest::Threshold(a) => verifier.verify_threshold(a).map(|report| {
+            json!({
+                "valid": true,
+                "threshold": report.threshold,
+                "counted": report.counted,
+                "rejected": report.rejected,
+            })
+        }),
+    }
+}
+
+/// Largest number of attestations accepted in one batch.
+pub const MAX_BATCH_SIZE: usize = 1000;
+
+#[derive(Debug, Deserialize)]
+pub struct BatchVerifyRequest {
+    pub attestations: Vec<AttestationRequest>,
+}
+
+/// Verifies a batch of attestations in parallel. Each result carries the
+/// index of its attestation; failures report an error and its code.
+pub async fn verify_attestation_batch(
+    State(app): State<AppState>,
+    Json(request): Json<BatchVerifyRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    let total = request.attestations.len();
+    if total > MAX_BATCH_SIZE {
+        return Err(api_error(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!("Batch of {} exceeds the limit of {}", total, MAX_BATCH_SIZE),
+        ));
+    }
+
+    let hashes: Vec<String> = request.attestations.iter().map(request_hash).collect();
+    let kinds: Vec<&str> = request.attestations.iter().map(|a| a.kind()).collect();
+    let screened: Vec<ConxianResult<()>> = request
+        .attestations
+        .iter()
+        .map(|a| screen_request(&app, a))
+        .collect();
+    let inputs: Vec<PolicyInput> = request
+        .attestations
+        .iter()
+        .map(|a| attestation_input(&app, a))
+        .collect();
+    let verifier = app.verifier.clone();
+    let outcomes =
+        tokio::task::spawn_blocking(move || verify_batch(&verifier, request.attestations))
+            .await
+            .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e))?;
+
+    let mut valid = 0;
+    let mut events = Vec::with_capacity(total);
+    let results: Vec<Value> = outcomes
+        .into_iter()
+        .zip(screened)
+        .enumerate()
+        .map(|(index, (outcome, screened))| {
+            let outcome = outcome
+                .and_then(|body| screened.map(|_| body))
+                .and_then(|body| {
+                    let decision = app.policy.evaluate(&inputs[index], unix_now());
+                    events.push(policy_event(&decision, &hashes[index]));
+                    decision.check().map(|_| body)
+                });
+            events.push(verification_event(
+                kinds[index],
+                &hashes[index],
+                outcome.as_ref().err(),
+            ));
+            let receipt = issue_receipt(&app, &hashes[index], outcome.is_ok());
+            let mut body = match outcome {
+                Ok(mut body) => {
+                    valid += 1;
+                    body["index"] = json!(index);
+                    body
+                }
+                Err(e) => json!({
+                    "index": index,
+                    "valid": false,
+                    "error": e.to_string(),
+                    "code": e.code(),
+                }),
+            };
+            if let Some(receipt) = receipt {
+                body["receipt"] = json!(receipt);
+            }
+            body
+        })
+        .collect();
+    if let Err(e) = app.audit.append_all(events, unix_now()) {
+        warn!("Failed to write audit log entries: {}", e);
+    }
+    {
+        let mut s = app.state.write().unwrap();
+        s.metrics.verification_count += total as u64;
+        s.metrics.verifications_valid += valid as u64;
+        s.metrics.verifications_failed += (total - valid) as u64;
+    }
+
+    Ok(Json(json!({
+        "total": total,
+        "valid": valid,
+        "invalid": total - valid,
+        "results": results,
+    })))
+}
+
+/// Schnorr attestations share one batch equation; the rest are spread
+/// across worker threads. Outcomes are in input order.
+fn verify_batch(
+    verifier: &ZkcVerifier,
+    requests: Vec<AttestationRequest>,
+) -> Vec<ConxianResult<Value>> {
+    let mut outcomes: Vec<Option<ConxianResult<Value>>> = requests.iter().map(|_| None).collect();
+
+    let (schnorr_indices, schnorr): (Vec<usize>, Vec<_>) = requests
+        .iter()
+        .enumerate()
+        .filter_map(|(i, r)| match r {
+            AttestationRequest::Schnorr(a) => Some((i, a.clone())),
+            _ => None,
+        })
+        .unzip();
+    for (i, result) in schnorr_indices
+        .into_iter()
+        .zip(verifier.verify_schnorr_batch(&schnorr))
+    {
+        outcomes[i] = Some(result.map(|valid| json!({ "valid": valid })));
+    }
+
+    let rest: Vec<usize> = (0..requests.len())
+        .filter(|&i| outcomes[i].is_none())
+        .collect();
+    let workers = std::thread::available_parallelism().map_or(1, |n| n.get());
+    let chunk_size = rest.len().div_ceil(workers).max(1);
+    std::thread::scope(|scope| {
+        let handles: Vec<_> = rest
+            .chunks(chunk_size)
+            .map(|chunk| {
+                let requests = &requests;
+                scope.spawn(move || {
+                    chunk
+                        .iter()
+                        .map(|&i| (i, verify_request(verifier, &requests[i])))
+                        .collect::<Vec<_>>()
+                })
+            })
+            .collect();
+        for handle in handles {
+            for (i, result) in handle.join().expect("verification worker panicked") {
+                outcomes[i] = Some(result);
+            }
+        }
+    });
+
+    outcomes
+        .into_iter()
+        .map(|o| o.expect("every attestation verified"))
+        .collect()
+}
+
+/// The active device keys that ring attestations are made under, and the
+/// registry root that commits to them.
+pub async fn get_zkc_ring(State(app): State<AppState>) -> Json<Value> {
+    let ring = app.devices.ring();
+    Json(json!({
+        "root": ring::ring_root(&ring),
+        "size": ring.len(),
+        "keys": ring.iter().map(|key| key.to_string()).collect::<Vec<_>>(),
+    }))
+}
+
+pub async fn get_stacks_account(
+    State(app): State<AppState>,
+    Path(principal): Path<String>,
+) -> Result<Json<StacksAccount>, (StatusCode, Json<Value>)> {
+    {
+        let mut s = app.state.write().unwrap();
+        s.metrics.total_requests += 1;
+    }
+
+    let principal: PrincipalData = principal
+        .parse()
+        .map_err(|e| api_error(StatusCode::BAD_REQUEST, e))?;
+    let rpc = app
+        .stacks
+        .clone()
+        .ok_or_else(|| api_error(StatusCode::SERVICE_UNAVAILABLE, "Stacks RPC not configured"))?;
+
+    let principals = vec![principal.to_string()];
+    let input = PolicyInput {
+        screening: screening_status(&app, &principals),
+        principals,
+        ..PolicyInput::new(PolicyTarget::Account)
+    };
+    decide(&app, &input, &principal.to_string())
+        .check()
+        .map_err(|e| api_error(StatusCode::FORBIDDEN, e))?;
+
+    rpc.get_account(&principal.to_string())
+        .await
+        .map(Json)
+        .map_err(|e| api_error(StatusCode::BAD_GATEWAY, e))
+}
+
+/// Resolves a BNS name (`alice.btc`), or a principal to its primary name.
+pub async fn resolve_bns(
+    State(app): State<AppState>,
+    Path(name): Path<String>,
+) -> Result<Json<BnsName>, (StatusCode, Json<Value>)> {
+    {
+        let mut s = app.state.write().unwrap();
+        s.metrics.total_requests += 1;
+    }
+
+    let resolver = app.bns.clone().ok_or_else(|| {
+        api_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "BNS resolver not configured",
+        )
+    })?;
+
+    let resolved = match name.parse::<PrincipalData>() {
+        Ok(principal) => resolver.resolve_principal(&principal).await,
+        Err(_) => {
+            engine::stacks::bns::parse_fqn(&name)
+                .map_err(|e| api_error(StatusCode::BAD_REQUEST, e))?;
+            resolver.resolve_name(&name).await
+        }
+    }
+    .map_err(|e| api_error(StatusCode::BAD_GATEWAY, e))?;
+
+    resolved.map(Json).ok_or_else(|| {
+        api_error(
+            StatusCode::NOT_FOUND,
+            format!("BNS name not found: {}", name),
+        )
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct ScreenRequest {
+    /// Bitcoin addresses, Stacks principals, public keys or names.
+    pub subjects: Vec<String>,
+}
+
+/// Screens subjects against the configured denylists.
+pub async fn screen(
+    State(app): State<AppState>,
+    Json(request): Json<ScreenRequest>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    let screener = app.screener.clone().ok_or_else(|| {
+        api_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Screening lists not configured",
+        )
+    })?;
+    if request.subjects.len() > MAX_BATCH_SIZE {
+        return Err(api_error(
+            StatusCode::PAYLOAD_TOO_LARGE,
+            format!(
+                "{} subjects exceed the limit of {}",
+                request.subjects.len(),
+                MAX_BATCH_SIZE
+            ),
+        ));
+    }
+
+    let hits: Vec<ScreeningHit> = request
+        .subjects
+        .iter()
+        .flat_map(|subject| screener.screen(subject))
+        .collect();
+    Ok(Json(json!({
+        "screened": request.subjects.len(),
+        "clear": hits.is_empty(),
+        "hits": hits,
+        "lists": screener.lists(),
+    })))
+}
+
+/// The compliance policy in force and its hash.
+pub async fn get_policy(State(app): State<AppState>) -> Json<Value> {
+    Json(json!({
+        "policy_hash": app.policy.hash(),
+        "policy": app.policy.policy(),
+    }))
+}
+
+/// Evaluates the policy over the facts supplied, without acting on the
+/// decision. Dry runs are audited like any other decision.
+pub async fn evaluate_policy(
+    State(app): State<AppState>,
+    Json(input): Json<PolicyInput>,
+) -> Json<PolicyDecision> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    let decision = app.policy.evaluate(&input, unix_now());
+    audit::record(
+        &app.audit,
+        AuditEvent {
+            action: "policy.evaluate".to_string(),
+            ..policy_event(&decision, "dry-run")
+        },
+    );
+    Json(decision)
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RiskRequest {
+    /// Txid of a transaction the Bitcoin node knows, e.g. a deposit.
+    pub txid: Option<String>,
+    /// Hex-encoded transaction, e.g. one about to be broadcast.
+    pub raw_tx: Option<String>,
+}
+
+/// Scores a Bitcoin transaction by its exposure to denylisted outputs.
+pub async fn score_bitcoin_tx(
+    State(app): State<AppState>,
+    Json(request): Json<RiskRequest>,
+) -> Result<Json<RiskAssessment>, (StatusCode, Json<Value>)> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    let scorer = app.risk.clone().ok_or_else(|| {
+        api_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Bitcoin risk scoring not configured",
+        )
+    })?;
+
+    let assessment = match (request.txid, request.raw_tx) {
+        (Some(txid), None) => {
+            let txid: Txid = txid.parse().map_err(|e| {
+                api_error(
+                    StatusCode::BAD_REQUEST,
+                    ConxianError::Bitcoin(format!("Invalid txid {}: {}", txid, e)),
+                )
+            })?;
+            scorer.score_txid(&txid.to_string()).await
+        }
+        (None, Some(raw_tx)) => {
+            let tx: Transaction = deserialize_hex(&raw_tx).map_err(|e| {
+                api_error(
+                    StatusCode::BAD_REQUEST,
+                    ConxianError::Bitcoin(format!("Invalid transaction: {}", e)),
+                )
+            })?;
+            scorer.score(&tx).await
+        }
+        _ => {
+            return Err(api_error(
+                StatusCode::BAD_REQUEST,
+                "Exactly one of txid and raw_tx is required",
+            ))
+        }
+    };
+    assessment
+        .map(Json)
+        .map_err(|e| api_error(StatusCode::BAD_GATEWAY, e))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TravelRuleRequest {
+    /// Public key of the counterparty VASP the payload is sealed to, in hex.
+    pub counterparty_key: String,
+    pub payload: IdentityPayload,
+}
+
+/// Seals an IVMS101 payload to the counterparty VASP for a tracked transaction.
+pub async fn attach_travel_rule(
+    State(app): State<AppState>,
+    Path(txid): Path<String>,
+    Json(request): Json<TravelRuleRequest>,
+) -> Result<Json<TravelRuleRecord>, (StatusCode, Json<Value>)> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    let txid = txid.trim_start_matches("0x").to_string();
+    if !app.state.read().unwrap().transactions.contains_key(&txid) {
+        return Err(api_error(StatusCode::NOT_FOUND, "Transaction not tracked"));
+    }
+    if app
+        .travel_rule
+        .get(&txid)
+        .is_some_and(|r| r.status == TravelRuleStatus::Acknowledged)
+    {
+        return Err(api_error(
+            StatusCode::CONFLICT,
+            format!("Travel Rule payload for {} was already acknowledged", txid),
+        ));
+    }
+
+    let record = app
+        .travel_rule
+        .attach(
+            &txid,
+            &request.counterparty_key,
+            &request.payload,
+            unix_now(),
+        )
+        .map_err(|e| api_error(StatusCode::BAD_REQUEST, e))?;
+    if let Some(tx) = app.state.write().unwrap().transactions.get_mut(&txid) {
+        tx.travel_rule = Some(record.status);
+    }
+    Ok(Json(record))
+}
+
+pub async fn get_travel_rule(
+    State(app): State<AppState>,
+    Path(txid): Path<String>,
+) -> Result<Json<TravelRuleRecord>, (StatusCode, Json<Value>)> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    app.travel_rule
+        .get(txid.trim_start_matches("0x"))
+        .map(Json)
+        .ok_or_else(|| api_error(StatusCode::NOT_FOUND, "No Travel Rule payload"))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct TravelRuleAckRequest {
+    pub accepted: bool,
+    #[serde(default)]
+    pub reason: Option<String>,
+    /// BIP-340 signature by the counterparty key over the acknowledgement.
+    pub signature: String,
+}
+
+/// Records the counterparty VASP's signed acceptance or rejection of a payload.
+pub async fn acknowledge_travel_rule(
+    State(app): State<AppState>,
+    Path(txid): Path<String>,
+    Json(request): Json<TravelRuleAckRequest>,
+) -> Result<Json<TravelRuleRecord>, (StatusCode, Json<Value>)> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    let txid = txid.trim_start_matches("0x").to_string();
+    if app.travel_rule.get(&txid).is_none() {
+        return Err(api_error(StatusCode::NOT_FOUND, "No Travel Rule payload"));
+    }
+
+    let record = app
+        .travel_rule
+        .acknowledge(
+            &txid,
+            request.accepted,
+            request.reason.clone(),
+            &request.signature,
+            unix_now(),
+        )
+        .map_err(|e| {
+            let status = match e {
+                ConxianError::Compliance(_) => StatusCode::CONFLICT,
+                _ => StatusCode::BAD_REQUEST,
+            };
+            api_error(status, e)
+        })?;
+    let mut s = app.state.write().unwrap();
+    if let Some(tx) = s.transactions.get_mut(&txid) {
+        tx.travel_rule = Some(record.status);
+    }
+    s.emit_event(GatewayEvent::TravelRuleAcknowledged {
+        txid,
+        accepted: request.accepted,
+        reason: request.reason,
+    });
+    Ok(Json(record))
+}
+
+/// Body of the node's `drop_mempool_tx` event-observer callback.
+#[derive(Debug, Deserialize)]
+pub struct DropMempoolTx {
+    pub dropped_txids: Vec<String>,
+    pub reason: String,
+    pub new_txid: Option<String>,
+}
+
+/// Event-observer `new_mempool_tx`: a list of hex-encoded raw transactions.
+/// Always acknowledged so the node does not retry undecodable payloads.
+pub async fn observe_new_mempool_tx(
+    State(tracker): State<MempoolTracker>,
+    Json(txs): Json<Vec<String>>,
+) -> StatusCode {
+    for tx in txs {
+        let observed = hex::decode(tx.trim_start_matches("0x"))
+            .map_err(|e| ConxianError::Stacks(e.to_string()))
+            .and_then(|raw| tracker.observe_raw(&raw));
+        if let Err(e) = observed {
+            warn!("Ignoring undecodable mempool transaction: {}", e);
+        }
+    }
+    StatusCode::OK
+}
+
+pub async fn observe_drop_mempool_tx(
+    State(tracker): State<MempoolTracker>,
+    Json(event): Json<DropMempoolTx>,
+) -> StatusCode {
+    tracker.drop_txs(
+        &event.dropped_txids,
+        &event.reason,
+        event.new_txid.as_deref(),
+    );
+    StatusCode::OK
+}
+
+pub async fn get_events(State(state): State<SharedState>) -> Json<Vec<GatewayEvent>> {
+    let mut s = state.write().unwrap();
+    s.metrics.total_requests += 1;
+    Json(s.events.clone())
+}
+
+pub(crate) fn unix_now() -> u64 {
+    SystemTime::now()
+        .duration_since(UNIX_EPOCH)
+        .unwrap()
+        .as_secs()
+}
+
+pub async fn list_devices(State(app): State<AppState>) -> Json<Vec<DeviceRecord>> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    Json(app.devices.list())
+}
+
+pub async fn get_device(
+    State(app): State<AppState>,
+    Path(device_id): Path<String>,
+) -> Result<Json<DeviceRecord>, (StatusCode, Json<Value>)> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    app.devices.get(&device_id).map(Json).ok_or_else(|| {
+        api_error(
+            StatusCode::NOT_FOUND,
+            format!("Device not found: {}", device_id),
+        )
+    })
+}
+
+#[derive(Debug, Deserialize)]
+pub struct EnrollDeviceRequest {
+    pub device_id: String,
+    /// Hex-encoded secp256k1 public key.
+    pub public_key: String,
+}
+
+pub async fn enroll_device(
+    State(app): State<AppState>,
+    Json(request): Json<EnrollDeviceRequest>,
+) -> Result<(StatusCode, Json<DeviceRecord>), (StatusCode, Json<Value>)> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    if app.devices.get(&request.device_id).is_some() {
+        return Err(api_error(
+            StatusCode::CONFLICT,
+            format!("Device already enrolled: {}", request.device_id),
+        ));
+    }
+    let record = app
+        .devices
+        .enroll(&request.device_id, &request.public_key, unix_now())
+        .map_err(|e| api_error(StatusCode::BAD_REQUEST, e))?;
+    Ok((StatusCode::CREATED, Json(record)))
+}
+
+#[derive(Debug, Default, Deserialize)]
+pub struct UpdateDeviceRequest {
+    /// New key, required for `rotate`.
+    pub public_key: Option<String>,
+    pub reason: Option<String>,
+}
+
+/// Applies `rotate`, `suspend`, `reactivate` or `revoke` to a device.
+pub async fn update_device(
+    State(app): State<AppState>,
+    Path((device_id, action)): Path<(String, String)>,
+    request: Option<Json<UpdateDeviceRequest>>,
+) -> Result<Json<DeviceRecord>, (StatusCode, Json<Value>)> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    let Json(request) = request.unwrap_or_default();
+
+    if app.devices.get(&device_id).is_none() {
+        return Err(api_error(
+            StatusCode::NOT_FOUND,
+            format!("Device not found: {}", device_id),
+        ));
+    }
+    let now = unix_now();
+    let result = match action.as_str() {
+        "rotate" => {
+            let public_key = request.public_key.ok_or_else(|| {
+                api_error(StatusCode::BAD_REQUEST, "public_key is required to rotate")
+            })?;
+            app.devices
+                .rotate(&device_id, &public_key, request.reason, now)
+        }
+        "suspend" => app.devices.suspend(&device_id, request.reason, now),
+        "reactivate" => app.devices.reactivate(&device_id, request.reason, now),
+        "revoke" => app.devices.revoke(&device_id, request.reason, now),
+        _ => {
+            return Err(api_error(
+                StatusCode::NOT_FOUND,
+                format!("Unknown device action: {}", action),
+            ))
+        }
+    };
+    result
+        .map(Json)
+        .map_err(|e| api_error(StatusCode::CONFLICT, e))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct DeviceAttestationRequest {
+    /// `android` or `apple`.
+    pub platform: String,
+    /// Base64 DER certificate chain, leaf first.
+    pub certificates: Vec<String>,
+    /// Base64 App Attest authenticator data, required for `apple`.
+    pub auth_data: Option<String>,
+}
+
+/// Validates a hardware attestation chain for a device and binds the attested
+/// enclave key to its record.
+pub async fn attest_device(
+    State(app): State<AppState>,
+    Path(device_id): Path<String>,
+    Json(request): Json<DeviceAttestationRequest>,
+) -> Result<Json<DeviceRecord>, (StatusCode, Json<Value>)> {
+    use compliance::hardware::{decode_auth_data, decode_certificates};
+
+    app.state.write().unwrap().metrics.total_requests += 1;
+    let hardware = app.hardware.as_ref().ok_or_else(|| {
+        api_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Hardware attestation roots not configured",
+        )
+    })?;
+    if app.devices.get(&device_id).is_none() {
+        return Err(api_error(
+            StatusCode::NOT_FOUND,
+            format!("Device not found: {}", device_id),
+        ));
+    }
+
+    let now = unix_now();
+    let chain = decode_certificates(&request.certificates)
+        .map_err(|e| api_error(StatusCode::BAD_REQUEST, e))?;
+    let attestation = match request.platform.as_str() {
+        "android" => hardware.verify_android(&device_id, &chain, now),
+        "apple" => {
+            let auth_data = request
+                .auth_data
+                .as_deref()
+                .ok_or_else(|| {
+                    api_error(
+                        StatusCode::BAD_REQUEST,
+                        "auth_data is required for Apple App Attest",
+                    )
+                })
+                .and_then(|data| {
+                    decode_auth_data(data).map_err(|e| api_error(StatusCode::BAD_REQUEST, e))
+                })?;
+            hardware.verify_apple(&device_id, &chain, &auth_data, now)
+        }
+        other => {
+            return Err(api_error(
+                StatusCode::BAD_REQUEST,
+                format!("Unsupported attestation platform: {}", other),
+            ))
+        }
+    }
+    .map_err(|e| api_error(StatusCode::BAD_REQUEST, e))?;
+
+    app.devices
+        .bind_hardware(&device_id, attestation, now)
+        .map(Json)
+        .map_err(|e| api_error(StatusCode::CONFLICT, e))
+}
+
+pub async fn list_passkeys(State(app): State<AppState>) -> Json<Vec<PasskeyCredential>> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    Json(app.passkeys.list())
+}
+
+#[derive(Debug, Deserialize)]
+pub struct RegisterPasskeyRequest {
+    /// Base64url credential id.
+    pub credential_id: String,
+    pub operator_id: String,
+    pub algorithm: PasskeyAlgorithm,
+    /// Hex-encoded SEC1 P-256 or raw Ed25519 public key.
+    pub public_key: String,
+}
+
+pub async fn register_passkey(
+    State(app): State<AppState>,
+    Json(request): Json<RegisterPasskeyRequest>,
+) -> Result<(StatusCode, Json<PasskeyCredential>), (StatusCode, Json<Value>)> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    if app.passkeys.get(&request.credential_id).is_some() {
+        return Err(api_error(
+            StatusCode::CONFLICT,
+            format!("Credential already registered: {}", request.credential_id),
+        ));
+    }
+    let credential = app
+        .passkeys
+        .register(
+            &request.credential_id,
+            &request.operator_id,
+            request.algorithm,
+            &request.public_key,
+            unix_now(),
+        )
+        .map_err(|e| api_error(StatusCode::BAD_REQUEST, e))?;
+    Ok((StatusCode::CREATED, Json(credential)))
+}
+
+pub async fn remove_passkey(
+    State(app): State<AppState>,
+    Path(credential_id): Path<String>,
+) -> Result<Json<PasskeyCredential>, (StatusCode, Json<Value>)> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    app.passkeys
+        .remove(&credential_id)
+        .map(Json)
+        .ok_or_else(|| {
+            api_error(
+                StatusCode::NOT_FOUND,
+                format!("Credential not found: {}", credential_id),
+            )
+        })
+}
+
+/// The gateway key receipts are signed with.
+pub async fn get_receipt_key(
+    State(app): State<AppState>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    let signer = app.receipts.as_ref().ok_or_else(|| {
+        api_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Receipt signing not configured",
+        )
+    })?;
+    Ok(Json(json!({ "issuer": signer.public_key() })))
+}
+
+/// Checks a receipt against this gateway's key, as a third party would offline
+/// with `conxian_core::receipt::verify_receipt`.
+pub async fn verify_receipt(
+    State(app): State<AppState>,
+    Json(request): Json<Receipt>,
+) -> Result<Json<Value>, (StatusCode, Json<Value>)> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    let signer = app.receipts.as_ref().ok_or_else(|| {
+        api_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Receipt signing not configured",
+        )
+    })?;
+    receipt::verify_receipt(&request, &signer.public_key())
+        .map(|claims| Json(json!({ "valid": true, "claims": claims })))
+        .map_err(|e| api_error(StatusCode::BAD_REQUEST, e))
+}
+
+/// Checks the audit log's hash chain across its segments.
+pub async fn verify_audit_log(
+    State(app): State<AppState>,
+) -> Result<Json<AuditReport>, (StatusCode, Json<Value>)> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    app.audit
+        .verify()
+        .map(Json)
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct AuditExportQuery {
+    #[serde(default)]
+    pub from: u64,
+    pub to: Option<u64>,
+}
+
+/// Audit entries with `from <= seq <= to`, with their hashes so the range can
+/// be checked against the chain.
+pub async fn export_audit_log(
+    State(app): State<AppState>,
+    Query(query): Query<AuditExportQuery>,
+) -> Result<Json<Vec<AuditEntry>>, (StatusCode, Json<Value>)> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    app.audit
+        .export(query.from, query.to)
+        .map(Json)
+        .map_err(|e| api_error(StatusCode::INTERNAL_SERVER_ERROR, e))
+}
+
+/// Starts a new audit log segment, returning the entry that links it to the
+/// previous one.
+pub async fn rotate_audit_log(
+    State(app): State<AppState>,
+) -> Result<Json<AuditEntry>, (StatusCode, Json<Value>)> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    app.audit.rotate(unix_now()).map(Json).map_err(|e| {
+        let status = match e {
+            ConxianError::Compliance(_) => StatusCode::CONFLICT,
+            _ => StatusCode::INTERNAL_SERVER_ERROR,
+        };
+        api_error(status, e)
+    })
+}
+
+fn notary(app: &AppState) -> Result<&Notary, (StatusCode, Json<Value>)> {
+    app.notary.as_deref().ok_or_else(|| {
+        api_error(
+            StatusCode::SERVICE_UNAVAILABLE,
+            "Bitcoin commitments not configured",
+        )
+    })
+}
+
+/// Batches committed to Bitcoin, oldest first.
+pub async fn list_commitments(
+    State(app): State<AppState>,
+) -> Result<Json<Vec<CommitmentBatch>>, (StatusCode, Json<Value>)> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    Ok(Json(notary(&app)?.batches()))
+}
+
+pub async fn get_commitment(
+    State(app): State<AppState>,
+    Path(id): Path<u64>,
+) -> Result<Json<CommitmentBatch>, (StatusCode, Json<Value>)> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    notary(&app)?.batch(id).map(Json).ok_or_else(|| {
+        api_error(
+            StatusCode::NOT_FOUND,
+            format!("Commitment not found: {}", id),
+        )
+    })
+}
+
+/// Inclusion proof for an audit entry hash or receipt hash.
+pub async fn get_inclusion_proof(
+    State(app): State<AppState>,
+    Path(leaf_id): Path<String>,
+) -> Result<Json<InclusionProof>, (StatusCode, Json<Value>)> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    notary(&app)?
+        .proof(&leaf_id)
+        .map(Json)
+        .map_err(|e| api_error(StatusCode::NOT_FOUND, e))
+}
+
+#[derive(Debug, Deserialize)]
+pub struct CommitmentTransactionRequest {
+    /// Signed commitment transaction in hex.
+    pub raw_tx: String,
+}
+
+/// Records an externally signed commitment transaction, to be broadcast by
+/// the commitment service. Transactions with high risk inputs are refused.
+pub async fn submit_commitment_transaction(
+    State(app): State<AppState>,
+    Path(id): Path<u64>,
+    Json(request): Json<CommitmentTransactionRequest>,
+) -> Result<Json<CommitmentBatch>, (StatusCode, Json<Value>)> {
+    app.state.write().unwrap().metrics.total_requests += 1;
+    let notary = notary(&app)?;
+    if notary.batch(id).is_none() {
+        return Err(api_error(
+            StatusCode::NOT_FOUND,
+            format!("Commitment not found: {}", id),
+        ));
+    }
+    if let Some(scorer) = &app.risk {
+        let tx: Transaction = deserialize_hex(&request.raw_tx).map_err(|e| {
+            api_error(
+                StatusCode::BAD_REQUEST,
+                ConxianError::Bitcoin(format!("Invalid transaction: {}", e)),
+            )
+        })?;
+        scorer.check(&tx).await.map_err(|e| {
+            let status = match e {
+                ConxianError::Compliance(_) => StatusCode::FORBIDDEN,
+                _ => StatusCode::BAD_GATEWAY,
+            };
+            api_error(status, e)
+        })?;
+    }
+    notary
+        .attach_transaction(id, &request.raw_tx)
+        .map(Json)
+        .map_err(|e| {
+            let status = match e {
+                ConxianError::Compliance(_) => StatusCode::CONFLICT,
+                _ => StatusCode::BAD_REQUEST,
+            };
+            api_error(status, e)
+        })
 }
 
 #[cfg(test)]
@@ -112,8 +1321,62 @@ mod tests {
             payload: "payload".to_string(),
             public_key: "0250863ad64a87ad8a2bf2bb8ae16617bc25e101c70628d01f0599a4f7bb4d602f".to_string(),
         };
-        let res =
-            verify_attestation(State(state), Json(AttestationRequest::Ecdsa(attestation))).await;
+        let res = verify_attestation(
+            State(AppState::new(state)),
+            Json(AttestationRequest::Ecdsa(attestation)),
+        )
+        .await;
         assert!(res.is_err());
     }
+
+    #[tokio::test]
+    async fn test_get_stacks_account_handler() {
+        use engine::{SimulatedStacksRpc, StacksTxPolicy};
+        use std::sync::Arc as StdArc;
+
+        let state = Arc::new(RwLock::new(GatewayState::default()));
+        let app = AppState::new(state).with_stacks(
+            StdArc::new(SimulatedStacksRpc { initial_height: 1 }),
+            StacksTxPolicy::default(),
+        );
+
+        let res = get_stacks_account(
+            State(app.clone()),
+            Path("SP000000000000000000002Q6VF78".to_string()),
+        )
+        .await
+        .unwrap();
+        assert_eq!(res.0.balance, 1_000_000);
+
+        let res = get_stacks_account(State(app), Path("not-a-principal".to_string())).await;
+        assert_eq!(res.unwrap_err().0, StatusCode::BAD_REQUEST);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_bns_handler() {
+        use engine::{BnsResolver, SimulatedStacksRpc};
+        use std::sync::Arc as StdArc;
+
+        let state = Arc::new(RwLock::new(GatewayState::default()));
+        let res = resolve_bns(
+            State(AppState::new(state.clone())),
+            Path("alice.btc".to_string()),
+        )
+        .await;
+        assert_eq!(res.unwrap_err().0, StatusCode::SERVICE_UNAVAILABLE);
+
+        let rpc = StdArc::new(SimulatedStacksRpc { initial_height: 1 });
+        let app = AppState::new(state.clone()).with_bns(BnsResolver::new(rpc, state));
+
+        let res = resolve_bns(State(app.clone()), Path("alice.btc".to_string())).await;
+        assert_eq!(res.unwrap_err().0, StatusCode::NOT_FOUND);
+        let res = resolve_bns(
+            State(app.clone()),
+            Path("SP000000000000000000002Q6VF78".to_string()),
+        )
+        .await;
+        assert_eq!(res.unwrap_err().0, StatusCode::NOT_FOUND);
+        let res = resolve_bns(State(app), Path("Not A Name".to_string())).await;
+        assert_eq!(res.unwrap_err().0, StatusCode::BAD_REQUEST);
+    }
 }
@@ -1,5 +1,8 @@
+pub mod audit;
 pub mod auth;
 pub mod handlers;
 pub mod routes;
+pub mod state;
 
-pub use routes::configure_routes;
+pub use routes::{configure_routes, observer_routes};
+pub use state::AppState;
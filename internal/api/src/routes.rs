@@ -1,27 +1,127 @@
-use crate::auth::auth_middleware;
+use crate::audit::audit_middleware;
+use crate::auth::{admin_auth_middleware, auth_middleware};
 use crate::handlers;
+use crate::state::AppState;
+use axum::http::StatusCode;
 use axum::{
     middleware,
-    routing::{get, post},
+    routing::{delete, get, post},
     Router,
 };
-use conxian_core::SharedState;
+use engine::MempoolTracker;
 
-pub fn configure_routes(state: SharedState, api_token: String) -> Router {
+pub fn configure_routes(state: impl Into<AppState>, api_token: String) -> Router {
+    let state = state.into();
     let token_for_auth = api_token.clone();
+    let audit = state.audit.clone();
+    let auth_failures = state.auth_failures.clone();
+    let admin_token = state.admin_token.clone();
+    let admin_audit = state.audit.clone();
+    let admin_auth_failures = state.auth_failures.clone();
 
     let public_routes = Router::new()
         .route("/health", get(handlers::health_check))
         .route("/metrics", get(handlers::get_metrics))
+        .route("/receipts/key", get(handlers::get_receipt_key))
+        .route("/receipts/verify", post(handlers::verify_receipt))
+        .with_state(state.clone());
+
+    let admin_routes = Router::new()
+        .route(
+            "/admin/devices",
+            get(handlers::list_devices).post(handlers::enroll_device),
+        )
+        .route("/admin/devices/{device_id}", get(handlers::get_device))
+        .route(
+            "/admin/devices/{device_id}/attestation",
+            post(handlers::attest_device),
+        )
+        .route(
+            "/admin/devices/{device_id}/{action}",
+            post(handlers::update_device),
+        )
+        .route(
+            "/admin/passkeys",
+            get(handlers::list_passkeys).post(handlers::register_passkey),
+        )
+        .route(
+            "/admin/passkeys/{credential_id}",
+            delete(handlers::remove_passkey),
+        )
+        .route("/admin/audit/verify", get(handlers::verify_audit_log))
+        .route("/admin/audit/export", get(handlers::export_audit_log))
+        .route("/admin/audit/rotate", post(handlers::rotate_audit_log))
+        .route(
+            "/admin/commitments/{id}/transaction",
+            post(handlers::submit_commitment_transaction),
+        )
+        .layer(middleware::from_fn_with_state(
+            state.clone(),
+            audit_middleware,
+        ))
+        .layer(middleware::from_fn(move |req, next| {
+            admin_auth_middleware(
+                req,
+                next,
+                admin_token.clone(),
+                admin_audit.clone(),
+                admin_auth_failures.clone(),
+            )
+        }))
         .with_state(state.clone());
 
     let private_routes = Router::new()
         .route("/state", get(handlers::get_state))
         .route("/verify", post(handlers::verify_attestation))
+        .route("/verify/batch", post(handlers::verify_attestation_batch))
+        .route("/zkc/ring", get(handlers::get_zkc_ring))
+        .route("/stacks/tx", post(handlers::broadcast_stacks_tx))
+        .route("/stacks/tx/{txid}", get(handlers::get_stacks_tx))
+        .route(
+            "/stacks/accounts/{principal}",
+            get(handlers::get_stacks_account),
+        )
+        .route("/stacks/bns/{name}", get(handlers::resolve_bns))
+        .route("/screen", post(handlers::screen))
+        .route("/bitcoin/risk", post(handlers::score_bitcoin_tx))
+        .route("/policy", get(handlers::get_policy))
+        .route("/policy/evaluate", post(handlers::evaluate_policy))
+        .route(
+            "/travel-rule/{txid}",
+            get(handlers::get_travel_rule).post(handlers::attach_travel_rule),
+        )
+        .route(
+            "/travel-rule/{txid}/ack",
+            post(handlers::acknowledge_travel_rule),
+        )
+        .route("/events", get(handlers::get_events))
+        .route("/commitments", get(handlers::list_commitments))
+        .route("/commitments/{id}", get(handlers::get_commitment))
+        .route("/proofs/{leaf_id}", get(handlers::get_inclusion_proof))
         .layer(middleware::from_fn(move |req, next| {
-            auth_middleware(req, next, token_for_auth.clone())
+            auth_middleware(
+                req,
+                next,
+                token_for_auth.clone(),
+                audit.clone(),
+                auth_failures.clone(),
+            )
         }))
         .with_state(state);
 
-    Router::new().nest("/api/v1", public_routes.merge(private_routes))
+    Router::new().nest(
+        "/api/v1",
+        public_routes.merge(private_routes).merge(admin_routes),
+    )
+}
+
+/// Routes for a Stacks node's event observer. The node cannot authenticate, so
+/// these are served on a separate listener; unhandled events are acknowledged
+/// so the node does not retry them.
+pub fn observer_routes(tracker: MempoolTracker) -> Router {
+    Router::new()
+        .route("/new_mempool_tx", post(handlers::observe_new_mempool_tx))
+        .route("/drop_mempool_tx", post(handlers::observe_drop_mempool_tx))
+        .fallback(|| async { StatusCode::OK })
+        .with_state(tracker)
 }
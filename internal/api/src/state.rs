@@ -0,0 +1,157 @@
+use crate::auth::AuthFailureThrottle;
+use axum::extract::FromRef;
+use compliance::{
+    AuditLog, DeviceRegistry, HardwareVerifier, PasskeyRegistry, PolicyEngine, Screener,
+    TravelRuleStore, ZkcVerifier,
+};
+use conxian_core::receipt::ReceiptSigner;
+use conxian_core::SharedState;
+use engine::{BnsResolver, Notary, RiskScorer, StacksRpc, StacksTxPolicy};
+use std::sync::Arc;
+
+/// State shared by all API handlers.
+#[derive(Clone)]
+pub struct AppState {
+    pub state: SharedState,
+    pub stacks: Option<Arc<dyn StacksRpc>>,
+    pub stacks_policy: Arc<StacksTxPolicy>,
+    pub bns: Option<Arc<BnsResolver>>,
+    /// Shared so that the replay guard's nonces span requests.
+    pub verifier: Arc<ZkcVerifier>,
+    /// The verifier's device registry, managed through the admin API.
+    pub devices: Arc<DeviceRegistry>,
+    /// The verifier's operator passkeys, managed through the admin API.
+    pub passkeys: Arc<PasskeyRegistry>,
+    /// Manufacturer roots for device attestation; unset disables it.
+    pub hardware: Option<Arc<HardwareVerifier>>,
+    /// Gateway key verdicts are signed with; unset issues no receipts.
+    pub receipts: Option<Arc<ReceiptSigner>>,
+    /// Hash-chained record of verifications, auth failures and admin actions.
+    pub audit: Arc<AuditLog>,
+    /// Aggregates the audit entries written for authentication failures.
+    pub auth_failures: Arc<AuthFailureThrottle>,
+    /// Bearer token of the `/admin` routes, distinct from the API token;
+    /// unset disables them.
+    pub admin_token: Option<String>,
+    /// Commits audit entries and receipts to Bitcoin; unset disables it.
+    pub notary: Option<Arc<Notary>>,
+    /// Denylists attesters are screened against; unset disables screening.
+    pub screener: Option<Arc<Screener>>,
+    /// Scores Bitcoin transactions by their ancestors' exposure to the
+    /// denylists; unset disables it.
+    pub risk: Option<Arc<RiskScorer>>,
+    /// Compliance rules attestations, transactions and account lookups are
+    /// evaluated against; the built-in policy applies until one is loaded.
+    pub policy: Arc<PolicyEngine>,
+    /// Travel Rule payloads sealed to counterparties and their acknowledgements.
+    pub travel_rule: Arc<TravelRuleStore>,
+}
+
+impl AppState {
+    pub fn new(state: SharedState) -> Self {
+        let verifier = ZkcVerifier::new();
+        let devices = verifier.device_registry();
+        let passkeys = verifier.passkey_registry();
+        Self {
+            state,
+            stacks: None,
+            stacks_policy: Arc::new(StacksTxPolicy::default()),
+            bns: None,
+            verifier: Arc::new(verifier),
+            devices,
+            passkeys,
+            hardware: None,
+            receipts: None,
+            audit: Arc::new(AuditLog::in_memory()),
+            auth_failures: Arc::new(AuthFailureThrottle::default()),
+            admin_token: None,
+            notary: None,
+            screener: None,
+            risk: None,
+            policy: Arc::new(PolicyEngine::default()),
+            travel_rule: Arc::new(TravelRuleStore::in_memory()),
+        }
+    }
+
+    pub fn with_stacks(mut self, rpc: Arc<dyn StacksRpc>, policy: StacksTxPolicy) -> Self {
+        self.stacks = Some(rpc);
+        self.stacks_policy = Arc::new(policy);
+        self
+    }
+
+    pub fn with_verifier(mut self, verifier: ZkcVerifier) -> Self {
+        self.devices = verifier.device_registry();
+        self.passkeys = verifier.passkey_registry();
+        self.verifier = Arc::new(verifier);
+        self
+    }
+
+    pub fn with_hardware_verifier(mut self, hardware: HardwareVerifier) -> Self {
+        self.hardware = Some(Arc::new(hardware));
+        self
+    }
+
+    pub fn with_receipt_signer(mut self, signer: ReceiptSigner) -> Self {
+        self.receipts = Some(Arc::new(signer));
+        self
+    }
+
+    pub fn with_audit_log(mut self, log: AuditLog) -> Self {
+        self.audit = Arc::new(log);
+        self
+    }
+
+    /// Records one authentication failure per source per `window_secs`,
+    /// summarizing the rest; zero records every failure.
+    pub fn with_auth_failure_window(mut self, window_secs: u64) -> Self {
+        self.auth_failures = Arc::new(AuthFailureThrottle::new(window_secs));
+        self
+    }
+
+    pub fn with_admin_token(mut self, token: String) -> Self {
+        self.admin_token = Some(token);
+        self
+    }
+
+    pub fn with_notary(mut self, notary: Arc<Notary>) -> Self {
+        self.notary = Some(notary);
+        self
+    }
+
+    pub fn with_screener(mut self, screener: Arc<Screener>) -> Self {
+        self.screener = Some(screener);
+        self
+    }
+
+    pub fn with_risk_scorer(mut self, scorer: RiskScorer) -> Self {
+        self.risk = Some(Arc::new(scorer));
+        self
+    }
+
+    pub fn with_policy(mut self, policy: Arc<PolicyEngine>) -> Self {
+        self.policy = policy;
+        self
+    }
+
+    pub fn with_travel_rule(mut self, store: TravelRuleStore) -> Self {
+        self.travel_rule = Arc::new(store);
+        self
+    }
+
+    pub fn with_bns(mut self, resolver: BnsResolver) -> Self {
+        self.bns = Some(Arc::new(resolver));
+        self
+    }
+}
+
+impl From<SharedState> for AppState {
+    fn from(state: SharedState) -> Self {
+        Self::new(state)
+    }
+}
+
+impl FromRef<AppState> for SharedState {
+    fn from_ref(app: &AppState) -> Self {
+        app.state.clone()
+    }
+}
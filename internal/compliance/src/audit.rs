@@ -0,0 +1,512 @@
+//! Append-only audit log. Each entry's hash commits to the previous entry's,
+//! so altering, dropping or reordering any entry breaks the chain from that
+//! point on. The log is written as JSON-lines segment files that can be
+//! rotated, with the first entry of each new segment linking to the last
+//! entry of the one before.
+
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+use conxian_core::{AuditEntry, AuditEvent, AuditKind, AuditReport, ConxianError, ConxianResult};
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::{Path, PathBuf};
+use std::sync::Mutex;
+
+/// `prev_hash` of the first entry of a log.
+pub const GENESIS_HASH: &str = "0000000000000000000000000000000000000000000000000000000000000000";
+
+const SEGMENT_PREFIX: &str = "audit-";
+const SEGMENT_SUFFIX: &str = ".jsonl";
+
+/// SHA-256 over an entry's sequence number, time, previous hash and event.
+pub fn entry_hash(seq: u64, at: u64, prev_hash: &str, event: &AuditEvent) -> String {
+    let event = serde_json::to_string(event).expect("audit events serialize");
+    let mut engine = sha256::Hash::engine();
+    engine.input(&seq.to_be_bytes());
+    engine.input(&at.to_be_bytes());
+    engine.input(prev_hash.as_bytes());
+    engine.input(event.as_bytes());
+    sha256::Hash::from_engine(engine).to_string()
+}
+
+fn link(seq: u64, prev_hash: &str, event: AuditEvent, at: u64) -> AuditEntry {
+    AuditEntry {
+        seq,
+        at,
+        hash: entry_hash(seq, at, prev_hash, &event),
+        event,
+        prev_hash: prev_hash.to_string(),
+    }
+}
+
+pub fn segment_name(segment: u64) -> String {
+    format!("{}{:06}{}", SEGMENT_PREFIX, segment, SEGMENT_SUFFIX)
+}
+
+/// One segment of the log as raw JSON lines.
+struct Segment {
+    name: String,
+    lines: Vec<String>,
+}
+
+/// Where the next entry goes and what it links to.
+#[derive(Debug)]
+struct Head {
+    segment: u64,
+    segment_entries: usize,
+    next_seq: u64,
+    hash: String,
+    /// Segments of an in-memory log.
+    memory: Vec<Vec<AuditEntry>>,
+}
+
+impl Head {
+    fn genesis() -> Self {
+        Self {
+            segment: 1,
+            segment_entries: 0,
+            next_seq: 0,
+            hash: GENESIS_HASH.to_string(),
+            memory: vec![Vec::new()],
+        }
+    }
+}
+
+/// Hash-chained audit log, kept in memory or in segment files in a directory.
+#[derive(Debug)]
+pub struct AuditLog {
+    dir: Option<PathBuf>,
+    max_segment_entries: Option<usize>,
+    head: Mutex<Head>,
+}
+
+impl Default for AuditLog {
+    fn default() -> Self {
+        Self::in_memory()
+    }
+}
+
+impl AuditLog {
+    pub fn in_memory() -> Self {
+        Self {
+            dir: None,
+            max_segment_entries: None,
+            head: Mutex::new(Head::genesis()),
+        }
+    }
+
+    /// Opens the log in `dir`, continuing the chain from its newest entry.
+    pub fn open(dir: &str) -> ConxianResult<Self> {
+        let dir = PathBuf::from(dir);
+        fs::create_dir_all(&dir).map_err(|e| ConxianError::Io(e.to_string()))?;
+
+        let mut head = Head::genesis();
+        let segments = load_segments(&dir)?;
+        if let Some(newest) = segments.last() {
+            head.segment = segment_number(&newest.name).unwrap_or(1);
+            head.segment_entries = newest.lines.len();
+        }
+        // The newest segment can only be empty if a rotation was interrupted
+        if let Some(line) = segments.iter().rev().find_map(|s| s.lines.last()) {
+            let last: AuditEntry = serde_json::from_str(line).map_err(|e| {
+                ConxianError::Internal(format!("Audit log head is unreadable: {}", e))
+            })?;
+            head.next_seq = last.seq + 1;
+            head.hash = last.hash;
+        }
+
+        Ok(Self {
+            dir: Some(dir),
+            max_segment_entries: None,
+            head: Mutex::new(head),
+        })
+    }
+
+    /// Starts a new segment once the current one holds `max` entries.
+    pub fn with_max_segment_entries(mut self, max: usize) -> Self {
+        self.max_segment_entries = Some(max).filter(|&max| max > 0);
+        self
+    }
+
+    pub fn append(&self, event: AuditEvent, now: u64) -> ConxianResult<AuditEntry> {
+        let mut entries = self.append_all(vec![event], now)?;
+        Ok(entries.pop().expect("one entry appended"))
+    }
+
+    /// Appends `events` in order, writing each segment's share at once.
+    /// Returns the entries written, including any rotation entries.
+    pub fn append_all(&self, events: Vec<AuditEvent>, now: u64) -> ConxianResult<Vec<AuditEntry>> {
+        let mut head = self.head.lock().unwrap();
+        let mut written = Vec::with_capacity(events.len());
+        let mut pending: Vec<AuditEntry> = Vec::new();
+
+        for event in events {
+            let full = self
+                .max_segment_entries
+                .is_some_and(|max| head.segment_entries + pending.len() >= max);
+            if full {
+                self.commit(&mut head, &pending)?;
+                written.append(&mut pending);
+                written.push(self.start_segment(&mut head, now)?);
+            }
+            let entry = match pending.last() {
+                Some(prev) => link(prev.seq + 1, &prev.hash, event, now),
+                None => link(head.next_seq, &head.hash, event, now),
+            };
+            pending.push(entry);
+        }
+        self.commit(&mut head, &pending)?;
+        written.append(&mut pending);
+        Ok(written)
+    }
+
+    /// Closes the current segment and starts a new one, whose first entry
+    /// records the rotation and links to the last entry of the old one.
+    pub fn rotate(&self, now: u64) -> ConxianResult<AuditEntry> {
+        let mut head = self.head.lock().unwrap();
+        if head.segment_entries == 0 {
+            return Err(ConxianError::Compliance(
+                "Current audit segment is empty".to_string(),
+            ));
+        }
+        self.start_segment(&mut head, now)
+    }
+
+    /// Checks the hash chain across every segment still in the log.
+    pub fn verify(&self) -> ConxianResult<AuditReport> {
+        let head = self.head.lock().unwrap();
+        Ok(verify_segments(&self.segments(&head)?))
+    }
+
+    /// Entries with `from <= seq <= to`, in order.
+    pub fn export(&self, from: u64, to: Option<u64>) -> ConxianResult<Vec<AuditEntry>> {
+        let head = self.head.lock().unwrap();
+        export_segments(&self.segments(&head)?, from, to)
+    }
+
+    fn segments(&self, head: &Head) -> ConxianResult<Vec<Segment>> {
+        match &self.dir {
+            Some(dir) => load_segments(dir),
+            None => Ok(head
+                .memory
+                .iter()
+                .enumerate()
+                .map(|(i, entries)| Segment {
+                    name: segment_name(i as u64 + 1),
+                    lines: entries
+                        .iter()
+                        .map(|e| serde_json::to_string(e).expect("audit entries serialize"))
+                        .collect(),
+                })
+                .collect()),
+        }
+    }
+
+    fn start_segment(&self, head: &mut Head, now: u64) -> ConxianResult<AuditEntry> {
+        let previous = segment_name(head.segment);
+        head.segment += 1;
+        head.segment_entries = 0;
+        let entry = link(
+            head.next_seq,
+            &head.hash,
+            AuditEvent {
+                kind: AuditKind::Rotation,
+                action: "segment.rotate".to_string(),
+                subject: Some(segment_name(head.segment)),
+                success: true,
+                detail: Some(format!("Continues from {}", previous)),
+            },
+            now,
+        );
+        if self.dir.is_none() {
+            head.memory.push(Vec::new());
+        }
+        if let Err(e) = self.commit(head, std::slice::from_ref(&entry)) {
+            head.segment -= 1;
+            if self.dir.is_none() {
+                head.memory.pop();
+            }
+            return Err(e);
+        }
+        Ok(entry)
+    }
+
+    /// Writes `entries` to the current segment and advances the head.
+    fn commit(&self, head: &mut Head, entries: &[AuditEntry]) -> ConxianResult<()> {
+        let Some(last) = entries.last() else {
+            return Ok(());
+        };
+        match &self.dir {
+            Some(dir) => {
+                let mut lines = String::new();
+                for entry in entries {
+                    let json = serde_json::to_string(entry)
+                        .map_err(|e| ConxianError::Internal(e.to_string()))?;
+                    lines.push_str(&json);
+                    lines.push('\n');
+                }
+                let mut file = OpenOptions::new()
+                    .create(true)
+                    .append(true)
+                    .open(dir.join(segment_name(head.segment)))
+                    .map_err(|e| ConxianError::Io(e.to_string()))?;
+                file.write_all(lines.as_bytes())
+                    .and_then(|_| file.sync_data())
+                    .map_err(|e| ConxianError::Io(e.to_string()))?;
+            }
+            None => head
+                .memory
+                .last_mut()
+                .expect("in-memory log has a segment")
+                .extend_from_slice(entries),
+        }
+        head.next_seq = last.seq + 1;
+        head.hash = last.hash.clone();
+        head.segment_entries += entries.len();
+        Ok(())
+    }
+}
+
+/// Checks the log in `dir` without opening it for writing.
+pub fn verify_dir(dir: &str) -> ConxianResult<AuditReport> {
+    Ok(verify_segments(&load_segments(Path::new(dir))?))
+}
+
+/// Exports entries from the log in `dir` without opening it for writing.
+pub fn export_dir(dir: &str, from: u64, to: Option<u64>) -> ConxianResult<Vec<AuditEntry>> {
+    export_segments(&load_segments(Path::new(dir))?, from, to)
+}
+
+fn segment_number(name: &str) -> Option<u64> {
+    name.strip_prefix(SEGMENT_PREFIX)?
+        .strip_suffix(SEGMENT_SUFFIX)?
+        .parse()
+        .ok()
+}
+
+/// Segment files in `dir`, oldest first.
+fn load_segments(dir: &Path) -> ConxianResult<Vec<Segment>> {
+    if !dir.exists() {
+        return Ok(Vec::new());
+    }
+    let mut numbers: Vec<u64> = fs::read_dir(dir)
+        .map_err(|e| ConxianError::Io(e.to_string()))?
+        .filter_map(|entry| entry.ok()?.file_name().to_str().and_then(segment_number))
+        .collect();
+    numbers.sort_unstable();
+
+    numbers
+        .into_iter()
+        .map(|number| {
+            let name = segment_name(number);
+            let contents =
+                fs::read_to_string(dir.join(&name)).map_err(|e| ConxianError::Io(e.to_string()))?;
+            Ok(Segment {
+                name,
+                lines: contents
+                    .lines()
+                    .filter(|line| !line.trim().is_empty())
+                    .map(str::to_string)
+                    .collect(),
+            })
+        })
+        .collect()
+}
+
+fn verify_segments(segments: &[Segment]) -> AuditReport {
+    let mut report = AuditReport {
+        valid: true,
+        segments: segments.len(),
+        entries: 0,
+        first_seq: None,
+        last_seq: None,
+        head_hash: GENESIS_HASH.to_string(),
+        from_genesis: true,
+        error: None,
+    };
+    let mut expected: Option<(u64, String)> = None;
+
+    for segment in segments {
+        for (i, line) in segment.lines.iter().enumerate() {
+            let checked = serde_json::from_str::<AuditEntry>(line)
+                .map_err(|e| format!("unreadable entry: {}", e))
+                .and_then(|entry| check_entry(&entry, expected.as_ref()).map(|_| entry));
+            let entry = match checked {
+                Ok(entry) => entry,
+                Err(reason) => {
+                    report.valid = false;
+                    report.error = Some(format!("{} line {}: {}", segment.name, i + 1, reason));
+                    return report;
+                }
+            };
+
+            if report.first_seq.is_none() {
+                report.first_seq = Some(entry.seq);
+                report.from_genesis = entry.seq == 0 && entry.prev_hash == GENESIS_HASH;
+            }
+            report.entries += 1;
+            report.last_seq = Some(entry.seq);
+            report.head_hash = entry.hash.clone();
+            expected = Some((entry.seq + 1, entry.hash));
+        }
+    }
+    report
+}
+
+fn check_entry(entry: &AuditEntry, expected: Option<&(u64, String)>) -> Result<(), String> {
+    match expected {
+        Some((seq, _)) if entry.seq != *seq => {
+            return Err(format!("expected entry {} but found {}", seq, entry.seq));
+        }
+        Some((_, hash)) if entry.prev_hash != *hash => {
+            return Err(format!(
+                "entry {} does not link to the entry before it",
+                entry.seq
+            ));
+        }
+        None if entry.seq == 0 && entry.prev_hash != GENESIS_HASH => {
+            return Err("entry 0 does not start from the genesis hash".to_string());
+        }
+        _ => {}
+    }
+    if entry_hash(entry.seq, entry.at, &entry.prev_hash, &entry.event) != entry.hash {
+        return Err(format!("entry {} does not match its hash", entry.seq));
+    }
+    Ok(())
+}
+
+fn export_segments(
+    segments: &[Segment],
+    from: u64,
+    to: Option<u64>,
+) -> ConxianResult<Vec<AuditEntry>> {
+    let mut entries = Vec::new();
+    for segment in segments {
+        for line in &segment.lines {
+            let entry: AuditEntry = serde_json::from_str(line).map_err(|e| {
+                ConxianError::Internal(format!("Unreadable entry in {}: {}", segment.name, e))
+            })?;
+            if to.is_some_and(|to| entry.seq > to) {
+                return Ok(entries);
+            }
+            if entry.seq >= from {
+                entries.push(entry);
+            }
+        }
+    }
+    Ok(entries)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn event(action: &str) -> AuditEvent {
+        AuditEvent {
+            kind: AuditKind::Admin,
+            action: action.to_string(),
+            subject: Some("conxius-1".to_string()),
+            success: true,
+            detail: None,
+        }
+    }
+
+    fn temp_dir() -> PathBuf {
+        std::env::temp_dir().join(format!("conxian-audit-{}", rand::random::<u64>()))
+    }
+
+    #[test]
+    fn test_audit_chain_links_entries() {
+        let log = AuditLog::in_memory();
+        let first = log.append(event("device.enroll"), 100).unwrap();
+        let second = log.append(event("device.revoke"), 101).unwrap();
+        assert_eq!(first.prev_hash, GENESIS_HASH);
+        assert_eq!(second.prev_hash, first.hash);
+        assert_eq!(second.seq, 1);
+
+        let report = log.verify().unwrap();
+        assert!(report.valid);
+        assert!(report.from_genesis);
+        assert_eq!(report.entries, 2);
+        assert_eq!(report.head_hash, second.hash);
+    }
+
+    #[test]
+    fn test_audit_detects_tampering() {
+        let dir = temp_dir();
+        let log = AuditLog::open(dir.to_str().unwrap()).unwrap();
+        for action in ["device.enroll", "device.suspend", "device.revoke"] {
+            log.append(event(action), 100).unwrap();
+        }
+        let path = dir.join(segment_name(1));
+        let original = fs::read_to_string(&path).unwrap();
+
+        // Rewriting an event breaks its hash
+        fs::write(
+            &path,
+            original.replace("device.suspend", "device.reactivate"),
+        )
+        .unwrap();
+        let report = verify_dir(dir.to_str().unwrap()).unwrap();
+        assert!(!report.valid);
+        assert!(report.error.unwrap().contains("line 2"));
+
+        // Dropping an entry breaks the sequence
+        let lines: Vec<&str> = original.lines().collect();
+        fs::write(&path, format!("{}\n{}\n", lines[0], lines[2])).unwrap();
+        assert!(!verify_dir(dir.to_str().unwrap()).unwrap().valid);
+
+        fs::write(&path, &original).unwrap();
+        assert!(verify_dir(dir.to_str().unwrap()).unwrap().valid);
+        fs::remove_dir_all(dir).unwrap();
+    }
+
+    #[test]
+    fn test_audit_rotation_keeps_chain_linked() {
+        let dir = temp_dir();
+        let log = AuditLog::open(dir.to_str().unwrap())
+            .unwrap()
+            .with_max_segment_entries(3);
+        let entries = log
+            .append_all(
+                (0..5).map(|i| event(&format!("action.{}", i))).collect(),
+                100,
+            )
+            .unwrap();
+        // The fourth slot in the log is taken by the rotation entry
+        assert_eq!(entries.len(), 6);
+        assert_eq!(entries[3].event.kind, AuditKind::Rotation);
+        assert_eq!(entries[3].prev_hash, entries[2].hash);
+
+        let rotation = log.rotate(101).unwrap();
+        assert!(AuditLog::in_memory().rotate(101).is_err());
+        assert_eq!(
+            rotation.event.subject.as_deref(),
+            Some("audit-000003.jsonl")
+        );
+        drop(log);
+
+        // Reopening continues the chain in the newest segment
+        let log = AuditLog::open(dir.to_str().unwrap()).unwrap();
+        let next = log.append(event("device.enroll"), 102).unwrap();
+        assert_eq!(next.seq, 7);
+        assert_eq!(next.prev_hash, rotation.hash);
+        let report = log.verify().unwrap();
+        assert!(report.valid);
+        assert_eq!(report.segments, 3);
+        assert_eq!(report.entries, 8);
+
+        let exported = log.export(2, Some(4)).unwrap();
+        assert_eq!(
+            exported.iter().map(|e| e.seq).collect::<Vec<_>>(),
+            vec![2, 3, 4]
+        );
+
+        // Archiving old segments leaves a valid chain that no longer starts at genesis
+        fs::remove_file(dir.join(segment_name(1))).unwrap();
+        let report = log.verify().unwrap();
+        assert!(report.valid);
+        assert!(!report.from_genesis);
+        assert_eq!(report.first_seq, Some(3));
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
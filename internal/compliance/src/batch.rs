@@ -0,0 +1,97 @@
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+use secp256k1::schnorr::Signature;
+use secp256k1::{Message, Parity, PublicKey, Scalar, Secp256k1, SecretKey, XOnlyPublicKey};
+
+/// BIP-340 batch verification: checks
+/// `(s_0 + a_1 s_1 + ...) G == R_0 + e_0 P_0 + a_1 (R_1 + e_1 P_1) + ...`
+/// for random `a_i`. Returns `false` if any signature is invalid, in which
+/// case callers verify individually to find which.
+pub fn verify_schnorr_batch(
+    secp: &Secp256k1<secp256k1::All>,
+    items: &[(Signature, Message, XOnlyPublicKey)],
+) -> bool {
+    if items.is_empty() {
+        return true;
+    }
+    batch_equation(secp, items).unwrap_or(false)
+}
+
+fn batch_equation(
+    secp: &Secp256k1<secp256k1::All>,
+    items: &[(Signature, Message, XOnlyPublicKey)],
+) -> Option<bool> {
+    let mut rng = rand::thread_rng();
+    let mut s_sum: Option<SecretKey> = None;
+    let mut points = Vec::with_capacity(items.len() * 2);
+
+    for (i, (sig, msg, pubkey)) in items.iter().enumerate() {
+        let bytes = sig.as_ref();
+        let (r, s) = bytes.split_at(32);
+        let a = if i == 0 {
+            Scalar::ONE
+        } else {
+            Scalar::from(SecretKey::new(&mut rng))
+        };
+
+        // Out-of-range r, s or e, and zero sums, are left to individual checks
+        let r_point = XOnlyPublicKey::from_slice(r).ok()?.public_key(Parity::Even);
+        let e = SecretKey::from_slice(&challenge(r, pubkey, msg)).ok()?;
+        let a_s = SecretKey::from_slice(s).ok()?.mul_tweak(&a).ok()?;
+        let a_e = e.mul_tweak(&a).ok()?;
+
+        s_sum = Some(match s_sum {
+            None => a_s,
+            Some(sum) => sum.add_tweak(&Scalar::from(a_s)).ok()?,
+        });
+        points.push(r_point.mul_tweak(secp, &a).ok()?);
+        points.push(
+            pubkey
+                .public_key(Parity::Even)
+                .mul_tweak(secp, &Scalar::from(a_e))
+                .ok()?,
+        );
+    }
+
+    let lhs = PublicKey::from_secret_key(secp, &s_sum?);
+    let rhs = PublicKey::combine_keys(&points.iter().collect::<Vec<_>>()).ok()?;
+    Some(lhs == rhs)
+}
+
+/// `e = tagged_hash("BIP0340/challenge", r || P || m)`, unreduced.
+fn challenge(r: &[u8], pubkey: &XOnlyPublicKey, msg: &Message) -> [u8; 32] {
+    let tag = sha256::Hash::hash(b"BIP0340/challenge");
+    let mut engine = sha256::Hash::engine();
+    engine.input(tag.as_byte_array());
+    engine.input(tag.as_byte_array());
+    engine.input(r);
+    engine.input(&pubkey.serialize());
+    engine.input(msg.as_ref());
+    sha256::Hash::from_engine(engine).to_byte_array()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use secp256k1::Keypair;
+
+    fn signed(secp: &Secp256k1<secp256k1::All>, n: u8) -> (Signature, Message, XOnlyPublicKey) {
+        let kp = Keypair::new(secp, &mut rand::thread_rng());
+        let msg = Message::from_digest([n; 32]);
+        (secp.sign_schnorr(&msg, &kp), msg, kp.x_only_public_key().0)
+    }
+
+    #[test]
+    fn test_schnorr_batch_verification() {
+        let secp = Secp256k1::new();
+        let mut items: Vec<_> = (0..8).map(|n| signed(&secp, n)).collect();
+        assert!(verify_schnorr_batch(&secp, &items));
+        assert!(verify_schnorr_batch(&secp, &items[..1]));
+
+        // Any one bad signature fails the batch
+        items[5].1 = Message::from_digest([0xff; 32]);
+        assert!(!verify_schnorr_batch(&secp, &items));
+        let (sig, _, _) = signed(&secp, 9);
+        items[5] = (sig, items[4].1, items[4].2);
+        assert!(!verify_schnorr_batch(&secp, &items));
+    }
+}
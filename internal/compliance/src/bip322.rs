@@ -0,0 +1,185 @@
+use bitcoin::absolute::LockTime;
+use bitcoin::base64::{engine::general_purpose::STANDARD, Engine};
+use bitcoin::consensus::encode::deserialize;
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+use bitcoin::opcodes::all::OP_RETURN;
+use bitcoin::script::Builder;
+use bitcoin::transaction::Version;
+use bitcoin::{
+    Amount, OutPoint, Script, ScriptBuf, Sequence, Transaction, TxIn, TxOut, Txid, Witness,
+};
+use conxian_core::{ConxianError, ConxianResult};
+
+/// Tag of the BIP-340 style tagged hash committed to by `to_spend`.
+pub const BIP322_TAG: &[u8] = b"BIP0322-signed-message";
+
+/// A decoded BIP-322 signature.
+#[derive(Debug, Clone)]
+pub enum Bip322Signature {
+    /// Witness stack spending the `to_spend` output.
+    Simple(Witness),
+    /// Fully signed `to_sign` transaction.
+    Full(Transaction),
+}
+
+/// Tagged hash of the message placed in the `to_spend` scriptSig.
+pub fn message_hash(message: &[u8]) -> [u8; 32] {
+    let tag = sha256::Hash::hash(BIP322_TAG);
+    let mut engine = sha256::Hash::engine();
+    engine.input(tag.as_ref());
+    engine.input(tag.as_ref());
+    engine.input(message);
+    sha256::Hash::from_engine(engine).to_byte_array()
+}
+
+/// Builds the virtual `to_spend` transaction paying `script_pubkey`.
+pub fn to_spend(script_pubkey: &Script, message: &[u8]) -> Transaction {
+    let script_sig = Builder::new()
+        .push_int(0)
+        .push_slice(message_hash(message))
+        .into_script();
+    Transaction {
+        version: Version(0),
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint {
+                txid: Txid::all_zeros(),
+                vout: 0xFFFF_FFFF,
+            },
+            script_sig,
+            sequence: Sequence::ZERO,
+            witness: Witness::new(),
+        }],
+        output: vec![TxOut {
+            value: Amount::ZERO,
+            script_pubkey: script_pubkey.to_owned(),
+        }],
+    }
+}
+
+/// Builds the virtual `to_sign` transaction spending `to_spend` with `witness`.
+pub fn to_sign(to_spend: &Transaction, witness: Witness) -> Transaction {
+    Transaction {
+        version: Version(0),
+        lock_time: LockTime::ZERO,
+        input: vec![TxIn {
+            previous_output: OutPoint {
+                txid: to_spend.compute_txid(),
+                vout: 0,
+            },
+            script_sig: ScriptBuf::new(),
+            sequence: Sequence::ZERO,
+            witness,
+        }],
+        output: vec![TxOut {
+            value: Amount::ZERO,
+            script_pubkey: Builder::new().push_opcode(OP_RETURN).into_script(),
+        }],
+    }
+}
+
+/// Decodes a base64 signature in either the simple or the full format.
+pub fn decode_signature(signature: &str) -> ConxianResult<Bip322Signature> {
+    let bytes = STANDARD.decode(signature.trim()).map_err(|e| {
+        ConxianError::Compliance(format!("Invalid BIP-322 signature base64: {}", e))
+    })?;
+
+    // Both decoders require every byte to be consumed, so the formats cannot overlap
+    if let Ok(witness) = deserialize::<Witness>(&bytes) {
+        return Ok(Bip322Signature::Simple(witness));
+    }
+    deserialize::<Transaction>(&bytes)
+        .map(Bip322Signature::Full)
+        .map_err(|_| {
+            ConxianError::Compliance(
+                "BIP-322 signature is neither a witness stack nor a transaction".to_string(),
+            )
+        })
+}
+
+/// Returns the `to_sign` transaction for `signature`, checking that a full
+/// signature actually spends `to_spend` into the expected `OP_RETURN` output.
+pub fn signed_transaction(
+    to_spend: &Transaction,
+    signature: Bip322Signature,
+) -> ConxianResult<Transaction> {
+    let tx = match signature {
+        Bip322Signature::Simple(witness) => return Ok(to_sign(to_spend, witness)),
+        Bip322Signature::Full(tx) => tx,
+    };
+
+    let expected = to_sign(to_spend, Witness::new());
+    // Proof-of-funds inputs are not supported, so the only input must be to_spend
+    if tx.input.len() != 1 || tx.input[0].previous_output != expected.input[0].previous_output {
+        return Err(ConxianError::Compliance(
+            "BIP-322 transaction must spend only the to_spend output".to_string(),
+        ));
+    }
+    if tx.output != expected.output {
+        return Err(ConxianError::Compliance(
+            "BIP-322 transaction must have a single empty OP_RETURN output".to_string(),
+        ));
+    }
+    Ok(tx)
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::hex::DisplayHex;
+    use bitcoin::Address;
+
+    const ADDRESS: &str = "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l";
+
+    #[test]
+    fn test_bip322_reference_vectors() {
+        assert_eq!(
+            message_hash(b"").to_lower_hex_string(),
+            "c90c269c4f8fcbe6880f72a721ddfbf1914268a794cbb21cfafee13770ae19f1"
+        );
+        assert_eq!(
+            message_hash(b"Hello World").to_lower_hex_string(),
+            "f0eb03b1a75ac6d9847f55c624a99169b5dccba2a31f5b23bea77ba270de0a7a"
+        );
+
+        let script_pubkey = ADDRESS
+            .parse::<Address<_>>()
+            .unwrap()
+            .assume_checked()
+            .script_pubkey();
+        let cases = [
+            (
+                "",
+                "c5680aa69bb8d860bf82d4e9cd3504b55dde018de765a91bb566283c545a99a7",
+                "1e9654e951a5ba44c8604c4de6c67fd78a27e81dcadcfe1edf638ba3aaebaed6",
+            ),
+            (
+                "Hello World",
+                "b79d196740ad5217771c1098fc4a4b51e0535c32236c71f1ea4d61a2d603352b",
+                "88737ae86f2077145f93cc4b153ae9a1cb8d56afa511988c149c5c8c9d93bddf",
+            ),
+        ];
+        for (message, spend_txid, sign_txid) in cases {
+            let spend = to_spend(&script_pubkey, message.as_bytes());
+            assert_eq!(spend.compute_txid().to_string(), spend_txid);
+            assert_eq!(
+                to_sign(&spend, Witness::new()).compute_txid().to_string(),
+                sign_txid
+            );
+        }
+    }
+
+    #[test]
+    fn test_bip322_full_signature_must_spend_to_spend() {
+        let spend = to_spend(ScriptBuf::new().as_script(), b"msg");
+        let mut tx = to_sign(&spend, Witness::new());
+        assert!(signed_transaction(&spend, Bip322Signature::Full(tx.clone())).is_ok());
+
+        tx.input[0].previous_output.vout = 1;
+        assert!(signed_transaction(&spend, Bip322Signature::Full(tx.clone())).is_err());
+
+        tx.input[0].previous_output.vout = 0;
+        tx.output[0].value = Amount::from_sat(1);
+        assert!(signed_transaction(&spend, Bip322Signature::Full(tx)).is_err());
+    }
+}
@@ -0,0 +1,442 @@
+use bitcoin::base64::{engine::general_purpose::STANDARD, Engine};
+use bitcoin::hashes::{sha256, Hash};
+use conxian_core::{ConxianError, ConxianResult, HardwareAttestation};
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use x509_parser::certificate::X509Certificate;
+use x509_parser::der_parser::ber::{BerObject, Class};
+use x509_parser::der_parser::der::parse_der;
+use x509_parser::der_parser::oid;
+use x509_parser::pem::Pem;
+use x509_parser::prelude::FromDer;
+use x509_parser::time::ASN1Time;
+
+/// Android Key Attestation `KeyDescription` extension.
+const ANDROID_KEY_DESCRIPTION: x509_parser::der_parser::Oid<'static> =
+    oid!(1.3.6 .1 .4 .1 .11129 .2 .1 .17);
+/// Apple App Attest nonce extension.
+const APPLE_NONCE: x509_parser::der_parser::Oid<'static> = oid!(1.2.840 .113635 .100 .8 .2);
+/// `rootOfTrust` tag in an Android `AuthorizationList`.
+const ROOT_OF_TRUST_TAG: u32 = 704;
+
+const APPLE_AAGUID_DEVELOPMENT: &[u8; 16] = b"appattestdevelop";
+const APPLE_AAGUID_PRODUCTION: &[u8; 16] = b"appattest\0\0\0\0\0\0\0";
+
+/// Challenge a device's enclave must attest to, binding the attestation to
+/// the device it is presented for.
+pub fn attestation_challenge(device_id: &str) -> [u8; 32] {
+    sha256::Hash::hash(format!("conxian-device-attestation:{}", device_id).as_bytes())
+        .to_byte_array()
+}
+
+/// Parses a PEM bundle into DER certificates, in order.
+pub fn parse_pem_certificates(pem: &str) -> ConxianResult<Vec<Vec<u8>>> {
+    let certs = Pem::iter_from_buffer(pem.as_bytes())
+        .map(|p| {
+            p.map(|p| p.contents)
+                .map_err(|e| ConxianError::Compliance(format!("Invalid PEM certificate: {}", e)))
+        })
+        .collect::<ConxianResult<Vec<_>>>()?;
+    if certs.is_empty() {
+        return Err(ConxianError::Compliance(
+            "No certificates found in PEM bundle".to_string(),
+        ));
+    }
+    Ok(certs)
+}
+
+/// Decodes base64 DER certificates, as carried in an `x5c` array.
+pub fn decode_certificates(certs: &[String]) -> ConxianResult<Vec<Vec<u8>>> {
+    certs
+        .iter()
+        .map(|c| {
+            STANDARD
+                .decode(c.trim())
+                .map_err(|e| ConxianError::Compliance(format!("Invalid certificate base64: {}", e)))
+        })
+        .collect()
+}
+
+/// Decodes base64 App Attest authenticator data.
+pub fn decode_auth_data(data: &str) -> ConxianResult<Vec<u8>> {
+    STANDARD
+        .decode(data.trim())
+        .map_err(|e| ConxianError::Compliance(format!("Invalid auth_data base64: {}", e)))
+}
+
+/// Validates Android Key Attestation and Apple App Attest certificate chains
+/// against configured manufacturer roots.
+#[derive(Debug, Clone, Default)]
+pub struct HardwareVerifier {
+    android_roots: Vec<Vec<u8>>,
+    apple_roots: Vec<Vec<u8>>,
+    /// `TEAMID.bundle-id` whose hash App Attest authenticator data must carry.
+    apple_app_id: Option<String>,
+}
+
+impl HardwareVerifier {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    pub fn with_android_roots(mut self, roots: Vec<Vec<u8>>) -> Self {
+        self.android_roots = roots;
+        self
+    }
+
+    pub fn with_apple_roots(mut self, roots: Vec<Vec<u8>>, app_id: String) -> Self {
+        self.apple_roots = roots;
+        self.apple_app_id = Some(app_id);
+        self
+    }
+
+    /// Verifies an Android Key Attestation chain (leaf first) for `device_id`,
+    /// requiring a hardware-backed key on a locked device with verified boot.
+    pub fn verify_android(
+        &self,
+        device_id: &str,
+        chain: &[Vec<u8>],
+        now: u64,
+    ) -> ConxianResult<HardwareAttestation> {
+        let root_fingerprint = verify_chain(chain, &self.android_roots, now)?;
+        let leaf = parse_certificate(&chain[0])?;
+
+        let extension = leaf
+            .get_extension_unique(&ANDROID_KEY_DESCRIPTION)
+            .map_err(|e| invalid(format!("duplicate key description: {}", e)))?
+            .ok_or_else(|| invalid("leaf certificate has no key description"))?;
+        let description = KeyDescription::parse(extension.value)?;
+
+        if description.challenge != attestation_challenge(device_id) {
+            return Err(invalid("attestation challenge does not match the device"));
+        }
+        let security_level = match description.security_level {
+            1 => "trusted_environment",
+            2 => "strongbox",
+            _ => return Err(invalid("key is not hardware-backed")),
+        };
+        // A missing rootOfTrust proves neither, so both must be attested
+        if description.device_locked != Some(true) {
+            return Err(invalid("device bootloader is not attested as locked"));
+        }
+        if description.verified_boot != Some(true) {
+            return Err(invalid("device boot state is not attested as verified"));
+        }
+
+        Ok(HardwareAttestation {
+            platform: "android".to_string(),
+            public_key: p256_key(&leaf)?,
+            security_level: security_level.to_string(),
+            device_locked: description.device_locked,
+            verified_boot: description.verified_boot,
+            root_fingerprint,
+            attested_at: now,
+        })
+    }
+
+    /// Verifies an Apple App Attest chain (leaf first) and the authenticator
+    /// data of the attestation object for `device_id`.
+    pub fn verify_apple(
+        &self,
+        device_id: &str,
+        chain: &[Vec<u8>],
+        auth_data: &[u8],
+        now: u64,
+    ) -> ConxianResult<HardwareAttestation> {
+        let app_id = self
+            .apple_app_id
+            .as_deref()
+            .ok_or_else(|| invalid("App Attest is not configured"))?;
+        let root_fingerprint = verify_chain(chain, &self.apple_roots, now)?;
+        let leaf = parse_certificate(&chain[0])?;
+
+        // The leaf commits to sha256(authData || clientDataHash)
+        let extension = leaf
+            .get_extension_unique(&APPLE_NONCE)
+            .map_err(|e| invalid(format!("duplicate nonce extension: {}", e)))?
+            .ok_or_else(|| invalid("leaf certificate has no App Attest nonce"))?;
+        let nonce = parse_der(extension.value)
+            .ok()
+            .and_then(|(_, seq)| {
+                let seq = seq.as_sequence().ok()?;
+                explicit_inner(seq.first()?)?
+                    .as_slice()
+                    .ok()
+                    .map(<[u8]>::to_vec)
+            })
+            .ok_or_else(|| invalid("malformed App Attest nonce"))?;
+        let mut data = auth_data.to_vec();
+        data.extend_from_slice(&attestation_challenge(device_id));
+        if nonce != sha256::Hash::hash(&data).to_byte_array() {
+            return Err(invalid("App Attest nonce does not match the device"));
+        }
+
+        // rpIdHash(32) || flags(1) || counter(4) || aaguid(16) || credIdLen(2) || credId
+        if auth_data.len() < 55 {
+            return Err(invalid("authenticator data is too short"));
+        }
+        if auth_data[..32] != sha256::Hash::hash(app_id.as_bytes()).to_byte_array() {
+            return Err(invalid("authenticator data is for a different app"));
+        }
+        if auth_data[33..37] != [0, 0, 0, 0] {
+            return Err(invalid("attestation counter must be zero"));
+        }
+        let environment = match &auth_data[37..53] {
+            aaguid if aaguid == APPLE_AAGUID_PRODUCTION => "production",
+            aaguid if aaguid == APPLE_AAGUID_DEVELOPMENT => "development",
+            _ => return Err(invalid("unknown App Attest environment")),
+        };
+        let cred_len = u16::from_be_bytes([auth_data[53], auth_data[54]]) as usize;
+        let key_id = auth_data
+            .get(55..55 + cred_len)
+            .ok_or_else(|| invalid("authenticator data is too short"))?;
+        let leaf_key = leaf.public_key().subject_public_key.data.as_ref();
+        if key_id != sha256::Hash::hash(leaf_key).as_byte_array() {
+            return Err(invalid("credential id does not match the attested key"));
+        }
+
+        Ok(HardwareAttestation {
+            platform: format!("apple-{}", environment),
+            public_key: p256_key(&leaf)?,
+            security_level: "secure_enclave".to_string(),
+            device_locked: None,
+            verified_boot: None,
+            root_fingerprint,
+            attested_at: now,
+        })
+    }
+}
+
+fn invalid(reason: impl std::fmt::Display) -> ConxianError {
+    ConxianError::Compliance(format!("Invalid hardware attestation: {}", reason))
+}
+
+fn parse_certificate(der: &[u8]) -> ConxianResult<X509Certificate<'_>> {
+    X509Certificate::from_der(der)
+        .map(|(_, cert)| cert)
+        .map_err(|e| invalid(format!("malformed certificate: {}", e)))
+}
+
+/// Checks each certificate is valid at `now` and signed by the next, and that
+/// the chain ends in, or is issued by, one of `roots`. Returns the SHA-256
+/// fingerprint of the anchoring root.
+fn verify_chain(chain: &[Vec<u8>], roots: &[Vec<u8>], now: u64) -> ConxianResult<String> {
+    if roots.is_empty() {
+        return Err(invalid("no root certificates configured"));
+    }
+    let certs = chain
+        .iter()
+        .map(|der| parse_certificate(der))
+        .collect::<ConxianResult<Vec<_>>>()?;
+    let last = certs
+        .last()
+        .ok_or_else(|| invalid("empty certificate chain"))?;
+    let at = ASN1Time::from_timestamp(now as i64).map_err(invalid)?;
+
+    for (i, cert) in certs.iter().enumerate() {
+        if !cert.validity().is_valid_at(at) {
+            return Err(invalid(format!(
+                "certificate {} is outside its validity period",
+                i
+            )));
+        }
+        if let Some(issuer) = certs.get(i + 1) {
+            verify_issued_by(cert, issuer).map_err(|e| {
+                invalid(format!(
+                    "certificate {} is not issued by the next: {}",
+                    i, e
+                ))
+            })?;
+        }
+    }
+
+    // The chain may carry the root itself or stop below it
+    for root_der in roots {
+        let root = parse_certificate(root_der)?;
+        if chain.last() == Some(root_der) || verify_issued_by(last, &root).is_ok() {
+            return Ok(hex::encode(sha256::Hash::hash(root_der).as_byte_array()));
+        }
+    }
+    Err(invalid("chain is not anchored in a configured root"))
+}
+
+fn verify_issued_by(cert: &X509Certificate, issuer: &X509Certificate) -> Result<(), String> {
+    if cert.issuer() != issuer.subject() {
+        return Err("issuer name mismatch".to_string());
+    }
+    match issuer.basic_constraints() {
+        Ok(Some(bc)) if bc.value.ca => {}
+        _ => return Err("issuer is not a CA".to_string()),
+    }
+    cert.verify_signature(Some(issuer.public_key()))
+        .map_err(|e| e.to_string())
+}
+
+/// The certificate's key, which must be P-256, in compressed SEC1 hex.
+fn p256_key(cert: &X509Certificate) -> ConxianResult<String> {
+    let key = p256::PublicKey::from_sec1_bytes(&cert.public_key().subject_public_key.data)
+        .map_err(|_| invalid("attested key is not a P-256 key"))?;
+    Ok(hex::encode(key.to_encoded_point(true).as_bytes()))
+}
+
+/// Contents of an EXPLICIT context-specific tag, whether der-parser surfaced
+/// it as tagged or as unknown raw content.
+fn explicit_inner<'a>(obj: &BerObject<'a>) -> Option<BerObject<'a>> {
+    if let Ok((_, _, inner)) = obj.as_tagged() {
+        return Some(inner.clone());
+    }
+    obj.as_slice()
+        .ok()
+        .and_then(|data| parse_der(data).ok().map(|(_, o)| o))
+}
+
+/// Fields of an Android `KeyDescription` relevant to the binding.
+struct KeyDescription {
+    security_level: u64,
+    challenge: Vec<u8>,
+    device_locked: Option<bool>,
+    verified_boot: Option<bool>,
+}
+
+impl KeyDescription {
+    fn parse(der: &[u8]) -> ConxianResult<Self> {
+        let malformed = || invalid("malformed key description");
+        let (_, obj) = parse_der(der).map_err(|_| malformed())?;
+        let fields = obj.as_sequence().map_err(|_| malformed())?;
+        if fields.len() < 8 {
+            return Err(malformed());
+        }
+
+        // attestationVersion, attestationSecurityLevel, keyMintVersion,
+        // keyMintSecurityLevel, attestationChallenge, uniqueId,
+        // softwareEnforced, hardwareEnforced
+        let security_level = fields[1].as_u64().map_err(|_| malformed())?;
+        let challenge = fields[4].as_slice().map_err(|_| malformed())?.to_vec();
+        let hardware_enforced = fields[7].as_sequence().map_err(|_| malformed())?;
+
+        let root_of_trust = hardware_enforced
+            .iter()
+            .find(|o| {
+                o.header.class() == Class::ContextSpecific && o.header.tag().0 == ROOT_OF_TRUST_TAG
+            })
+            .and_then(explicit_inner);
+        let (device_locked, verified_boot) = match root_of_trust {
+            // verifiedBootKey, deviceLocked, verifiedBootState (0 = Verified), ...
+            Some(rot) => {
+                let rot = rot.as_sequence().map_err(|_| malformed())?;
+                let locked = rot.get(1).and_then(|o| o.as_bool().ok());
+                let state = rot.get(2).and_then(|o| o.as_u64().ok());
+                (locked, state.map(|s| s == 0))
+            }
+            None => (None, None),
+        };
+
+        Ok(Self {
+            security_level,
+            challenge,
+            device_locked,
+            verified_boot,
+        })
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    /// 2030-01-01, inside the fixtures' validity period.
+    pub(crate) const NOW: u64 = 1_893_456_000;
+
+    pub(crate) fn fixture(name: &str) -> String {
+        std::fs::read_to_string(format!(
+            "{}/tests/fixtures/{}",
+            env!("CARGO_MANIFEST_DIR"),
+            name
+        ))
+        .unwrap()
+    }
+
+    pub(crate) fn verifier() -> HardwareVerifier {
+        HardwareVerifier::new()
+            .with_android_roots(parse_pem_certificates(&fixture("android_root.pem")).unwrap())
+            .with_apple_roots(
+                parse_pem_certificates(&fixture("apple_root.pem")).unwrap(),
+                "TEAMID1234.com.conxian.wallet".to_string(),
+            )
+    }
+
+    #[test]
+    fn test_android_key_attestation() {
+        let verifier = verifier();
+        let chain = parse_pem_certificates(&fixture("android_chain.pem")).unwrap();
+
+        let attestation = verifier
+            .verify_android("conxius-android-1", &chain, NOW)
+            .unwrap();
+        assert_eq!(attestation.platform, "android");
+        assert_eq!(attestation.security_level, "strongbox");
+        assert_eq!(attestation.device_locked, Some(true));
+        assert_eq!(attestation.verified_boot, Some(true));
+        assert_eq!(attestation.public_key.len(), 66);
+
+        // Bound to the device the challenge was issued for
+        assert!(verifier
+            .verify_android("conxius-other", &chain, NOW)
+            .is_err());
+        // Anchored without the root in the chain, but not in another root
+        assert!(verifier
+            .verify_android("conxius-android-1", &chain[..2], NOW)
+            .is_ok());
+        let apple_only = HardwareVerifier::new()
+            .with_android_roots(parse_pem_certificates(&fixture("apple_root.pem")).unwrap());
+        assert!(apple_only
+            .verify_android("conxius-android-1", &chain, NOW)
+            .is_err());
+        // Outside the validity period, or with a broken chain
+        assert!(verifier
+            .verify_android("conxius-android-1", &chain, 1_600_000_000)
+            .is_err());
+        let broken = vec![chain[0].clone(), chain[2].clone()];
+        assert!(verifier
+            .verify_android("conxius-android-1", &broken, NOW)
+            .is_err());
+    }
+
+    #[test]
+    fn test_android_attestation_requires_root_of_trust() {
+        // A hardware-backed key whose leaf omits rootOfTrust
+        let chain = parse_pem_certificates(&fixture("android_chain_no_root_of_trust.pem")).unwrap();
+        let verifier = HardwareVerifier::new().with_android_roots(vec![chain[2].clone()]);
+        let err = verifier
+            .verify_android("conxius-android-2", &chain, NOW)
+            .unwrap_err();
+        assert!(err.to_string().contains("not attested as locked"));
+    }
+
+    #[test]
+    fn test_apple_app_attest() {
+        let verifier = verifier();
+        let chain = parse_pem_certificates(&fixture("apple_chain.pem")).unwrap();
+        let auth_data = STANDARD
+            .decode(fixture("apple_auth_data.b64").trim())
+            .unwrap();
+
+        let attestation = verifier
+            .verify_apple("conxius-ios-1", &chain, &auth_data, NOW)
+            .unwrap();
+        assert_eq!(attestation.platform, "apple-development");
+        assert_eq!(attestation.security_level, "secure_enclave");
+
+        assert!(verifier
+            .verify_apple("conxius-other", &chain, &auth_data, NOW)
+            .is_err());
+        let mut tampered = auth_data.clone();
+        tampered[36] = 1;
+        assert!(verifier
+            .verify_apple("conxius-ios-1", &chain, &tampered, NOW)
+            .is_err());
+        // Android roots do not anchor Apple chains
+        assert!(verifier
+            .verify_android("conxius-ios-1", &chain, NOW)
+            .is_err());
+    }
+}
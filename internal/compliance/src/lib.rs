@@ -1,2 +1,22 @@
+pub mod audit;
+pub mod batch;
+pub mod bip322;
+pub mod hardware;
+pub mod musig;
+pub mod policy;
+pub mod registry;
+pub mod replay;
+pub mod screening;
+pub mod sip018;
+pub mod travel_rule;
+pub mod webauthn;
 pub mod zkc;
+pub use audit::AuditLog;
+pub use hardware::HardwareVerifier;
+pub use policy::{PolicyEngine, PolicySet};
+pub use registry::DeviceRegistry;
+pub use replay::{NonceStore, ReplayGuard, ReplayPolicy};
+pub use screening::Screener;
+pub use travel_rule::TravelRuleStore;
+pub use webauthn::{PasskeyRegistry, WebAuthnPolicy};
 pub use zkc::{Attestation, ZkcVerifier};
@@ -0,0 +1,507 @@
+//! Declarative compliance policy over attestations, transactions and accounts.
+//!
+//! A policy is a JSON file of rules, each with an effect, the targets it
+//! applies to and a condition over a [`PolicyInput`]:
+//!
+//! ```json
+//! {
+//!   "default": "allow",
+//!   "tenants": [{ "name": "acme", "device_prefixes": ["conxius-acme-"] }],
+//!   "rules": [{
+//!     "id": "acme-transfer-limit",
+//!     "applies_to": ["transaction"],
+//!     "effect": "deny",
+//!     "when": { "all": [{ "tenant": ["acme"] }, { "amount_above": 1000000 }] },
+//!     "reason": "acme transfers are limited to 1 STX"
+//!   }]
+//! }
+//! ```
+//!
+//! Without a policy file the built-in policy applies, which keeps the
+//! `conxius-` device ID prefix the verifier used to require.
+
+use bitcoin::hashes::{sha256, Hash};
+use conxian_core::{
+    ConxianError, ConxianResult, MatchedRule, PolicyDecision, PolicyEffect, PolicyInput,
+    PolicyTarget, ScreeningStatus,
+};
+use serde::{Deserialize, Serialize};
+use std::collections::HashSet;
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::SystemTime;
+
+const BUILTIN_POLICY: &str = r#"{
+    "default": "allow",
+    "rules": [{
+        "id": "device-prefix",
+        "applies_to": ["attestation"],
+        "effect": "deny",
+        "when": { "all": [
+            { "scheme": ["Ecdsa", "Sip018", "Bip322", "Bip137", "P256"] },
+            { "not": { "device_prefix": ["conxius-"] } }
+        ]},
+        "reason": "device ID must start with 'conxius-'"
+    }]
+}"#;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum Weekday {
+    Mon,
+    Tue,
+    Wed,
+    Thu,
+    Fri,
+    Sat,
+    Sun,
+}
+
+impl Weekday {
+    /// Day of the week in UTC at `now`.
+    pub fn at(now: u64) -> Self {
+        // 1970-01-01 was a Thursday
+        match (now / 86_400 + 3) % 7 {
+            0 => Weekday::Mon,
+            1 => Weekday::Tue,
+            2 => Weekday::Wed,
+            3 => Weekday::Thu,
+            4 => Weekday::Fri,
+            5 => Weekday::Sat,
+            _ => Weekday::Sun,
+        }
+    }
+}
+
+/// Condition a rule matches on.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case", deny_unknown_fields)]
+pub enum Condition {
+    All(Vec<Condition>),
+    Any(Vec<Condition>),
+    Not(Box<Condition>),
+    /// The device ID starts with one of the prefixes.
+    DevicePrefix(Vec<String>),
+    /// The attestation type is one of these, ignoring case.
+    Scheme(Vec<String>),
+    Tenant(Vec<String>),
+    /// Any of the input's principals is one of these.
+    Principal(Vec<String>),
+    /// The amount is greater than this; false without an amount.
+    AmountAbove(u64),
+    Screening(Vec<ScreeningStatus>),
+    /// The UTC hour is in `[from, to)`, wrapping past midnight when `to`
+    /// is not after `from`.
+    Hours {
+        from: u8,
+        to: u8,
+    },
+    /// The UTC day of the week is one of these.
+    Weekdays(Vec<Weekday>),
+}
+
+impl Condition {
+    fn matches(&self, input: &PolicyInput, tenant: Option<&str>, now: u64) -> bool {
+        match self {
+            Condition::All(conditions) => conditions.iter().all(|c| c.matches(input, tenant, now)),
+            Condition::Any(conditions) => conditions.iter().any(|c| c.matches(input, tenant, now)),
+            Condition::Not(condition) => !condition.matches(input, tenant, now),
+            Condition::DevicePrefix(prefixes) => input
+                .device_id
+                .as_ref()
+                .is_some_and(|id| prefixes.iter().any(|p| id.starts_with(p.as_str()))),
+            Condition::Scheme(schemes) => input
+                .scheme
+                .as_ref()
+                .is_some_and(|scheme| schemes.iter().any(|s| s.eq_ignore_ascii_case(scheme))),
+            Condition::Tenant(tenants) => tenant.is_some_and(|t| tenants.iter().any(|n| n == t)),
+            Condition::Principal(principals) => input
+                .principals
+                .iter()
+                .any(|p| principals.iter().any(|listed| listed == p)),
+            Condition::AmountAbove(limit) => input.amount.is_some_and(|amount| amount > *limit),
+            Condition::Screening(statuses) => statuses.contains(&input.screening),
+            Condition::Hours { from, to } => {
+                let hour = ((now % 86_400) / 3_600) as u8;
+                if from < to {
+                    (*from..*to).contains(&hour)
+                } else {
+                    hour >= *from || hour < *to
+                }
+            }
+            Condition::Weekdays(days) => days.contains(&Weekday::at(now)),
+        }
+    }
+
+    fn validate(&self, rule: &str) -> ConxianResult<()> {
+        match self {
+            Condition::All(conditions) | Condition::Any(conditions) => {
+                conditions.iter().try_for_each(|c| c.validate(rule))
+            }
+            Condition::Not(condition) => condition.validate(rule),
+            Condition::Hours { from, to } if *from > 23 || *to > 24 || from == to => Err(
+                ConxianError::Compliance(format!("Rule {} has an invalid hour range", rule)),
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Devices and principals that belong to a tenant.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Tenant {
+    pub name: String,
+    #[serde(default)]
+    pub device_prefixes: Vec<String>,
+    #[serde(default)]
+    pub principals: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct Rule {
+    pub id: String,
+    /// Targets the rule is evaluated for; empty applies to all of them.
+    #[serde(default)]
+    pub applies_to: Vec<PolicyTarget>,
+    pub effect: PolicyEffect,
+    /// Condition under which the rule matches; unset always matches.
+    #[serde(default)]
+    pub when: Option<Condition>,
+    #[serde(default)]
+    pub reason: Option<String>,
+}
+
+/// A policy as loaded from its file.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(deny_unknown_fields)]
+pub struct PolicySet {
+    /// Effect when no rule matches.
+    pub default: PolicyEffect,
+    #[serde(default)]
+    pub tenants: Vec<Tenant>,
+    pub rules: Vec<Rule>,
+}
+
+impl Default for PolicySet {
+    fn default() -> Self {
+        Self::parse(BUILTIN_POLICY).expect("built-in policy is valid")
+    }
+}
+
+impl PolicySet {
+    pub fn parse(json: &str) -> ConxianResult<Self> {
+        let policy: Self = serde_json::from_str(json)
+            .map_err(|e| ConxianError::Compliance(format!("Invalid policy: {}", e)))?;
+        policy.validate()?;
+        Ok(policy)
+    }
+
+    fn validate(&self) -> ConxianResult<()> {
+        let mut ids = HashSet::new();
+        for rule in &self.rules {
+            if rule.id.trim().is_empty() || !ids.insert(rule.id.as_str()) {
+                return Err(ConxianError::Compliance(format!(
+                    "Policy rule IDs must be unique and non-empty: {:?}",
+                    rule.id
+                )));
+            }
+            if let Some(condition) = &rule.when {
+                condition.validate(&rule.id)?;
+            }
+        }
+        let mut names = HashSet::new();
+        if let Some(tenant) = self.tenants.iter().find(|t| !names.insert(t.name.as_str())) {
+            return Err(ConxianError::Compliance(format!(
+                "Tenant {} is defined more than once",
+                tenant.name
+            )));
+        }
+        Ok(())
+    }
+
+    /// SHA-256 hex of the policy's canonical JSON, identifying the version
+    /// a decision was made under.
+    pub fn hash(&self) -> String {
+        let json = serde_json::to_string(self).expect("policies serialize");
+        sha256::Hash::hash(json.as_bytes()).to_string()
+    }
+
+    /// The input's own tenant, or the first whose devices or principals it names.
+    fn tenant<'a>(&'a self, input: &'a PolicyInput) -> Option<&'a str> {
+        if let Some(tenant) = &input.tenant {
+            return Some(tenant);
+        }
+        self.tenants
+            .iter()
+            .find(|tenant| {
+                input.device_id.as_ref().is_some_and(|id| {
+                    tenant
+                        .device_prefixes
+                        .iter()
+                        .any(|p| id.starts_with(p.as_str()))
+                }) || input
+                    .principals
+                    .iter()
+                    .any(|p| tenant.principals.contains(p))
+            })
+            .map(|tenant| tenant.name.as_str())
+    }
+
+    pub fn evaluate(&self, input: &PolicyInput, now: u64) -> PolicyDecision {
+        let tenant = self.tenant(input);
+        let matched: Vec<MatchedRule> = self
+            .rules
+            .iter()
+            .filter(|rule| rule.applies_to.is_empty() || rule.applies_to.contains(&input.target))
+            .filter(|rule| {
+                rule.when
+                    .as_ref()
+                    .is_none_or(|c| c.matches(input, tenant, now))
+            })
+            .map(|rule| MatchedRule {
+                id: rule.id.clone(),
+                effect: rule.effect,
+                reason: rule.reason.clone(),
+            })
+            .collect();
+        let effect = if matched.iter().any(|r| r.effect == PolicyEffect::Deny) {
+            PolicyEffect::Deny
+        } else if matched.is_empty() {
+            self.default
+        } else {
+            PolicyEffect::Allow
+        };
+        PolicyDecision {
+            allowed: effect == PolicyEffect::Allow,
+            target: input.target,
+            tenant: tenant.map(str::to_string),
+            matched,
+            policy_hash: self.hash(),
+            evaluated_at: now,
+        }
+    }
+}
+
+/// Evaluates the policy loaded from a file, reloading it when it changes.
+#[derive(Debug)]
+pub struct PolicyEngine {
+    path: Option<PathBuf>,
+    policy: RwLock<(PolicySet, String)>,
+    stamp: RwLock<Option<(Option<SystemTime>, u64)>>,
+}
+
+impl Default for PolicyEngine {
+    fn default() -> Self {
+        Self::new(PolicySet::default())
+    }
+}
+
+impl PolicyEngine {
+    pub fn new(policy: PolicySet) -> Self {
+        let hash = policy.hash();
+        Self {
+            path: None,
+            policy: RwLock::new((policy, hash)),
+            stamp: RwLock::new(None),
+        }
+    }
+
+    /// Loads the policy at `path`.
+    pub fn open(path: impl AsRef<Path>) -> ConxianResult<Self> {
+        let path = path.as_ref().to_path_buf();
+        let stamp = Self::stamp(&path)?;
+        let engine = Self::new(Self::load(&path)?);
+        *engine.stamp.write().unwrap() = Some(stamp);
+        Ok(Self {
+            path: Some(path),
+            ..engine
+        })
+    }
+
+    fn stamp(path: &Path) -> ConxianResult<(Option<SystemTime>, u64)> {
+        let meta = fs::metadata(path)
+            .map_err(|e| ConxianError::Io(format!("{}: {}", path.display(), e)))?;
+        Ok((meta.modified().ok(), meta.len()))
+    }
+
+    fn load(path: &Path) -> ConxianResult<PolicySet> {
+        let json = fs::read_to_string(path)
+            .map_err(|e| ConxianError::Io(format!("{}: {}", path.display(), e)))?;
+        PolicySet::parse(&json)
+    }
+
+    /// Reloads the policy if its file was modified. On error the previous
+    /// policy stays in force and the change is not retried until the file
+    /// changes again.
+    pub fn reload_if_changed(&self) -> ConxianResult<bool> {
+        let Some(path) = &self.path else {
+            return Ok(false);
+        };
+        let stamp = Self::stamp(path)?;
+        if *self.stamp.read().unwrap() == Some(stamp) {
+            return Ok(false);
+        }
+        *self.stamp.write().unwrap() = Some(stamp);
+        let policy = Self::load(path)?;
+        let hash = policy.hash();
+        *self.policy.write().unwrap() = (policy, hash);
+        Ok(true)
+    }
+
+    pub fn policy(&self) -> PolicySet {
+        self.policy.read().unwrap().0.clone()
+    }
+
+    pub fn hash(&self) -> String {
+        self.policy.read().unwrap().1.clone()
+    }
+
+    pub fn evaluate(&self, input: &PolicyInput, now: u64) -> PolicyDecision {
+        self.policy.read().unwrap().0.evaluate(input, now)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    /// Wednesday 2025-10-15 14:00 UTC.
+    const NOW: u64 = 1_760_536_800;
+
+    fn attestation(device_id: &str, scheme: &str) -> PolicyInput {
+        PolicyInput {
+            device_id: Some(device_id.to_string()),
+            scheme: Some(scheme.to_string()),
+            ..PolicyInput::new(PolicyTarget::Attestation)
+        }
+    }
+
+    fn transfer(principal: &str, amount: u64) -> PolicyInput {
+        PolicyInput {
+            principals: vec![principal.to_string()],
+            amount: Some(amount),
+            ..PolicyInput::new(PolicyTarget::Transaction)
+        }
+    }
+
+    #[test]
+    fn test_builtin_policy_requires_device_prefix() {
+        let engine = PolicyEngine::default();
+        assert!(
+            engine
+                .evaluate(&attestation("conxius-1", "Ecdsa"), NOW)
+                .allowed
+        );
+        // Schnorr keys are bound through the device registry instead
+        assert!(
+            engine
+                .evaluate(&attestation("other-1", "Schnorr"), NOW)
+                .allowed
+        );
+
+        let decision = engine.evaluate(&attestation("other-1", "bip322"), NOW);
+        assert!(!decision.allowed);
+        assert_eq!(decision.matched[0].id, "device-prefix");
+        assert_eq!(decision.policy_hash, engine.hash());
+        let err = decision.check().unwrap_err();
+        assert!(err.to_string().contains("device-prefix"));
+        assert!(engine.evaluate(&transfer("SP1", 1), NOW).allowed);
+    }
+
+    #[test]
+    fn test_policy_rules_tenants_and_time() {
+        let policy = PolicySet::parse(
+            r#"{
+                "default": "deny",
+                "tenants": [
+                    { "name": "acme", "device_prefixes": ["conxius-acme-"], "principals": ["SPACME"] }
+                ],
+                "rules": [
+                    { "id": "acme-transfers", "applies_to": ["transaction"], "effect": "allow",
+                      "when": { "tenant": ["acme"] } },
+                    { "id": "acme-limit", "effect": "deny", "reason": "over limit",
+                      "when": { "all": [{ "tenant": ["acme"] }, { "amount_above": 1000 }] } },
+                    { "id": "screened", "effect": "deny",
+                      "when": { "not": { "screening": ["clear"] } } },
+                    { "id": "night", "effect": "deny",
+                      "when": { "hours": { "from": 22, "to": 6 } } },
+                    { "id": "weekend", "effect": "deny",
+                      "when": { "weekdays": ["sat", "sun"] } }
+                ]
+            }"#,
+        )
+        .unwrap();
+        let mut input = transfer("SPACME", 1_000);
+        input.screening = ScreeningStatus::Clear;
+
+        let decision = policy.evaluate(&input, NOW);
+        assert!(decision.allowed);
+        assert_eq!(decision.tenant.as_deref(), Some("acme"));
+        assert_eq!(decision.matched.len(), 1);
+
+        // Every matching rule is reported; any deny wins
+        input.amount = Some(1_001);
+        input.screening = ScreeningStatus::NotScreened;
+        let decision = policy.evaluate(&input, NOW);
+        assert!(!decision.allowed);
+        let ids: Vec<&str> = decision.matched.iter().map(|r| r.id.as_str()).collect();
+        assert_eq!(ids, ["acme-transfers", "acme-limit", "screened"]);
+
+        // Hour windows wrap past midnight; Saturday is three days on
+        input.amount = Some(1);
+        input.screening = ScreeningStatus::Clear;
+        assert!(!policy.evaluate(&input, NOW + 9 * 3_600).allowed);
+        assert!(policy.evaluate(&input, NOW - 8 * 3_600).allowed);
+        assert!(!policy.evaluate(&input, NOW + 3 * 86_400).allowed);
+
+        // Nothing matches for other tenants, so the default denies
+        input = transfer("SPOTHER", 1);
+        input.screening = ScreeningStatus::Clear;
+        let decision = policy.evaluate(&input, NOW);
+        assert!(!decision.allowed && decision.matched.is_empty());
+        assert!(decision
+            .check()
+            .unwrap_err()
+            .to_string()
+            .contains("default"));
+    }
+
+    #[test]
+    fn test_policy_validation() {
+        let duplicate = r#"{"default":"allow","rules":[
+            {"id":"a","effect":"deny"},{"id":"a","effect":"allow"}]}"#;
+        assert!(PolicySet::parse(duplicate).is_err());
+        let hours = r#"{"default":"allow","rules":[
+            {"id":"a","effect":"deny","when":{"hours":{"from":25,"to":3}}}]}"#;
+        assert!(PolicySet::parse(hours).is_err());
+        let unknown = r#"{"default":"allow","rules":[
+            {"id":"a","effect":"deny","when":{"color":["red"]}}]}"#;
+        assert!(PolicySet::parse(unknown).is_err());
+    }
+
+    #[test]
+    fn test_policy_engine_reloads() {
+        let path =
+            std::env::temp_dir().join(format!("conxian-policy-{}.json", rand::random::<u64>()));
+        fs::write(&path, r#"{"default":"allow","rules":[]}"#).unwrap();
+        let engine = PolicyEngine::open(&path).unwrap();
+        let input = transfer("SP1", 1);
+        assert!(engine.evaluate(&input, NOW).allowed);
+        assert!(!engine.reload_if_changed().unwrap());
+
+        let before = engine.hash();
+        fs::write(&path, r#"{"default":"deny","rules":[]}"#).unwrap();
+        assert!(engine.reload_if_changed().unwrap());
+        assert!(!engine.evaluate(&input, NOW).allowed);
+        assert_ne!(engine.hash(), before);
+
+        // A broken policy keeps the previous one in force
+        fs::write(&path, "{").unwrap();
+        assert!(engine.reload_if_changed().is_err());
+        assert!(!engine.evaluate(&input, NOW).allowed);
+        fs::remove_file(path).unwrap();
+    }
+}
@@ -0,0 +1,430 @@
+use bitcoin::key::TapTweak;
+use conxian_core::persistence::write_atomic;
+use conxian_core::ring::canonical_ring;
+use conxian_core::{
+    ConxianError, ConxianResult, DeviceChange, DeviceRecord, DeviceStatus, HardwareAttestation,
+};
+use secp256k1::{PublicKey, XOnlyPublicKey, SECP256K1};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tracing::warn;
+
+/// Enrolled Conxius devices and their bound keys, optionally persisted to a
+/// JSON file. Every change is appended to the device's history.
+#[derive(Debug, Default)]
+pub struct DeviceRegistry {
+    path: Option<PathBuf>,
+    devices: Mutex<BTreeMap<String, DeviceRecord>>,
+}
+
+impl DeviceRegistry {
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Opens the registry at `path`, loading any devices persisted there.
+    pub fn open(path: &str) -> ConxianResult<Self> {
+        let path = PathBuf::from(path);
+        let devices = if path.exists() {
+            let json = fs::read_to_string(&path).map_err(|e| ConxianError::Io(e.to_string()))?;
+            serde_json::from_str(&json).map_err(|e| ConxianError::Internal(e.to_string()))?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self {
+            path: Some(path),
+            devices: Mutex::new(devices),
+        })
+    }
+
+    pub fn get(&self, device_id: &str) -> Option<DeviceRecord> {
+        self.devices.lock().unwrap().get(device_id).cloned()
+    }
+
+    pub fn list(&self) -> Vec<DeviceRecord> {
+        self.devices.lock().unwrap().values().cloned().collect()
+    }
+
+    /// Keys of the active devices in canonical order, the ring that
+    /// membership attestations are made under.
+    pub fn ring(&self) -> Vec<PublicKey> {
+        canonical_ring(
+            self.devices
+                .lock()
+                .unwrap()
+                .values()
+                .filter(|record| record.status == DeviceStatus::Active)
+                .filter_map(|record| hex::decode(&record.public_key).ok())
+                .filter_map(|bytes| PublicKey::from_slice(&bytes).ok()),
+        )
+    }
+
+    /// Enrolls a new device bound to a compressed secp256k1 `public_key`.
+    /// Device ID formats are left to the compliance policy.
+    pub fn enroll(
+        &self,
+        device_id: &str,
+        public_key: &str,
+        now: u64,
+    ) -> ConxianResult<DeviceRecord> {
+        if device_id.is_empty() {
+            return Err(ConxianError::Compliance(
+                "Device ID cannot be empty".to_string(),
+            ));
+        }
+        let public_key = normalize_key(public_key)?;
+
+        let mut devices = self.devices.lock().unwrap();
+        if devices.contains_key(device_id) {
+            return Err(ConxianError::Compliance(format!(
+                "Device already enrolled: {}",
+                device_id
+            )));
+        }
+        let record = DeviceRecord {
+            device_id: device_id.to_string(),
+            public_key: public_key.clone(),
+            status: DeviceStatus::Active,
+            enrolled_at: now,
+            updated_at: now,
+            history: vec![DeviceChange {
+                action: "enrolled".to_string(),
+                public_key: Some(public_key),
+                reason: None,
+                at: now,
+            }],
+            hardware: None,
+        };
+        devices.insert(device_id.to_string(), record.clone());
+        self.persist(&devices);
+        Ok(record)
+    }
+
+    /// Binds a new key to an active device.
+    pub fn rotate(
+        &self,
+        device_id: &str,
+        public_key: &str,
+        reason: Option<String>,
+        now: u64,
+    ) -> ConxianResult<DeviceRecord> {
+        let public_key = normalize_key(public_key)?;
+        self.update(device_id, "rotated", reason, now, |record| {
+            if record.status != DeviceStatus::Active {
+                return Err("only active devices can rotate keys");
+            }
+            if record.public_key == public_key {
+                return Err("new key must differ from the current key");
+            }
+            record.public_key = public_key.clone();
+            Ok(Some(public_key.clone()))
+        })
+    }
+
+    pub fn suspend(
+        &self,
+        device_id: &str,
+        reason: Option<String>,
+        now: u64,
+    ) -> ConxianResult<DeviceRecord> {
+        self.transition(
+            device_id,
+            DeviceStatus::Active,
+            DeviceStatus::Suspended,
+            "suspended",
+            reason,
+            now,
+        )
+    }
+
+    pub fn reactivate(
+        &self,
+        device_id: &str,
+        reason: Option<String>,
+        now: u64,
+    ) -> ConxianResult<DeviceRecord> {
+        self.transition(
+            device_id,
+            DeviceStatus::Suspended,
+            DeviceStatus::Active,
+            "reactivated",
+            reason,
+            now,
+        )
+    }
+
+    pub fn revoke(
+        &self,
+        device_id: &str,
+        reason: Option<String>,
+        now: u64,
+    ) -> ConxianResult<DeviceRecord> {
+        self.update(device_id, "revoked", reason, now, |record| {
+            if record.status == DeviceStatus::Revoked {
+                return Err("device is already revoked");
+            }
+            record.status = DeviceStatus::Revoked;
+            Ok(None)
+        })
+    }
+
+    /// Binds a manufacturer-attested enclave key to an active device, replacing
+    /// any earlier attestation.
+    pub fn bind_hardware(
+        &self,
+        device_id: &str,
+        attestation: HardwareAttestation,
+        now: u64,
+    ) -> ConxianResult<DeviceRecord> {
+        let reason = Some(format!(
+            "{} {}",
+            attestation.platform, attestation.security_level
+        ));
+        self.update(device_id, "attested", reason, now, |record| {
+            if record.status != DeviceStatus::Active {
+                return Err("only active devices can be attested");
+            }
+            let public_key = attestation.public_key.clone();
+            record.hardware = Some(attestation);
+            Ok(Some(public_key))
+        })
+    }
+
+    /// The attested enclave key of an active device.
+    pub fn hardware_key(&self, device_id: &str) -> ConxianResult<HardwareAttestation> {
+        self.active(device_id)?.hardware.ok_or_else(|| {
+            ConxianError::Security(format!("Device {} has no hardware-attested key", device_id))
+        })
+    }
+
+    /// The key bound to an active device.
+    pub fn public_key(&self, device_id: &str) -> ConxianResult<PublicKey> {
+        let record = self.active(device_id)?;
+        hex::decode(&record.public_key)
+            .ok()
+            .and_then(|bytes| PublicKey::from_slice(&bytes).ok())
+            .ok_or_else(|| {
+                ConxianError::Internal(format!("Invalid key bound to device {}", device_id))
+            })
+    }
+
+    /// Checks that `device_id` is active and bound to the compressed `public_key`.
+    pub fn check_key(&self, device_id: &str, public_key: &PublicKey) -> ConxianResult<()> {
+        let record = self.active(device_id)?;
+        if record.public_key != hex::encode(public_key.serialize()) {
+            return Err(ConxianError::Security(format!(
+                "Public key is not bound to device {}",
+                device_id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks that `device_id` is active and its key's x-only form is `public_key`.
+    pub fn check_x_only_key(
+        &self,
+        device_id: &str,
+        public_key: &XOnlyPublicKey,
+    ) -> ConxianResult<()> {
+        let record = self.active(device_id)?;
+        if record.public_key[2..] != hex::encode(public_key.serialize()) {
+            return Err(ConxianError::Security(format!(
+                "Public key is not bound to device {}",
+                device_id
+            )));
+        }
+        Ok(())
+    }
+
+    /// Checks that `device_id` is active and `output_key` is the BIP-86
+    /// taproot output key of its key, tweaked with no script tree.
+    pub fn check_taproot_output_key(
+        &self,
+        device_id: &str,
+        output_key: &XOnlyPublicKey,
+    ) -> ConxianResult<()> {
+        let (internal_key, _) = self.public_key(device_id)?.x_only_public_key();
+        let (tweaked, _) = internal_key.tap_tweak(SECP256K1, None);
+        if tweaked.to_x_only_public_key() != *output_key {
+            return Err(ConxianError::Security(format!(
+                "Taproot output key is not bound to device {}",
+                device_id
+            )));
+        }
+        Ok(())
+    }
+
+    fn active(&self, device_id: &str) -> ConxianResult<DeviceRecord> {
+        let record = self.get(device_id).ok_or_else(|| {
+            ConxianError::Security(format!("Device is not enrolled: {}", device_id))
+        })?;
+        match record.status {
+            DeviceStatus::Active => Ok(record),
+            status => Err(ConxianError::Security(format!(
+                "Device {} is {:?}",
+                device_id, status
+            ))),
+        }
+    }
+
+    fn transition(
+        &self,
+        device_id: &str,
+        from: DeviceStatus,
+        to: DeviceStatus,
+        action: &str,
+        reason: Option<String>,
+        now: u64,
+    ) -> ConxianResult<DeviceRecord> {
+        self.update(device_id, action, reason, now, |record| {
+            if record.status != from {
+                return Err("invalid status transition");
+            }
+            record.status = to;
+            Ok(None)
+        })
+    }
+
+    /// Applies `change` to a device and records it in the history. `change`
+    /// returns the key bound by the change, if any.
+    fn update(
+        &self,
+        device_id: &str,
+        action: &str,
+        reason: Option<String>,
+        now: u64,
+        change: impl FnOnce(&mut DeviceRecord) -> Result<Option<String>, &'static str>,
+    ) -> ConxianResult<DeviceRecord> {
+        let mut devices = self.devices.lock().unwrap();
+        let record = devices.get_mut(device_id).ok_or_else(|| {
+            ConxianError::Compliance(format!("Device is not enrolled: {}", device_id))
+        })?;
+
+        let mut updated = record.clone();
+        let public_key = change(&mut updated).map_err(|e| {
+            ConxianError::Compliance(format!("Device {} not {}: {}", device_id, action, e))
+        })?;
+        updated.updated_at = now;
+        updated.history.push(DeviceChange {
+            action: action.to_string(),
+            public_key,
+            reason,
+            at: now,
+        });
+        *record = updated.clone();
+        self.persist(&devices);
+        Ok(updated)
+    }
+
+    fn persist(&self, devices: &BTreeMap<String, DeviceRecord>) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        // The in-memory registry stays authoritative if the write fails
+        let res = serde_json::to_string_pretty(devices)
+            .map_err(|e| ConxianError::Internal(e.to_string()))
+            .and_then(|json| write_atomic(path, &json));
+        if let Err(e) = res {
+            warn!("Failed to persist device registry: {}", e);
+        }
+    }
+}
+
+/// Parses a hex public key, returning it in compressed form.
+fn normalize_key(public_key: &str) -> ConxianResult<String> {
+    let bytes = hex::decode(public_key)
+        .map_err(|e| ConxianError::Compliance(format!("Invalid public key hex: {}", e)))?;
+    let key = PublicKey::from_slice(&bytes)
+        .map_err(|e| ConxianError::Compliance(format!("Invalid public key: {}", e)))?;
+    Ok(hex::encode(key.serialize()))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use rand::thread_rng;
+    use secp256k1::Secp256k1;
+
+    fn key() -> PublicKey {
+        Secp256k1::new().generate_keypair(&mut thread_rng()).1
+    }
+
+    #[test]
+    fn test_registry_lifecycle_and_history() {
+        let registry = DeviceRegistry::in_memory();
+        let (k1, k2) = (key(), key());
+
+        registry
+            .enroll("conxius-1", &hex::encode(k1.serialize()), 10)
+            .unwrap();
+        assert!(registry.check_key("conxius-1", &k1).is_ok());
+        assert!(registry
+            .check_x_only_key("conxius-1", &k1.x_only_public_key().0)
+            .is_ok());
+        assert!(registry.check_key("conxius-1", &k2).is_err());
+        assert!(registry
+            .enroll("conxius-1", &hex::encode(k2.serialize()), 11)
+            .is_err());
+
+        registry
+            .rotate("conxius-1", &hex::encode(k2.serialize()), None, 20)
+            .unwrap();
+        assert!(registry.check_key("conxius-1", &k1).is_err());
+        assert!(registry.check_key("conxius-1", &k2).is_ok());
+
+        registry
+            .suspend("conxius-1", Some("lost".to_string()), 30)
+            .unwrap();
+        assert!(registry.check_key("conxius-1", &k2).is_err());
+        assert!(registry
+            .rotate("conxius-1", &hex::encode(k1.serialize()), None, 31)
+            .is_err());
+        registry.reactivate("conxius-1", None, 40).unwrap();
+        assert!(registry.check_key("conxius-1", &k2).is_ok());
+
+        let record = registry.revoke("conxius-1", None, 50).unwrap();
+        assert_eq!(record.status, DeviceStatus::Revoked);
+        assert!(registry.reactivate("conxius-1", None, 60).is_err());
+        assert!(registry.check_key("conxius-1", &k2).is_err());
+
+        let actions: Vec<_> = record.history.iter().map(|c| c.action.as_str()).collect();
+        assert_eq!(
+            actions,
+            ["enrolled", "rotated", "suspended", "reactivated", "revoked"]
+        );
+        assert_eq!(record.updated_at, 50);
+
+        // Any non-empty ID is accepted; the policy decides which may attest
+        assert!(registry
+            .enroll("acme-7", &hex::encode(k1.serialize()), 70)
+            .is_ok());
+        assert!(registry
+            .enroll("", &hex::encode(k1.serialize()), 70)
+            .is_err());
+    }
+
+    #[test]
+    fn test_registry_normalizes_and_persists_keys() {
+        let path =
+            std::env::temp_dir().join(format!("conxian-devices-{}.json", rand::random::<u64>()));
+        let path = path.to_str().unwrap();
+        let k = key();
+
+        let registry = DeviceRegistry::open(path).unwrap();
+        // Uncompressed keys are stored in compressed form
+        registry
+            .enroll("conxius-1", &hex::encode(k.serialize_uncompressed()), 1)
+            .unwrap();
+        registry
+            .enroll("other-1", &hex::encode(k.serialize()), 1)
+            .unwrap();
+        drop(registry);
+
+        let reopened = DeviceRegistry::open(path).unwrap();
+        assert!(reopened.check_key("conxius-1", &k).is_ok());
+        assert!(reopened.check_key("other-1", &k).is_ok());
+        let _ = fs::remove_file(path);
+    }
+}
@@ -0,0 +1,417 @@
+use conxian_core::persistence::write_atomic;
+use conxian_core::{AttestationClaims, ConxianError, ConxianResult};
+use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
+use std::fs::{self, OpenOptions};
+use std::io::Write;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use tracing::warn;
+
+/// Rules applied to attestation payloads once their signature is valid.
+#[derive(Debug, Clone)]
+pub struct ReplayPolicy {
+    /// Required `audience` claim.
+    pub audience: String,
+    /// Tolerated clock difference between device and gateway, in seconds.
+    pub max_clock_skew: u64,
+    /// Longest accepted `expires_at - issued_at`, which bounds the nonce store.
+    pub max_validity: u64,
+    /// Accept unstructured payloads without replay protection.
+    pub allow_legacy_payloads: bool,
+}
+
+impl Default for ReplayPolicy {
+    fn default() -> Self {
+        Self {
+            audience: "conxian-gateway".to_string(),
+            max_clock_skew: 60,
+            max_validity: 600,
+            allow_legacy_payloads: false,
+        }
+    }
+}
+
+/// Log records appended since the last compaction before the log is
+/// rewritten, at least; larger stores wait until the log is twice their size.
+const MIN_COMPACTION_RECORDS: usize = 1024;
+
+/// One used nonce, as a line of the nonce log.
+#[derive(Debug, Serialize, Deserialize)]
+struct NonceRecord {
+    key: String,
+    forget_after: u64,
+}
+
+#[derive(Debug, Default)]
+struct Nonces {
+    /// `device_id:nonce` to the time after which it may be forgotten.
+    seen: BTreeMap<String, u64>,
+    /// Records in the log file, including those since forgotten.
+    logged: usize,
+}
+
+/// Nonces seen within their validity window, optionally persisted to an
+/// append-only JSON-lines log that is compacted as it grows.
+#[derive(Debug, Default)]
+pub struct NonceStore {
+    path: Option<PathBuf>,
+    nonces: Mutex<Nonces>,
+}
+
+impl NonceStore {
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Opens the store at `path`, loading any nonces persisted there. A store
+    /// written as a single JSON object is rewritten as a log.
+    pub fn open(path: &str) -> ConxianResult<Self> {
+        let path = PathBuf::from(path);
+        let mut nonces = Nonces::default();
+        let mut legacy = false;
+        if path.exists() {
+            let contents =
+                fs::read_to_string(&path).map_err(|e| ConxianError::Io(e.to_string()))?;
+            if let Ok(seen) = serde_json::from_str::<BTreeMap<String, u64>>(&contents) {
+                nonces.seen = seen;
+                legacy = true;
+            } else {
+                let lines: Vec<&str> = contents.lines().collect();
+                for (i, line) in lines.iter().enumerate() {
+                    match serde_json::from_str::<NonceRecord>(line) {
+                        Ok(record) => {
+                            nonces.seen.insert(record.key, record.forget_after);
+                            nonces.logged += 1;
+                        }
+                        // A crash can leave the last record partly written
+                        Err(e) if i + 1 == lines.len() => {
+                            warn!("Ignoring truncated attestation nonce record: {}", e)
+                        }
+                        Err(e) => return Err(ConxianError::Internal(e.to_string())),
+                    }
+                }
+            }
+        }
+
+        let store = Self {
+            path: Some(path),
+            nonces: Mutex::new(nonces),
+        };
+        if legacy {
+            store.compact(&mut store.nonces.lock().unwrap())?;
+        }
+        Ok(store)
+    }
+
+    /// Records `key` until `forget_after`, failing if it is already recorded
+    /// or cannot be persisted.
+    pub fn consume(&self, key: &str, forget_after: u64, now: u64) -> ConxianResult<()> {
+        let mut nonces = self.nonces.lock().unwrap();
+        nonces.seen.retain(|_, until| *until >= now);
+        if nonces.seen.contains_key(key) {
+            return Err(ConxianError::Security(format!(
+                "Attestation nonce already used: {}",
+                key
+            )));
+        }
+
+        self.append(&NonceRecord {
+            key: key.to_string(),
+            forget_after,
+        })?;
+        nonces.seen.insert(key.to_string(), forget_after);
+        nonces.logged += 1;
+
+        if nonces.logged > MIN_COMPACTION_RECORDS.max(2 * nonces.seen.len()) {
+            // The log still holds every live nonce if compaction fails
+            if let Err(e) = self.compact(&mut nonces) {
+                warn!("Failed to compact attestation nonces: {}", e);
+            }
+        }
+        Ok(())
+    }
+
+    pub fn len(&self) -> usize {
+        self.nonces.lock().unwrap().seen.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.len() == 0
+    }
+
+    fn append(&self, record: &NonceRecord) -> ConxianResult<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut line =
+            serde_json::to_string(record).map_err(|e| ConxianError::Internal(e.to_string()))?;
+        line.push('\n');
+        let mut file = OpenOptions::new()
+            .create(true)
+            .append(true)
+            .open(path)
+            .map_err(|e| ConxianError::Io(e.to_string()))?;
+        file.write_all(line.as_bytes())
+            .and_then(|_| file.sync_data())
+            .map_err(|e| ConxianError::Io(e.to_string()))
+    }
+
+    /// Rewrites the log with only the nonces still recorded.
+    fn compact(&self, nonces: &mut Nonces) -> ConxianResult<()> {
+        let Some(path) = &self.path else {
+            return Ok(());
+        };
+        let mut lines = String::new();
+        for (key, forget_after) in &nonces.seen {
+            let record = NonceRecord {
+                key: key.clone(),
+                forget_after: *forget_after,
+            };
+            lines.push_str(
+                &serde_json::to_string(&record)
+                    .map_err(|e| ConxianError::Internal(e.to_string()))?,
+            );
+            lines.push('\n');
+        }
+        write_atomic(path, &lines)?;
+        nonces.logged = nonces.seen.len();
+        Ok(())
+    }
+}
+
+/// Enforces freshness, audience and single use of attestation payloads.
+#[derive(Debug, Clone, Default)]
+pub struct ReplayGuard {
+    policy: ReplayPolicy,
+    nonces: Arc<NonceStore>,
+}
+
+impl ReplayGuard {
+    pub fn new(policy: ReplayPolicy, nonces: NonceStore) -> Self {
+        Self {
+            policy,
+            nonces: Arc::new(nonces),
+        }
+    }
+
+    pub fn policy(&self) -> &ReplayPolicy {
+        &self.policy
+    }
+
+    /// Checks a signed `payload` from `device_id` at time `now`, consuming its
+    /// nonce. Returns `None` for a legacy payload accepted by policy.
+    pub fn check(
+        &self,
+        device_id: &str,
+        payload: &str,
+        now: u64,
+    ) -> ConxianResult<Option<AttestationClaims>> {
+        let claims: AttestationClaims = match serde_json::from_str(payload) {
+            Ok(claims) => claims,
+            Err(_) if self.policy.allow_legacy_payloads => return Ok(None),
+            Err(e) => {
+                return Err(ConxianError::Compliance(format!(
+                    "Attestation payload is not a structured claim set ({}); raw payloads require legacy mode",
+                    e
+                )))
+            }
+        };
+
+        if claims.nonce.is_empty() {
+            return Err(ConxianError::Compliance(
+                "Attestation nonce cannot be empty".to_string(),
+            ));
+        }
+        if claims.audience != self.policy.audience {
+            return Err(ConxianError::Compliance(format!(
+                "Attestation audience {} does not match {}",
+                claims.audience, self.policy.audience
+            )));
+        }
+        if claims.expires_at <= claims.issued_at
+            || claims.expires_at - claims.issued_at > self.policy.max_validity
+        {
+            return Err(ConxianError::Compliance(format!(
+                "Attestation validity window must be between 1 and {} seconds",
+                self.policy.max_validity
+            )));
+        }
+        if claims.issued_at > now + self.policy.max_clock_skew {
+            return Err(ConxianError::Compliance(
+                "Attestation is not yet valid".to_string(),
+            ));
+        }
+        let forget_after = claims.expires_at + self.policy.max_clock_skew;
+        if now > forget_after {
+            return Err(ConxianError::Compliance(
+                "Attestation has expired".to_string(),
+            ));
+        }
+
+        self.nonces.consume(
+            &format!("{}:{}", device_id, claims.nonce),
+            forget_after,
+            now,
+        )?;
+        Ok(Some(claims))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn payload(nonce: &str, issued_at: u64, expires_at: u64) -> String {
+        serde_json::to_string(&AttestationClaims {
+            nonce: nonce.to_string(),
+            issued_at,
+            expires_at,
+            audience: "conxian-gateway".to_string(),
+        })
+        .unwrap()
+    }
+
+    #[test]
+    fn test_replay_guard_rejects_reused_nonce() {
+        let guard = ReplayGuard::default();
+        let p = payload("n1", 1_000, 1_300);
+
+        assert!(guard.check("conxius-1", &p, 1_100).unwrap().is_some());
+        assert!(guard.check("conxius-1", &p, 1_101).is_err());
+        // Nonces are scoped per device
+        assert!(guard.check("conxius-2", &p, 1_101).is_ok());
+        assert_eq!(guard.nonces.len(), 2);
+
+        // Expired nonces are pruned once no longer needed
+        assert!(guard
+            .check("conxius-1", &payload("n2", 5_000, 5_100), 5_000)
+            .is_ok());
+        assert_eq!(guard.nonces.len(), 1);
+    }
+
+    #[test]
+    fn test_replay_guard_validity_window() {
+        let guard = ReplayGuard::default();
+
+        // Not yet valid, beyond the clock skew
+        assert!(guard
+            .check("conxius-1", &payload("a", 2_000, 2_100), 1_900)
+            .is_err());
+        // Expired, beyond the clock skew
+        assert!(guard
+            .check("conxius-1", &payload("b", 1_000, 1_100), 1_200)
+            .is_err());
+        // Windows longer than the policy allows
+        assert!(guard
+            .check("conxius-1", &payload("c", 1_000, 9_000), 1_000)
+            .is_err());
+        // Within the clock skew either side
+        assert!(guard
+            .check("conxius-1", &payload("d", 1_030, 1_100), 1_000)
+            .is_ok());
+        assert!(guard
+            .check("conxius-1", &payload("e", 1_000, 1_100), 1_150)
+            .is_ok());
+
+        let mut wrong_audience: AttestationClaims =
+            serde_json::from_str(&payload("f", 1_000, 1_100)).unwrap();
+        wrong_audience.audience = "other-gateway".to_string();
+        let wrong_audience = serde_json::to_string(&wrong_audience).unwrap();
+        assert!(guard.check("conxius-1", &wrong_audience, 1_000).is_err());
+    }
+
+    #[test]
+    fn test_replay_guard_legacy_mode() {
+        let strict = ReplayGuard::default();
+        assert!(strict.check("conxius-1", "raw-payload", 0).is_err());
+
+        let legacy = ReplayGuard::new(
+            ReplayPolicy {
+                allow_legacy_payloads: true,
+                ..ReplayPolicy::default()
+            },
+            NonceStore::in_memory(),
+        );
+        assert!(legacy
+            .check("conxius-1", "raw-payload", 0)
+            .unwrap()
+            .is_none());
+        // Structured payloads are still checked in legacy mode
+        let p = payload("n", 1_000, 1_100);
+        assert!(legacy.check("conxius-1", &p, 1_000).is_ok());
+        assert!(legacy.check("conxius-1", &p, 1_000).is_err());
+    }
+
+    #[test]
+    fn test_nonce_store_persists_across_restarts() {
+        let path =
+            std::env::temp_dir().join(format!("conxian-nonces-{}.json", rand::random::<u64>()));
+        let path = path.to_str().unwrap();
+
+        let store = NonceStore::open(path).unwrap();
+        store.consume("conxius-1:n", 2_000, 1_000).unwrap();
+        drop(store);
+
+        let reopened = NonceStore::open(path).unwrap();
+        assert!(reopened.consume("conxius-1:n", 2_000, 1_500).is_err());
+        assert!(reopened.consume("conxius-1:n", 3_000, 2_500).is_ok());
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_nonce_log_is_compacted() {
+        let path =
+            std::env::temp_dir().join(format!("conxian-nonces-{}.log", rand::random::<u64>()));
+        let path = path.to_str().unwrap();
+
+        let store = NonceStore::open(path).unwrap();
+        let records = MIN_COMPACTION_RECORDS as u64 + 10;
+        for i in 0..records {
+            // Each nonce is forgotten soon after the next is consumed
+            store
+                .consume(&format!("conxius-1:{}", i), i + 1, i)
+                .unwrap();
+        }
+        let lines = fs::read_to_string(path).unwrap().lines().count();
+        assert!(lines < MIN_COMPACTION_RECORDS, "{} records logged", lines);
+        drop(store);
+
+        let reopened = NonceStore::open(path).unwrap();
+        let last = format!("conxius-1:{}", records - 1);
+        assert!(reopened.consume(&last, records, records - 1).is_err());
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_nonce_store_upgrades_json_object() {
+        let path =
+            std::env::temp_dir().join(format!("conxian-nonces-{}.json", rand::random::<u64>()));
+        fs::write(&path, r#"{"conxius-1:n":2000}"#).unwrap();
+        let path = path.to_str().unwrap();
+
+        let store = NonceStore::open(path).unwrap();
+        assert!(store.consume("conxius-1:n", 2_000, 1_000).is_err());
+        store.consume("conxius-1:m", 2_000, 1_000).unwrap();
+        assert_eq!(fs::read_to_string(path).unwrap().lines().count(), 2);
+        // A record cut short by a crash is dropped
+        fs::write(
+            path,
+            fs::read_to_string(path).unwrap() + r#"{"key":"conxius-1:o","forg"#,
+        )
+        .unwrap();
+        assert_eq!(NonceStore::open(path).unwrap().len(), 2);
+        let _ = fs::remove_file(path);
+    }
+
+    #[test]
+    fn test_nonce_store_reports_persist_failure() {
+        let dir = std::env::temp_dir().join(format!("conxian-missing-{}", rand::random::<u64>()));
+        let path = dir.join("nonces.log");
+        let store = NonceStore::open(path.to_str().unwrap()).unwrap();
+
+        let err = store.consume("conxius-1:n", 2_000, 1_000).unwrap_err();
+        assert!(matches!(err, ConxianError::Io(_)));
+        assert!(store.is_empty());
+    }
+}
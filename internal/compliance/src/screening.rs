@@ -0,0 +1,526 @@
+//! Screening of addresses, principals and keys against local denylists.
+//!
+//! Lists are CSV (`value[,reason]` per line) or JSON (an array of strings or
+//! of `{"value", "reason"}` objects) files, named after their file stem.
+//! Entries and subjects are normalised to an exact key (a Bitcoin address's
+//! script, a Stacks principal, a public key) and derived keys (the key or
+//! script hash behind it), so that one key is matched across P2PKH, P2WPKH,
+//! Stacks and raw key encodings, and a contract is matched by its deployer.
+
+use bitcoin::address::NetworkUnchecked;
+use bitcoin::hashes::{hash160, Hash};
+use bitcoin::secp256k1::{PublicKey, XOnlyPublicKey, SECP256K1};
+use bitcoin::{Address, Script, ScriptBuf};
+use conxian_core::c32::{MAINNET_MULTISIG, TESTNET_MULTISIG};
+use conxian_core::clarity::PrincipalData;
+use conxian_core::{ConxianError, ConxianResult, DenylistSummary, ScreeningHit, ScreeningMatch};
+use serde::Deserialize;
+use std::collections::{HashMap, HashSet};
+use std::fs;
+use std::path::{Path, PathBuf};
+use std::sync::RwLock;
+use std::time::SystemTime;
+
+/// Modification time and length of each list file, to detect changes.
+type Stamps = Vec<(PathBuf, Option<SystemTime>, u64)>;
+
+#[derive(Debug, Clone, PartialEq, Eq)]
+struct Normalized {
+    exact: String,
+    /// Other encodings of the same key or script hash.
+    derived: Vec<String>,
+    /// Keys of whoever controls the subject: a contract's deployer.
+    parents: Vec<String>,
+}
+
+fn normalize(value: &str) -> Normalized {
+    let value = value.trim();
+    if let Ok(address) = value.parse::<Address<NetworkUnchecked>>() {
+        return normalize_script(&address.assume_checked().script_pubkey());
+    }
+    if let Ok(principal) = value.parse::<PrincipalData>() {
+        let address = principal.address();
+        let kind = match address.version {
+            MAINNET_MULTISIG | TESTNET_MULTISIG => "sh",
+            _ => "pkh",
+        };
+        let hash_key = format!("{}:{}", kind, hex::encode(address.hash160));
+        return match principal {
+            PrincipalData::Standard(_) => Normalized {
+                exact: format!("stx:{}", address),
+                derived: vec![hash_key],
+                parents: Vec::new(),
+            },
+            PrincipalData::Contract(..) => Normalized {
+                exact: format!("stx:{}", principal),
+                derived: Vec::new(),
+                parents: vec![format!("stx:{}", address), hash_key],
+            },
+        };
+    }
+    if let Ok(bytes) = hex::decode(value.trim_start_matches("0x")) {
+        if let Ok(key) = PublicKey::from_slice(&bytes) {
+            return key_keys(&key);
+        }
+        if let Ok(key) = XOnlyPublicKey::from_slice(&bytes) {
+            return Normalized {
+                exact: format!("xonly:{}", key),
+                derived: vec![taproot_key(key)],
+                parents: Vec::new(),
+            };
+        }
+    }
+    Normalized {
+        exact: format!("text:{}", value.to_lowercase()),
+        derived: Vec::new(),
+        parents: Vec::new(),
+    }
+}
+
+fn normalize_script(script: &Script) -> Normalized {
+    Normalized {
+        exact: format!("script:{}", script.to_hex_string()),
+        derived: script_keys(script),
+        parents: Vec::new(),
+    }
+}
+
+/// The key or script hash an output script pays to.
+fn script_keys(script: &Script) -> Vec<String> {
+    let bytes = script.as_bytes();
+    let key = if script.is_p2pkh() {
+        format!("pkh:{}", hex::encode(&bytes[3..23]))
+    } else if script.is_p2wpkh() {
+        format!("pkh:{}", hex::encode(&bytes[2..22]))
+    } else if script.is_p2sh() {
+        format!("sh:{}", hex::encode(&bytes[2..22]))
+    } else if script.is_p2wsh() {
+        format!("wsh:{}", hex::encode(&bytes[2..34]))
+    } else if script.is_p2tr() {
+        format!("tr:{}", hex::encode(&bytes[2..34]))
+    } else {
+        return Vec::new();
+    };
+    vec![key]
+}
+
+/// Every standard single-key output a public key can be paid through.
+fn key_keys(key: &PublicKey) -> Normalized {
+    let compressed = hash160::Hash::hash(&key.serialize());
+    let uncompressed = hash160::Hash::hash(&key.serialize_uncompressed());
+    let wpkh = [&[0x00, 0x14][..], compressed.as_byte_array()].concat();
+    let (x_only, _) = key.x_only_public_key();
+    Normalized {
+        exact: format!("key:{}", key),
+        derived: vec![
+            format!("pkh:{}", compressed),
+            format!("pkh:{}", uncompressed),
+            format!("sh:{}", hash160::Hash::hash(&wpkh)),
+            format!("xonly:{}", x_only),
+            taproot_key(x_only),
+        ],
+        parents: Vec::new(),
+    }
+}
+
+/// BIP-86 key-path-only taproot output of `key`.
+fn taproot_key(key: XOnlyPublicKey) -> String {
+    let script = ScriptBuf::new_p2tr(SECP256K1, key, None);
+    format!("tr:{}", hex::encode(&script.as_bytes()[2..34]))
+}
+
+#[derive(Debug)]
+struct Entry {
+    list: usize,
+    value: String,
+    reason: Option<String>,
+}
+
+#[derive(Debug, Default)]
+struct Index {
+    lists: Vec<DenylistSummary>,
+    entries: Vec<Entry>,
+    exact: HashMap<String, Vec<usize>>,
+    derived: HashMap<String, Vec<usize>>,
+}
+
+impl Index {
+    fn insert(&mut self, list: usize, value: String, reason: Option<String>) {
+        let key = normalize(&value);
+        let id = self.entries.len();
+        self.exact.entry(key.exact).or_default().push(id);
+        for derived in key.derived {
+            self.derived.entry(derived).or_default().push(id);
+        }
+        self.entries.push(Entry {
+            list,
+            value,
+            reason,
+        });
+    }
+
+    fn screen(&self, subject: &str, key: Normalized) -> Vec<ScreeningHit> {
+        let mut seen = HashSet::new();
+        let mut hits = Vec::new();
+        let mut hit = |ids: Option<&Vec<usize>>, matched: ScreeningMatch, key: &str| {
+            for &id in ids.into_iter().flatten() {
+                if !seen.insert(id) {
+                    continue;
+                }
+                let entry = &self.entries[id];
+                hits.push(ScreeningHit {
+                    subject: subject.to_string(),
+                    entry: entry.value.clone(),
+                    list: self.lists[entry.list].name.clone(),
+                    matched,
+                    key: key.to_string(),
+                    reason: entry.reason.clone(),
+                });
+            }
+        };
+
+        hit(
+            self.exact.get(&key.exact),
+            ScreeningMatch::Exact,
+            &key.exact,
+        );
+        for derived in key.derived.iter().chain(&key.parents) {
+            hit(self.exact.get(derived), ScreeningMatch::Derived, derived);
+            hit(self.derived.get(derived), ScreeningMatch::Derived, derived);
+        }
+        hit(
+            self.derived.get(&key.exact),
+            ScreeningMatch::Derived,
+            &key.exact,
+        );
+        hits
+    }
+}
+
+#[derive(Deserialize)]
+#[serde(untagged)]
+enum JsonEntry {
+    Value(String),
+    Entry {
+        #[serde(alias = "address", alias = "principal")]
+        value: String,
+        #[serde(default)]
+        reason: Option<String>,
+    },
+}
+
+fn parse_list(path: &Path) -> ConxianResult<Vec<(String, Option<String>)>> {
+    let content = fs::read_to_string(path)
+        .map_err(|e| ConxianError::Io(format!("{}: {}", path.display(), e)))?;
+    if path.extension().is_some_and(|ext| ext == "json") {
+        let entries: Vec<JsonEntry> = serde_json::from_str(&content).map_err(|e| {
+            ConxianError::Compliance(format!("Invalid denylist {}: {}", path.display(), e))
+        })?;
+        return Ok(entries
+            .into_iter()
+            .map(|entry| match entry {
+                JsonEntry::Value(value) => (value, None),
+                JsonEntry::Entry { value, reason } => (value, reason),
+            })
+            .collect());
+    }
+
+    let unquote = |field: &str| field.trim().trim_matches('"').trim().to_string();
+    let mut entries = Vec::new();
+    for line in content.lines() {
+        let line = line.trim();
+        if line.is_empty() || line.starts_with('#') {
+            continue;
+        }
+        let (value, reason) = match line.split_once(',') {
+            Some((value, reason)) => (unquote(value), Some(unquote(reason))),
+            None => (unquote(line), None),
+        };
+        let header = ["value", "address", "principal", "entry"];
+        if entries.is_empty() && header.contains(&value.to_lowercase().as_str()) {
+            continue;
+        }
+        if !value.is_empty() {
+            entries.push((value, reason.filter(|r| !r.is_empty())));
+        }
+    }
+    Ok(entries)
+}
+
+/// Screens subjects against denylists loaded from files, reloading them when
+/// they change.
+#[derive(Debug, Default)]
+pub struct Screener {
+    sources: Vec<PathBuf>,
+    index: RwLock<Index>,
+    stamps: RwLock<Stamps>,
+}
+
+impl Screener {
+    /// A screener with no lists, which clears every subject.
+    pub fn empty() -> Self {
+        Self::default()
+    }
+
+    /// Loads the lists at `paths`; a directory contributes each `.csv` and
+    /// `.json` file in it.
+    pub fn open(paths: &[impl AsRef<Path>]) -> ConxianResult<Self> {
+        let screener = Self {
+            sources: paths.iter().map(|p| p.as_ref().to_path_buf()).collect(),
+            ..Self::default()
+        };
+        let stamps = screener.stamps()?;
+        *screener.index.write().unwrap() = screener.load(&stamps)?;
+        *screener.stamps.write().unwrap() = stamps;
+        Ok(screener)
+    }
+
+    fn files(&self) -> ConxianResult<Vec<PathBuf>> {
+        let io =
+            |path: &Path, e: std::io::Error| ConxianError::Io(format!("{}: {}", path.display(), e));
+        let mut files = Vec::new();
+        for source in &self.sources {
+            if !source.is_dir() {
+                files.push(source.clone());
+                continue;
+            }
+            let mut listed: Vec<PathBuf> = fs::read_dir(source)
+                .map_err(|e| io(source, e))?
+                .filter_map(|entry| entry.ok().map(|e| e.path()))
+                .filter(|path| {
+                    path.extension()
+                        .is_some_and(|ext| ext == "csv" || ext == "json")
+                })
+                .collect();
+            listed.sort();
+            files.extend(listed);
+        }
+        Ok(files)
+    }
+
+    fn stamps(&self) -> ConxianResult<Stamps> {
+        self.files()?
+            .into_iter()
+            .map(|path| {
+                let meta = fs::metadata(&path)
+                    .map_err(|e| ConxianError::Io(format!("{}: {}", path.display(), e)))?;
+                Ok((path, meta.modified().ok(), meta.len()))
+            })
+            .collect()
+    }
+
+    fn load(&self, stamps: &Stamps) -> ConxianResult<Index> {
+        let mut index = Index::default();
+        for (path, _, _) in stamps {
+            let entries = parse_list(path)?;
+            let list = index.lists.len();
+            index.lists.push(DenylistSummary {
+                name: path
+                    .file_stem()
+                    .map(|s| s.to_string_lossy().into_owned())
+                    .unwrap_or_default(),
+                path: path.display().to_string(),
+                entries: entries.len(),
+            });
+            for (value, reason) in entries {
+                index.insert(list, value, reason);
+            }
+        }
+        Ok(index)
+    }
+
+    /// Reloads the lists if a file was added, removed or modified. On error
+    /// the previous lists stay in force and the change is not retried until
+    /// the files change again.
+    pub fn reload_if_changed(&self) -> ConxianResult<bool> {
+        let stamps = self.stamps()?;
+        if *self.stamps.read().unwrap() == stamps {
+            return Ok(false);
+        }
+        let loaded = self.load(&stamps);
+        *self.stamps.write().unwrap() = stamps;
+        *self.index.write().unwrap() = loaded?;
+        Ok(true)
+    }
+
+    pub fn lists(&self) -> Vec<DenylistSummary> {
+        self.index.read().unwrap().lists.clone()
+    }
+
+    /// Every entry `subject` matches.
+    pub fn screen(&self, subject: &str) -> Vec<ScreeningHit> {
+        self.index
+            .read()
+            .unwrap()
+            .screen(subject, normalize(subject))
+    }
+
+    /// Every entry an output script matches, reported against `subject`,
+    /// the script's address where it has one.
+    pub fn screen_script(&self, subject: &str, script: &Script) -> Vec<ScreeningHit> {
+        self.index
+            .read()
+            .unwrap()
+            .screen(subject, normalize_script(script))
+    }
+
+    /// Screens each subject, returning a `Compliance` error naming the first hit.
+    pub fn check<S: AsRef<str>>(&self, subjects: impl IntoIterator<Item = S>) -> ConxianResult<()> {
+        let index = self.index.read().unwrap();
+        for subject in subjects {
+            let subject = subject.as_ref();
+            if let Some(hit) = index.screen(subject, normalize(subject)).into_iter().next() {
+                return Err(ConxianError::Compliance(format!(
+                    "{} is denylisted: {} match of {} on {}",
+                    hit.subject,
+                    match hit.matched {
+                        ScreeningMatch::Exact => "exact",
+                        ScreeningMatch::Derived => "derived",
+                    },
+                    hit.entry,
+                    hit.list
+                )));
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use bitcoin::{CompressedPublicKey, Network};
+    use conxian_core::c32::{StacksAddress, MAINNET_SINGLESIG};
+
+    fn key() -> PublicKey {
+        let secret = bitcoin::secp256k1::SecretKey::from_slice(&[0x21; 32]).unwrap();
+        PublicKey::from_secret_key(SECP256K1, &secret)
+    }
+
+    fn screen(index: &Index, subject: &str) -> Vec<ScreeningHit> {
+        index.screen(subject, normalize(subject))
+    }
+
+    fn temp_dir() -> PathBuf {
+        let dir = std::env::temp_dir().join(format!("conxian-lists-{}", rand::random::<u64>()));
+        fs::create_dir_all(&dir).unwrap();
+        dir
+    }
+
+    #[test]
+    fn test_screening_matches_across_encodings() {
+        let key = key();
+        let compressed = CompressedPublicKey(key);
+        let wpkh = Address::p2wpkh(&compressed, Network::Bitcoin);
+        let pkh = Address::p2pkh(compressed, Network::Bitcoin);
+        let testnet = Address::p2wpkh(&compressed, Network::Testnet);
+        let stacks =
+            StacksAddress::new(MAINNET_SINGLESIG, compressed.pubkey_hash().to_byte_array())
+                .unwrap();
+
+        let mut index = Index::default();
+        index.lists.push(DenylistSummary {
+            name: "ofac".to_string(),
+            path: "ofac.csv".to_string(),
+            entries: 2,
+        });
+        index.insert(0, wpkh.to_string(), Some("SDN".to_string()));
+        index.insert(0, "SP000000000000000000002Q6VF78".to_string(), None);
+
+        let exact = screen(&index, &wpkh.to_string().to_uppercase());
+        assert_eq!(exact.len(), 1);
+        assert_eq!(exact[0].matched, ScreeningMatch::Exact);
+        assert_eq!(exact[0].reason.as_deref(), Some("SDN"));
+        // The same script on another network is the same output
+        assert_eq!(
+            screen(&index, &testnet.to_string())[0].matched,
+            ScreeningMatch::Exact
+        );
+
+        for subject in [pkh.to_string(), stacks.to_string(), key.to_string()] {
+            let hits = screen(&index, &subject);
+            assert_eq!(hits.len(), 1, "{}", subject);
+            assert_eq!(hits[0].matched, ScreeningMatch::Derived, "{}", subject);
+            assert_eq!(hits[0].entry, wpkh.to_string());
+        }
+
+        // A listed key also covers its x-only form and taproot output
+        index.insert(0, key.to_string(), None);
+        let x_only = key.x_only_public_key().0;
+        assert_eq!(
+            screen(&index, &x_only.to_string())[0].entry,
+            key.to_string()
+        );
+        let taproot = Address::p2tr(SECP256K1, x_only, None, Network::Bitcoin);
+        assert_eq!(
+            screen(&index, &taproot.to_string())[0].entry,
+            key.to_string()
+        );
+
+        // Contracts are screened by their deployer, not the other way round
+        let contract = screen(&index, "SP000000000000000000002Q6VF78.pox-4");
+        assert_eq!(contract[0].matched, ScreeningMatch::Derived);
+        index.insert(
+            0,
+            "SP3FBR2AGK5H9QBDH3EEN6DF8EK8JY7RX8QJ5SVTE.bad".to_string(),
+            None,
+        );
+        assert!(screen(&index, "SP3FBR2AGK5H9QBDH3EEN6DF8EK8JY7RX8QJ5SVTE").is_empty());
+
+        let other = Address::p2wpkh(
+            &CompressedPublicKey::from_slice(
+                &hex::decode("0279be667ef9dcbbac55a06295ce870b07029bfcdb2dce28d959f2815b16f81798")
+                    .unwrap(),
+            )
+            .unwrap(),
+            Network::Bitcoin,
+        );
+        assert!(screen(&index, &other.to_string()).is_empty());
+        assert!(screen(&index, "not an address").is_empty());
+    }
+
+    #[test]
+    fn test_screener_loads_and_reloads_lists() {
+        let dir = temp_dir();
+        let key = key();
+        fs::write(
+            dir.join("ofac.csv"),
+            format!("address,reason\n# comment\n{},\"SDN list\"\n", key),
+        )
+        .unwrap();
+        fs::write(
+            dir.join("internal.json"),
+            r#"["SP000000000000000000002Q6VF78", {"principal": "Blocked Name", "reason": "fraud"}]"#,
+        )
+        .unwrap();
+        fs::write(dir.join("notes.txt"), "ignored").unwrap();
+
+        let screener = Screener::open(&[&dir]).unwrap();
+        let lists = screener.lists();
+        assert_eq!(lists.len(), 2);
+        assert_eq!(lists[0].name, "internal");
+        assert_eq!(lists[0].entries, 2);
+        assert_eq!(lists[1].entries, 1);
+        assert_eq!(screener.screen(&key.to_string())[0].list, "ofac");
+        assert_eq!(
+            screener.screen("blocked name")[0].reason.as_deref(),
+            Some("fraud")
+        );
+        assert!(screener.check(["SP000000000000000000002Q6VF78"]).is_err());
+        assert!(!screener.reload_if_changed().unwrap());
+
+        // Edits take effect on reload; a broken file keeps the previous lists
+        fs::write(dir.join("internal.json"), "[]").unwrap();
+        assert!(screener.reload_if_changed().unwrap());
+        assert!(screener.check(["SP000000000000000000002Q6VF78"]).is_ok());
+        fs::write(dir.join("ofac.csv"), "").unwrap();
+        fs::write(dir.join("internal.json"), "{").unwrap();
+        assert!(screener.reload_if_changed().is_err());
+        assert!(!screener.screen(&key.to_string()).is_empty());
+        assert!(!screener.reload_if_changed().unwrap());
+
+        assert!(Screener::open(&[dir.join("missing.csv")]).is_err());
+        assert!(Screener::empty().check([key.to_string()]).is_ok());
+        fs::remove_dir_all(dir).unwrap();
+    }
+}
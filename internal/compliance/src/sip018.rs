@@ -0,0 +1,186 @@
+use bitcoin::hashes::{sha256, Hash};
+use bitcoin::hex::DisplayHex;
+use conxian_core::clarity::ClarityValue;
+use conxian_core::{AttestationClaims, ConxianError, ConxianResult};
+use std::collections::BTreeMap;
+
+/// Prefix of every SIP-018 message hash: ASCII "SIP018".
+pub const SIP018_PREFIX: [u8; 6] = *b"SIP018";
+
+/// Builds a SIP-018 domain tuple.
+pub fn domain(name: &str, version: &str, chain_id: u32) -> ClarityValue {
+    ClarityValue::Tuple(BTreeMap::from([
+        (
+            "name".to_string(),
+            ClarityValue::StringAscii(name.to_string()),
+        ),
+        (
+            "version".to_string(),
+            ClarityValue::StringAscii(version.to_string()),
+        ),
+        ("chain-id".to_string(), ClarityValue::UInt(chain_id as u128)),
+    ]))
+}
+
+/// Returns the chain id of a well-formed domain tuple.
+pub fn domain_chain_id(domain: &ClarityValue) -> ConxianResult<u32> {
+    let invalid =
+        |reason: &str| ConxianError::Compliance(format!("Invalid SIP-018 domain: {}", reason));
+
+    let ClarityValue::Tuple(fields) = domain else {
+        return Err(invalid("must be a tuple"));
+    };
+    if fields.len() != 3 {
+        return Err(invalid("must contain exactly name, version and chain-id"));
+    }
+    for key in ["name", "version"] {
+        if !matches!(fields.get(key), Some(ClarityValue::StringAscii(_))) {
+            return Err(invalid(&format!("{} must be a string-ascii", key)));
+        }
+    }
+    match fields.get("chain-id") {
+        Some(ClarityValue::UInt(id)) => {
+            u32::try_from(*id).map_err(|_| invalid("chain-id out of range"))
+        }
+        _ => Err(invalid("chain-id must be a uint")),
+    }
+}
+
+/// `sha256` of the consensus serialization of a Clarity value.
+pub fn structured_data_hash(value: &ClarityValue) -> ConxianResult<[u8; 32]> {
+    Ok(sha256::Hash::hash(&value.serialize_to_vec()?).to_byte_array())
+}
+
+/// The digest signed for `message` under `domain`:
+/// `sha256(prefix || hash(domain) || hash(message))`.
+pub fn message_hash(domain: &ClarityValue, message: &ClarityValue) -> ConxianResult<[u8; 32]> {
+    domain_chain_id(domain)?;
+    let mut data = SIP018_PREFIX.to_vec();
+    data.extend_from_slice(&structured_data_hash(domain)?);
+    data.extend_from_slice(&structured_data_hash(message)?);
+    Ok(sha256::Hash::hash(&data).to_byte_array())
+}
+
+/// Builds the message tuple carrying attestation claims:
+/// `{nonce, issued-at, expires-at, audience}`.
+pub fn claims_message(claims: &AttestationClaims) -> ClarityValue {
+    ClarityValue::Tuple(BTreeMap::from([
+        (
+            "nonce".to_string(),
+            ClarityValue::StringAscii(claims.nonce.clone()),
+        ),
+        (
+            "issued-at".to_string(),
+            ClarityValue::UInt(claims.issued_at as u128),
+        ),
+        (
+            "expires-at".to_string(),
+            ClarityValue::UInt(claims.expires_at as u128),
+        ),
+        (
+            "audience".to_string(),
+            ClarityValue::StringAscii(claims.audience.clone()),
+        ),
+    ]))
+}
+
+fn tuple_claims(fields: &BTreeMap<String, ClarityValue>) -> Option<AttestationClaims> {
+    let text = |key: &str| match fields.get(key) {
+        Some(ClarityValue::StringAscii(s)) | Some(ClarityValue::StringUtf8(s)) => Some(s.clone()),
+        _ => None,
+    };
+    let time = |key: &str| match fields.get(key) {
+        Some(ClarityValue::UInt(v)) => u64::try_from(*v).ok(),
+        _ => None,
+    };
+    if fields.len() != 4 {
+        return None;
+    }
+    Some(AttestationClaims {
+        nonce: text("nonce")?,
+        issued_at: time("issued-at")?,
+        expires_at: time("expires-at")?,
+        audience: text("audience")?,
+    })
+}
+
+/// The payload a signed `message` is checked as by the replay guard: the
+/// claims of a [`claims_message`] tuple as JSON, the text of a string, or
+/// the hex serialization of any other value, which only legacy mode accepts.
+pub fn claims_payload(message: &ClarityValue) -> ConxianResult<String> {
+    match message {
+        ClarityValue::StringAscii(s) | ClarityValue::StringUtf8(s) => Ok(s.clone()),
+        ClarityValue::Tuple(fields) => match tuple_claims(fields) {
+            Some(claims) => {
+                serde_json::to_string(&claims).map_err(|e| ConxianError::Internal(e.to_string()))
+            }
+            None => Ok(message.serialize_to_vec()?.to_lower_hex_string()),
+        },
+        _ => Ok(message.serialize_to_vec()?.to_lower_hex_string()),
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_sip018_reference_vector() {
+        let domain = domain("Test App", "1.0.0", 1);
+        let message = ClarityValue::StringAscii("Hello World".to_string());
+
+        assert_eq!(
+            structured_data_hash(&message)
+                .unwrap()
+                .to_lower_hex_string(),
+            "5297eef9765c466d945ad1cb2c81b30b9fed6c165575dc9226e9edf78b8cd9e8"
+        );
+        assert_eq!(
+            structured_data_hash(&domain).unwrap().to_lower_hex_string(),
+            "2538b5dc06c5ae2f11549261d7ae174d9f77a55a92b00f330884695497be5065"
+        );
+        assert_eq!(
+            message_hash(&domain, &message)
+                .unwrap()
+                .to_lower_hex_string(),
+            "1bfdab6d4158313ce34073fbb8d6b0fc32c154d439def12247a0f44bb2225259"
+        );
+    }
+
+    #[test]
+    fn test_sip018_rejects_malformed_domain() {
+        let message = ClarityValue::UInt(1);
+        assert!(message_hash(&ClarityValue::UInt(1), &message).is_err());
+
+        let ClarityValue::Tuple(mut fields) = domain("App", "1", 1) else {
+            unreachable!()
+        };
+        fields.insert("chain-id".to_string(), ClarityValue::Int(1));
+        assert!(message_hash(&ClarityValue::Tuple(fields), &message).is_err());
+    }
+
+    #[test]
+    fn test_claims_payload() {
+        let claims = AttestationClaims {
+            nonce: "n-1".to_string(),
+            issued_at: 1_700_000_000,
+            expires_at: 1_700_000_300,
+            audience: "conxian-gateway".to_string(),
+        };
+        let payload = claims_payload(&claims_message(&claims)).unwrap();
+        assert_eq!(
+            serde_json::from_str::<AttestationClaims>(&payload).unwrap(),
+            claims
+        );
+
+        let raw = ClarityValue::StringAscii("attest".to_string());
+        assert_eq!(claims_payload(&raw).unwrap(), "attest");
+        let other = ClarityValue::Tuple(BTreeMap::from([(
+            "nonce".to_string(),
+            ClarityValue::UInt(1),
+        )]));
+        assert!(
+            serde_json::from_str::<AttestationClaims>(&claims_payload(&other).unwrap()).is_err()
+        );
+    }
+}
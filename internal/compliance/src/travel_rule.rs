@@ -0,0 +1,268 @@
+use conxian_core::ivms101::{self, IdentityPayload};
+use conxian_core::persistence::write_atomic;
+use conxian_core::travel_rule::{seal, verify_acknowledgement};
+use conxian_core::{
+    ConxianError, ConxianResult, TravelRuleAck, TravelRuleRecord, TravelRuleStatus,
+};
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tracing::warn;
+
+/// Travel Rule payloads attached to transactions and the counterparties'
+/// acknowledgements, optionally persisted to a JSON file. Payloads are kept
+/// only in their sealed form.
+#[derive(Debug, Default)]
+pub struct TravelRuleStore {
+    path: Option<PathBuf>,
+    /// Transfer amount from which a payload is required; unset requires none.
+    threshold: Option<u64>,
+    records: Mutex<BTreeMap<String, TravelRuleRecord>>,
+}
+
+impl TravelRuleStore {
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Opens the store at `path`, loading any records persisted there.
+    pub fn open(path: &str) -> ConxianResult<Self> {
+        let path = PathBuf::from(path);
+        let records = if path.exists() {
+            let json = fs::read_to_string(&path).map_err(|e| ConxianError::Io(e.to_string()))?;
+            serde_json::from_str(&json).map_err(|e| ConxianError::Internal(e.to_string()))?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self {
+            path: Some(path),
+            threshold: None,
+            records: Mutex::new(records),
+        })
+    }
+
+    pub fn with_threshold(mut self, threshold: u64) -> Self {
+        self.threshold = Some(threshold);
+        self
+    }
+
+    /// Whether a transfer of `amount` needs a Travel Rule payload.
+    pub fn is_required(&self, amount: u64) -> bool {
+        self.threshold.is_some_and(|threshold| amount >= threshold)
+    }
+
+    pub fn get(&self, txid: &str) -> Option<TravelRuleRecord> {
+        self.records.lock().unwrap().get(txid).cloned()
+    }
+
+    pub fn list(&self) -> Vec<TravelRuleRecord> {
+        self.records.lock().unwrap().values().cloned().collect()
+    }
+
+    /// Validates `payload` and seals it to `counterparty_key` for `txid`,
+    /// replacing a payload that has not been accepted.
+    pub fn attach(
+        &self,
+        txid: &str,
+        counterparty_key: &str,
+        payload: &IdentityPayload,
+        now: u64,
+    ) -> ConxianResult<TravelRuleRecord> {
+        payload.validate(&ivms101::date(now))?;
+        let (sealed, payload_hash) = seal(payload, counterparty_key)?;
+
+        let mut records = self.records.lock().unwrap();
+        if records
+            .get(txid)
+            .is_some_and(|r| r.status == TravelRuleStatus::Acknowledged)
+        {
+            return Err(ConxianError::Compliance(format!(
+                "Travel Rule payload for {} was already acknowledged",
+                txid
+            )));
+        }
+        let record = TravelRuleRecord {
+            txid: txid.to_string(),
+            counterparty_key: counterparty_key.trim().to_lowercase(),
+            payload_hash,
+            sealed,
+            status: TravelRuleStatus::Sent,
+            attached_at: now,
+            acknowledgement: None,
+        };
+        records.insert(txid.to_string(), record.clone());
+        self.persist(&records);
+        Ok(record)
+    }
+
+    /// Records the counterparty's signed acceptance or rejection of the
+    /// payload sent for `txid`.
+    pub fn acknowledge(
+        &self,
+        txid: &str,
+        accepted: bool,
+        reason: Option<String>,
+        signature: &str,
+        now: u64,
+    ) -> ConxianResult<TravelRuleRecord> {
+        let mut records = self.records.lock().unwrap();
+        let record = records.get_mut(txid).ok_or_else(|| {
+            ConxianError::Compliance(format!("No Travel Rule payload for {}", txid))
+        })?;
+        if record.status != TravelRuleStatus::Sent {
+            return Err(ConxianError::Compliance(format!(
+                "Travel Rule payload for {} is not awaiting acknowledgement",
+                txid
+            )));
+        }
+        verify_acknowledgement(
+            &record.counterparty_key,
+            txid,
+            &record.payload_hash,
+            accepted,
+            signature,
+        )?;
+
+        record.status = if accepted {
+            TravelRuleStatus::Acknowledged
+        } else {
+            TravelRuleStatus::Rejected
+        };
+        record.acknowledgement = Some(TravelRuleAck {
+            accepted,
+            reason,
+            signature: signature.trim().to_lowercase(),
+            received_at: now,
+        });
+        let record = record.clone();
+        self.persist(&records);
+        Ok(record)
+    }
+
+    fn persist(&self, records: &BTreeMap<String, TravelRuleRecord>) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        // The in-memory store stays authoritative if the write fails
+        let res = serde_json::to_string_pretty(records)
+            .map_err(|e| ConxianError::Internal(e.to_string()))
+            .and_then(|json| write_atomic(path, &json));
+        if let Err(e) = res {
+            warn!("Failed to persist Travel Rule records: {}", e);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use conxian_core::travel_rule::{open, sign_acknowledgement};
+    use secp256k1::{PublicKey, SecretKey, SECP256K1};
+
+    const SECRET: &str = "0707070707070707070707070707070707070707070707070707070707070707";
+    const NOW: u64 = 1_760_000_000;
+
+    fn counterparty_key() -> String {
+        let secret = SecretKey::from_slice(&[7; 32]).unwrap();
+        PublicKey::from_secret_key(SECP256K1, &secret).to_string()
+    }
+
+    fn payload() -> IdentityPayload {
+        serde_json::from_value(serde_json::json!({
+            "originator": {
+                "originatorPersons": [{
+                    "legalPerson": {
+                        "name": { "nameIdentifier": [{
+                            "legalPersonName": "Conxian Treasury Ltd",
+                            "legalPersonNameIdentifierType": "LEGL"
+                        }]},
+                        "customerIdentification": "CX-0001"
+                    }
+                }]
+            },
+            "beneficiary": {
+                "beneficiaryPersons": [{
+                    "naturalPerson": {
+                        "name": { "nameIdentifier": [{
+                            "primaryIdentifier": "Finney",
+                            "secondaryIdentifier": "Hal",
+                            "nameIdentifierType": "LEGL"
+                        }]}
+                    }
+                }]
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_travel_rule_lifecycle() {
+        let path =
+            std::env::temp_dir().join(format!("conxian-travel-{}.json", rand::random::<u64>()));
+        let store = TravelRuleStore::open(path.to_str().unwrap())
+            .unwrap()
+            .with_threshold(1_000);
+        assert!(store.is_required(1_000));
+        assert!(!store.is_required(999));
+        assert!(!TravelRuleStore::in_memory().is_required(u64::MAX));
+
+        let record = store
+            .attach("ab", &counterparty_key(), &payload(), NOW)
+            .unwrap();
+        assert_eq!(record.status, TravelRuleStatus::Sent);
+        let (opened, hash) = open(&record.sealed, SECRET).unwrap();
+        assert_eq!(opened, payload());
+        assert_eq!(hash, record.payload_hash);
+
+        // Only the counterparty key can answer, and only for this payload
+        let other = sign_acknowledgement(&"08".repeat(32), "ab", &hash, true).unwrap();
+        assert!(store.acknowledge("ab", true, None, &other, NOW).is_err());
+        let rejected = sign_acknowledgement(SECRET, "ab", &hash, false).unwrap();
+        assert!(store.acknowledge("ab", true, None, &rejected, NOW).is_err());
+        let record = store
+            .acknowledge(
+                "ab",
+                false,
+                Some("name mismatch".to_string()),
+                &rejected,
+                NOW,
+            )
+            .unwrap();
+        assert_eq!(record.status, TravelRuleStatus::Rejected);
+
+        // A corrected payload replaces a rejected one; an accepted one is final
+        let record = store
+            .attach("ab", &counterparty_key(), &payload(), NOW + 1)
+            .unwrap();
+        let accepted = sign_acknowledgement(SECRET, "ab", &record.payload_hash, true).unwrap();
+        store
+            .acknowledge("ab", true, None, &accepted, NOW + 2)
+            .unwrap();
+        let err = store
+            .attach("ab", &counterparty_key(), &payload(), NOW + 3)
+            .unwrap_err();
+        assert!(err.to_string().contains("already acknowledged"));
+        assert!(store.acknowledge("ab", true, None, &accepted, NOW).is_err());
+
+        let reopened = TravelRuleStore::open(path.to_str().unwrap()).unwrap();
+        assert_eq!(
+            reopened.get("ab").unwrap().status,
+            TravelRuleStatus::Acknowledged
+        );
+        fs::remove_file(path).unwrap();
+    }
+
+    #[test]
+    fn test_travel_rule_rejects_invalid_payloads() {
+        let store = TravelRuleStore::in_memory();
+        let mut invalid = payload();
+        invalid.beneficiary.beneficiary_persons.clear();
+        let err = store
+            .attach("ab", &counterparty_key(), &invalid, NOW)
+            .unwrap_err();
+        assert!(matches!(err, ConxianError::Compliance(_)));
+        assert!(store.attach("ab", "02ff", &payload(), NOW).is_err());
+        assert!(store.get("ab").is_none());
+    }
+}
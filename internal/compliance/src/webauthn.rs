@@ -0,0 +1,462 @@
+use bitcoin::base64::{engine::general_purpose::URL_SAFE_NO_PAD, Engine};
+use bitcoin::hashes::{sha256, Hash};
+use conxian_core::persistence::write_atomic;
+use conxian_core::{
+    ConxianError, ConxianResult, PasskeyAlgorithm, PasskeyCredential, WebAuthnAssertion,
+};
+use p256::elliptic_curve::sec1::ToEncodedPoint;
+use serde::Deserialize;
+use std::collections::BTreeMap;
+use std::fs;
+use std::path::PathBuf;
+use std::sync::Mutex;
+use tracing::warn;
+
+/// Authenticator data flag: user present.
+const FLAG_UP: u8 = 0x01;
+/// Authenticator data flag: user verified.
+const FLAG_UV: u8 = 0x04;
+
+/// Relying party settings assertions are checked against.
+#[derive(Debug, Clone)]
+pub struct WebAuthnPolicy {
+    pub rp_id: String,
+    /// Accepted `origin` values; when empty, only `https://<rp_id>`.
+    pub origins: Vec<String>,
+    /// Require the UV flag in addition to UP.
+    pub require_user_verification: bool,
+}
+
+impl Default for WebAuthnPolicy {
+    fn default() -> Self {
+        Self {
+            rp_id: "localhost".to_string(),
+            origins: Vec::new(),
+            require_user_verification: true,
+        }
+    }
+}
+
+impl WebAuthnPolicy {
+    fn allows_origin(&self, origin: &str) -> bool {
+        if self.origins.is_empty() {
+            origin == format!("https://{}", self.rp_id)
+        } else {
+            self.origins.iter().any(|o| o == origin)
+        }
+    }
+}
+
+#[derive(Debug, Deserialize)]
+struct ClientData {
+    #[serde(rename = "type")]
+    kind: String,
+    challenge: String,
+    origin: String,
+    #[serde(default, rename = "crossOrigin")]
+    cross_origin: bool,
+}
+
+/// Base64url challenge an assertion over `payload` must carry.
+pub fn challenge(payload: &str) -> String {
+    URL_SAFE_NO_PAD.encode(sha256::Hash::hash(payload.as_bytes()).as_byte_array())
+}
+
+fn decode(field: &str, data: &str) -> ConxianResult<Vec<u8>> {
+    // Some clients pad base64url; the padding carries no information
+    URL_SAFE_NO_PAD
+        .decode(data.trim().trim_end_matches('='))
+        .map_err(|e| ConxianError::Compliance(format!("Invalid {} base64url: {}", field, e)))
+}
+
+/// Checks `assertion` against the registered `credential` and `policy`,
+/// returning the authenticator's signature counter.
+pub fn verify_assertion(
+    policy: &WebAuthnPolicy,
+    credential: &PasskeyCredential,
+    assertion: &WebAuthnAssertion,
+) -> ConxianResult<u32> {
+    let auth_data = decode("authenticator_data", &assertion.authenticator_data)?;
+    let client_json = decode("client_data_json", &assertion.client_data_json)?;
+    let signature = decode("signature", &assertion.signature)?;
+
+    // rpIdHash(32) || flags(1) || signCount(4) || extensions
+    if auth_data.len() < 37 {
+        return Err(ConxianError::Compliance(
+            "Authenticator data is too short".to_string(),
+        ));
+    }
+    if auth_data[..32] != sha256::Hash::hash(policy.rp_id.as_bytes()).to_byte_array() {
+        return Err(ConxianError::Compliance(format!(
+            "Assertion is not for relying party {}",
+            policy.rp_id
+        )));
+    }
+    let flags = auth_data[32];
+    if flags & FLAG_UP == 0 {
+        return Err(ConxianError::Compliance(
+            "Assertion lacks user presence".to_string(),
+        ));
+    }
+    if policy.require_user_verification && flags & FLAG_UV == 0 {
+        return Err(ConxianError::Compliance(
+            "Assertion lacks user verification".to_string(),
+        ));
+    }
+    let sign_count =
+        u32::from_be_bytes([auth_data[33], auth_data[34], auth_data[35], auth_data[36]]);
+
+    let client: ClientData = serde_json::from_slice(&client_json)
+        .map_err(|e| ConxianError::Compliance(format!("Invalid client data: {}", e)))?;
+    if client.kind != "webauthn.get" {
+        return Err(ConxianError::Compliance(format!(
+            "Unexpected client data type: {}",
+            client.kind
+        )));
+    }
+    if client.challenge.trim_end_matches('=') != challenge(&assertion.payload) {
+        return Err(ConxianError::Compliance(
+            "Assertion challenge does not match the payload".to_string(),
+        ));
+    }
+    if client.cross_origin || !policy.allows_origin(&client.origin) {
+        return Err(ConxianError::Compliance(format!(
+            "Origin not allowed: {}",
+            client.origin
+        )));
+    }
+
+    // The authenticator signs authenticatorData || SHA-256(clientDataJSON)
+    let mut signed = auth_data;
+    signed.extend_from_slice(sha256::Hash::hash(&client_json).as_byte_array());
+    let key = hex::decode(&credential.public_key)
+        .map_err(|e| ConxianError::Internal(format!("Invalid credential key: {}", e)))?;
+    let verified = match credential.algorithm {
+        PasskeyAlgorithm::Es256 => {
+            use p256::ecdsa::{signature::Verifier, Signature, VerifyingKey};
+            let key = VerifyingKey::from_sec1_bytes(&key)
+                .map_err(|e| ConxianError::Internal(format!("Invalid credential key: {}", e)))?;
+            let sig = Signature::from_der(&signature).map_err(|e| {
+                ConxianError::Compliance(format!("Invalid signature format: {}", e))
+            })?;
+            key.verify(&signed, &sig).map_err(|e| e.to_string())
+        }
+        PasskeyAlgorithm::EdDsa => {
+            use ed25519_dalek::{Signature, Verifier, VerifyingKey};
+            let key = <[u8; 32]>::try_from(key.as_slice())
+                .ok()
+                .and_then(|k| VerifyingKey::from_bytes(&k).ok())
+                .ok_or_else(|| ConxianError::Internal("Invalid credential key".to_string()))?;
+            let sig = Signature::from_slice(&signature).map_err(|e| {
+                ConxianError::Compliance(format!("Invalid signature format: {}", e))
+            })?;
+            key.verify(&signed, &sig).map_err(|e| e.to_string())
+        }
+    };
+    verified
+        .map_err(|e| ConxianError::Compliance(format!("Signature verification failed: {}", e)))?;
+    Ok(sign_count)
+}
+
+/// Registered operator passkeys, optionally persisted to a JSON file.
+#[derive(Debug, Default)]
+pub struct PasskeyRegistry {
+    path: Option<PathBuf>,
+    credentials: Mutex<BTreeMap<String, PasskeyCredential>>,
+}
+
+impl PasskeyRegistry {
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Opens the registry at `path`, loading any credentials persisted there.
+    pub fn open(path: &str) -> ConxianResult<Self> {
+        let path = PathBuf::from(path);
+        let credentials = if path.exists() {
+            let json = fs::read_to_string(&path).map_err(|e| ConxianError::Io(e.to_string()))?;
+            serde_json::from_str(&json).map_err(|e| ConxianError::Internal(e.to_string()))?
+        } else {
+            BTreeMap::new()
+        };
+        Ok(Self {
+            path: Some(path),
+            credentials: Mutex::new(credentials),
+        })
+    }
+
+    pub fn get(&self, credential_id: &str) -> Option<PasskeyCredential> {
+        self.credentials.lock().unwrap().get(credential_id).cloned()
+    }
+
+    pub fn list(&self) -> Vec<PasskeyCredential> {
+        self.credentials.lock().unwrap().values().cloned().collect()
+    }
+
+    /// Registers a credential for `operator_id`. P-256 keys may be given in
+    /// any SEC1 form and are stored compressed.
+    pub fn register(
+        &self,
+        credential_id: &str,
+        operator_id: &str,
+        algorithm: PasskeyAlgorithm,
+        public_key: &str,
+        now: u64,
+    ) -> ConxianResult<PasskeyCredential> {
+        if operator_id.is_empty() {
+            return Err(ConxianError::Compliance(
+                "Operator id cannot be empty".to_string(),
+            ));
+        }
+        if decode("credential_id", credential_id)?.is_empty() {
+            return Err(ConxianError::Compliance(
+                "Credential id cannot be empty".to_string(),
+            ));
+        }
+        let public_key = normalize_key(algorithm, public_key)?;
+
+        let mut credentials = self.credentials.lock().unwrap();
+        if credentials.contains_key(credential_id) {
+            return Err(ConxianError::Compliance(format!(
+                "Credential already registered: {}",
+                credential_id
+            )));
+        }
+        let credential = PasskeyCredential {
+            credential_id: credential_id.to_string(),
+            operator_id: operator_id.to_string(),
+            algorithm,
+            public_key,
+            sign_count: 0,
+            registered_at: now,
+            last_used_at: None,
+        };
+        credentials.insert(credential_id.to_string(), credential.clone());
+        self.persist(&credentials);
+        Ok(credential)
+    }
+
+    pub fn remove(&self, credential_id: &str) -> Option<PasskeyCredential> {
+        let mut credentials = self.credentials.lock().unwrap();
+        let removed = credentials.remove(credential_id);
+        if removed.is_some() {
+            self.persist(&credentials);
+        }
+        removed
+    }
+
+    /// Records a verified use of a credential. A counter that does not
+    /// advance suggests a cloned authenticator and is rejected; authenticators
+    /// without a counter always report zero.
+    pub fn record_use(
+        &self,
+        credential_id: &str,
+        sign_count: u32,
+        now: u64,
+    ) -> ConxianResult<PasskeyCredential> {
+        let mut credentials = self.credentials.lock().unwrap();
+        let credential = credentials.get_mut(credential_id).ok_or_else(|| {
+            ConxianError::Security(format!("Credential is not registered: {}", credential_id))
+        })?;
+        if (sign_count != 0 || credential.sign_count != 0) && sign_count <= credential.sign_count {
+            return Err(ConxianError::Security(format!(
+                "Signature counter {} did not advance past {}; possible cloned authenticator",
+                sign_count, credential.sign_count
+            )));
+        }
+        credential.sign_count = sign_count;
+        credential.last_used_at = Some(now);
+        let credential = credential.clone();
+        self.persist(&credentials);
+        Ok(credential)
+    }
+
+    fn persist(&self, credentials: &BTreeMap<String, PasskeyCredential>) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        // The in-memory registry stays authoritative if the write fails
+        let res = serde_json::to_string_pretty(credentials)
+            .map_err(|e| ConxianError::Internal(e.to_string()))
+            .and_then(|json| write_atomic(path, &json));
+        if let Err(e) = res {
+            warn!("Failed to persist passkey registry: {}", e);
+        }
+    }
+}
+
+fn normalize_key(algorithm: PasskeyAlgorithm, public_key: &str) -> ConxianResult<String> {
+    let bytes = hex::decode(public_key)
+        .map_err(|e| ConxianError::Compliance(format!("Invalid public key hex: {}", e)))?;
+    match algorithm {
+        PasskeyAlgorithm::Es256 => p256::PublicKey::from_sec1_bytes(&bytes)
+            .map(|key| hex::encode(key.to_encoded_point(true).as_bytes()))
+            .map_err(|e| ConxianError::Compliance(format!("Invalid P-256 public key: {}", e))),
+        PasskeyAlgorithm::EdDsa => <[u8; 32]>::try_from(bytes.as_slice())
+            .ok()
+            .and_then(|k| ed25519_dalek::VerifyingKey::from_bytes(&k).ok())
+            .map(|key| hex::encode(key.as_bytes()))
+            .ok_or_else(|| ConxianError::Compliance("Invalid Ed25519 public key".to_string())),
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) const CREDENTIAL_ID: &str = "Y3JlZC0x";
+
+    /// Builds an assertion over `payload` as a browser on `origin` would, with
+    /// the given flags and counter, signed by `sign`.
+    pub(crate) fn assertion(
+        payload: &str,
+        origin: &str,
+        flags: u8,
+        sign_count: u32,
+        sign: impl Fn(&[u8]) -> Vec<u8>,
+    ) -> WebAuthnAssertion {
+        let mut auth_data = sha256::Hash::hash(b"localhost").to_byte_array().to_vec();
+        auth_data.push(flags);
+        auth_data.extend_from_slice(&sign_count.to_be_bytes());
+        let client_json = serde_json::json!({
+            "type": "webauthn.get",
+            "challenge": challenge(payload),
+            "origin": origin,
+        })
+        .to_string();
+
+        let mut signed = auth_data.clone();
+        signed.extend_from_slice(sha256::Hash::hash(client_json.as_bytes()).as_byte_array());
+        WebAuthnAssertion {
+            credential_id: CREDENTIAL_ID.to_string(),
+            authenticator_data: URL_SAFE_NO_PAD.encode(&auth_data),
+            client_data_json: URL_SAFE_NO_PAD.encode(client_json),
+            signature: URL_SAFE_NO_PAD.encode(sign(&signed)),
+            payload: payload.to_string(),
+        }
+    }
+
+    pub(crate) fn es256_key() -> p256::ecdsa::SigningKey {
+        p256::ecdsa::SigningKey::from_slice(&[7u8; 32]).unwrap()
+    }
+
+    pub(crate) fn es256_sign(data: &[u8]) -> Vec<u8> {
+        use p256::ecdsa::{signature::Signer, Signature};
+        let sig: Signature = es256_key().sign(data);
+        sig.to_der().as_bytes().to_vec()
+    }
+
+    fn credential(algorithm: PasskeyAlgorithm, public_key: &str) -> PasskeyCredential {
+        PasskeyRegistry::in_memory()
+            .register(CREDENTIAL_ID, "operator-1", algorithm, public_key, 0)
+            .unwrap()
+    }
+
+    #[test]
+    fn test_verify_es256_assertion() {
+        let key = hex::encode(
+            es256_key()
+                .verifying_key()
+                .to_encoded_point(false)
+                .as_bytes(),
+        );
+        let credential = credential(PasskeyAlgorithm::Es256, &key);
+        let policy = WebAuthnPolicy::default();
+
+        let valid = assertion("approve", "https://localhost", 0x05, 3, es256_sign);
+        assert_eq!(verify_assertion(&policy, &credential, &valid).unwrap(), 3);
+
+        // Payload, origin and flags are all bound
+        let mut other_payload = valid.clone();
+        other_payload.payload = "approve-other".to_string();
+        assert!(verify_assertion(&policy, &credential, &other_payload).is_err());
+        let wrong_origin = assertion("approve", "https://evil.example", 0x05, 3, es256_sign);
+        assert!(verify_assertion(&policy, &credential, &wrong_origin).is_err());
+        let not_verified = assertion("approve", "https://localhost", 0x01, 3, es256_sign);
+        assert!(verify_assertion(&policy, &credential, &not_verified).is_err());
+        let presence_only = WebAuthnPolicy {
+            require_user_verification: false,
+            ..WebAuthnPolicy::default()
+        };
+        assert!(verify_assertion(&presence_only, &credential, &not_verified).is_ok());
+        let not_present = assertion("approve", "https://localhost", 0x04, 3, es256_sign);
+        assert!(verify_assertion(&presence_only, &credential, &not_present).is_err());
+        let other_rp = WebAuthnPolicy {
+            rp_id: "conxian.io".to_string(),
+            origins: vec!["https://localhost".to_string()],
+            ..WebAuthnPolicy::default()
+        };
+        assert!(verify_assertion(&other_rp, &credential, &valid).is_err());
+    }
+
+    #[test]
+    fn test_verify_eddsa_assertion() {
+        use ed25519_dalek::{Signer, SigningKey};
+        let key = SigningKey::from_bytes(&[9u8; 32]);
+        let credential = credential(
+            PasskeyAlgorithm::EdDsa,
+            &hex::encode(key.verifying_key().as_bytes()),
+        );
+        let policy = WebAuthnPolicy::default();
+
+        let sign = |data: &[u8]| key.sign(data).to_bytes().to_vec();
+        let valid = assertion("approve", "https://localhost", 0x05, 0, sign);
+        assert_eq!(verify_assertion(&policy, &credential, &valid).unwrap(), 0);
+
+        let mut tampered = valid.clone();
+        tampered.signature = assertion("approve", "https://localhost", 0x05, 1, sign).signature;
+        assert!(verify_assertion(&policy, &credential, &tampered).is_err());
+    }
+
+    #[test]
+    fn test_registry_sign_counter() {
+        let path =
+            std::env::temp_dir().join(format!("conxian-passkeys-{}.json", rand::random::<u64>()));
+        let path = path.to_str().unwrap();
+        let key = hex::encode(
+            es256_key()
+                .verifying_key()
+                .to_encoded_point(true)
+                .as_bytes(),
+        );
+
+        let registry = PasskeyRegistry::open(path).unwrap();
+        registry
+            .register(
+                CREDENTIAL_ID,
+                "operator-1",
+                PasskeyAlgorithm::Es256,
+                &key,
+                1,
+            )
+            .unwrap();
+        assert!(registry
+            .register(
+                CREDENTIAL_ID,
+                "operator-2",
+                PasskeyAlgorithm::Es256,
+                &key,
+                1
+            )
+            .is_err());
+        assert!(registry
+            .register("Y3JlZC0y", "operator-1", PasskeyAlgorithm::EdDsa, &key, 1)
+            .is_err());
+
+        // Counterless authenticators keep reporting zero
+        registry.record_use(CREDENTIAL_ID, 0, 2).unwrap();
+        registry.record_use(CREDENTIAL_ID, 5, 3).unwrap();
+        assert!(registry.record_use(CREDENTIAL_ID, 5, 4).is_err());
+        assert!(registry.record_use(CREDENTIAL_ID, 0, 4).is_err());
+        drop(registry);
+
+        let reopened = PasskeyRegistry::open(path).unwrap();
+        let credential = reopened.get(CREDENTIAL_ID).unwrap();
+        assert_eq!(credential.sign_count, 5);
+        assert_eq!(credential.last_used_at, Some(3));
+        assert!(reopened.record_use(CREDENTIAL_ID, 4, 5).is_err());
+        assert!(reopened.remove(CREDENTIAL_ID).is_some());
+        assert!(reopened.record_use(CREDENTIAL_ID, 6, 6).is_err());
+        let _ = fs::remove_file(path);
+    }
+}
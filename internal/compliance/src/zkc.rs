@@ -1,11 +1,32 @@
-use bitcoin::hashes::{sha256, Hash};
-pub use conxian_core::{Attestation, ConxianError, ConxianResult, SchnorrAttestation};
+use crate::registry::DeviceRegistry;
+use crate::replay::ReplayGuard;
+use crate::webauthn::{self, PasskeyRegistry, WebAuthnPolicy};
+use crate::{batch, bip322, musig, sip018};
+use bitcoin::address::NetworkUnchecked;
+use bitcoin::hashes::{hash160, sha256, Hash};
+use bitcoin::sighash::{EcdsaSighashType, Prevouts, SighashCache, TapSighashType};
+use bitcoin::sign_message::signed_msg_hash;
+use bitcoin::{Address, Amount, ScriptBuf};
+use conxian_core::c32::{StacksAddress, MAINNET_SINGLESIG, TESTNET_SINGLESIG};
+use conxian_core::ring;
+pub use conxian_core::{
+    Attestation, Bip137Attestation, Bip322Attestation, ConxianError, ConxianResult,
+    P256Attestation, RejectedSignature, RingAttestation, SchnorrAttestation, SignatureScheme,
+    Sip018Attestation, ThresholdAttestation, ThresholdReport, ThresholdSignature,
+    WebAuthnAssertion,
+};
+use secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
 use secp256k1::schnorr::Signature as SchnorrSignature;
 use secp256k1::XOnlyPublicKey;
 use secp256k1::{ecdsa::Signature, Message, PublicKey, Secp256k1};
+use std::sync::Arc;
 
 pub struct ZkcVerifier {
     secp: Secp256k1<secp256k1::All>,
+    replay: ReplayGuard,
+    devices: Arc<DeviceRegistry>,
+    passkeys: Arc<PasskeyRegistry>,
+    webauthn: WebAuthnPolicy,
 }
 
 impl Default for ZkcVerifier {
@@ -18,17 +39,56 @@ impl ZkcVerifier {
     pub fn new() -> Self {
         Self {
             secp: Secp256k1::new(),
+            replay: ReplayGuard::default(),
+            devices: Arc::new(DeviceRegistry::in_memory()),
+            passkeys: Arc::new(PasskeyRegistry::in_memory()),
+            webauthn: WebAuthnPolicy::default(),
         }
     }
 
-    pub fn verify(&self, attestation: &Attestation) -> ConxianResult<bool> {
-        // Validation: device_id must follow the expected format
-        if !attestation.device_id.starts_with("conxius-") {
-            return Err(ConxianError::Compliance(
-                "Invalid device ID: must start with 'conxius-'".to_string(),
-            ));
-        }
+    /// Replaces the default registry, which starts with no enrolled devices.
+    pub fn with_device_registry(mut self, devices: Arc<DeviceRegistry>) -> Self {
+        self.devices = devices;
+        self
+    }
+
+    /// Registry that presented keys are checked against.
+    pub fn device_registry(&self) -> Arc<DeviceRegistry> {
+        self.devices.clone()
+    }
+
+    /// Replaces the default passkey registry, which starts empty, and the
+    /// relying party assertions are checked against.
+    pub fn with_passkeys(mut self, passkeys: Arc<PasskeyRegistry>, policy: WebAuthnPolicy) -> Self {
+        self.passkeys = passkeys;
+        self.webauthn = policy;
+        self
+    }
+
+    /// Registry of operator passkeys accepted for WebAuthn assertions.
+    pub fn passkey_registry(&self) -> Arc<PasskeyRegistry> {
+        self.passkeys.clone()
+    }
+
+    /// Replaces the default guard, which keeps nonces in memory and rejects
+    /// legacy payloads.
+    pub fn with_replay_guard(mut self, guard: ReplayGuard) -> Self {
+        self.replay = guard;
+        self
+    }
+
+    /// Applies the replay guard to a payload whose signature has been verified.
+    fn check_replay(&self, device_id: &str, payload: &str) -> ConxianResult<()> {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        self.replay.check(device_id, payload, now).map(|_| ())
+    }
 
+    /// Verifies an ECDSA attestation. Device ID formats are left to the
+    /// compliance policy; see [`PolicyEngine`](crate::policy::PolicyEngine).
+    pub fn verify(&self, attestation: &Attestation) -> ConxianResult<bool> {
         // Validation: signature must not be empty
         if attestation.signature.is_empty() {
             return Err(ConxianError::Compliance(
@@ -48,6 +108,7 @@ impl ZkcVerifier {
             .map_err(|e| ConxianError::Compliance(format!("Invalid public key hex: {}", e)))?;
         let pubkey = PublicKey::from_slice(&pubkey_bytes)
             .map_err(|e| ConxianError::Compliance(format!("Invalid public key: {}", e)))?;
+        self.devices.check_key(&attestation.device_id, &pubkey)?;
 
         // Parse signature
         let sig_bytes = hex::decode(&attestation.signature)
@@ -63,7 +124,9 @@ impl ZkcVerifier {
 
         // Verify signature
         match self.secp.verify_ecdsa(&message, &sig, &pubkey) {
-            Ok(_) => Ok(true),
+            Ok(_) => self
+                .check_replay(&attestation.device_id, &attestation.payload)
+                .map(|_| true),
             Err(e) => Err(ConxianError::Compliance(format!(
                 "Signature verification failed: {}",
                 e
@@ -73,6 +136,35 @@ impl ZkcVerifier {
 
     /// Research enhancement: Verify Schnorr signature for Taproot-compatible attestations.
     pub fn verify_schnorr(&self, attestation: &SchnorrAttestation) -> ConxianResult<bool> {
+        let (sig, message, pubkey) = self.parse_schnorr(attestation)?;
+
+        // Verify signature
+        match self.secp.verify_schnorr(&sig, &message, &pubkey) {
+            Ok(_) => self
+                .check_replay(&attestation.device_id, &attestation.payload)
+                .map(|_| true),
+            Err(e) => Err(ConxianError::Compliance(format!(
+                "Schnorr signature verification failed: {}",
+                e
+            ))),
+        }
+    }
+
+    /// Verifies that a ring attestation was signed by one of the active
+    /// devices, without learning which, returning the size of the ring.
+    /// Nonces are shared by every member of a ring.
+    pub fn verify_ring(&self, attestation: &RingAttestation) -> ConxianResult<usize> {
+        let ring = self.devices.ring();
+        ring::verify_ring(&ring, attestation)?;
+        self.check_replay(&format!("ring:{}", attestation.root), &attestation.payload)?;
+        Ok(ring.len())
+    }
+
+    /// Parses a Schnorr attestation whose key is bound to its device.
+    fn parse_schnorr(
+        &self,
+        attestation: &SchnorrAttestation,
+    ) -> ConxianResult<(SchnorrSignature, Message, XOnlyPublicKey)> {
         // Parse X-only public key
         let pubkey_bytes = hex::decode(&attestation.x_only_public_key).map_err(|e| {
             ConxianError::Compliance(format!("Invalid x-only public key hex: {}", e))
@@ -80,6 +172,22 @@ impl ZkcVerifier {
         let pubkey = XOnlyPublicKey::from_slice(&pubkey_bytes)
             .map_err(|e| ConxianError::Compliance(format!("Invalid x-only public key: {}", e)))?;
 
+        // A MuSig2 signature verifies like any BIP-340 signature, under the
+        // aggregate key; the device's registered taproot internal key must be it
+        if let Some(participants) = &attestation.participants {
+            let keys = participants
+                .iter()
+                .map(|k| musig::parse_participant(k))
+                .collect::<ConxianResult<Vec<_>>>()?;
+            if musig::key_agg(&musig::key_sort(&keys))? != pubkey {
+                return Err(ConxianError::Compliance(
+                    "Public key is not the MuSig2 aggregate of the participants".to_string(),
+                ));
+            }
+        }
+        self.devices
+            .check_x_only_key(&attestation.device_id, &pubkey)?;
+
         // Parse Schnorr signature
         let sig_bytes = hex::decode(&attestation.signature).map_err(|e| {
             ConxianError::Compliance(format!("Invalid Schnorr signature hex: {}", e))
@@ -90,30 +198,478 @@ impl ZkcVerifier {
         // Hash the payload
         let message_hash = sha256::Hash::hash(attestation.payload.as_bytes());
         let message = Message::from_digest(message_hash.to_byte_array());
+        Ok((sig, message, pubkey))
+    }
+}
 
-        // Verify signature
-        match self.secp.verify_schnorr(&sig, &message, &pubkey) {
-            Ok(_) => Ok(true),
+impl ZkcVerifier {
+    /// Verifies a SIP-018 structured data signature, returning the Stacks
+    /// address of the recovered key once it matches `expected_address` and
+    /// is bound to the device.
+    pub fn verify_sip018(&self, attestation: &Sip018Attestation) -> ConxianResult<StacksAddress> {
+        let chain_id = sip018::domain_chain_id(&attestation.domain)?;
+        let digest = sip018::message_hash(&attestation.domain, &attestation.message)?;
+
+        // Wallets return r || s || v, with v optionally offset by 27
+        let sig_bytes = hex::decode(attestation.signature.trim_start_matches("0x"))
+            .map_err(|e| ConxianError::Compliance(format!("Invalid signature hex: {}", e)))?;
+        if sig_bytes.len() != 65 {
+            return Err(ConxianError::Compliance(format!(
+                "SIP-018 signature must be 65 bytes, got {}",
+                sig_bytes.len()
+            )));
+        }
+        let v = sig_bytes[64];
+        let recid = RecoveryId::from_i32(if v >= 27 { v - 27 } else { v } as i32)
+            .map_err(|e| ConxianError::Compliance(format!("Invalid recovery id: {}", e)))?;
+        let sig = RecoverableSignature::from_compact(&sig_bytes[..64], recid)
+            .map_err(|e| ConxianError::Compliance(format!("Invalid signature format: {}", e)))?;
+
+        let pubkey = self
+            .secp
+            .recover_ecdsa(&Message::from_digest(digest), &sig)
+            .map_err(|e| ConxianError::Compliance(format!("Signature recovery failed: {}", e)))?;
+
+        let version = if chain_id == 1 {
+            MAINNET_SINGLESIG
+        } else {
+            TESTNET_SINGLESIG
+        };
+        let signer = StacksAddress::new(
+            version,
+            hash160::Hash::hash(&pubkey.serialize()).to_byte_array(),
+        )?;
+        if signer != attestation.expected_address {
+            return Err(ConxianError::Compliance(format!(
+                "SIP-018 signer {} does not match expected address {}",
+                signer, attestation.expected_address
+            )));
+        }
+        self.devices.check_key(&attestation.device_id, &pubkey)?;
+        self.check_replay(
+            &attestation.device_id,
+            &sip018::claims_payload(&attestation.message)?,
+        )?;
+        Ok(signer)
+    }
+}
+
+impl ZkcVerifier {
+    /// Verifies a BIP-322 signature over `message` for a P2WPKH or P2TR
+    /// (key path) address, in either the simple or the full format. The
+    /// witness key, or for P2TR the output key, must be the device's.
+    pub fn verify_bip322(&self, attestation: &Bip322Attestation) -> ConxianResult<bool> {
+        let script_pubkey = attestation
+            .address
+            .parse::<Address<NetworkUnchecked>>()
+            .map_err(|e| ConxianError::Compliance(format!("Invalid address: {}", e)))?
+            .assume_checked()
+            .script_pubkey();
+        let to_spend = bip322::to_spend(&script_pubkey, attestation.message.as_bytes());
+        let to_sign = bip322::signed_transaction(
+            &to_spend,
+            bip322::decode_signature(&attestation.signature)?,
+        )?;
+        let witness = &to_sign.input[0].witness;
+        let mut cache = SighashCache::new(&to_sign);
+
+        let result = if script_pubkey.is_p2wpkh() {
+            let (Some(sig), Some(pubkey), 2) = (witness.nth(0), witness.nth(1), witness.len())
+            else {
+                return Err(ConxianError::Compliance(
+                    "P2WPKH witness must contain a signature and a public key".to_string(),
+                ));
+            };
+            if hash160::Hash::hash(pubkey).as_byte_array()[..] != script_pubkey.as_bytes()[2..] {
+                return Err(ConxianError::Compliance(
+                    "Witness public key does not match address".to_string(),
+                ));
+            }
+            let pubkey = PublicKey::from_slice(pubkey)
+                .map_err(|e| ConxianError::Compliance(format!("Invalid public key: {}", e)))?;
+            self.devices.check_key(&attestation.device_id, &pubkey)?;
+            let sig = bitcoin::ecdsa::Signature::from_slice(sig).map_err(|e| {
+                ConxianError::Compliance(format!("Invalid signature format: {}", e))
+            })?;
+            // Other types leave parts of the message unsigned
+            if sig.sighash_type != EcdsaSighashType::All {
+                return Err(ConxianError::Compliance(format!(
+                    "Unsupported sighash type: {}",
+                    sig.sighash_type
+                )));
+            }
+            let sighash = cache
+                .p2wpkh_signature_hash(0, &script_pubkey, Amount::ZERO, sig.sighash_type)
+                .map_err(|e| ConxianError::Compliance(format!("Sighash failed: {}", e)))?;
+            self.secp.verify_ecdsa(
+                &Message::from_digest(sighash.to_byte_array()),
+                &sig.signature,
+                &pubkey,
+            )
+        } else if script_pubkey.is_p2tr() {
+            let (Some(sig), 1) = (witness.nth(0), witness.len()) else {
+                return Err(ConxianError::Compliance(
+                    "Only taproot key path spends are supported".to_string(),
+                ));
+            };
+            let output_key = XOnlyPublicKey::from_slice(&script_pubkey.as_bytes()[2..])
+                .map_err(|e| ConxianError::Compliance(format!("Invalid output key: {}", e)))?;
+            self.devices
+                .check_taproot_output_key(&attestation.device_id, &output_key)?;
+            let sig = bitcoin::taproot::Signature::from_slice(sig).map_err(|e| {
+                ConxianError::Compliance(format!("Invalid Schnorr signature: {}", e))
+            })?;
+            if !matches!(
+                sig.sighash_type,
+                TapSighashType::Default | TapSighashType::All
+            ) {
+                return Err(ConxianError::Compliance(format!(
+                    "Unsupported sighash type: {}",
+                    sig.sighash_type
+                )));
+            }
+            let sighash = cache
+                .taproot_key_spend_signature_hash(
+                    0,
+                    &Prevouts::All(&to_spend.output),
+                    sig.sighash_type,
+                )
+                .map_err(|e| ConxianError::Compliance(format!("Sighash failed: {}", e)))?;
+            self.secp.verify_schnorr(
+                &sig.signature,
+                &Message::from_digest(sighash.to_byte_array()),
+                &output_key,
+            )
+        } else {
+            return Err(ConxianError::Compliance(
+                "BIP-322 verification supports P2WPKH and P2TR addresses only".to_string(),
+            ));
+        };
+
+        match result {
+            Ok(_) => self
+                .check_replay(&attestation.device_id, &attestation.message)
+                .map(|_| true),
             Err(e) => Err(ConxianError::Compliance(format!(
-                "Schnorr signature verification failed: {}",
+                "BIP-322 signature verification failed: {}",
+                e
+            ))),
+        }
+    }
+}
+
+impl ZkcVerifier {
+    /// Verifies a BIP-137 signed message by recovering the signing key and
+    /// matching it against the P2PKH, P2SH-P2WPKH or P2WPKH `address` and
+    /// the key bound to the device.
+    pub fn verify_bip137(&self, attestation: &Bip137Attestation) -> ConxianResult<bool> {
+        use bitcoin::base64::{engine::general_purpose::STANDARD, Engine};
+
+        let script_pubkey = attestation
+            .address
+            .parse::<Address<NetworkUnchecked>>()
+            .map_err(|e| ConxianError::Compliance(format!("Invalid address: {}", e)))?
+            .assume_checked()
+            .script_pubkey();
+
+        let sig_bytes = STANDARD
+            .decode(attestation.signature.trim())
+            .map_err(|e| ConxianError::Compliance(format!("Invalid signature base64: {}", e)))?;
+        if sig_bytes.len() != 65 {
+            return Err(ConxianError::Compliance(format!(
+                "BIP-137 signature must be 65 bytes, got {}",
+                sig_bytes.len()
+            )));
+        }
+
+        // Header: 27-30 uncompressed P2PKH, 31-34 compressed P2PKH, 35-38
+        // P2SH-P2WPKH, 39-42 P2WPKH. Wallets disagree on the segwit ranges,
+        // so the address type is taken from the address itself.
+        let header = sig_bytes[0];
+        if !(27..=42).contains(&header) {
+            return Err(ConxianError::Compliance(format!(
+                "Invalid BIP-137 header byte: {}",
+                header
+            )));
+        }
+        let recid = RecoveryId::from_i32(((header - 27) % 4) as i32)
+            .map_err(|e| ConxianError::Compliance(format!("Invalid recovery id: {}", e)))?;
+        let sig = RecoverableSignature::from_compact(&sig_bytes[1..], recid)
+            .map_err(|e| ConxianError::Compliance(format!("Invalid signature format: {}", e)))?;
+
+        let digest = signed_msg_hash(&attestation.message);
+        let pubkey = bitcoin::PublicKey {
+            compressed: header >= 31,
+            inner: self
+                .secp
+                .recover_ecdsa(&Message::from_digest(digest.to_byte_array()), &sig)
+                .map_err(|e| {
+                    ConxianError::Compliance(format!("Signature recovery failed: {}", e))
+                })?,
+        };
+
+        let signer = if script_pubkey.is_p2pkh() {
+            Some(ScriptBuf::new_p2pkh(&pubkey.pubkey_hash()))
+        } else if script_pubkey.is_p2wpkh() {
+            pubkey
+                .wpubkey_hash()
+                .ok()
+                .map(|h| ScriptBuf::new_p2wpkh(&h))
+        } else if script_pubkey.is_p2sh() {
+            pubkey
+                .wpubkey_hash()
+                .ok()
+                .map(|h| ScriptBuf::new_p2sh(&ScriptBuf::new_p2wpkh(&h).script_hash()))
+        } else {
+            return Err(ConxianError::Compliance(
+                "BIP-137 verification supports P2PKH, P2SH-P2WPKH and P2WPKH addresses only"
+                    .to_string(),
+            ));
+        };
+
+        if signer.as_ref() != Some(&script_pubkey) {
+            return Err(ConxianError::Compliance(format!(
+                "Recovered key does not match address {}",
+                attestation.address
+            )));
+        }
+        self.devices
+            .check_key(&attestation.device_id, &pubkey.inner)?;
+        self.check_replay(&attestation.device_id, &attestation.message)?;
+        Ok(true)
+    }
+}
+
+impl ZkcVerifier {
+    /// Verifies an ECDSA P-256 signature over SHA-256 of the payload, made by
+    /// the enclave key bound to the device through hardware attestation.
+    pub fn verify_p256(&self, attestation: &P256Attestation) -> ConxianResult<bool> {
+        use p256::ecdsa::signature::Verifier;
+        use p256::ecdsa::{Signature as P256Signature, VerifyingKey};
+
+        let hardware = self.devices.hardware_key(&attestation.device_id)?;
+        let key_bytes = hex::decode(&hardware.public_key)
+            .map_err(|e| ConxianError::Internal(format!("Invalid attested key: {}", e)))?;
+        let key = VerifyingKey::from_sec1_bytes(&key_bytes)
+            .map_err(|e| ConxianError::Internal(format!("Invalid attested key: {}", e)))?;
+
+        let sig_bytes = hex::decode(&attestation.signature)
+            .map_err(|e| ConxianError::Compliance(format!("Invalid signature hex: {}", e)))?;
+        let sig = if sig_bytes.len() == 64 {
+            P256Signature::from_slice(&sig_bytes)
+        } else {
+            P256Signature::from_der(&sig_bytes)
+        }
+        .map_err(|e| ConxianError::Compliance(format!("Invalid signature format: {}", e)))?;
+
+        match key.verify(attestation.payload.as_bytes(), &sig) {
+            Ok(_) => {
+                self.check_replay(&attestation.device_id, &attestation.payload)?;
+                Ok(true)
+            }
+            Err(e) => Err(ConxianError::Compliance(format!(
+                "Signature verification failed: {}",
                 e
             ))),
         }
     }
 }
 
+impl ZkcVerifier {
+    /// Verifies a WebAuthn assertion from a registered operator passkey,
+    /// advancing its signature counter. Returns the approving operator.
+    pub fn verify_webauthn(&self, assertion: &WebAuthnAssertion) -> ConxianResult<String> {
+        let credential = self.passkeys.get(&assertion.credential_id).ok_or_else(|| {
+            ConxianError::Security(format!(
+                "Credential is not registered: {}",
+                assertion.credential_id
+            ))
+        })?;
+        let sign_count = webauthn::verify_assertion(&self.webauthn, &credential, assertion)?;
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        // A replayed payload must not advance the stored counter
+        self.check_replay(&credential.operator_id, &assertion.payload)?;
+        self.passkeys
+            .record_use(&credential.credential_id, sign_count, now)?;
+        Ok(credential.operator_id)
+    }
+}
+
+impl ZkcVerifier {
+    /// Verifies Schnorr attestations together, checking all signatures with a
+    /// single batch equation and falling back to individual verification only
+    /// if the batch fails. Results are in input order.
+    pub fn verify_schnorr_batch(
+        &self,
+        attestations: &[SchnorrAttestation],
+    ) -> Vec<ConxianResult<bool>> {
+        let parsed: Vec<_> = attestations.iter().map(|a| self.parse_schnorr(a)).collect();
+        let items: Vec<_> = parsed
+            .iter()
+            .filter_map(|p| p.as_ref().ok())
+            .copied()
+            .collect();
+        let batch_valid = batch::verify_schnorr_batch(&self.secp, &items);
+
+        attestations
+            .iter()
+            .zip(parsed)
+            .map(|(attestation, parsed)| {
+                let (sig, message, pubkey) = parsed?;
+                if !batch_valid {
+                    self.secp
+                        .verify_schnorr(&sig, &message, &pubkey)
+                        .map_err(|e| {
+                            ConxianError::Compliance(format!(
+                                "Schnorr signature verification failed: {}",
+                                e
+                            ))
+                        })?;
+                }
+                self.check_replay(&attestation.device_id, &attestation.payload)
+                    .map(|_| true)
+            })
+            .collect()
+    }
+}
+
+impl ZkcVerifier {
+    /// Verifies an M-of-N threshold attestation. Each signer must be in the
+    /// policy, and is checked against the key bound to its device; a device or
+    /// key is counted at most once. The payload's nonce is consumed once for
+    /// the whole approval.
+    pub fn verify_threshold(
+        &self,
+        attestation: &ThresholdAttestation,
+    ) -> ConxianResult<ThresholdReport> {
+        let policy = &attestation.policy;
+        let mut allowed: Vec<&str> = policy.signers.iter().map(String::as_str).collect();
+        allowed.sort_unstable();
+        allowed.dedup();
+        if allowed.len() != policy.signers.len() {
+            return Err(ConxianError::Compliance(
+                "Threshold policy lists a signer more than once".to_string(),
+            ));
+        }
+        if policy.threshold == 0 || policy.threshold > allowed.len() {
+            return Err(ConxianError::Compliance(format!(
+                "Threshold must be between 1 and {}, got {}",
+                allowed.len(),
+                policy.threshold
+            )));
+        }
+
+        let message = Message::from_digest(
+            sha256::Hash::hash(attestation.payload.as_bytes()).to_byte_array(),
+        );
+        let mut counted: Vec<String> = Vec::new();
+        let mut counted_keys: Vec<PublicKey> = Vec::new();
+        let mut rejected = Vec::new();
+        for signature in &attestation.signatures {
+            let check = || -> Result<PublicKey, String> {
+                if allowed
+                    .binary_search(&signature.device_id.as_str())
+                    .is_err()
+                {
+                    return Err("signer is not in the policy".to_string());
+                }
+                if counted.contains(&signature.device_id) {
+                    return Err("signer already counted".to_string());
+                }
+                let key = self
+                    .devices
+                    .public_key(&signature.device_id)
+                    .map_err(|e| e.to_string())?;
+                if counted_keys.contains(&key) {
+                    return Err("key already counted for another signer".to_string());
+                }
+                self.verify_cosignature(signature, &message, &key)?;
+                Ok(key)
+            };
+            match check() {
+                Ok(key) => {
+                    counted.push(signature.device_id.clone());
+                    counted_keys.push(key);
+                }
+                Err(reason) => rejected.push(RejectedSignature {
+                    device_id: signature.device_id.clone(),
+                    reason,
+                }),
+            }
+        }
+
+        if counted.len() < policy.threshold {
+            return Err(ConxianError::Security(format!(
+                "Threshold not met: {} of {} required signers verified ({})",
+                counted.len(),
+                policy.threshold,
+                counted.join(", ")
+            )));
+        }
+        self.check_replay("threshold", &attestation.payload)?;
+        Ok(ThresholdReport {
+            threshold: policy.threshold,
+            counted,
+            rejected,
+        })
+    }
+
+    fn verify_cosignature(
+        &self,
+        signature: &ThresholdSignature,
+        message: &Message,
+        key: &PublicKey,
+    ) -> Result<(), String> {
+        let bytes = hex::decode(&signature.signature)
+            .map_err(|e| format!("invalid signature hex: {}", e))?;
+        match signature.scheme {
+            SignatureScheme::Ecdsa => {
+                let sig = Signature::from_der(&bytes)
+                    .or_else(|_| Signature::from_compact(&bytes))
+                    .map_err(|e| format!("invalid signature format: {}", e))?;
+                self.secp.verify_ecdsa(message, &sig, key)
+            }
+            SignatureScheme::Schnorr => {
+                let sig = SchnorrSignature::from_slice(&bytes)
+                    .map_err(|e| format!("invalid signature format: {}", e))?;
+                self.secp
+                    .verify_schnorr(&sig, message, &key.x_only_public_key().0)
+            }
+        }
+        .map_err(|e| format!("signature verification failed: {}", e))
+    }
+}
+
 #[cfg(test)]
 mod tests {
     use super::*;
+    use conxian_core::AttestationClaims;
     use rand::thread_rng;
     use secp256k1::Keypair;
 
+    fn claims_payload(nonce: &str) -> String {
+        let now = std::time::SystemTime::now()
+            .duration_since(std::time::UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        serde_json::to_string(&AttestationClaims {
+            nonce: nonce.to_string(),
+            issued_at: now,
+            expires_at: now + 300,
+            audience: "conxian-gateway".to_string(),
+        })
+        .unwrap()
+    }
+
     #[test]
     fn test_zkc_verify_valid() {
         let secp = Secp256k1::new();
         let (sk, pk) = secp.generate_keypair(&mut thread_rng());
 
-        let payload = "valid-payload";
+        let payload = claims_payload("valid-payload");
         let message_hash = sha256::Hash::hash(payload.as_bytes());
         let message = Message::from_digest(message_hash.to_byte_array());
         let sig = secp.sign_ecdsa(&message, &sk);
@@ -125,7 +681,83 @@ mod tests {
             payload: payload.to_string(),
             public_key: hex::encode(pk.serialize()),
         };
+        // Keys must be bound to an enrolled device
+        assert!(verifier.verify(&attestation).is_err());
+        verifier
+            .device_registry()
+            .enroll("conxius-123", &attestation.public_key, 0)
+            .unwrap();
         assert!(verifier.verify(&attestation).unwrap());
+        // Replaying the same attestation is rejected
+        assert!(verifier.verify(&attestation).is_err());
+    }
+
+    #[test]
+    fn test_zkc_verify_rejects_unbound_keys() {
+        let secp = Secp256k1::new();
+        let (sk, pk) = secp.generate_keypair(&mut thread_rng());
+        let (_, enrolled) = secp.generate_keypair(&mut thread_rng());
+        let sign = |nonce: &str| {
+            let payload = claims_payload(nonce);
+            let message =
+                Message::from_digest(sha256::Hash::hash(payload.as_bytes()).to_byte_array());
+            Attestation {
+                device_id: "conxius-123".to_string(),
+                signature: hex::encode(secp.sign_ecdsa(&message, &sk).serialize_der()),
+                payload,
+                public_key: hex::encode(pk.serialize()),
+            }
+        };
+
+        let verifier = ZkcVerifier::new();
+        let devices = verifier.device_registry();
+        devices
+            .enroll("conxius-123", &hex::encode(enrolled.serialize()), 0)
+            .unwrap();
+        // A valid signature under a key other than the bound one
+        assert!(verifier.verify(&sign("a")).is_err());
+
+        devices
+            .rotate("conxius-123", &hex::encode(pk.serialize()), None, 1)
+            .unwrap();
+        assert!(verifier.verify(&sign("b")).unwrap());
+
+        devices.revoke("conxius-123", None, 2).unwrap();
+        assert!(verifier.verify(&sign("c")).is_err());
+    }
+
+    #[test]
+    fn test_zkc_verify_legacy_payload() {
+        use crate::replay::{NonceStore, ReplayPolicy};
+
+        let secp = Secp256k1::new();
+        let (sk, pk) = secp.generate_keypair(&mut thread_rng());
+        let payload = "raw-payload";
+        let message = Message::from_digest(sha256::Hash::hash(payload.as_bytes()).to_byte_array());
+        let attestation = Attestation {
+            device_id: "conxius-123".to_string(),
+            signature: hex::encode(secp.sign_ecdsa(&message, &sk).serialize_der()),
+            payload: payload.to_string(),
+            public_key: hex::encode(pk.serialize()),
+        };
+
+        let devices = Arc::new(DeviceRegistry::in_memory());
+        devices
+            .enroll("conxius-123", &attestation.public_key, 0)
+            .unwrap();
+
+        let strict = ZkcVerifier::new().with_device_registry(devices.clone());
+        assert!(strict.verify(&attestation).is_err());
+        let legacy = ZkcVerifier::new()
+            .with_device_registry(devices)
+            .with_replay_guard(ReplayGuard::new(
+                ReplayPolicy {
+                    allow_legacy_payloads: true,
+                    ..ReplayPolicy::default()
+                },
+                NonceStore::in_memory(),
+            ));
+        assert!(legacy.verify(&attestation).unwrap());
     }
 
     #[test]
@@ -135,18 +767,719 @@ mod tests {
         let kp = Keypair::new(&secp, &mut rng);
         let (pk, _) = kp.x_only_public_key();
 
-        let payload = "valid-schnorr-payload";
+        let payload = claims_payload("valid-schnorr-payload");
         let message_hash = sha256::Hash::hash(payload.as_bytes());
         let message = Message::from_digest(message_hash.to_byte_array());
         let sig = secp.sign_schnorr(&message, &kp);
 
         let verifier = ZkcVerifier::new();
+        verifier
+            .device_registry()
+            .enroll(
+                "conxius-schnorr-123",
+                &hex::encode(kp.public_key().serialize()),
+                0,
+            )
+            .unwrap();
         let attestation = SchnorrAttestation {
             device_id: "conxius-schnorr-123".to_string(),
             signature: hex::encode(sig.as_ref()),
             payload: payload.to_string(),
             x_only_public_key: hex::encode(pk.serialize()),
+            participants: None,
         };
         assert!(verifier.verify_schnorr(&attestation).unwrap());
     }
+
+    fn legacy_verifier() -> ZkcVerifier {
+        use crate::replay::{NonceStore, ReplayPolicy};
+
+        ZkcVerifier::new().with_replay_guard(ReplayGuard::new(
+            ReplayPolicy {
+                allow_legacy_payloads: true,
+                ..ReplayPolicy::default()
+            },
+            NonceStore::in_memory(),
+        ))
+    }
+
+    #[test]
+    fn test_zkc_verify_sip018() {
+        let secp = Secp256k1::new();
+        let (sk, pk) = secp.generate_keypair(&mut thread_rng());
+
+        let domain = sip018::domain("Conxian", "1.0.0", 0x80000000);
+        let sign = |message: &conxian_core::clarity::ClarityValue| {
+            let digest = sip018::message_hash(&domain, message).unwrap();
+            let (recid, compact) = secp
+                .sign_ecdsa_recoverable(&Message::from_digest(digest), &sk)
+                .serialize_compact();
+            let mut signature = compact.to_vec();
+            signature.push(recid.to_i32() as u8);
+            signature
+        };
+        let claims = |nonce: &str| {
+            sip018::claims_message(&serde_json::from_str(&claims_payload(nonce)).unwrap())
+        };
+
+        let expected = StacksAddress::new(
+            TESTNET_SINGLESIG,
+            hash160::Hash::hash(&pk.serialize()).to_byte_array(),
+        )
+        .unwrap();
+        let message = claims("sip018-1");
+        let mut attestation = Sip018Attestation {
+            device_id: "conxius-stx-1".to_string(),
+            domain: domain.clone(),
+            message: message.clone(),
+            signature: hex::encode(sign(&message)),
+            expected_address: expected,
+        };
+
+        // The recovered key must be bound to the device
+        let verifier = ZkcVerifier::new();
+        let err = verifier.verify_sip018(&attestation).unwrap_err();
+        assert!(err.to_string().contains("not enrolled"));
+        verifier
+            .device_registry()
+            .enroll("conxius-stx-1", &hex::encode(pk.serialize()), 0)
+            .unwrap();
+        assert_eq!(verifier.verify_sip018(&attestation).unwrap(), expected);
+        let err = verifier.verify_sip018(&attestation).unwrap_err();
+        assert!(err.to_string().contains("already used"));
+
+        // Legacy v offset is accepted
+        attestation.message = claims("sip018-2");
+        let mut signature = sign(&attestation.message);
+        signature[64] += 27;
+        attestation.signature = hex::encode(&signature);
+        assert!(verifier.verify_sip018(&attestation).is_ok());
+
+        // A different address, or a different message, fails
+        attestation.message = claims("sip018-3");
+        attestation.signature = hex::encode(sign(&attestation.message));
+        attestation.expected_address =
+            StacksAddress::new(MAINNET_SINGLESIG, expected.hash160).unwrap();
+        assert!(verifier.verify_sip018(&attestation).is_err());
+        attestation.expected_address = expected;
+        attestation.message = claims("sip018-4");
+        assert!(verifier.verify_sip018(&attestation).is_err());
+
+        // Raw messages are only accepted in legacy mode
+        attestation.message =
+            conxian_core::clarity::ClarityValue::StringAscii("attest".to_string());
+        attestation.signature = hex::encode(sign(&attestation.message));
+        assert!(verifier.verify_sip018(&attestation).is_err());
+        let legacy = legacy_verifier();
+        legacy
+            .device_registry()
+            .enroll("conxius-stx-1", &hex::encode(pk.serialize()), 0)
+            .unwrap();
+        assert!(legacy.verify_sip018(&attestation).is_ok());
+
+        // Another device's key is rejected
+        let (_, other) = secp.generate_keypair(&mut thread_rng());
+        verifier
+            .device_registry()
+            .rotate("conxius-stx-1", &hex::encode(other.serialize()), None, 1)
+            .unwrap();
+        attestation.message = claims("sip018-5");
+        attestation.signature = hex::encode(sign(&attestation.message));
+        let err = verifier.verify_sip018(&attestation).unwrap_err();
+        assert!(err.to_string().contains("not bound"));
+    }
+
+    /// Key of the BIP-322 reference vectors, for both the P2WPKH and the
+    /// P2TR address.
+    const BIP322_VECTOR_KEY: &str =
+        "02c7f12003196442943d8588e01aee840423cc54fc1521526a3b85c2b0cbd58872";
+
+    fn bip322(address: &str, message: &str, signature: &str) -> Bip322Attestation {
+        Bip322Attestation {
+            device_id: "conxius-btc-1".to_string(),
+            address: address.to_string(),
+            message: message.to_string(),
+            signature: signature.to_string(),
+        }
+    }
+
+    #[test]
+    fn test_zkc_verify_bip322_simple_vectors() {
+        // The reference vectors sign raw messages, accepted in legacy mode only
+        let verifier = legacy_verifier();
+        let segwit_only = legacy_verifier();
+        let segwit = "bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l";
+        let hello = "AkcwRAIgZRfIY3p7/DoVTty6YZbWS71bc5Vct9p9Fia83eRmw2QCICK/ENGfwLtptFluMGs2KsqoNSk89pO7F29zJLUx9a/sASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=";
+        let empty = "AkcwRAIgM2gBAQqvZX15ZiysmKmQpDrG83avLIT492QBzLnQIxYCIBaTpOaD20qRlEylyxFSeEA2ba9YOixpX8z46TSDtS40ASECx/EgAxlkQpQ9hYjgGu6EBCPMVPwVIVJqO4XCsMvViHI=";
+        assert!(verifier
+            .verify_bip322(&bip322(segwit, "Hello World", hello))
+            .is_err());
+        for verifier in [&verifier, &segwit_only] {
+            verifier
+                .device_registry()
+                .enroll("conxius-btc-1", BIP322_VECTOR_KEY, 0)
+                .unwrap();
+        }
+        assert!(verifier
+            .verify_bip322(&bip322(segwit, "Hello World", hello))
+            .unwrap());
+        assert!(ZkcVerifier::new()
+            .verify_bip322(&bip322(segwit, "Hello World", hello))
+            .is_err());
+        assert!(verifier.verify_bip322(&bip322(segwit, "", empty)).unwrap());
+        assert!(verifier.verify_bip322(&bip322(segwit, "", hello)).is_err());
+
+        let taproot = "bc1ppv609nr0vr25u07u95waq5lucwfm6tde4nydujnu8npg4q75mr5sxq8lt3";
+        let sig = "AUHd69PrJQEv+oKTfZ8l+WROBHuy9HKrbFCJu7U1iK2iiEy1vMU5EfMtjc+VSHM7aU0SDbak5IUZRVno2P5mjSafAQ==";
+        assert!(verifier
+            .verify_bip322(&bip322(taproot, "Hello World", sig))
+            .unwrap());
+        assert!(verifier
+            .verify_bip322(&bip322(taproot, "Hello", sig))
+            .is_err());
+
+        // The output key must commit to the device key with no script tree
+        segwit_only
+            .device_registry()
+            .rotate(
+                "conxius-btc-1",
+                &hex::encode(
+                    Secp256k1::new()
+                        .generate_keypair(&mut thread_rng())
+                        .1
+                        .serialize(),
+                ),
+                None,
+                1,
+            )
+            .unwrap();
+        let err = segwit_only
+            .verify_bip322(&bip322(taproot, "Hello World", sig))
+            .unwrap_err();
+        assert!(err.to_string().contains("not bound"));
+    }
+
+    #[test]
+    fn test_zkc_verify_bip322_full_format() {
+        use bitcoin::base64::{engine::general_purpose::STANDARD, Engine};
+        use bitcoin::{consensus, key::TapTweak, Network, Witness};
+
+        let secp = Secp256k1::new();
+        let kp = Keypair::new(&secp, &mut thread_rng());
+        let (internal_key, _) = kp.x_only_public_key();
+        let address = Address::p2tr(&secp, internal_key, None, Network::Testnet);
+        let message = claims_payload("bip322-full");
+
+        let to_spend = bip322::to_spend(&address.script_pubkey(), message.as_bytes());
+        let mut to_sign = bip322::to_sign(&to_spend, Witness::new());
+        // Full signatures may carry their own version
+        to_sign.version = bitcoin::transaction::Version::TWO;
+        let sighash = SighashCache::new(&to_sign)
+            .taproot_key_spend_signature_hash(
+                0,
+                &Prevouts::All(&to_spend.output),
+                TapSighashType::Default,
+            )
+            .unwrap();
+        let tweaked = kp.tap_tweak(&secp, None).to_keypair();
+        let sig = secp.sign_schnorr(&Message::from_digest(sighash.to_byte_array()), &tweaked);
+        to_sign.input[0].witness.push(sig.as_ref());
+        let signature = STANDARD.encode(consensus::serialize(&to_sign));
+
+        let verifier = ZkcVerifier::new();
+        let attestation = bip322(&address.to_string(), &message, &signature);
+        let err = verifier.verify_bip322(&attestation).unwrap_err();
+        assert!(err.to_string().contains("not enrolled"));
+        verifier
+            .device_registry()
+            .enroll(
+                "conxius-btc-1",
+                &hex::encode(kp.public_key().serialize()),
+                0,
+            )
+            .unwrap();
+        assert!(verifier
+            .verify_bip322(&bip322(&address.to_string(), "other", &signature))
+            .is_err());
+        assert!(verifier.verify_bip322(&attestation).unwrap());
+        let err = verifier.verify_bip322(&attestation).unwrap_err();
+        assert!(err.to_string().contains("already used"));
+    }
+
+    #[test]
+    fn test_zkc_verify_bip322_rejects_partial_sighash() {
+        use bitcoin::base64::{engine::general_purpose::STANDARD, Engine};
+        use bitcoin::{consensus, CompressedPublicKey, Network, Witness};
+
+        let secp = Secp256k1::new();
+        let (sk, pk) = secp.generate_keypair(&mut thread_rng());
+        let address = Address::p2wpkh(&CompressedPublicKey(pk), Network::Bitcoin);
+        let message = claims_payload("bip322-sighash-none");
+        let sign = |sighash_type: EcdsaSighashType| {
+            let to_spend = bip322::to_spend(&address.script_pubkey(), message.as_bytes());
+            let mut to_sign = bip322::to_sign(&to_spend, Witness::new());
+            let sighash = SighashCache::new(&to_sign)
+                .p2wpkh_signature_hash(0, &address.script_pubkey(), Amount::ZERO, sighash_type)
+                .unwrap();
+            let signature = bitcoin::ecdsa::Signature {
+                signature: secp.sign_ecdsa(&Message::from_digest(sighash.to_byte_array()), &sk),
+                sighash_type,
+            };
+            to_sign.input[0].witness = Witness::p2wpkh(&signature, &pk);
+            STANDARD.encode(consensus::serialize(&to_sign))
+        };
+
+        let verifier = ZkcVerifier::new();
+        verifier
+            .device_registry()
+            .enroll("conxius-btc-1", &hex::encode(pk.serialize()), 0)
+            .unwrap();
+        let err = verifier
+            .verify_bip322(&bip322(
+                &address.to_string(),
+                &message,
+                &sign(EcdsaSighashType::None),
+            ))
+            .unwrap_err();
+        assert!(err.to_string().contains("Unsupported sighash type"));
+        assert!(verifier
+            .verify_bip322(&bip322(
+                &address.to_string(),
+                &message,
+                &sign(EcdsaSighashType::All),
+            ))
+            .unwrap());
+    }
+
+    #[test]
+    fn test_zkc_verify_bip137() {
+        use bitcoin::base64::{engine::general_purpose::STANDARD, Engine};
+        use bitcoin::{CompressedPublicKey, Network};
+
+        let secp = Secp256k1::new();
+        let (sk, pk) = secp.generate_keypair(&mut thread_rng());
+        let compressed = CompressedPublicKey(pk);
+        let sign = |message: &str, base: u8| {
+            let digest = signed_msg_hash(message);
+            let (recid, compact) = secp
+                .sign_ecdsa_recoverable(&Message::from_digest(digest.to_byte_array()), &sk)
+                .serialize_compact();
+            let mut bytes = vec![base + recid.to_i32() as u8];
+            bytes.extend_from_slice(&compact);
+            STANDARD.encode(bytes)
+        };
+
+        let verifier = ZkcVerifier::new();
+        verifier
+            .device_registry()
+            .enroll("conxius-ledger-1", &hex::encode(pk.serialize()), 0)
+            .unwrap();
+        let addresses = [
+            (Address::p2pkh(compressed, Network::Bitcoin), 31),
+            (Address::p2shwpkh(&compressed, Network::Bitcoin), 35),
+            (Address::p2wpkh(&compressed, Network::Bitcoin), 39),
+        ];
+        for (address, base) in &addresses {
+            let message = claims_payload(&format!("bip137-{}", base));
+            let attestation = Bip137Attestation {
+                device_id: "conxius-ledger-1".to_string(),
+                address: address.to_string(),
+                signature: sign(&message, *base),
+                message,
+            };
+            assert!(verifier.verify_bip137(&attestation).unwrap());
+            let err = verifier.verify_bip137(&attestation).unwrap_err();
+            assert!(err.to_string().contains("already used"));
+        }
+
+        // An uncompressed header recovers a different P2PKH address
+        let message = claims_payload("bip137-uncompressed");
+        let mut attestation = Bip137Attestation {
+            device_id: "conxius-ledger-1".to_string(),
+            address: addresses[0].0.to_string(),
+            signature: sign(&message, 27),
+            message,
+        };
+        assert!(verifier.verify_bip137(&attestation).is_err());
+
+        attestation.signature = sign(&attestation.message, 31);
+        attestation.message = claims_payload("tampered");
+        assert!(verifier.verify_bip137(&attestation).is_err());
+
+        // Raw messages are only accepted in legacy mode
+        attestation.message = "I control this address".to_string();
+        attestation.signature = sign(&attestation.message, 31);
+        assert!(verifier.verify_bip137(&attestation).is_err());
+        let legacy = legacy_verifier();
+        legacy
+            .device_registry()
+            .enroll("conxius-ledger-1", &hex::encode(pk.serialize()), 0)
+            .unwrap();
+        assert!(legacy.verify_bip137(&attestation).unwrap());
+
+        // A valid signature from a key not bound to the device is rejected
+        let (_, other) = secp.generate_keypair(&mut thread_rng());
+        verifier
+            .device_registry()
+            .rotate("conxius-ledger-1", &hex::encode(other.serialize()), None, 1)
+            .unwrap();
+        attestation.message = claims_payload("bip137-rotated");
+        attestation.signature = sign(&attestation.message, 31);
+        let err = verifier.verify_bip137(&attestation).unwrap_err();
+        assert!(err.to_string().contains("not bound"));
+    }
+
+    #[test]
+    fn test_zkc_verify_p256_requires_attested_key() {
+        use crate::hardware::parse_pem_certificates;
+        use crate::hardware::tests::{fixture, verifier as hardware, NOW};
+        use p256::ecdsa::{signature::Signer, Signature as P256Signature, SigningKey};
+        use p256::pkcs8::DecodePrivateKey;
+
+        let key = SigningKey::from_pkcs8_pem(&fixture("android_key.pem")).unwrap();
+        let sign = |nonce: &str| {
+            let payload = claims_payload(nonce);
+            let sig: P256Signature = key.sign(payload.as_bytes());
+            P256Attestation {
+                device_id: "conxius-android-1".to_string(),
+                signature: hex::encode(sig.to_der().as_bytes()),
+                payload,
+            }
+        };
+
+        let verifier = ZkcVerifier::new();
+        let devices = verifier.device_registry();
+        let (_, pk) = Secp256k1::new().generate_keypair(&mut thread_rng());
+        devices
+            .enroll("conxius-android-1", &hex::encode(pk.serialize()), 0)
+            .unwrap();
+        // Enrolled, but the enclave key is not yet attested
+        assert!(verifier.verify_p256(&sign("a")).is_err());
+
+        let chain = parse_pem_certificates(&fixture("android_chain.pem")).unwrap();
+        let attested = hardware()
+            .verify_android("conxius-android-1", &chain, NOW)
+            .unwrap();
+        let record = devices
+            .bind_hardware("conxius-android-1", attested, NOW)
+            .unwrap();
+        assert_eq!(record.history.last().unwrap().action, "attested");
+
+        let attestation = sign("b");
+        assert!(verifier.verify_p256(&attestation).unwrap());
+        assert!(verifier.verify_p256(&attestation).is_err());
+        // Compact signatures are accepted too
+        let payload = claims_payload("c");
+        let sig: P256Signature = key.sign(payload.as_bytes());
+        assert!(verifier
+            .verify_p256(&P256Attestation {
+                device_id: "conxius-android-1".to_string(),
+                signature: hex::encode(sig.to_bytes()),
+                payload,
+            })
+            .unwrap());
+
+        devices.suspend("conxius-android-1", None, NOW).unwrap();
+        assert!(verifier.verify_p256(&sign("d")).is_err());
+    }
+
+    #[test]
+    fn test_zkc_verify_webauthn() {
+        use crate::webauthn::tests::{assertion, es256_key, es256_sign, CREDENTIAL_ID};
+        use conxian_core::PasskeyAlgorithm;
+
+        let verifier = ZkcVerifier::new();
+        let valid = assertion(
+            &claims_payload("w1"),
+            "https://localhost",
+            0x05,
+            1,
+            es256_sign,
+        );
+        // Only registered credentials are accepted
+        assert!(verifier.verify_webauthn(&valid).is_err());
+
+        let key = es256_key().verifying_key().to_encoded_point(true);
+        verifier
+            .passkey_registry()
+            .register(
+                CREDENTIAL_ID,
+                "operator-1",
+                PasskeyAlgorithm::Es256,
+                &hex::encode(key.as_bytes()),
+                0,
+            )
+            .unwrap();
+        assert_eq!(verifier.verify_webauthn(&valid).unwrap(), "operator-1");
+        // A replayed assertion is rejected
+        assert!(verifier.verify_webauthn(&valid).is_err());
+        // As does a fresh payload from a cloned authenticator
+        let cloned = assertion(
+            &claims_payload("w2"),
+            "https://localhost",
+            0x05,
+            1,
+            es256_sign,
+        );
+        assert!(verifier.verify_webauthn(&cloned).is_err());
+        let next = assertion(
+            &claims_payload("w3"),
+            "https://localhost",
+            0x05,
+            2,
+            es256_sign,
+        );
+        assert!(verifier.verify_webauthn(&next).is_ok());
+
+        // Replaying a payload under a higher counter leaves the counter alone
+        let replayed = assertion(
+            &claims_payload("w3"),
+            "https://localhost",
+            0x05,
+            10,
+            es256_sign,
+        );
+        let err = verifier.verify_webauthn(&replayed).unwrap_err();
+        assert!(err.to_string().contains("already used"));
+        let credential = verifier.passkey_registry().get(CREDENTIAL_ID).unwrap();
+        assert_eq!(credential.sign_count, 2);
+        let after = assertion(
+            &claims_payload("w4"),
+            "https://localhost",
+            0x05,
+            3,
+            es256_sign,
+        );
+        assert!(verifier.verify_webauthn(&after).is_ok());
+    }
+
+    #[test]
+    fn test_zkc_verify_schnorr_batch() {
+        let secp = Secp256k1::new();
+        let verifier = ZkcVerifier::new();
+        let keys: Vec<_> = (0..4)
+            .map(|i| {
+                let kp = Keypair::new(&secp, &mut thread_rng());
+                verifier
+                    .device_registry()
+                    .enroll(
+                        &format!("conxius-batch-{}", i),
+                        &hex::encode(kp.public_key().serialize()),
+                        0,
+                    )
+                    .unwrap();
+                kp
+            })
+            .collect();
+        let sign = |i: usize, nonce: &str| {
+            let payload = claims_payload(nonce);
+            let message =
+                Message::from_digest(sha256::Hash::hash(payload.as_bytes()).to_byte_array());
+            SchnorrAttestation {
+                device_id: format!("conxius-batch-{}", i),
+                signature: hex::encode(secp.sign_schnorr(&message, &keys[i]).as_ref()),
+                payload,
+                x_only_public_key: hex::encode(keys[i].x_only_public_key().0.serialize()),
+                participants: None,
+            }
+        };
+
+        let attestations: Vec<_> = (0..4).map(|i| sign(i, &format!("a{}", i))).collect();
+        let results = verifier.verify_schnorr_batch(&attestations[..2]);
+        assert!(results.iter().all(|r| *r.as_ref().unwrap()));
+        // Replays are caught per item even when the batch equation holds
+        let results = verifier.verify_schnorr_batch(&attestations);
+        assert!(results[0].is_err() && results[1].is_err());
+        assert!(results[2].is_ok() && results[3].is_ok());
+
+        // A bad signature is isolated without failing its neighbours
+        let mut mixed: Vec<_> = (0..4).map(|i| sign(i, &format!("b{}", i))).collect();
+        mixed[2].payload = claims_payload("tampered");
+        let results = verifier.verify_schnorr_batch(&mixed);
+        assert!(results[0].is_ok() && results[1].is_ok() && results[3].is_ok());
+        assert!(results[2].is_err());
+    }
+
+    #[test]
+    fn test_zkc_verify_threshold() {
+        use conxian_core::ThresholdPolicy;
+
+        let secp = Secp256k1::new();
+        let verifier = ZkcVerifier::new();
+        let keys: Vec<_> = (0..4)
+            .map(|i| {
+                let kp = Keypair::new(&secp, &mut thread_rng());
+                verifier
+                    .device_registry()
+                    .enroll(
+                        &format!("conxius-signer-{}", i),
+                        &hex::encode(kp.public_key().serialize()),
+                        0,
+                    )
+                    .unwrap();
+                kp
+            })
+            .collect();
+        let policy = ThresholdPolicy {
+            threshold: 2,
+            signers: (0..3).map(|i| format!("conxius-signer-{}", i)).collect(),
+        };
+        let payload = claims_payload("threshold-1");
+        let message = Message::from_digest(sha256::Hash::hash(payload.as_bytes()).to_byte_array());
+        let ecdsa = |i: usize| ThresholdSignature {
+            device_id: format!("conxius-signer-{}", i),
+            scheme: SignatureScheme::Ecdsa,
+            signature: hex::encode(
+                secp.sign_ecdsa(&message, &keys[i].secret_key())
+                    .serialize_der(),
+            ),
+        };
+        let schnorr = |i: usize| ThresholdSignature {
+            device_id: format!("conxius-signer-{}", i),
+            scheme: SignatureScheme::Schnorr,
+            signature: hex::encode(secp.sign_schnorr(&message, &keys[i]).as_ref()),
+        };
+        let attestation = |signatures| ThresholdAttestation {
+            policy: policy.clone(),
+            payload: payload.clone(),
+            signatures,
+        };
+
+        // Duplicates and signers outside the policy do not count
+        let short = attestation(vec![ecdsa(0), schnorr(0), ecdsa(3)]);
+        let err = verifier.verify_threshold(&short).unwrap_err();
+        assert!(err.to_string().contains("1 of 2"));
+
+        let mut forged = schnorr(2);
+        forged.signature = schnorr(1).signature;
+        let report = verifier
+            .verify_threshold(&attestation(vec![ecdsa(0), forged, ecdsa(3), schnorr(1)]))
+            .unwrap();
+        assert_eq!(report.counted, ["conxius-signer-0", "conxius-signer-1"]);
+        let rejected: Vec<_> = report
+            .rejected
+            .iter()
+            .map(|r| r.device_id.as_str())
+            .collect();
+        assert_eq!(rejected, ["conxius-signer-2", "conxius-signer-3"]);
+
+        // The approval cannot be replayed
+        assert!(verifier
+            .verify_threshold(&attestation(vec![ecdsa(0), ecdsa(1)]))
+            .is_err());
+
+        let mut bad_policy = attestation(vec![ecdsa(0)]);
+        bad_policy.policy.threshold = 4;
+        assert!(verifier.verify_threshold(&bad_policy).is_err());
+    }
+
+    #[test]
+    fn test_zkc_verify_musig2_schnorr() {
+        use secp256k1::{Scalar, SecretKey};
+
+        let secp = Secp256k1::new();
+        let secrets: Vec<SecretKey> = (0..3).map(|_| SecretKey::new(&mut thread_rng())).collect();
+        let keys = musig::key_sort(
+            &secrets
+                .iter()
+                .map(|sk| PublicKey::from_secret_key(&secp, sk))
+                .collect::<Vec<_>>(),
+        );
+        let aggregate = musig::key_agg(&keys).unwrap();
+
+        // The signature the participants would jointly produce is a plain
+        // BIP-340 signature by the aggregate secret sum(a_i * x_i)
+        let coefficients = musig::coefficients(&keys).unwrap();
+        let mut aggregate_secret: Option<SecretKey> = None;
+        for (key, a) in keys.iter().zip(coefficients) {
+            let sk = secrets
+                .iter()
+                .find(|sk| PublicKey::from_secret_key(&secp, sk) == *key)
+                .unwrap();
+            let term = sk.mul_tweak(&a).unwrap();
+            aggregate_secret = Some(match aggregate_secret {
+                None => term,
+                Some(sum) => sum.add_tweak(&Scalar::from(term)).unwrap(),
+            });
+        }
+        let kp = Keypair::from_secret_key(&secp, &aggregate_secret.unwrap());
+        assert_eq!(kp.x_only_public_key().0, aggregate);
+
+        let verifier = ZkcVerifier::new();
+        let sign = |nonce: &str, participants: Vec<String>| {
+            let payload = claims_payload(nonce);
+            let message =
+                Message::from_digest(sha256::Hash::hash(payload.as_bytes()).to_byte_array());
+            SchnorrAttestation {
+                device_id: "conxius-vault-1".to_string(),
+                signature: hex::encode(secp.sign_schnorr(&message, &kp).as_ref()),
+                payload,
+                x_only_public_key: hex::encode(aggregate.serialize()),
+                participants: Some(participants),
+            }
+        };
+        // Participants may be given in any order
+        let mut participants: Vec<String> =
+            keys.iter().map(|k| hex::encode(k.serialize())).collect();
+        participants.reverse();
+
+        // The aggregate must be the vault's registered internal key
+        assert!(verifier
+            .verify_schnorr(&sign("m1", participants.clone()))
+            .is_err());
+        verifier
+            .device_registry()
+            .enroll(
+                "conxius-vault-1",
+                &hex::encode(aggregate.public_key(secp256k1::Parity::Even).serialize()),
+                0,
+            )
+            .unwrap();
+        assert!(verifier
+            .verify_schnorr(&sign("m2", participants.clone()))
+            .unwrap());
+
+        // A different participant set does not aggregate to the key
+        let subset = participants[..2].to_vec();
+        assert!(verifier.verify_schnorr(&sign("m3", subset)).is_err());
+    }
+
+    #[test]
+    fn test_verify_ring_membership() {
+        let secp = Secp256k1::new();
+        let verifier = ZkcVerifier::new();
+        let devices = verifier.device_registry();
+        let secrets: Vec<secp256k1::SecretKey> = (0..3)
+            .map(|i| {
+                let (sk, pk) = secp.generate_keypair(&mut thread_rng());
+                devices
+                    .enroll(
+                        &format!("conxius-ring-{}", i),
+                        &hex::encode(pk.serialize()),
+                        0,
+                    )
+                    .unwrap();
+                sk
+            })
+            .collect();
+
+        let ring = devices.ring();
+        let attestation =
+            zkc_prover::prove_membership(&ring, &secrets[1], &claims_payload("ring-1")).unwrap();
+        assert_eq!(verifier.verify_ring(&attestation).unwrap(), 3);
+        assert!(verifier.verify_ring(&attestation).is_err());
+
+        // A revoked device leaves the ring, and proofs under the old root fail
+        let attestation =
+            zkc_prover::prove_membership(&ring, &secrets[2], &claims_payload("ring-2")).unwrap();
+        devices.revoke("conxius-ring-2", None, 1).unwrap();
+        assert_eq!(devices.ring().len(), 2);
+        assert!(verifier.verify_ring(&attestation).is_err());
+        assert!(zkc_prover::prove_membership(
+            &devices.ring(),
+            &secrets[2],
+            &claims_payload("ring-3")
+        )
+        .is_err());
+    }
 }
@@ -0,0 +1,409 @@
+use crate::bitcoin::{AnchorMatch, SharedHeaderIndex};
+use crate::stacks::{StacksBlockInfo, StacksRpc};
+use conxian_core::{
+    AlertSeverity, AnchorState, ConxianError, ConxianResult, SharedState, TransactionInfo,
+};
+use std::collections::{BTreeMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::time::{sleep, Duration};
+use tracing::{error, info, warn};
+
+/// Number of recent Stacks blocks re-checked against the Bitcoin header index.
+pub const DEFAULT_ANCHOR_WINDOW: u64 = 32;
+
+/// Cross-checks the burn block of each Stacks block against the Bitcoin
+/// header index maintained by the `BitcoinListener`.
+pub struct AnchorMonitor<R: StacksRpc> {
+    rpc: R,
+    state: SharedState,
+    index: SharedHeaderIndex,
+    window: u64,
+    recent: BTreeMap<u64, StacksBlockInfo>,
+    alerted: HashSet<String>,
+}
+
+impl<R: StacksRpc> AnchorMonitor<R> {
+    pub fn new(rpc: R, state: SharedState, index: SharedHeaderIndex) -> Self {
+        Self {
+            rpc,
+            state,
+            index,
+            window: DEFAULT_ANCHOR_WINDOW,
+            recent: BTreeMap::new(),
+            alerted: HashSet::new(),
+        }
+    }
+
+    pub async fn check_once(&mut self) -> ConxianResult<()> {
+        let (stacks_tip, bitcoin_tip) = {
+            let s = self.state.read().unwrap();
+            (s.stacks.height, s.bitcoin.height)
+        };
+        if stacks_tip == 0 {
+            return Ok(());
+        }
+
+        self.fetch_new_blocks(stacks_tip).await?;
+
+        let mut anchor = AnchorState {
+            status: "anchored".to_string(),
+            stacks_height: stacks_tip,
+            last_checked: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            ..AnchorState::default()
+        };
+        let mut new_alerts = Vec::new();
+
+        {
+            let index = self.index.read().unwrap();
+            for block in self.recent.values() {
+                match index.lookup(block.burn_block_height, &block.burn_block_hash) {
+                    AnchorMatch::Anchored => anchor.anchored_blocks += 1,
+                    AnchorMatch::Unknown => anchor.unknown_anchors += 1,
+                    AnchorMatch::Mismatch { expected } => {
+                        anchor.mismatched_anchors += 1;
+                        if self.alerted.insert(block.hash.clone()) {
+                            new_alerts.push((
+                                AlertSeverity::Warning,
+                                format!(
+                                    "Stacks block {} ({}) reports burn block {} at height {}, Bitcoin best chain has {}",
+                                    block.height,
+                                    block.hash,
+                                    block.burn_block_hash,
+                                    block.burn_block_height,
+                                    expected
+                                ),
+                            ));
+                        }
+                    }
+                    AnchorMatch::Orphaned => {
+                        anchor.orphaned_anchors += 1;
+                        if self.alerted.insert(block.hash.clone()) {
+                            new_alerts.push((
+                                AlertSeverity::Critical,
+                                format!(
+                                    "Stacks block {} ({}) is anchored to orphaned Bitcoin block {} at height {}",
+                                    block.height,
+                                    block.hash,
+                                    block.burn_block_hash,
+                                    block.burn_block_height
+                                ),
+                            ));
+                        }
+                    }
+                }
+            }
+
+            if let Some(tip) = self.recent.values().next_back() {
+                anchor.burn_block_height = tip.burn_block_height;
+                anchor.burn_block_hash = tip.burn_block_hash.clone();
+                anchor.lag_blocks = bitcoin_tip.saturating_sub(tip.burn_block_height);
+                anchor.status = match index.lookup(tip.burn_block_height, &tip.burn_block_hash) {
+                    AnchorMatch::Anchored => "anchored",
+                    AnchorMatch::Orphaned => "orphaned",
+                    AnchorMatch::Mismatch { .. } => "mismatch",
+                    AnchorMatch::Unknown => "unverified",
+                }
+                .to_string();
+            }
+        }
+
+        // Forget alert keys for blocks that have left the window
+        let recent = &self.recent;
+        self.alerted
+            .retain(|hash| recent.values().any(|b| &b.hash == hash));
+
+        {
+            let mut state = self.state.write().unwrap();
+            for (severity, message) in new_alerts {
+                warn!("{}", message);
+                state.raise_alert("anchor", severity, message);
+            }
+            state.anchor = anchor;
+        }
+
+        self.track_transactions(stacks_tip).await;
+        Ok(())
+    }
+
+    /// Follows Stacks transactions submitted through the gateway until they
+    /// are included in a block whose burn block is on the Bitcoin best chain.
+    async fn track_transactions(&self, stacks_tip: u64) {
+        let tracked: Vec<TransactionInfo> = self
+            .state
+            .read()
+            .unwrap()
+            .transactions
+            .values()
+            .filter(|tx| tx.chain == "stacks" && !tx.is_final())
+            .cloned()
+            .collect();
+
+        for mut tx in tracked {
+            if matches!(tx.status.as_str(), "pending" | "confirmed") {
+                if let Err(e) = self.refresh_inclusion(&mut tx).await {
+                    error!("Failed to refresh Stacks transaction {}: {}", tx.txid, e);
+                    continue;
+                }
+            }
+
+            let previous = tx.status.clone();
+            if let (Some(height), Some(hash)) = (tx.burn_block_height, &tx.burn_block_hash) {
+                if matches!(previous.as_str(), "confirmed" | "anchored" | "orphaned") {
+                    tx.status = match self.index.read().unwrap().lookup(height, hash) {
+                        AnchorMatch::Anchored => "anchored",
+                        AnchorMatch::Orphaned => "orphaned",
+                        _ => "confirmed",
+                    }
+                    .to_string();
+                }
+            }
+            if let Some(height) = tx.block_height {
+                tx.confirmations = stacks_tip.saturating_sub(height).saturating_add(1) as u32;
+            }
+
+            let mut state = self.state.write().unwrap();
+            if tx.status == "orphaned" && previous != "orphaned" {
+                state.raise_alert(
+                    "anchor",
+                    AlertSeverity::Critical,
+                    format!(
+                        "Stacks transaction {} is anchored to an orphaned Bitcoin block",
+                        tx.txid
+                    ),
+                );
+            }
+            state.transactions.insert(tx.txid.clone(), tx);
+        }
+    }
+
+    async fn refresh_inclusion(&self, tx: &mut TransactionInfo) -> ConxianResult<()> {
+        let status = self.rpc.get_transaction_status(&tx.txid).await?;
+        match status.tx_status.as_str() {
+            "pending" => {}
+            s if s.starts_with("dropped") => tx.status = "dropped".to_string(),
+            s => {
+                let Some(height) = status.block_height else {
+                    return Ok(());
+                };
+                let block = match self.recent.get(&height) {
+                    Some(block) => block.clone(),
+                    None => self.rpc.get_block(height).await?,
+                };
+                tx.block_height = Some(height);
+                tx.block_hash = status.block_hash;
+                tx.burn_block_height = Some(block.burn_block_height);
+                tx.burn_block_hash = Some(block.burn_block_hash);
+                tx.status = if s == "success" {
+                    "confirmed"
+                } else {
+                    "failed"
+                }
+                .to_string();
+            }
+        }
+        Ok(())
+    }
+
+    /// Fetches Stacks blocks up to `tip`, refetching the window if the chain
+    /// no longer links to the blocks seen previously.
+    async fn fetch_new_blocks(&mut self, tip: u64) -> ConxianResult<()> {
+        let floor = tip.saturating_sub(self.window - 1).max(1);
+
+        for _ in 0..2 {
+            let start = match self.recent.keys().next_back() {
+                Some(&last) if last >= floor && last <= tip => last + 1,
+                _ => {
+                    self.recent.clear();
+                    floor
+                }
+            };
+
+            let mut forked = false;
+            for h in start..=tip {
+                let block = self.rpc.get_block(h).await?;
+                if let Some(parent) = self.recent.get(&(h - 1)) {
+                    if parent.hash != block.parent_block_hash {
+                        forked = true;
+                        break;
+                    }
+                }
+                self.recent.insert(h, block);
+            }
+
+            if !forked {
+                while self.recent.len() as u64 > self.window {
+                    self.recent.pop_first();
+                }
+                return Ok(());
+            }
+            info!(
+                "Stacks fork detected below height {}, refreshing window",
+                tip
+            );
+            self.recent.clear();
+        }
+
+        Err(ConxianError::Stacks(
+            "Stacks chain changed while checking anchors".to_string(),
+        ))
+    }
+
+    pub async fn run(&mut self) -> ConxianResult<()> {
+        info!("Starting Stacks/Bitcoin anchor monitor...");
+
+        loop {
+            if let Err(e) = self.check_once().await {
+                error!("Failed to check Stacks anchors: {}", e);
+                self.state.write().unwrap().anchor.status = format!("error: {}", e);
+            }
+            sleep(Duration::from_secs(30)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::bitcoin::HeaderIndex;
+    use crate::stacks::block::NakamotoBlockHeader;
+    use crate::stacks::rpc::{StacksNetworkInfo, StacksPoxInfo, StacksSigner, StacksTxStatus};
+    use async_trait::async_trait;
+    use conxian_core::clarity::ClarityValue;
+    use conxian_core::{GatewayState, MempoolTx, StacksAccount};
+    use std::sync::{Arc, RwLock};
+
+    struct MockStacksRpc;
+
+    #[async_trait]
+    impl StacksRpc for MockStacksRpc {
+        async fn get_block_count(&self) -> ConxianResult<u64> {
+            Ok(20)
+        }
+        async fn get_network_info(&self) -> ConxianResult<StacksNetworkInfo> {
+            Ok(StacksNetworkInfo {
+                height: 20,
+                network: "mainnet".to_string(),
+                epoch: "3.0".to_string(),
+                burn_block_height: 2,
+            })
+        }
+        async fn get_block(&self, height: u64) -> ConxianResult<StacksBlockInfo> {
+            Ok(StacksBlockInfo {
+                height,
+                hash: format!("0xstx{}", height),
+                parent_block_hash: format!("0xstx{}", height - 1),
+                burn_block_height: height / 10,
+                burn_block_hash: format!("0xbtc{}", height / 10),
+            })
+        }
+        async fn broadcast_transaction(&self, _raw: &[u8]) -> ConxianResult<String> {
+            Ok("txid".to_string())
+        }
+        async fn get_transaction_status(&self, txid: &str) -> ConxianResult<StacksTxStatus> {
+            let included = txid == "mined";
+            Ok(StacksTxStatus {
+                tx_status: if included { "success" } else { "pending" }.to_string(),
+                block_height: included.then_some(15),
+                block_hash: included.then(|| "0xstx15".to_string()),
+            })
+        }
+        async fn get_account(&self, _principal: &str) -> ConxianResult<StacksAccount> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_pox_info(&self) -> ConxianResult<StacksPoxInfo> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_signer_set(&self, _cycle: u64) -> ConxianResult<Vec<StacksSigner>> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_block_header(&self, _height: u64) -> ConxianResult<NakamotoBlockHeader> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn call_read_only(
+            &self,
+            _contract: &str,
+            _function: &str,
+            _args: &[ClarityValue],
+        ) -> ConxianResult<ClarityValue> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_mempool_transactions(
+            &self,
+            _principal: &str,
+        ) -> ConxianResult<Vec<MempoolTx>> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+    }
+
+    fn setup() -> (SharedState, SharedHeaderIndex) {
+        let state = Arc::new(RwLock::new(GatewayState::default()));
+        {
+            let mut s = state.write().unwrap();
+            s.stacks.height = 20;
+            s.bitcoin.height = 3;
+        }
+        let mut index = HeaderIndex::new(10);
+        for h in 0..=3 {
+            index.insert(h, &format!("btc{}", h));
+        }
+        (state, Arc::new(RwLock::new(index)))
+    }
+
+    #[tokio::test]
+    async fn test_anchor_monitor_anchored() {
+        let (state, index) = setup();
+        let mut monitor = AnchorMonitor::new(MockStacksRpc, state.clone(), index);
+
+        monitor.check_once().await.unwrap();
+
+        let s = state.read().unwrap();
+        assert_eq!(s.anchor.status, "anchored");
+        assert_eq!(s.anchor.burn_block_height, 2);
+        assert_eq!(s.anchor.lag_blocks, 1);
+        assert_eq!(s.anchor.anchored_blocks, 20);
+        assert!(s.alerts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_anchor_monitor_flags_orphaned_anchor() {
+        let (state, index) = setup();
+        index.write().unwrap().insert(2, "btc2-reorg");
+        let mut monitor = AnchorMonitor::new(MockStacksRpc, state.clone(), index);
+
+        monitor.check_once().await.unwrap();
+        monitor.check_once().await.unwrap();
+
+        let s = state.read().unwrap();
+        assert_eq!(s.anchor.status, "orphaned");
+        assert_eq!(s.anchor.orphaned_anchors, 1);
+        // Alerts are raised once per Stacks block
+        assert_eq!(s.alerts.len(), 1);
+        assert_eq!(s.alerts[0].severity, AlertSeverity::Critical);
+    }
+
+    #[tokio::test]
+    async fn test_anchor_monitor_tracks_transactions() {
+        let (state, index) = setup();
+        {
+            let mut s = state.write().unwrap();
+            for txid in ["mined", "waiting"] {
+                s.transactions
+                    .insert(txid.to_string(), TransactionInfo::pending("stacks", txid));
+            }
+        }
+        let mut monitor = AnchorMonitor::new(MockStacksRpc, state.clone(), index);
+
+        monitor.check_once().await.unwrap();
+
+        let s = state.read().unwrap();
+        let mined = &s.transactions["mined"];
+        assert_eq!(mined.status, "anchored");
+        assert_eq!(mined.block_height, Some(15));
+        assert_eq!(mined.burn_block_height, Some(1));
+        assert_eq!(mined.confirmations, 6);
+        assert_eq!(s.transactions["waiting"].status, "pending");
+    }
+}
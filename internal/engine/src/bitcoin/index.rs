@@ -0,0 +1,171 @@
+use std::collections::{BTreeMap, HashMap};
+use std::sync::{Arc, RwLock};
+
+/// Default number of recent Bitcoin headers kept in the index.
+pub const DEFAULT_INDEX_CAPACITY: usize = 2016;
+
+/// Outcome of looking up a burn block in the header index.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum AnchorMatch {
+    /// The hash is on the best chain at the given height.
+    Anchored,
+    /// The hash was on the best chain but has since been reorganised out.
+    Orphaned,
+    /// The best chain has a different block at this height.
+    Mismatch { expected: String },
+    /// The height is outside the indexed range.
+    Unknown,
+}
+
+/// Best-chain index of recent Bitcoin block hashes by height.
+pub struct HeaderIndex {
+    headers: BTreeMap<u64, String>,
+    orphaned: HashMap<String, u64>,
+    capacity: usize,
+}
+
+pub type SharedHeaderIndex = Arc<RwLock<HeaderIndex>>;
+
+impl Default for HeaderIndex {
+    fn default() -> Self {
+        Self::new(DEFAULT_INDEX_CAPACITY)
+    }
+}
+
+impl HeaderIndex {
+    pub fn new(capacity: usize) -> Self {
+        Self {
+            headers: BTreeMap::new(),
+            orphaned: HashMap::new(),
+            capacity: capacity.max(1),
+        }
+    }
+
+    /// Inserts a best-chain header. A different hash previously stored at the
+    /// same height is moved to the orphan set and returned.
+    pub fn insert(&mut self, height: u64, hash: &str) -> Option<String> {
+        let hash = normalize_hash(hash);
+        self.orphaned.remove(&hash);
+        let replaced = match self.headers.insert(height, hash.clone()) {
+            Some(old) if old != hash => {
+                self.orphaned.insert(old.clone(), height);
+                Some(old)
+            }
+            _ => None,
+        };
+
+        while self.headers.len() > self.capacity {
+            self.headers.pop_first();
+        }
+        if let Some(low) = self.lowest_height() {
+            self.orphaned.retain(|_, h| *h >= low);
+        }
+        replaced
+    }
+
+    /// Moves every header above `height` to the orphan set, as when the best
+    /// chain forks at `height`, and returns their hashes.
+    pub fn truncate_above(&mut self, height: u64) -> Vec<String> {
+        let removed: Vec<(u64, String)> =
+            self.headers.split_off(&(height + 1)).into_iter().collect();
+        for (h, hash) in &removed {
+            self.orphaned.insert(hash.clone(), *h);
+        }
+        removed.into_iter().map(|(_, hash)| hash).collect()
+    }
+
+    pub fn capacity(&self) -> usize {
+        self.capacity
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.headers.is_empty()
+    }
+
+    pub fn hash_at(&self, height: u64) -> Option<&str> {
+        self.headers.get(&height).map(|h| h.as_str())
+    }
+
+    pub fn tip_height(&self) -> Option<u64> {
+        self.headers.keys().next_back().copied()
+    }
+
+    pub fn lowest_height(&self) -> Option<u64> {
+        self.headers.keys().next().copied()
+    }
+
+    pub fn is_orphaned(&self, hash: &str) -> bool {
+        self.orphaned.contains_key(&normalize_hash(hash))
+    }
+
+    pub fn lookup(&self, height: u64, hash: &str) -> AnchorMatch {
+        let hash = normalize_hash(hash);
+        if self.orphaned.contains_key(&hash) {
+            return AnchorMatch::Orphaned;
+        }
+        match self.headers.get(&height) {
+            Some(h) if *h == hash => AnchorMatch::Anchored,
+            Some(h) => AnchorMatch::Mismatch {
+                expected: h.clone(),
+            },
+            None => AnchorMatch::Unknown,
+        }
+    }
+}
+
+/// Bitcoin Core reports bare hex hashes while Stacks APIs prefix them with `0x`.
+pub fn normalize_hash(hash: &str) -> String {
+    hash.trim_start_matches("0x").to_ascii_lowercase()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_header_index_lookup_and_orphans() {
+        let mut index = HeaderIndex::new(10);
+        index.insert(100, "aa");
+        index.insert(101, "bb");
+
+        assert_eq!(index.lookup(100, "0xAA"), AnchorMatch::Anchored);
+        assert_eq!(index.lookup(102, "cc"), AnchorMatch::Unknown);
+
+        // Reorg at height 101
+        assert_eq!(index.insert(101, "cc"), Some("bb".to_string()));
+        assert_eq!(index.lookup(101, "bb"), AnchorMatch::Orphaned);
+        assert_eq!(
+            index.lookup(101, "dd"),
+            AnchorMatch::Mismatch {
+                expected: "cc".to_string()
+            }
+        );
+    }
+
+    #[test]
+    fn test_header_index_truncate_above_fork() {
+        let mut index = HeaderIndex::new(10);
+        for (h, hash) in [(100, "aa"), (101, "bb"), (102, "cc")] {
+            index.insert(h, hash);
+        }
+
+        // The new best chain is shorter than the indexed one
+        assert_eq!(index.truncate_above(100), vec!["bb", "cc"]);
+        assert_eq!(index.tip_height(), Some(100));
+        assert_eq!(index.lookup(102, "cc"), AnchorMatch::Orphaned);
+        assert_eq!(index.lookup(102, "dd"), AnchorMatch::Unknown);
+        assert!(index.truncate_above(100).is_empty());
+    }
+
+    #[test]
+    fn test_header_index_capacity() {
+        let mut index = HeaderIndex::new(2);
+        index.insert(1, "a");
+        index.insert(2, "b");
+        index.insert(3, "c");
+
+        assert_eq!(index.lowest_height(), Some(2));
+        assert_eq!(index.tip_height(), Some(3));
+        assert_eq!(index.hash_at(1), None);
+    }
+}
@@ -1,8 +1,11 @@
-use crate::bitcoin::BitcoinRpc;
-use conxian_core::{ConxianResult, Persistence, PersistentState, SharedState};
-use std::sync::Arc;
+use crate::bitcoin::index::{HeaderIndex, SharedHeaderIndex};
+use crate::bitcoin::{BitcoinRpc, Notary};
+use conxian_core::{
+    AlertSeverity, BlockInfo, ConxianResult, Persistence, PersistentState, SharedState,
+};
+use std::sync::{Arc, RwLock};
 use tokio::time::{sleep, Duration};
-use tracing::{error, info};
+use tracing::{error, info, warn};
 
 pub struct BitcoinListener<R: BitcoinRpc> {
     rpc: R,
@@ -10,6 +13,8 @@ pub struct BitcoinListener<R: BitcoinRpc> {
     persistence: Arc<dyn Persistence>,
     last_height: u64,
     network: Option<String>,
+    index: SharedHeaderIndex,
+    notary: Option<Arc<Notary>>,
 }
 
 impl<R: BitcoinRpc> BitcoinListener<R> {
@@ -21,6 +26,95 @@ impl<R: BitcoinRpc> BitcoinListener<R> {
             persistence,
             last_height,
             network: None,
+            index: Arc::new(RwLock::new(HeaderIndex::default())),
+            notary: None,
+        }
+    }
+
+    /// Tracks confirmation of the notary's commitment transactions.
+    pub fn with_notary(mut self, notary: Arc<Notary>) -> Self {
+        self.notary = Some(notary);
+        self
+    }
+
+    /// Confirms commitments included in `block`, with a proof of inclusion.
+    async fn check_commitments(&self, block: &BlockInfo) -> ConxianResult<()> {
+        let Some(notary) = &self.notary else {
+            return Ok(());
+        };
+        let awaiting = notary.awaiting_confirmation();
+        if awaiting.is_empty() {
+            return Ok(());
+        }
+        let txids = self.rpc.get_block_txids(&block.hash).await?;
+        for txid in awaiting.iter().filter(|txid| txids.contains(txid)) {
+            let proof = self.rpc.get_tx_out_proof(txid, &block.hash).await?;
+            notary.confirm(txid, &block.hash, block.height, &proof);
+        }
+        Ok(())
+    }
+
+    /// Shared handle to the best-chain header index maintained by this listener.
+    pub fn header_index(&self) -> SharedHeaderIndex {
+        self.index.clone()
+    }
+
+    /// Fills an empty index with the best-chain headers up to the sync point,
+    /// so that anchors older than the listener can be checked.
+    async fn backfill(&self) -> ConxianResult<()> {
+        let capacity = {
+            let index = self.index.read().unwrap();
+            if !index.is_empty() {
+                return Ok(());
+            }
+            index.capacity() as u64
+        };
+        let current = self.rpc.get_block_count().await?;
+        let end = match self.last_height {
+            0 => current,
+            last => last.min(current),
+        };
+        let start = end.saturating_sub(capacity - 1);
+
+        let mut blocks = Vec::new();
+        for h in start..=end {
+            blocks.push(self.rpc.get_block_info(h).await?);
+        }
+        let mut index = self.index.write().unwrap();
+        for block in blocks {
+            index.insert(block.height, &block.hash);
+        }
+        info!("Indexed Bitcoin headers {} to {}", start, end);
+        Ok(())
+    }
+
+    /// Walks back from the indexed tip and returns the highest height still on
+    /// the node's best chain, or `None` if the indexed tip is unchanged.
+    async fn find_fork_point(&self) -> ConxianResult<Option<u64>> {
+        let (tip, low) = {
+            let index = self.index.read().unwrap();
+            match (index.tip_height(), index.lowest_height()) {
+                (Some(tip), Some(low)) => (tip, low),
+                _ => return Ok(None),
+            }
+        };
+
+        // A best chain shorter than the index has forked at or below its tip
+        let current = self.rpc.get_block_count().await?;
+        if current < low {
+            return Ok(Some(current));
+        }
+        let mut h = tip.min(current);
+        loop {
+            let block = self.rpc.get_block_info(h).await?;
+            let matches = self.index.read().unwrap().hash_at(h) == Some(block.hash.as_str());
+            if matches {
+                return Ok(if h == tip { None } else { Some(h) });
+            }
+            if h == low {
+                return Ok(Some(low.saturating_sub(1)));
+            }
+            h -= 1;
         }
     }
 
@@ -32,18 +126,69 @@ impl<R: BitcoinRpc> BitcoinListener<R> {
             }
         }
 
+        if let Err(e) = self.backfill().await {
+            error!("Failed to backfill Bitcoin header index: {}", e);
+        }
+
+        let fork_point = if self.last_height > 0 {
+            self.find_fork_point().await.unwrap_or_else(|e| {
+                error!("Failed to check Bitcoin chain for reorgs: {}", e);
+                None
+            })
+        } else {
+            None
+        };
+
+        if let Some(fork) = fork_point {
+            let depth = self.last_height.saturating_sub(fork);
+            warn!("Bitcoin reorg detected: {} block(s) above {}", depth, fork);
+            self.state.write().unwrap().raise_alert(
+                "bitcoin",
+                AlertSeverity::Warning,
+                format!(
+                    "Chain reorganisation of {} block(s) above height {}",
+                    depth, fork
+                ),
+            );
+            if let Some(notary) = &self.notary {
+                notary.unconfirm_above(fork);
+            }
+
+            // Blocks above the fork are orphaned even if none replace them
+            let best = {
+                let mut index = self.index.write().unwrap();
+                index.truncate_above(fork);
+                index.hash_at(fork).map(str::to_string)
+            };
+            let mut state = self.state.write().unwrap();
+            state.bitcoin.height = fork;
+            if let Some(hash) = best {
+                state.bitcoin.best_block_hash = hash;
+            }
+            self.last_height = fork;
+        }
+
         match self.rpc.get_block_count().await {
             Ok(current_height) => {
                 if current_height > self.last_height || self.last_height == 0 {
-                    let start_h = if self.last_height == 0 {
-                        current_height
-                    } else {
-                        self.last_height + 1
+                    let start_h = match self.last_height {
+                        0 => current_height,
+                        last => last + 1,
                     };
                     for h in start_h..=current_height {
                         match self.rpc.get_block_info(h).await {
                             Ok(block) => {
                                 info!("New Bitcoin block: {} ({})", block.height, block.hash);
+                                self.index
+                                    .write()
+                                    .unwrap()
+                                    .insert(block.height, &block.hash);
+                                if let Err(e) = self.check_commitments(&block).await {
+                                    error!(
+                                        "Failed to check commitments in block {}: {}",
+                                        block.hash, e
+                                    );
+                                }
                                 let mut state = self.state.write().unwrap();
                                 state.bitcoin.height = block.height;
                                 state.bitcoin.last_updated = block.timestamp;
@@ -95,11 +240,14 @@ impl<R: BitcoinRpc> BitcoinListener<R> {
 mod tests {
     use super::*;
     use async_trait::async_trait;
-    use conxian_core::{BlockInfo, GatewayState};
+    use conxian_core::{CommitmentStatus, GatewayState};
     use std::sync::{Arc, RwLock};
 
     struct MockBitcoinRpc {
         height: u64,
+        fork_from: Option<u64>,
+        /// Transactions mined at each height.
+        txids: std::collections::HashMap<u64, Vec<String>>,
     }
 
     #[async_trait]
@@ -108,8 +256,12 @@ mod tests {
             Ok(self.height)
         }
         async fn get_block_info(&self, height: u64) -> ConxianResult<BlockInfo> {
+            let hash = match self.fork_from {
+                Some(f) if height >= f => format!("fork-{}", height),
+                _ => format!("hash-{}", height),
+            };
             Ok(BlockInfo {
-                hash: format!("hash-{}", height),
+                hash,
                 height,
                 timestamp: 123456789,
             })
@@ -117,6 +269,25 @@ mod tests {
         async fn get_network_info(&self) -> ConxianResult<String> {
             Ok("testnet".to_string())
         }
+        async fn get_block_txids(&self, block_hash: &str) -> ConxianResult<Vec<String>> {
+            let height: u64 = block_hash.rsplit('-').next().unwrap().parse().unwrap();
+            Ok(self.txids.get(&height).cloned().unwrap_or_default())
+        }
+        async fn get_tx_out_proof(&self, txid: &str, block_hash: &str) -> ConxianResult<String> {
+            Ok(format!("proof-{}-{}", txid, block_hash))
+        }
+        async fn create_funded_psbt(&self, _data: &[u8]) -> ConxianResult<String> {
+            unimplemented!()
+        }
+        async fn sign_psbt(&self, _psbt: &str) -> ConxianResult<String> {
+            unimplemented!()
+        }
+        async fn send_raw_transaction(&self, _tx_hex: &str) -> ConxianResult<String> {
+            unimplemented!()
+        }
+        async fn get_raw_transaction(&self, _txid: &str) -> ConxianResult<String> {
+            unimplemented!()
+        }
     }
 
     struct MockPersistence;
@@ -132,7 +303,11 @@ mod tests {
     #[tokio::test]
     async fn test_bitcoin_listener_sync_once() {
         let state = Arc::new(RwLock::new(GatewayState::default()));
-        let rpc = MockBitcoinRpc { height: 100 };
+        let rpc = MockBitcoinRpc {
+            height: 100,
+            fork_from: None,
+            txids: Default::default(),
+        };
         let persistence = Arc::new(MockPersistence);
         let mut listener = BitcoinListener::new(rpc, state.clone(), persistence);
 
@@ -156,4 +331,113 @@ mod tests {
             assert_eq!(s.bitcoin.best_block_hash, "hash-101");
         }
     }
+
+    #[tokio::test]
+    async fn test_bitcoin_listener_detects_reorg() {
+        let state = Arc::new(RwLock::new(GatewayState::default()));
+        let rpc = MockBitcoinRpc {
+            height: 100,
+            fork_from: None,
+            txids: Default::default(),
+        };
+        let persistence = Arc::new(MockPersistence);
+        let mut listener = BitcoinListener::new(rpc, state.clone(), persistence);
+
+        listener.sync_once().await.unwrap();
+        listener.rpc.height = 102;
+        listener.sync_once().await.unwrap();
+
+        // Blocks 101 and 102 are replaced by a competing branch
+        listener.rpc.fork_from = Some(101);
+        listener.sync_once().await.unwrap();
+
+        let index = listener.header_index();
+        let index = index.read().unwrap();
+        assert_eq!(index.hash_at(100), Some("hash-100"));
+        assert_eq!(index.hash_at(102), Some("fork-102"));
+        assert!(index.is_orphaned("hash-101"));
+
+        let s = state.read().unwrap();
+        assert_eq!(s.bitcoin.best_block_hash, "fork-102");
+        assert_eq!(s.alerts.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_bitcoin_listener_backfills_and_handles_shorter_chain() {
+        let state = Arc::new(RwLock::new(GatewayState::default()));
+        let rpc = MockBitcoinRpc {
+            height: 3000,
+            fork_from: None,
+            txids: Default::default(),
+        };
+        let mut listener = BitcoinListener::new(rpc, state.clone(), Arc::new(MockPersistence));
+        listener.sync_once().await.unwrap();
+        {
+            let index = listener.header_index();
+            let index = index.read().unwrap();
+            assert_eq!(index.tip_height(), Some(3000));
+            assert_eq!(index.lowest_height(), Some(3000 - 2015));
+        }
+
+        // Blocks 2999 and 3000 are dropped without a replacement yet
+        listener.rpc.height = 2998;
+        listener.sync_once().await.unwrap();
+        {
+            let index = listener.header_index();
+            let index = index.read().unwrap();
+            assert_eq!(index.tip_height(), Some(2998));
+            assert!(index.is_orphaned("hash-2999"));
+            assert!(index.is_orphaned("hash-3000"));
+            let s = state.read().unwrap();
+            assert_eq!(s.bitcoin.height, 2998);
+            assert_eq!(s.bitcoin.best_block_hash, "hash-2998");
+        }
+
+        listener.rpc.height = 2999;
+        listener.rpc.fork_from = Some(2999);
+        listener.sync_once().await.unwrap();
+        let index = listener.header_index();
+        assert_eq!(index.read().unwrap().hash_at(2999), Some("fork-2999"));
+        assert_eq!(state.read().unwrap().alerts.len(), 1);
+    }
+
+    #[tokio::test]
+    async fn test_bitcoin_listener_confirms_commitments() {
+        use crate::bitcoin::notary::tests::{commitment_tx, sealed_notary};
+        use bitcoin::consensus::encode::serialize_hex;
+
+        let state = Arc::new(RwLock::new(GatewayState::default()));
+        let (notary, batch) = sealed_notary();
+        let tx = commitment_tx(&batch.root);
+        let txid = tx.compute_txid().to_string();
+        notary
+            .attach_transaction(batch.id, &serialize_hex(&tx))
+            .unwrap();
+
+        let rpc = MockBitcoinRpc {
+            height: 100,
+            fork_from: None,
+            txids: [(102, vec![txid.clone()])].into_iter().collect(),
+        };
+        let mut listener = BitcoinListener::new(rpc, state.clone(), Arc::new(MockPersistence))
+            .with_notary(notary.clone());
+        listener.sync_once().await.unwrap();
+        listener.rpc.height = 102;
+        listener.sync_once().await.unwrap();
+
+        let confirmed = notary.batch(batch.id).unwrap();
+        assert_eq!(confirmed.status, CommitmentStatus::Confirmed);
+        assert_eq!(confirmed.block_height, Some(102));
+        assert_eq!(confirmed.block_hash.as_deref(), Some("hash-102"));
+        assert_eq!(confirmed.tx_proof, Some(format!("proof-{}-hash-102", txid)));
+
+        // A reorg replacing the block returns the commitment to broadcast
+        listener.rpc.fork_from = Some(102);
+        listener.rpc.txids.clear();
+        listener.sync_once().await.unwrap();
+        assert_eq!(
+            notary.batch(batch.id).unwrap().status,
+            CommitmentStatus::Broadcast
+        );
+    }
 }
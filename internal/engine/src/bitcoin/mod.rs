@@ -1,5 +1,11 @@
+pub mod index;
 pub mod listener;
+pub mod notary;
+pub mod risk;
 pub mod rpc;
 
+pub use index::{AnchorMatch, HeaderIndex, SharedHeaderIndex};
 pub use listener::BitcoinListener;
+pub use notary::{CommitmentMode, Notary, NotaryService};
+pub use risk::{RiskPolicy, RiskScorer};
 pub use rpc::{BitcoinRpc, BitcoinRpcClient};
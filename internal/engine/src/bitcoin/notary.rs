@@ -0,0 +1,607 @@
+use crate::bitcoin::{BitcoinRpc, RiskScorer};
+use bitcoin::consensus::encode::deserialize_hex;
+use bitcoin::hashes::{sha256, Hash};
+use bitcoin::Transaction;
+use conxian_core::merkle::{
+    commitment_data, find_commitment, leaf_hash, merkle_path, merkle_root, parse_id,
+};
+use conxian_core::persistence::write_atomic;
+use conxian_core::{
+    AuditEntry, CommitmentBatch, CommitmentLeaf, CommitmentLeafKind, CommitmentStatus,
+    ConxianError, ConxianResult, InclusionProof,
+};
+use serde::{Deserialize, Serialize};
+use std::fs;
+use std::path::PathBuf;
+use std::sync::{Arc, Mutex};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::time::{sleep, Duration};
+use tracing::{error, info, warn};
+
+#[derive(Debug, Default, Serialize, Deserialize)]
+struct NotaryState {
+    /// Leaves waiting for the next batch.
+    pending: Vec<CommitmentLeaf>,
+    batches: Vec<CommitmentBatch>,
+    /// Sequence number of the next audit entry to commit.
+    audit_cursor: u64,
+}
+
+/// Batches audit entries and receipts into merkle trees whose roots are
+/// committed to Bitcoin, and tracks each commitment until it confirms.
+/// Optionally persisted to a JSON file.
+#[derive(Debug, Default)]
+pub struct Notary {
+    path: Option<PathBuf>,
+    state: Mutex<NotaryState>,
+}
+
+impl Notary {
+    pub fn in_memory() -> Self {
+        Self::default()
+    }
+
+    /// Opens the notary at `path`, loading any batches persisted there.
+    pub fn open(path: &str) -> ConxianResult<Self> {
+        let path = PathBuf::from(path);
+        let state = if path.exists() {
+            let json = fs::read_to_string(&path).map_err(|e| ConxianError::Io(e.to_string()))?;
+            serde_json::from_str(&json).map_err(|e| ConxianError::Internal(e.to_string()))?
+        } else {
+            NotaryState::default()
+        };
+        Ok(Self {
+            path: Some(path),
+            state: Mutex::new(state),
+        })
+    }
+
+    /// Queues a receipt or other leaf for the next batch.
+    pub fn submit(&self, kind: CommitmentLeafKind, id: &str) -> ConxianResult<()> {
+        parse_id(id)?;
+        let mut state = self.state.lock().unwrap();
+        state.pending.push(CommitmentLeaf {
+            kind,
+            id: id.to_lowercase(),
+        });
+        self.persist(&state);
+        Ok(())
+    }
+
+    /// Queues audit entries not yet committed and advances the audit cursor.
+    pub fn submit_audit_entries(&self, entries: &[AuditEntry]) {
+        let mut state = self.state.lock().unwrap();
+        for entry in entries {
+            if entry.seq < state.audit_cursor {
+                continue;
+            }
+            state.pending.push(CommitmentLeaf {
+                kind: CommitmentLeafKind::AuditEntry,
+                id: entry.hash.clone(),
+            });
+            state.audit_cursor = entry.seq + 1;
+        }
+        self.persist(&state);
+    }
+
+    pub fn audit_cursor(&self) -> u64 {
+        self.state.lock().unwrap().audit_cursor
+    }
+
+    pub fn pending_len(&self) -> usize {
+        self.state.lock().unwrap().pending.len()
+    }
+
+    /// Closes the pending leaves into a batch, or returns `None` if there are none.
+    pub fn seal(&self, now: u64) -> ConxianResult<Option<CommitmentBatch>> {
+        let mut state = self.state.lock().unwrap();
+        if state.pending.is_empty() {
+            return Ok(None);
+        }
+        let leaves = std::mem::take(&mut state.pending);
+        let hashes = leaf_hashes(&leaves)?;
+        let root = merkle_root(&hashes).expect("batch has leaves");
+        let batch = CommitmentBatch {
+            id: state.batches.last().map_or(1, |b| b.id + 1),
+            root: sha256::Hash::from_byte_array(root).to_string(),
+            leaves,
+            created_at: now,
+            status: CommitmentStatus::Pending,
+            psbt: None,
+            raw_tx: None,
+            txid: None,
+            block_hash: None,
+            block_height: None,
+            tx_proof: None,
+        };
+        state.batches.push(batch.clone());
+        self.persist(&state);
+        Ok(Some(batch))
+    }
+
+    pub fn batches(&self) -> Vec<CommitmentBatch> {
+        self.state.lock().unwrap().batches.clone()
+    }
+
+    pub fn batch(&self, id: u64) -> Option<CommitmentBatch> {
+        let state = self.state.lock().unwrap();
+        state.batches.iter().find(|b| b.id == id).cloned()
+    }
+
+    /// Records a funded PSBT for the batch to be signed externally.
+    pub fn attach_psbt(&self, id: u64, psbt: &str) -> ConxianResult<CommitmentBatch> {
+        self.update(id, |batch| {
+            if batch.status != CommitmentStatus::Pending {
+                return Err(ConxianError::Compliance(format!(
+                    "Commitment {} already has a transaction",
+                    batch.id
+                )));
+            }
+            batch.psbt = Some(psbt.to_string());
+            batch.status = CommitmentStatus::Unsigned;
+            Ok(())
+        })
+    }
+
+    /// Records the signed commitment transaction for a batch, checking that
+    /// it commits to the batch root.
+    pub fn attach_transaction(&self, id: u64, tx_hex: &str) -> ConxianResult<CommitmentBatch> {
+        let tx: Transaction = deserialize_hex(tx_hex)
+            .map_err(|e| ConxianError::Bitcoin(format!("Invalid transaction: {}", e)))?;
+        self.update(id, |batch| {
+            if !matches!(
+                batch.status,
+                CommitmentStatus::Pending | CommitmentStatus::Unsigned
+            ) {
+                return Err(ConxianError::Compliance(format!(
+                    "Commitment {} already has a signed transaction",
+                    batch.id
+                )));
+            }
+            let root = parse_id(&batch.root)?;
+            if find_commitment(&tx) != Some(root) {
+                return Err(ConxianError::Security(
+                    "Transaction does not commit to the batch root".to_string(),
+                ));
+            }
+            batch.raw_tx = Some(tx_hex.to_lowercase());
+            batch.txid = Some(tx.compute_txid().to_string());
+            batch.status = CommitmentStatus::Signed;
+            Ok(())
+        })
+    }
+
+    pub fn mark_broadcast(&self, id: u64) -> ConxianResult<CommitmentBatch> {
+        self.update(id, |batch| {
+            if batch.status == CommitmentStatus::Signed {
+                batch.status = CommitmentStatus::Broadcast;
+            }
+            Ok(())
+        })
+    }
+
+    /// Txids of signed or broadcast commitments not yet confirmed.
+    pub fn awaiting_confirmation(&self) -> Vec<String> {
+        let state = self.state.lock().unwrap();
+        state
+            .batches
+            .iter()
+            .filter(|b| {
+                matches!(
+                    b.status,
+                    CommitmentStatus::Signed | CommitmentStatus::Broadcast
+                )
+            })
+            .filter_map(|b| b.txid.clone())
+            .collect()
+    }
+
+    /// Marks the commitment with `txid` confirmed in a block.
+    pub fn confirm(&self, txid: &str, block_hash: &str, height: u64, tx_proof: &str) {
+        let mut state = self.state.lock().unwrap();
+        for batch in &mut state.batches {
+            if batch.txid.as_deref() == Some(txid) {
+                info!(
+                    "Commitment {} confirmed in block {} at height {}",
+                    batch.id, block_hash, height
+                );
+                batch.status = CommitmentStatus::Confirmed;
+                batch.block_hash = Some(block_hash.to_string());
+                batch.block_height = Some(height);
+                batch.tx_proof = Some(tx_proof.to_string());
+            }
+        }
+        self.persist(&state);
+    }
+
+    /// Returns commitments confirmed above `height` to broadcast after a reorg.
+    pub fn unconfirm_above(&self, height: u64) {
+        let mut state = self.state.lock().unwrap();
+        for batch in &mut state.batches {
+            if batch.block_height.is_some_and(|h| h > height) {
+                warn!("Commitment {} was reorganised out", batch.id);
+                batch.status = CommitmentStatus::Broadcast;
+                batch.block_hash = None;
+                batch.block_height = None;
+                batch.tx_proof = None;
+            }
+        }
+        self.persist(&state);
+    }
+
+    /// Proof tying the leaf with `id` to its batch and, once confirmed, to a block.
+    pub fn proof(&self, id: &str) -> ConxianResult<InclusionProof> {
+        let id = id.to_lowercase();
+        let state = self.state.lock().unwrap();
+        let (batch, index) = state
+            .batches
+            .iter()
+            .find_map(|b| Some((b, b.leaves.iter().position(|l| l.id == id)?)))
+            .ok_or_else(|| ConxianError::Compliance(format!("Leaf not committed: {}", id)))?;
+        let path = merkle_path(&leaf_hashes(&batch.leaves)?, index).expect("leaf in batch");
+        Ok(InclusionProof {
+            leaf: batch.leaves[index].clone(),
+            batch: batch.id,
+            root: batch.root.clone(),
+            path,
+            status: batch.status,
+            raw_tx: batch.raw_tx.clone(),
+            txid: batch.txid.clone(),
+            block_hash: batch.block_hash.clone(),
+            block_height: batch.block_height,
+            tx_proof: batch.tx_proof.clone(),
+        })
+    }
+
+    fn update(
+        &self,
+        id: u64,
+        apply: impl FnOnce(&mut CommitmentBatch) -> ConxianResult<()>,
+    ) -> ConxianResult<CommitmentBatch> {
+        let mut state = self.state.lock().unwrap();
+        let batch = state
+            .batches
+            .iter_mut()
+            .find(|b| b.id == id)
+            .ok_or_else(|| ConxianError::Compliance(format!("Commitment not found: {}", id)))?;
+        apply(batch)?;
+        let batch = batch.clone();
+        self.persist(&state);
+        Ok(batch)
+    }
+
+    // The in-memory state stays authoritative for this process if the write fails
+    fn persist(&self, state: &NotaryState) {
+        let Some(path) = &self.path else {
+            return;
+        };
+        let result = serde_json::to_string(state)
+            .map_err(|e| ConxianError::Internal(e.to_string()))
+            .and_then(|json| write_atomic(path, &json));
+        if let Err(e) = result {
+            warn!("Failed to persist commitments: {}", e);
+        }
+    }
+}
+
+fn leaf_hashes(leaves: &[CommitmentLeaf]) -> ConxianResult<Vec<[u8; 32]>> {
+    leaves
+        .iter()
+        .map(|leaf| parse_id(&leaf.id).map(|id| leaf_hash(&id)))
+        .collect()
+}
+
+/// How commitment transactions are produced.
+#[derive(Debug, Clone, Copy, PartialEq, Eq)]
+pub enum CommitmentMode {
+    /// The node's wallet funds a PSBT that is signed externally and submitted
+    /// back through the admin API.
+    Psbt,
+    /// The node's wallet funds, signs and broadcasts the commitment.
+    Wallet,
+}
+
+/// Source of audit entries from a sequence number onwards.
+pub type AuditSource = Box<dyn Fn(u64) -> ConxianResult<Vec<AuditEntry>> + Send + Sync>;
+
+/// Periodically seals new leaves into a batch and produces, signs or
+/// broadcasts its commitment transaction.
+pub struct NotaryService<R: BitcoinRpc> {
+    rpc: R,
+    notary: Arc<Notary>,
+    mode: CommitmentMode,
+    interval: Duration,
+    audit: Option<AuditSource>,
+    risk: Option<Arc<RiskScorer>>,
+}
+
+impl<R: BitcoinRpc> NotaryService<R> {
+    pub fn new(rpc: R, notary: Arc<Notary>, mode: CommitmentMode) -> Self {
+        Self {
+            rpc,
+            notary,
+            mode,
+            interval: Duration::from_secs(3600),
+            audit: None,
+            risk: None,
+        }
+    }
+
+    pub fn with_interval(mut self, interval: Duration) -> Self {
+        self.interval = interval;
+        self
+    }
+
+    pub fn with_audit_source(mut self, source: AuditSource) -> Self {
+        self.audit = Some(source);
+        self
+    }
+
+    /// Holds back commitment transactions whose inputs carry high risk.
+    pub fn with_risk_scorer(mut self, scorer: Arc<RiskScorer>) -> Self {
+        self.risk = Some(scorer);
+        self
+    }
+
+    pub async fn anchor_once(&self) -> ConxianResult<()> {
+        if let Some(source) = &self.audit {
+            let entries = source(self.notary.audit_cursor())?;
+            self.notary.submit_audit_entries(&entries);
+        }
+        let now = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .unwrap()
+            .as_secs();
+        if let Some(batch) = self.notary.seal(now)? {
+            info!(
+                "Sealed commitment {} over {} leaves with root {}",
+                batch.id,
+                batch.leaves.len(),
+                batch.root
+            );
+        }
+
+        for batch in self.notary.batches() {
+            if let Err(e) = self.advance(&batch).await {
+                error!("Failed to advance commitment {}: {}", batch.id, e);
+            }
+        }
+        Ok(())
+    }
+
+    /// Moves a batch one step towards broadcast.
+    async fn advance(&self, batch: &CommitmentBatch) -> ConxianResult<()> {
+        match (batch.status, self.mode) {
+            (CommitmentStatus::Pending, mode) => {
+                let data = commitment_data(&parse_id(&batch.root)?);
+                let psbt = self.rpc.create_funded_psbt(&data).await?;
+                if mode == CommitmentMode::Wallet {
+                    let tx_hex = self.rpc.sign_psbt(&psbt).await?;
+                    self.notary.attach_transaction(batch.id, &tx_hex)?;
+                    self.broadcast(batch.id).await?;
+                } else {
+                    self.notary.attach_psbt(batch.id, &psbt)?;
+                    info!(
+                        "Commitment {} is waiting for an external signature",
+                        batch.id
+                    );
+                }
+            }
+            (CommitmentStatus::Signed, _) => self.broadcast(batch.id).await?,
+            _ => {}
+        }
+        Ok(())
+    }
+
+    async fn broadcast(&self, id: u64) -> ConxianResult<()> {
+        let batch = self
+            .notary
+            .batch(id)
+            .ok_or_else(|| ConxianError::Compliance(format!("Commitment not found: {}", id)))?;
+        let Some(tx_hex) = batch.raw_tx else {
+            return Ok(());
+        };
+        if let Some(scorer) = &self.risk {
+            let tx: Transaction = deserialize_hex(&tx_hex)
+                .map_err(|e| ConxianError::Bitcoin(format!("Invalid transaction: {}", e)))?;
+            scorer.check(&tx).await?;
+        }
+        let txid = self.rpc.send_raw_transaction(&tx_hex).await?;
+        info!("Broadcast commitment {} in transaction {}", id, txid);
+        self.notary.mark_broadcast(id)?;
+        Ok(())
+    }
+
+    pub async fn run(&self) -> ConxianResult<()> {
+        info!("Starting Bitcoin commitment service...");
+        loop {
+            sleep(self.interval).await;
+            if let Err(e) = self.anchor_once().await {
+                error!("Failed to commit to Bitcoin: {}", e);
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use bitcoin::consensus::encode::serialize_hex;
+    use bitcoin::{absolute, transaction, Amount, TxOut};
+    use conxian_core::merkle::{commitment_script, verify_merkle_path};
+    use conxian_core::{AuditEvent, AuditKind, BlockInfo};
+
+    pub(crate) fn commitment_tx(root: &str) -> Transaction {
+        Transaction {
+            version: transaction::Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![Default::default()],
+            output: vec![TxOut {
+                value: Amount::ZERO,
+                script_pubkey: commitment_script(&parse_id(root).unwrap()),
+            }],
+        }
+    }
+
+    fn audit_entry(seq: u64) -> AuditEntry {
+        AuditEntry {
+            seq,
+            at: 100,
+            event: AuditEvent {
+                kind: AuditKind::Admin,
+                action: "device.enroll".to_string(),
+                subject: None,
+                success: true,
+                detail: None,
+            },
+            prev_hash: String::new(),
+            hash: format!("{:064x}", seq + 1),
+        }
+    }
+
+    /// A notary with one sealed batch of three audit entries and a receipt.
+    pub(crate) fn sealed_notary() -> (Arc<Notary>, CommitmentBatch) {
+        let notary = Arc::new(Notary::in_memory());
+        notary.submit_audit_entries(&(0..3).map(audit_entry).collect::<Vec<_>>());
+        notary
+            .submit(CommitmentLeafKind::Receipt, &"ab".repeat(32))
+            .unwrap();
+        let batch = notary.seal(100).unwrap().unwrap();
+        (notary, batch)
+    }
+
+    #[test]
+    fn test_notary_batches_and_proves_leaves() {
+        let (notary, batch) = sealed_notary();
+        assert_eq!(batch.id, 1);
+        assert_eq!(batch.leaves.len(), 4);
+        assert_eq!(notary.audit_cursor(), 3);
+        assert!(notary.seal(101).unwrap().is_none());
+
+        // Entries already committed are not queued again
+        notary.submit_audit_entries(&(0..4).map(audit_entry).collect::<Vec<_>>());
+        assert_eq!(notary.pending_len(), 1);
+
+        let proof = notary.proof(&"AB".repeat(32)).unwrap();
+        assert_eq!(proof.leaf.kind, CommitmentLeafKind::Receipt);
+        assert_eq!(proof.status, CommitmentStatus::Pending);
+        assert!(verify_merkle_path(
+            leaf_hash(&parse_id(&proof.leaf.id).unwrap()),
+            &proof.path,
+            &parse_id(&proof.root).unwrap()
+        ));
+        assert!(notary.proof(&"cd".repeat(32)).is_err());
+        assert!(notary.submit(CommitmentLeafKind::Receipt, "zz").is_err());
+    }
+
+    #[test]
+    fn test_notary_checks_commitment_transactions() {
+        let (notary, batch) = sealed_notary();
+        let wrong = commitment_tx(&"00".repeat(32));
+        assert!(notary
+            .attach_transaction(batch.id, &serialize_hex(&wrong))
+            .is_err());
+
+        let tx = commitment_tx(&batch.root);
+        let signed = notary
+            .attach_transaction(batch.id, &serialize_hex(&tx))
+            .unwrap();
+        assert_eq!(signed.status, CommitmentStatus::Signed);
+        assert_eq!(
+            notary.awaiting_confirmation(),
+            vec![tx.compute_txid().to_string()]
+        );
+        assert!(notary
+            .attach_transaction(batch.id, &serialize_hex(&tx))
+            .is_err());
+        assert!(notary.attach_psbt(batch.id, "cHNidP8=").is_err());
+    }
+
+    #[test]
+    fn test_notary_persists_batches() {
+        let path =
+            std::env::temp_dir().join(format!("conxian-notary-{}.json", rand::random::<u64>()));
+        let notary = Notary::open(path.to_str().unwrap()).unwrap();
+        notary
+            .submit(CommitmentLeafKind::Receipt, &"ab".repeat(32))
+            .unwrap();
+        let batch = notary.seal(100).unwrap().unwrap();
+
+        let reopened = Notary::open(path.to_str().unwrap()).unwrap();
+        assert_eq!(reopened.batch(1), Some(batch));
+        fs::remove_file(path).unwrap();
+    }
+
+    /// Node wallet that funds and signs commitments itself.
+    #[derive(Default)]
+    struct MockWallet {
+        sent: Mutex<Vec<String>>,
+    }
+
+    #[async_trait]
+    impl BitcoinRpc for MockWallet {
+        async fn get_block_count(&self) -> ConxianResult<u64> {
+            Ok(0)
+        }
+        async fn get_block_info(&self, _height: u64) -> ConxianResult<BlockInfo> {
+            unimplemented!()
+        }
+        async fn get_network_info(&self) -> ConxianResult<String> {
+            Ok("regtest".to_string())
+        }
+        async fn get_block_txids(&self, _block_hash: &str) -> ConxianResult<Vec<String>> {
+            Ok(Vec::new())
+        }
+        async fn get_tx_out_proof(&self, _txid: &str, _block_hash: &str) -> ConxianResult<String> {
+            unimplemented!()
+        }
+        async fn create_funded_psbt(&self, data: &[u8]) -> ConxianResult<String> {
+            // Stands in for a PSBT; the mock signs by reading the data back
+            Ok(bitcoin::hex::DisplayHex::to_lower_hex_string(data))
+        }
+        async fn sign_psbt(&self, psbt: &str) -> ConxianResult<String> {
+            Ok(serialize_hex(&commitment_tx(&psbt[8..])))
+        }
+        async fn send_raw_transaction(&self, tx_hex: &str) -> ConxianResult<String> {
+            self.sent.lock().unwrap().push(tx_hex.to_string());
+            let tx: Transaction = deserialize_hex(tx_hex).unwrap();
+            Ok(tx.compute_txid().to_string())
+        }
+        async fn get_raw_transaction(&self, _txid: &str) -> ConxianResult<String> {
+            unimplemented!()
+        }
+    }
+
+    #[tokio::test]
+    async fn test_notary_service_commits_audit_entries() {
+        let notary = Arc::new(Notary::in_memory());
+        let service = NotaryService::new(
+            MockWallet::default(),
+            notary.clone(),
+            CommitmentMode::Wallet,
+        )
+        .with_audit_source(Box::new(|from| Ok((from..5).map(audit_entry).collect())));
+        service.anchor_once().await.unwrap();
+
+        let batch = notary.batch(1).unwrap();
+        assert_eq!(batch.leaves.len(), 5);
+        assert_eq!(batch.status, CommitmentStatus::Broadcast);
+        assert_eq!(service.rpc.sent.lock().unwrap().len(), 1);
+        assert_eq!(notary.audit_cursor(), 5);
+
+        // Nothing new to commit
+        service.anchor_once().await.unwrap();
+        assert_eq!(notary.batches().len(), 1);
+
+        let notary = Arc::new(Notary::in_memory());
+        let service =
+            NotaryService::new(MockWallet::default(), notary.clone(), CommitmentMode::Psbt)
+                .with_audit_source(Box::new(|from| Ok((from..2).map(audit_entry).collect())));
+        service.anchor_once().await.unwrap();
+        let batch = notary.batch(1).unwrap();
+        assert_eq!(batch.status, CommitmentStatus::Unsigned);
+        assert!(batch.psbt.is_some());
+        assert!(service.rpc.sent.lock().unwrap().is_empty());
+    }
+}
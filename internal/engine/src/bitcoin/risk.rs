@@ -0,0 +1,504 @@
+//! Risk scoring of Bitcoin transactions by their exposure to denylisted outputs.
+//!
+//! Each input carries its share of the transaction's input value back to the
+//! output it spends. A spent output that matches a denylist contributes that
+//! share to the score, decayed once for every hop beyond the first; any other
+//! output passes it on to the inputs of its own transaction, until
+//! `max_hops`. Paying a listed output is exposure in full.
+
+use crate::bitcoin::rpc::BitcoinRpc;
+use bitcoin::consensus::encode::deserialize_hex;
+use bitcoin::{Address, Network, OutPoint, Script, Transaction, TxOut, Txid};
+use compliance::Screener;
+use conxian_core::{
+    ConxianError, ConxianResult, RiskAssessment, RiskExposure, RiskHop, RiskLevel, ScreeningHit,
+};
+use std::collections::{HashMap, VecDeque};
+use std::sync::{Arc, Mutex};
+
+/// Default number of fetched transactions kept for later walks.
+pub const DEFAULT_CACHE_CAPACITY: usize = 10_000;
+
+/// How far inputs are traced and where scores become findings.
+#[derive(Debug, Clone)]
+pub struct RiskPolicy {
+    /// Hops the inputs are walked back; 1 inspects only the outputs spent.
+    pub max_hops: u32,
+    /// Factor applied to an exposure for each hop beyond the first.
+    pub decay: f64,
+    /// Score from which a transaction is flagged for review.
+    pub medium_threshold: f64,
+    /// Score from which a transaction is refused.
+    pub high_threshold: f64,
+    /// Ancestor transactions inspected per assessment.
+    pub max_transactions: usize,
+}
+
+impl Default for RiskPolicy {
+    fn default() -> Self {
+        Self {
+            max_hops: 3,
+            decay: 0.5,
+            medium_threshold: 0.1,
+            high_threshold: 0.5,
+            max_transactions: 500,
+        }
+    }
+}
+
+impl RiskPolicy {
+    pub fn level(&self, score: f64) -> RiskLevel {
+        if score >= self.high_threshold {
+            RiskLevel::High
+        } else if score >= self.medium_threshold {
+            RiskLevel::Medium
+        } else {
+            RiskLevel::Low
+        }
+    }
+}
+
+/// Transactions by txid, evicted oldest first.
+struct TxCache {
+    txs: HashMap<Txid, Arc<Transaction>>,
+    order: VecDeque<Txid>,
+    capacity: usize,
+}
+
+/// A transaction whose inputs are still to be walked.
+struct Pending {
+    tx: Arc<Transaction>,
+    /// Share of the scored transaction's value it carries.
+    share: f64,
+    /// Hops to the outputs its inputs spend.
+    hops: u32,
+    path: Vec<RiskHop>,
+}
+
+/// Scores transactions by walking their inputs back through the node.
+pub struct RiskScorer {
+    rpc: Arc<dyn BitcoinRpc>,
+    screener: Arc<Screener>,
+    policy: RiskPolicy,
+    network: Network,
+    cache: Mutex<TxCache>,
+}
+
+impl RiskScorer {
+    pub fn new(rpc: Arc<dyn BitcoinRpc>, screener: Arc<Screener>, policy: RiskPolicy) -> Self {
+        Self {
+            rpc,
+            screener,
+            policy,
+            network: Network::Bitcoin,
+            cache: Mutex::new(TxCache {
+                txs: HashMap::new(),
+                order: VecDeque::new(),
+                capacity: DEFAULT_CACHE_CAPACITY,
+            }),
+        }
+    }
+
+    /// Network addresses on explanation paths are encoded for.
+    pub fn with_network(mut self, network: Network) -> Self {
+        self.network = network;
+        self
+    }
+
+    pub fn policy(&self) -> &RiskPolicy {
+        &self.policy
+    }
+
+    async fn transaction(&self, txid: &Txid) -> ConxianResult<Arc<Transaction>> {
+        if let Some(tx) = self.cache.lock().unwrap().txs.get(txid) {
+            return Ok(tx.clone());
+        }
+        let hex = self.rpc.get_raw_transaction(&txid.to_string()).await?;
+        let tx: Transaction = deserialize_hex(&hex)
+            .map_err(|e| ConxianError::Bitcoin(format!("Invalid transaction {}: {}", txid, e)))?;
+        if tx.compute_txid() != *txid {
+            return Err(ConxianError::Bitcoin(format!(
+                "Node returned {} for transaction {}",
+                tx.compute_txid(),
+                txid
+            )));
+        }
+
+        let tx = Arc::new(tx);
+        let mut cache = self.cache.lock().unwrap();
+        if cache.txs.insert(*txid, tx.clone()).is_none() {
+            cache.order.push_back(*txid);
+        }
+        while cache.order.len() > cache.capacity {
+            if let Some(old) = cache.order.pop_front() {
+                cache.txs.remove(&old);
+            }
+        }
+        Ok(tx)
+    }
+
+    fn hop(&self, txid: Txid, vout: u32, output: &TxOut) -> RiskHop {
+        RiskHop {
+            txid: txid.to_string(),
+            vout,
+            address: Address::from_script(&output.script_pubkey, self.network)
+                .ok()
+                .map(|a| a.to_string()),
+            value: output.value.to_sat(),
+        }
+    }
+
+    fn screen(&self, hop: &RiskHop, script: &Script) -> Vec<ScreeningHit> {
+        let subject = hop
+            .address
+            .clone()
+            .unwrap_or_else(|| script.to_hex_string());
+        self.screener.screen_script(&subject, script)
+    }
+
+    /// Scores a transaction known to the node by its txid.
+    pub async fn score_txid(&self, txid: &str) -> ConxianResult<RiskAssessment> {
+        let txid: Txid = txid
+            .parse()
+            .map_err(|e| ConxianError::Bitcoin(format!("Invalid txid {}: {}", txid, e)))?;
+        let tx = self.transaction(&txid).await?;
+        self.score(&tx).await
+    }
+
+    /// Scores a transaction, signed or not, whose inputs the node can resolve.
+    pub async fn score(&self, tx: &Transaction) -> ConxianResult<RiskAssessment> {
+        let txid = tx.compute_txid();
+        let mut exposures = Vec::new();
+        for (vout, output) in tx.output.iter().enumerate() {
+            let hop = self.hop(txid, vout as u32, output);
+            let hits = self.screen(&hop, &output.script_pubkey);
+            if !hits.is_empty() {
+                exposures.push(RiskExposure {
+                    hops: 0,
+                    share: 1.0,
+                    contribution: 1.0,
+                    path: vec![hop],
+                    hits,
+                });
+            }
+        }
+
+        // Breadth first, so that the nearest exposures are found before the
+        // transaction limit is reached
+        let mut transactions = 0;
+        let mut truncated = false;
+        let mut queue = VecDeque::from([Pending {
+            tx: Arc::new(tx.clone()),
+            share: 1.0,
+            hops: 1,
+            path: Vec::new(),
+        }]);
+        while let Some(pending) = queue.pop_front() {
+            let spent: Vec<OutPoint> = pending
+                .tx
+                .input
+                .iter()
+                .map(|input| input.previous_output)
+                .filter(|outpoint| !outpoint.is_null())
+                .collect();
+            if transactions + spent.len() > self.policy.max_transactions {
+                truncated = true;
+                continue;
+            }
+
+            let mut outputs = Vec::with_capacity(spent.len());
+            for outpoint in spent {
+                let prev = self.transaction(&outpoint.txid).await?;
+                transactions += 1;
+                let output = prev.output.get(outpoint.vout as usize).ok_or_else(|| {
+                    ConxianError::Bitcoin(format!(
+                        "{} spends missing output {}",
+                        pending.tx.compute_txid(),
+                        outpoint
+                    ))
+                })?;
+                outputs.push((prev.clone(), outpoint.vout, output.clone()));
+            }
+            let total: u64 = outputs.iter().map(|(_, _, o)| o.value.to_sat()).sum();
+            if total == 0 {
+                continue;
+            }
+
+            for (prev, vout, output) in outputs {
+                let share = pending.share * output.value.to_sat() as f64 / total as f64;
+                let hop = self.hop(prev.compute_txid(), vout, &output);
+                let hits = self.screen(&hop, &output.script_pubkey);
+                let mut path = pending.path.clone();
+                path.push(hop);
+                if !hits.is_empty() {
+                    // Traced funds stop at the listed output
+                    exposures.push(RiskExposure {
+                        hops: pending.hops,
+                        share,
+                        contribution: share * self.policy.decay.powi(pending.hops as i32 - 1),
+                        path,
+                        hits,
+                    });
+                } else if pending.hops < self.policy.max_hops && share > 0.0 {
+                    queue.push_back(Pending {
+                        tx: prev,
+                        share,
+                        hops: pending.hops + 1,
+                        path,
+                    });
+                }
+            }
+        }
+
+        exposures.sort_by(|a, b| b.contribution.total_cmp(&a.contribution));
+        let score = exposures
+            .iter()
+            .map(|e| e.contribution)
+            .sum::<f64>()
+            .min(1.0);
+        Ok(RiskAssessment {
+            txid: txid.to_string(),
+            score,
+            level: self.policy.level(score),
+            exposures,
+            max_hops: self.policy.max_hops,
+            transactions,
+            truncated,
+        })
+    }
+
+    /// Scores a transaction, returning a `Compliance` error naming its largest
+    /// exposure when the risk is high.
+    pub async fn check(&self, tx: &Transaction) -> ConxianResult<RiskAssessment> {
+        let assessment = self.score(tx).await?;
+        if assessment.level != RiskLevel::High {
+            return Ok(assessment);
+        }
+        let exposure = &assessment.exposures[0];
+        let listed = exposure.path.last().expect("exposures have a path");
+        Err(ConxianError::Compliance(format!(
+            "Transaction {} has risk score {:.2}: {} hop(s) from {}:{} on {}",
+            assessment.txid,
+            assessment.score,
+            exposure.hops,
+            listed.txid,
+            listed.vout,
+            exposure.hits[0].list
+        )))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use async_trait::async_trait;
+    use bitcoin::absolute::LockTime;
+    use bitcoin::consensus::encode::serialize_hex;
+    use bitcoin::secp256k1::{PublicKey, SecretKey, SECP256K1};
+    use bitcoin::transaction::Version;
+    use bitcoin::{Amount, CompressedPublicKey, ScriptBuf, Sequence, TxIn, Witness};
+    use conxian_core::BlockInfo;
+
+    /// Node that knows a fixed set of transactions.
+    #[derive(Default)]
+    struct MockNode {
+        txs: HashMap<String, String>,
+        fetched: Mutex<usize>,
+    }
+
+    impl MockNode {
+        fn add(&mut self, tx: &Transaction) {
+            self.txs
+                .insert(tx.compute_txid().to_string(), serialize_hex(tx));
+        }
+    }
+
+    #[async_trait]
+    impl BitcoinRpc for MockNode {
+        async fn get_block_count(&self) -> ConxianResult<u64> {
+            unimplemented!()
+        }
+        async fn get_block_info(&self, _height: u64) -> ConxianResult<BlockInfo> {
+            unimplemented!()
+        }
+        async fn get_network_info(&self) -> ConxianResult<String> {
+            unimplemented!()
+        }
+        async fn get_block_txids(&self, _block_hash: &str) -> ConxianResult<Vec<String>> {
+            unimplemented!()
+        }
+        async fn get_tx_out_proof(&self, _txid: &str, _block_hash: &str) -> ConxianResult<String> {
+            unimplemented!()
+        }
+        async fn create_funded_psbt(&self, _data: &[u8]) -> ConxianResult<String> {
+            unimplemented!()
+        }
+        async fn sign_psbt(&self, _psbt: &str) -> ConxianResult<String> {
+            unimplemented!()
+        }
+        async fn send_raw_transaction(&self, _tx_hex: &str) -> ConxianResult<String> {
+            unimplemented!()
+        }
+        async fn get_raw_transaction(&self, txid: &str) -> ConxianResult<String> {
+            *self.fetched.lock().unwrap() += 1;
+            self.txs
+                .get(txid)
+                .cloned()
+                .ok_or_else(|| ConxianError::Bitcoin(format!("No such transaction {}", txid)))
+        }
+    }
+
+    fn address(seed: u8) -> Address {
+        let secret = SecretKey::from_slice(&[seed; 32]).unwrap();
+        let key = CompressedPublicKey(PublicKey::from_secret_key(SECP256K1, &secret));
+        Address::p2wpkh(&key, Network::Bitcoin)
+    }
+
+    fn tx(inputs: &[OutPoint], outputs: &[(&Address, u64)]) -> Transaction {
+        Transaction {
+            version: Version::TWO,
+            lock_time: LockTime::ZERO,
+            input: inputs
+                .iter()
+                .map(|outpoint| TxIn {
+                    previous_output: *outpoint,
+                    script_sig: ScriptBuf::new(),
+                    sequence: Sequence::MAX,
+                    witness: Witness::new(),
+                })
+                .collect(),
+            output: outputs
+                .iter()
+                .map(|(address, value)| TxOut {
+                    value: Amount::from_sat(*value),
+                    script_pubkey: address.script_pubkey(),
+                })
+                .collect(),
+        }
+    }
+
+    fn screener(listed: &Address) -> Arc<Screener> {
+        let path = std::env::temp_dir().join(format!("conxian-risk-{}.csv", rand::random::<u64>()));
+        std::fs::write(&path, format!("{},SDN\n", listed)).unwrap();
+        let screener = Screener::open(&[&path]).unwrap();
+        std::fs::remove_file(path).unwrap();
+        Arc::new(screener)
+    }
+
+    #[tokio::test]
+    async fn test_risk_decays_with_hops() {
+        let (listed, clean, user) = (address(1), address(2), address(3));
+        // 60% of `mid` comes from the listed output, and all of `child`
+        // comes from `mid`
+        let source = tx(&[OutPoint::null()], &[(&listed, 60_000), (&clean, 1)]);
+        let other = tx(&[OutPoint::null()], &[(&clean, 40_000)]);
+        let mid = tx(
+            &[
+                OutPoint::new(source.compute_txid(), 0),
+                OutPoint::new(other.compute_txid(), 0),
+            ],
+            &[(&clean, 99_000)],
+        );
+        let child = tx(&[OutPoint::new(mid.compute_txid(), 0)], &[(&user, 98_000)]);
+        let mut node = MockNode::default();
+        for t in [&source, &other, &mid, &child] {
+            node.add(t);
+        }
+        let node = Arc::new(node);
+        let scorer = RiskScorer::new(node.clone(), screener(&listed), RiskPolicy::default());
+
+        let direct = scorer.score(&mid).await.unwrap();
+        assert!((direct.score - 0.6).abs() < 1e-9);
+        assert_eq!(direct.level, RiskLevel::High);
+        assert!(scorer.check(&mid).await.is_err());
+
+        let assessment = scorer
+            .score_txid(&child.compute_txid().to_string())
+            .await
+            .unwrap();
+        assert!((assessment.score - 0.3).abs() < 1e-9);
+        assert_eq!(assessment.level, RiskLevel::Medium);
+        assert!(!assessment.truncated);
+        let exposure = &assessment.exposures[0];
+        assert_eq!(exposure.hops, 2);
+        assert!((exposure.share - 0.6).abs() < 1e-9);
+        assert_eq!(exposure.hits[0].reason.as_deref(), Some("SDN"));
+        let path: Vec<(String, u32)> = exposure
+            .path
+            .iter()
+            .map(|hop| (hop.txid.clone(), hop.vout))
+            .collect();
+        assert_eq!(
+            path,
+            vec![
+                (mid.compute_txid().to_string(), 0),
+                (source.compute_txid().to_string(), 0)
+            ]
+        );
+        assert_eq!(
+            exposure.path[1].address.as_deref(),
+            Some(listed.to_string().as_str())
+        );
+        assert!(scorer.check(&child).await.is_ok());
+
+        // Transactions are fetched from the node once
+        let fetched = *node.fetched.lock().unwrap();
+        scorer.score(&child).await.unwrap();
+        assert_eq!(*node.fetched.lock().unwrap(), fetched);
+    }
+
+    #[tokio::test]
+    async fn test_risk_limits() {
+        let (listed, clean) = (address(1), address(2));
+        let source = tx(&[OutPoint::null()], &[(&listed, 50_000)]);
+        let mid = tx(
+            &[OutPoint::new(source.compute_txid(), 0)],
+            &[(&clean, 49_000)],
+        );
+        let mut node = MockNode::default();
+        node.add(&source);
+        node.add(&mid);
+        let node: Arc<dyn BitcoinRpc> = Arc::new(node);
+        let spend = tx(&[OutPoint::new(mid.compute_txid(), 0)], &[(&clean, 48_000)]);
+
+        let one_hop = RiskScorer::new(
+            node.clone(),
+            screener(&listed),
+            RiskPolicy {
+                max_hops: 1,
+                ..RiskPolicy::default()
+            },
+        );
+        let assessment = one_hop.score(&spend).await.unwrap();
+        assert_eq!(assessment.score, 0.0);
+        assert_eq!(assessment.level, RiskLevel::Low);
+
+        let limited = RiskScorer::new(
+            node.clone(),
+            screener(&listed),
+            RiskPolicy {
+                max_transactions: 1,
+                ..RiskPolicy::default()
+            },
+        );
+        let assessment = limited.score(&spend).await.unwrap();
+        assert!(assessment.truncated);
+        assert_eq!(assessment.transactions, 1);
+
+        // Paying a listed address is a full exposure
+        let scorer = RiskScorer::new(node, screener(&listed), RiskPolicy::default());
+        let payment = tx(
+            &[OutPoint::new(mid.compute_txid(), 0)],
+            &[(&listed, 48_000)],
+        );
+        let assessment = scorer.score(&payment).await.unwrap();
+        assert_eq!(assessment.score, 1.0);
+        assert_eq!(assessment.exposures[0].hops, 0);
+        let err = scorer.check(&payment).await.unwrap_err();
+        assert!(err.to_string().contains("0 hop(s)"), "{}", err);
+
+        // Inputs the node does not know are an error, not a clean score
+        let unknown = tx(&[OutPoint::new(spend.compute_txid(), 0)], &[(&clean, 1)]);
+        assert!(scorer.score(&unknown).await.is_err());
+    }
+}
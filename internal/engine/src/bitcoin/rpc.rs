@@ -1,6 +1,8 @@
 use async_trait::async_trait;
+use bitcoin::{BlockHash, Txid};
 use bitcoincore_rpc::{Auth, Client, RpcApi};
 use conxian_core::{BlockInfo, ConxianError, ConxianResult};
+use serde_json::{json, Value};
 use std::sync::Arc;
 
 #[async_trait]
@@ -8,8 +10,20 @@ pub trait BitcoinRpc: Send + Sync {
     async fn get_block_count(&self) -> ConxianResult<u64>;
     async fn get_block_info(&self, height: u64) -> ConxianResult<BlockInfo>;
     async fn get_network_info(&self) -> ConxianResult<String>;
+    /// Txids of the transactions in a block.
+    async fn get_block_txids(&self, block_hash: &str) -> ConxianResult<Vec<String>>;
+    /// Hex merkle block proving `txid` is in the block (`gettxoutproof`).
+    async fn get_tx_out_proof(&self, txid: &str, block_hash: &str) -> ConxianResult<String>;
+    /// Base64 PSBT funded by the node's wallet with an OP_RETURN output of `data`.
+    async fn create_funded_psbt(&self, data: &[u8]) -> ConxianResult<String>;
+    /// Signs a PSBT with the node's wallet and returns the final transaction hex.
+    async fn sign_psbt(&self, psbt: &str) -> ConxianResult<String>;
+    async fn send_raw_transaction(&self, tx_hex: &str) -> ConxianResult<String>;
+    /// Hex of a transaction by txid; confirmed transactions need `-txindex`.
+    async fn get_raw_transaction(&self, txid: &str) -> ConxianResult<String>;
 }
 
+#[derive(Clone)]
 pub struct BitcoinRpcClient {
     client: Arc<Client>,
 }
@@ -69,4 +83,109 @@ impl BitcoinRpc for BitcoinRpcClient {
         .await
         .map_err(|e: tokio::task::JoinError| ConxianError::Internal(e.to_string()))?
     }
+
+    async fn get_block_txids(&self, block_hash: &str) -> ConxianResult<Vec<String>> {
+        let client = self.client.clone();
+        let hash = parse::<BlockHash>(block_hash)?;
+        tokio::task::spawn_blocking(move || {
+            let block = client
+                .get_block(&hash)
+                .map_err(|e: bitcoincore_rpc::Error| ConxianError::Bitcoin(e.to_string()))?;
+            Ok(block
+                .txdata
+                .iter()
+                .map(|tx| tx.compute_txid().to_string())
+                .collect())
+        })
+        .await
+        .map_err(|e: tokio::task::JoinError| ConxianError::Internal(e.to_string()))?
+    }
+
+    async fn get_tx_out_proof(&self, txid: &str, block_hash: &str) -> ConxianResult<String> {
+        let client = self.client.clone();
+        let txid = parse::<Txid>(txid)?;
+        let hash = parse::<BlockHash>(block_hash)?;
+        tokio::task::spawn_blocking(move || {
+            let proof = client
+                .get_tx_out_proof(&[txid], Some(&hash))
+                .map_err(|e: bitcoincore_rpc::Error| ConxianError::Bitcoin(e.to_string()))?;
+            Ok(bitcoin::hex::DisplayHex::to_lower_hex_string(&proof[..]))
+        })
+        .await
+        .map_err(|e: tokio::task::JoinError| ConxianError::Internal(e.to_string()))?
+    }
+
+    async fn create_funded_psbt(&self, data: &[u8]) -> ConxianResult<String> {
+        let client = self.client.clone();
+        let data = bitcoin::hex::DisplayHex::to_lower_hex_string(data);
+        tokio::task::spawn_blocking(move || {
+            let result: Value = client
+                .call(
+                    "walletcreatefundedpsbt",
+                    &[json!([]), json!([{ "data": data }])],
+                )
+                .map_err(|e: bitcoincore_rpc::Error| ConxianError::Bitcoin(e.to_string()))?;
+            result["psbt"].as_str().map(str::to_string).ok_or_else(|| {
+                ConxianError::Bitcoin("walletcreatefundedpsbt returned no psbt".to_string())
+            })
+        })
+        .await
+        .map_err(|e: tokio::task::JoinError| ConxianError::Internal(e.to_string()))?
+    }
+
+    async fn sign_psbt(&self, psbt: &str) -> ConxianResult<String> {
+        let client = self.client.clone();
+        let psbt = psbt.to_string();
+        tokio::task::spawn_blocking(move || {
+            let processed = client
+                .wallet_process_psbt(&psbt, Some(true), None, None)
+                .map_err(|e: bitcoincore_rpc::Error| ConxianError::Bitcoin(e.to_string()))?;
+            let finalized = client
+                .finalize_psbt(&processed.psbt, Some(true))
+                .map_err(|e: bitcoincore_rpc::Error| ConxianError::Bitcoin(e.to_string()))?;
+            match finalized.hex {
+                Some(hex) if finalized.complete => {
+                    Ok(bitcoin::hex::DisplayHex::to_lower_hex_string(&hex[..]))
+                }
+                _ => Err(ConxianError::Bitcoin(
+                    "Wallet could not fully sign the commitment".to_string(),
+                )),
+            }
+        })
+        .await
+        .map_err(|e: tokio::task::JoinError| ConxianError::Internal(e.to_string()))?
+    }
+
+    async fn send_raw_transaction(&self, tx_hex: &str) -> ConxianResult<String> {
+        let client = self.client.clone();
+        let tx_hex = tx_hex.to_string();
+        tokio::task::spawn_blocking(move || {
+            client
+                .send_raw_transaction(tx_hex)
+                .map(|txid| txid.to_string())
+                .map_err(|e: bitcoincore_rpc::Error| ConxianError::Bitcoin(e.to_string()))
+        })
+        .await
+        .map_err(|e: tokio::task::JoinError| ConxianError::Internal(e.to_string()))?
+    }
+
+    async fn get_raw_transaction(&self, txid: &str) -> ConxianResult<String> {
+        let client = self.client.clone();
+        let txid = parse::<Txid>(txid)?;
+        tokio::task::spawn_blocking(move || {
+            client
+                .get_raw_transaction_hex(&txid, None)
+                .map_err(|e: bitcoincore_rpc::Error| ConxianError::Bitcoin(e.to_string()))
+        })
+        .await
+        .map_err(|e: tokio::task::JoinError| ConxianError::Internal(e.to_string()))?
+    }
+}
+
+fn parse<T: std::str::FromStr>(s: &str) -> ConxianResult<T>
+where
+    T::Err: std::fmt::Display,
+{
+    s.parse()
+        .map_err(|e: T::Err| ConxianError::Bitcoin(format!("Invalid hash {}: {}", s, e)))
 }
@@ -1,5 +1,14 @@
+pub mod anchor;
 pub mod bitcoin;
 pub mod stacks;
 
-pub use bitcoin::{BitcoinListener, BitcoinRpc, BitcoinRpcClient};
-pub use stacks::{SimulatedStacksRpc, StacksListener, StacksRpc, StacksRpcClient};
+pub use anchor::AnchorMonitor;
+pub use bitcoin::{
+    BitcoinListener, BitcoinRpc, BitcoinRpcClient, CommitmentMode, HeaderIndex, Notary,
+    NotaryService, RiskPolicy, RiskScorer, SharedHeaderIndex,
+};
+pub use stacks::{
+    BnsName, BnsResolver, MempoolMonitor, MempoolTracker, SignerMonitor, SimulatedStacksRpc,
+    StacksClientConfig, StacksListener, StacksRpc, StacksRpcClient, StacksTransaction,
+    StacksTxPolicy,
+};
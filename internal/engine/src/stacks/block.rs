@@ -0,0 +1,193 @@
+use crate::stacks::tx::recover;
+use bitcoin::hashes::{sha512_256, Hash};
+use bitcoin::secp256k1::PublicKey;
+use conxian_core::codec::Reader;
+use conxian_core::{ConxianError, ConxianResult};
+
+/// Upper bound on signer signatures accepted in one header.
+const MAX_SIGNER_SIGNATURES: u32 = 4000;
+
+/// Header of a Nakamoto (epoch 3) Stacks block, as served by `/v3/blocks`.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct NakamotoBlockHeader {
+    pub version: u8,
+    pub chain_length: u64,
+    pub burn_spent: u64,
+    pub consensus_hash: [u8; 20],
+    pub parent_block_id: [u8; 32],
+    pub tx_merkle_root: [u8; 32],
+    pub state_index_root: [u8; 32],
+    pub timestamp: u64,
+    pub miner_signature: [u8; 65],
+    pub signer_signature: Vec<[u8; 65]>,
+    /// Number of bits in the PoX treatment bit vector.
+    pub pox_treatment_len: u16,
+    pub pox_treatment: Vec<u8>,
+}
+
+impl NakamotoBlockHeader {
+    /// Decodes the header from the start of a serialized block; the
+    /// transactions that follow it are ignored.
+    pub fn decode(raw: &[u8]) -> ConxianResult<Self> {
+        let mut r = Reader::new(raw);
+        let version = r.read_u8()?;
+        let chain_length = r.read_u64()?;
+        let burn_spent = r.read_u64()?;
+        let consensus_hash = r.read_array()?;
+        let parent_block_id = r.read_array()?;
+        let tx_merkle_root = r.read_array()?;
+        let state_index_root = r.read_array()?;
+        let timestamp = r.read_u64()?;
+        let miner_signature = r.read_array()?;
+
+        let count = r.read_u32()?;
+        if count > MAX_SIGNER_SIGNATURES {
+            return Err(ConxianError::Stacks(format!(
+                "Block header has {} signer signatures, more than {}",
+                count, MAX_SIGNER_SIGNATURES
+            )));
+        }
+        let signer_signature = (0..count)
+            .map(|_| r.read_array())
+            .collect::<ConxianResult<Vec<[u8; 65]>>>()?;
+
+        let pox_treatment_len = r.read_u16()?;
+        let data_len = r.read_u32()? as usize;
+        if data_len != (pox_treatment_len as usize).div_ceil(8) {
+            return Err(ConxianError::Stacks(format!(
+                "PoX treatment of {} bits cannot have {} bytes",
+                pox_treatment_len, data_len
+            )));
+        }
+        let pox_treatment = r.read_bytes(data_len)?.to_vec();
+
+        Ok(Self {
+            version,
+            chain_length,
+            burn_spent,
+            consensus_hash,
+            parent_block_id,
+            tx_merkle_root,
+            state_index_root,
+            timestamp,
+            miner_signature,
+            signer_signature,
+            pox_treatment_len,
+            pox_treatment,
+        })
+    }
+
+    /// Serializes the header in consensus format.
+    pub fn serialize_to_vec(&self) -> Vec<u8> {
+        let mut out = Vec::new();
+        self.write_fields(&mut out, true);
+        out
+    }
+
+    fn write_fields(&self, out: &mut Vec<u8>, with_signatures: bool) {
+        out.push(self.version);
+        out.extend_from_slice(&self.chain_length.to_be_bytes());
+        out.extend_from_slice(&self.burn_spent.to_be_bytes());
+        out.extend_from_slice(&self.consensus_hash);
+        out.extend_from_slice(&self.parent_block_id);
+        out.extend_from_slice(&self.tx_merkle_root);
+        out.extend_from_slice(&self.state_index_root);
+        out.extend_from_slice(&self.timestamp.to_be_bytes());
+        if with_signatures {
+            out.extend_from_slice(&self.miner_signature);
+            out.extend_from_slice(&(self.signer_signature.len() as u32).to_be_bytes());
+            for signature in &self.signer_signature {
+                out.extend_from_slice(signature);
+            }
+        }
+        out.extend_from_slice(&self.pox_treatment_len.to_be_bytes());
+        out.extend_from_slice(&(self.pox_treatment.len() as u32).to_be_bytes());
+        out.extend_from_slice(&self.pox_treatment);
+    }
+
+    /// Digest signed by the signer set: every header field except the
+    /// miner and signer signatures.
+    pub fn signer_signature_hash(&self) -> [u8; 32] {
+        let mut out = Vec::new();
+        self.write_fields(&mut out, false);
+        sha512_256::Hash::hash(&out).to_byte_array()
+    }
+
+    /// Recovers the key behind each signer signature, in header order.
+    pub fn signer_keys(&self) -> ConxianResult<Vec<PublicKey>> {
+        let sighash = self.signer_signature_hash();
+        self.signer_signature
+            .iter()
+            .map(|signature| recover(&sighash, signature))
+            .collect()
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use bitcoin::secp256k1::{Message, SecretKey, SECP256K1};
+
+    pub(crate) fn signed_header(height: u64, signers: &[SecretKey]) -> NakamotoBlockHeader {
+        let mut header = NakamotoBlockHeader {
+            version: 0,
+            chain_length: height,
+            burn_spent: 1_000,
+            consensus_hash: [0x11; 20],
+            parent_block_id: [height as u8; 32],
+            tx_merkle_root: [0x22; 32],
+            state_index_root: [0x33; 32],
+            timestamp: 1_700_000_000 + height,
+            miner_signature: [0x01; 65],
+            signer_signature: Vec::new(),
+            pox_treatment_len: 10,
+            pox_treatment: vec![0xff, 0xc0],
+        };
+        let msg = Message::from_digest(header.signer_signature_hash());
+        for sk in signers {
+            let (recid, compact) = SECP256K1
+                .sign_ecdsa_recoverable(&msg, sk)
+                .serialize_compact();
+            let mut signature = [0u8; 65];
+            signature[0] = recid.to_i32() as u8;
+            signature[1..].copy_from_slice(&compact);
+            header.signer_signature.push(signature);
+        }
+        header
+    }
+
+    #[test]
+    fn test_header_roundtrip_and_signer_recovery() {
+        let keys = [
+            SecretKey::from_slice(&[0x61; 32]).unwrap(),
+            SecretKey::from_slice(&[0x62; 32]).unwrap(),
+        ];
+        let header = signed_header(42, &keys);
+
+        // Trailing transaction bytes are ignored
+        let mut raw = header.serialize_to_vec();
+        raw.extend_from_slice(&[0xaa; 16]);
+        let decoded = NakamotoBlockHeader::decode(&raw).unwrap();
+        assert_eq!(decoded, header);
+
+        let recovered = decoded.signer_keys().unwrap();
+        let expected: Vec<PublicKey> = keys.iter().map(|k| k.public_key(SECP256K1)).collect();
+        assert_eq!(recovered, expected);
+    }
+
+    #[test]
+    fn test_signer_hash_excludes_signatures() {
+        let key = SecretKey::from_slice(&[0x63; 32]).unwrap();
+        let unsigned = signed_header(7, &[]);
+        let signed = signed_header(7, &[key]);
+        assert_eq!(
+            unsigned.signer_signature_hash(),
+            signed.signer_signature_hash()
+        );
+
+        let mut raw = signed.serialize_to_vec();
+        let len = raw.len();
+        raw[len - 3] = 0x05; // PoX treatment byte length no longer matches
+        assert!(NakamotoBlockHeader::decode(&raw).is_err());
+    }
+}
@@ -0,0 +1,420 @@
+use crate::stacks::rpc::StacksRpc;
+use bitcoin::hex::DisplayHex;
+use conxian_core::clarity::{ClarityValue, PrincipalData};
+use conxian_core::{ConxianError, ConxianResult, SharedState};
+use serde::Serialize;
+use std::collections::HashMap;
+use std::sync::{Arc, Mutex};
+use tracing::debug;
+
+/// BNS-V2 registry on mainnet.
+pub const BNS_V2_MAINNET: &str = "SP2QEZ06AGJ3RKJPBV14SY1V5BBFNAW33D96YPGZF.BNS-V2";
+/// On-chain zonefile storage for BNS-V2 names on mainnet.
+pub const ZONEFILE_RESOLVER_MAINNET: &str =
+    "SP2QEZ06AGJ3RKJPBV14SY1V5BBFNAW33D96YPGZF.zonefile-resolver";
+/// Stacks blocks a cached resolution stays valid for.
+pub const DEFAULT_BNS_TTL_BLOCKS: u64 = 6;
+/// Cached entries kept per direction before expired ones are evicted.
+const MAX_CACHE_ENTRIES: usize = 10_000;
+
+/// A BNS name and the on-chain records it resolves to.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+pub struct BnsName {
+    /// Fully qualified name, e.g. `alice.btc`.
+    pub name: String,
+    pub owner: PrincipalData,
+    pub renewal_height: Option<u64>,
+    /// Zonefile contents: UTF-8 text, or `0x`-prefixed hex for binary data.
+    pub zonefile: Option<String>,
+    /// Stacks height the records were read at.
+    pub resolved_at: u64,
+}
+
+/// Splits `name.namespace` into its buffers, enforcing the BNS length limits
+/// and character set.
+pub fn parse_fqn(fqn: &str) -> ConxianResult<(String, String)> {
+    let invalid =
+        |reason: &str| ConxianError::Stacks(format!("Invalid BNS name {}: {}", fqn, reason));
+
+    let (name, namespace) = fqn
+        .rsplit_once('.')
+        .ok_or_else(|| invalid("expected name.namespace"))?;
+    if name.is_empty() || name.len() > 48 {
+        return Err(invalid("name must be 1-48 characters"));
+    }
+    if namespace.is_empty() || namespace.len() > 20 {
+        return Err(invalid("namespace must be 1-20 characters"));
+    }
+    let allowed = |c: char| c.is_ascii_lowercase() || c.is_ascii_digit() || c == '-' || c == '_';
+    if !name.chars().all(allowed) || !namespace.chars().all(allowed) {
+        return Err(invalid(
+            "only lowercase letters, digits, '-' and '_' are allowed",
+        ));
+    }
+    Ok((name.to_string(), namespace.to_string()))
+}
+
+struct CacheEntry<T> {
+    value: T,
+    expires_at: u64,
+}
+
+struct Cache<T> {
+    entries: Mutex<HashMap<String, CacheEntry<T>>>,
+}
+
+impl<T: Clone> Cache<T> {
+    fn new() -> Self {
+        Self {
+            entries: Mutex::new(HashMap::new()),
+        }
+    }
+
+    fn get(&self, key: &str, height: u64) -> Option<T> {
+        self.entries
+            .lock()
+            .unwrap()
+            .get(key)
+            .filter(|e| e.expires_at > height)
+            .map(|e| e.value.clone())
+    }
+
+    fn insert(&self, key: String, value: T, expires_at: u64, height: u64) {
+        let mut entries = self.entries.lock().unwrap();
+        if entries.len() >= MAX_CACHE_ENTRIES {
+            entries.retain(|_, e| e.expires_at > height);
+            if entries.len() >= MAX_CACHE_ENTRIES {
+                entries.clear();
+            }
+        }
+        entries.insert(key, CacheEntry { value, expires_at });
+    }
+}
+
+/// Strips `ok` and `some` wrappers, returning `None` for `err` and `none`.
+fn unwrap_value(value: ClarityValue) -> Option<ClarityValue> {
+    match value {
+        ClarityValue::Ok(inner) | ClarityValue::Some(inner) => unwrap_value(*inner),
+        ClarityValue::Err(_) | ClarityValue::None => None,
+        value => Some(value),
+    }
+}
+
+fn unexpected(function: &str, value: &ClarityValue) -> ConxianError {
+    ConxianError::Stacks(format!(
+        "Unexpected result from BNS {}: {:?}",
+        function, value
+    ))
+}
+
+/// Resolves BNS names through read-only calls to the BNS contracts, caching
+/// results until the Stacks chain advances `ttl_blocks` past the lookup.
+pub struct BnsResolver {
+    rpc: Arc<dyn StacksRpc>,
+    state: SharedState,
+    registry: String,
+    zonefile_resolver: Option<String>,
+    ttl_blocks: u64,
+    names: Cache<Option<BnsName>>,
+    primaries: Cache<Option<String>>,
+}
+
+impl BnsResolver {
+    pub fn new(rpc: Arc<dyn StacksRpc>, state: SharedState) -> Self {
+        Self {
+            rpc,
+            state,
+            registry: BNS_V2_MAINNET.to_string(),
+            zonefile_resolver: Some(ZONEFILE_RESOLVER_MAINNET.to_string()),
+            ttl_blocks: DEFAULT_BNS_TTL_BLOCKS,
+            names: Cache::new(),
+            primaries: Cache::new(),
+        }
+    }
+
+    /// Overrides the registry and zonefile contracts, e.g. for testnet deployments.
+    pub fn with_contracts(mut self, registry: String, zonefile_resolver: Option<String>) -> Self {
+        self.registry = registry;
+        self.zonefile_resolver = zonefile_resolver;
+        self
+    }
+
+    pub fn with_ttl_blocks(mut self, ttl_blocks: u64) -> Self {
+        self.ttl_blocks = ttl_blocks.max(1);
+        self
+    }
+
+    fn height(&self) -> u64 {
+        self.state.read().unwrap().stacks.height
+    }
+
+    /// Resolves `name.namespace` to its owner and zonefile, or `None` if unregistered.
+    pub async fn resolve_name(&self, fqn: &str) -> ConxianResult<Option<BnsName>> {
+        let (name, namespace) = parse_fqn(fqn)?;
+        let height = self.height();
+        if let Some(cached) = self.names.get(fqn, height) {
+            return Ok(cached);
+        }
+        debug!("Resolving BNS name {} at Stacks height {}", fqn, height);
+
+        let args = [
+            ClarityValue::Buffer(name.into_bytes()),
+            ClarityValue::Buffer(namespace.into_bytes()),
+        ];
+        let info = self
+            .rpc
+            .call_read_only(&self.registry, "get-bns-info", &args)
+            .await?;
+
+        let resolved = match unwrap_value(info) {
+            None => None,
+            Some(ClarityValue::Tuple(fields)) => {
+                let owner = match fields.get("owner") {
+                    Some(ClarityValue::Principal(owner)) => owner.clone(),
+                    _ => return Err(unexpected("get-bns-info", &ClarityValue::Tuple(fields))),
+                };
+                let renewal_height = match fields.get("renewal-height") {
+                    Some(ClarityValue::UInt(h)) => u64::try_from(*h).ok(),
+                    _ => None,
+                };
+                Some(BnsName {
+                    name: fqn.to_string(),
+                    owner,
+                    renewal_height,
+                    zonefile: self.zonefile(&args).await?,
+                    resolved_at: height,
+                })
+            }
+            Some(other) => return Err(unexpected("get-bns-info", &other)),
+        };
+
+        self.names.insert(
+            fqn.to_string(),
+            resolved.clone(),
+            height + self.ttl_blocks,
+            height,
+        );
+        Ok(resolved)
+    }
+
+    async fn zonefile(&self, args: &[ClarityValue]) -> ConxianResult<Option<String>> {
+        let Some(resolver) = &self.zonefile_resolver else {
+            return Ok(None);
+        };
+        let value = self
+            .rpc
+            .call_read_only(resolver, "resolve-name", args)
+            .await?;
+        Ok(match unwrap_value(value) {
+            Some(ClarityValue::Buffer(data)) => Some(match String::from_utf8(data) {
+                Ok(text) => text,
+                Err(e) => format!("0x{}", e.as_bytes().to_lower_hex_string()),
+            }),
+            _ => None,
+        })
+    }
+
+    /// Resolves the primary name of `principal`, confirming it still owns the name.
+    pub async fn resolve_principal(
+        &self,
+        principal: &PrincipalData,
+    ) -> ConxianResult<Option<BnsName>> {
+        let key = principal.to_string();
+        let height = self.height();
+        let fqn = match self.primaries.get(&key, height) {
+            Some(fqn) => fqn,
+            None => {
+                let value = self
+                    .rpc
+                    .call_read_only(
+                        &self.registry,
+                        "get-primary",
+                        &[ClarityValue::Principal(principal.clone())],
+                    )
+                    .await?;
+                let fqn = match unwrap_value(value) {
+                    None => None,
+                    Some(ClarityValue::Tuple(fields)) => {
+                        match (fields.get("name"), fields.get("namespace")) {
+                            (
+                                Some(ClarityValue::Buffer(name)),
+                                Some(ClarityValue::Buffer(namespace)),
+                            ) => Some(format!(
+                                "{}.{}",
+                                String::from_utf8_lossy(name),
+                                String::from_utf8_lossy(namespace)
+                            )),
+                            _ => {
+                                return Err(unexpected("get-primary", &ClarityValue::Tuple(fields)))
+                            }
+                        }
+                    }
+                    Some(other) => return Err(unexpected("get-primary", &other)),
+                };
+                self.primaries
+                    .insert(key, fqn.clone(), height + self.ttl_blocks, height);
+                fqn
+            }
+        };
+
+        let Some(fqn) = fqn else {
+            return Ok(None);
+        };
+        Ok(self
+            .resolve_name(&fqn)
+            .await?
+            .filter(|name| &name.owner == principal))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stacks::block::NakamotoBlockHeader;
+    use crate::stacks::rpc::{
+        StacksBlockInfo, StacksNetworkInfo, StacksPoxInfo, StacksSigner, StacksTxStatus,
+    };
+    use async_trait::async_trait;
+    use conxian_core::{GatewayState, MempoolTx, StacksAccount};
+    use std::collections::BTreeMap;
+    use std::sync::atomic::{AtomicUsize, Ordering};
+    use std::sync::RwLock;
+
+    const ALICE: &str = "SP000000000000000000002Q6VF78";
+
+    /// Registers `alice.btc` to `ALICE` and counts contract calls.
+    #[derive(Default)]
+    struct MockStacksRpc {
+        calls: AtomicUsize,
+    }
+
+    #[async_trait]
+    impl StacksRpc for MockStacksRpc {
+        async fn get_block_count(&self) -> ConxianResult<u64> {
+            Ok(0)
+        }
+        async fn get_network_info(&self) -> ConxianResult<StacksNetworkInfo> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_block(&self, _height: u64) -> ConxianResult<StacksBlockInfo> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn broadcast_transaction(&self, _raw: &[u8]) -> ConxianResult<String> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_transaction_status(&self, _txid: &str) -> ConxianResult<StacksTxStatus> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_account(&self, _principal: &str) -> ConxianResult<StacksAccount> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_pox_info(&self) -> ConxianResult<StacksPoxInfo> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_signer_set(&self, _cycle: u64) -> ConxianResult<Vec<StacksSigner>> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_block_header(&self, _height: u64) -> ConxianResult<NakamotoBlockHeader> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn call_read_only(
+            &self,
+            _contract: &str,
+            function: &str,
+            args: &[ClarityValue],
+        ) -> ConxianResult<ClarityValue> {
+            self.calls.fetch_add(1, Ordering::SeqCst);
+            let alice = |value: ClarityValue| match args.first() {
+                Some(ClarityValue::Buffer(name)) if name == b"alice" => value,
+                _ => ClarityValue::None,
+            };
+            let some = |value| ClarityValue::Some(Box::new(value));
+            Ok(match function {
+                "get-bns-info" => alice(some(ClarityValue::Tuple(BTreeMap::from([
+                    (
+                        "owner".to_string(),
+                        ClarityValue::Principal(ALICE.parse().unwrap()),
+                    ),
+                    ("renewal-height".to_string(), ClarityValue::UInt(900_000)),
+                ])))),
+                "resolve-name" => alice(ClarityValue::Ok(Box::new(some(ClarityValue::Buffer(
+                    b"{\"owner\":\"alice\"}".to_vec(),
+                ))))),
+                "get-primary" => {
+                    ClarityValue::Ok(Box::new(some(ClarityValue::Tuple(BTreeMap::from([
+                        ("name".to_string(), ClarityValue::Buffer(b"alice".to_vec())),
+                        (
+                            "namespace".to_string(),
+                            ClarityValue::Buffer(b"btc".to_vec()),
+                        ),
+                    ])))))
+                }
+                _ => return Err(ConxianError::Stacks("unknown function".to_string())),
+            })
+        }
+        async fn get_mempool_transactions(
+            &self,
+            _principal: &str,
+        ) -> ConxianResult<Vec<MempoolTx>> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+    }
+
+    fn setup() -> (Arc<MockStacksRpc>, SharedState, BnsResolver) {
+        let rpc = Arc::new(MockStacksRpc::default());
+        let state = Arc::new(RwLock::new(GatewayState::default()));
+        state.write().unwrap().stacks.height = 100;
+        let resolver = BnsResolver::new(rpc.clone(), state.clone()).with_ttl_blocks(3);
+        (rpc, state, resolver)
+    }
+
+    #[test]
+    fn test_parse_fqn_and_default_contracts() {
+        assert_eq!(
+            parse_fqn("alice.btc").unwrap(),
+            ("alice".to_string(), "btc".to_string())
+        );
+        assert!(parse_fqn("alice").is_err());
+        assert!(parse_fqn("Alice.btc").is_err());
+        assert!(parse_fqn(&format!("{}.btc", "a".repeat(49))).is_err());
+
+        for contract in [BNS_V2_MAINNET, ZONEFILE_RESOLVER_MAINNET] {
+            assert!(contract.parse::<PrincipalData>().is_ok());
+        }
+    }
+
+    #[tokio::test]
+    async fn test_resolve_name_and_cache_expiry() {
+        let (rpc, state, resolver) = setup();
+
+        let name = resolver.resolve_name("alice.btc").await.unwrap().unwrap();
+        assert_eq!(name.owner.to_string(), ALICE);
+        assert_eq!(name.renewal_height, Some(900_000));
+        assert_eq!(name.zonefile.as_deref(), Some("{\"owner\":\"alice\"}"));
+        assert_eq!(rpc.calls.load(Ordering::SeqCst), 2);
+
+        // Served from cache, including unregistered names, until the TTL passes
+        assert!(resolver.resolve_name("bob.btc").await.unwrap().is_none());
+        resolver.resolve_name("alice.btc").await.unwrap();
+        assert!(resolver.resolve_name("bob.btc").await.unwrap().is_none());
+        assert_eq!(rpc.calls.load(Ordering::SeqCst), 3);
+
+        state.write().unwrap().stacks.height = 103;
+        let name = resolver.resolve_name("alice.btc").await.unwrap().unwrap();
+        assert_eq!(name.resolved_at, 103);
+        assert_eq!(rpc.calls.load(Ordering::SeqCst), 5);
+    }
+
+    #[tokio::test]
+    async fn test_resolve_principal_checks_ownership() {
+        let (_, _, resolver) = setup();
+
+        let alice: PrincipalData = ALICE.parse().unwrap();
+        let name = resolver.resolve_principal(&alice).await.unwrap().unwrap();
+        assert_eq!(name.name, "alice.btc");
+
+        // A primary name pointing at a name the principal no longer owns
+        let other: PrincipalData = "ST000000000000000000002AMW42H".parse().unwrap();
+        assert!(resolver.resolve_principal(&other).await.unwrap().is_none());
+    }
+}
@@ -0,0 +1,737 @@
+use crate::stacks::block::NakamotoBlockHeader;
+use crate::stacks::rpc::{
+    StacksBlockInfo, StacksNetworkInfo, StacksPoxInfo, StacksRpc, StacksSigner, StacksTxStatus,
+};
+use async_trait::async_trait;
+use bitcoin::hashes::{sha512_256, Hash};
+use bitcoin::hex::DisplayHex;
+use conxian_core::clarity::ClarityValue;
+use conxian_core::{ConxianError, ConxianResult, MempoolTx, StacksAccount};
+use rand::Rng;
+use reqwest::{Client, RequestBuilder, Response, StatusCode};
+use serde::de::DeserializeOwned;
+use serde::Deserialize;
+use std::time::Duration;
+use tokio::time::sleep;
+use tracing::warn;
+
+/// Tuning for the Stacks HTTP client.
+#[derive(Debug, Clone)]
+pub struct StacksClientConfig {
+    /// Timeout applied to each individual request attempt.
+    pub timeout: Duration,
+    /// Retries after the first attempt for connection errors, 429s and 5xxs.
+    /// Broadcasts are only retried when they could not connect.
+    pub max_retries: u32,
+    pub initial_backoff: Duration,
+    pub max_backoff: Duration,
+    /// API key for hosted providers, sent in `api_key_header`.
+    pub api_key: Option<String>,
+    pub api_key_header: String,
+    pub max_response_bytes: usize,
+}
+
+impl Default for StacksClientConfig {
+    fn default() -> Self {
+        Self {
+            timeout: Duration::from_secs(10),
+            max_retries: 3,
+            initial_backoff: Duration::from_millis(250),
+            max_backoff: Duration::from_secs(5),
+            api_key: None,
+            api_key_header: "x-api-key".to_string(),
+            max_response_bytes: 2 * 1024 * 1024,
+        }
+    }
+}
+
+/// Async Stacks node/API client. Clones share one connection pool.
+#[derive(Clone)]
+pub struct StacksRpcClient {
+    url: String,
+    client: Client,
+    config: StacksClientConfig,
+}
+
+impl StacksRpcClient {
+    pub fn new(url: &str) -> ConxianResult<Self> {
+        Self::with_config(url, StacksClientConfig::default())
+    }
+
+    pub fn with_config(url: &str, config: StacksClientConfig) -> ConxianResult<Self> {
+        let client = Client::builder()
+            .pool_idle_timeout(Duration::from_secs(90))
+            .build()
+            .map_err(|e| ConxianError::Stacks(e.to_string()))?;
+        Ok(Self {
+            url: url.trim_end_matches('/').to_string(),
+            client,
+            config,
+        })
+    }
+
+    /// Delay before retry `attempt` (0-based): exponential, capped, with the
+    /// upper half randomised to spread out retries from concurrent callers.
+    fn backoff(&self, attempt: u32) -> Duration {
+        let exp = self
+            .config
+            .initial_backoff
+            .saturating_mul(2u32.saturating_pow(attempt))
+            .min(self.config.max_backoff);
+        let half = exp / 2;
+        half + half.mul_f64(rand::thread_rng().gen::<f64>())
+    }
+
+    async fn read_body(&self, mut res: Response) -> ConxianResult<Vec<u8>> {
+        let limit = self.config.max_response_bytes;
+        if res.content_length().is_some_and(|len| len as usize > limit) {
+            return Err(ConxianError::Stacks(format!(
+                "Stacks RPC response exceeds {} bytes",
+                limit
+            )));
+        }
+
+        let mut body = Vec::new();
+        while let Some(chunk) = res
+            .chunk()
+            .await
+            .map_err(|e| ConxianError::Stacks(e.to_string()))?
+        {
+            if body.len() + chunk.len() > limit {
+                return Err(ConxianError::Stacks(format!(
+                    "Stacks RPC response exceeds {} bytes",
+                    limit
+                )));
+            }
+            body.extend_from_slice(&chunk);
+        }
+        Ok(body)
+    }
+
+    fn prepare(&self, req: RequestBuilder) -> RequestBuilder {
+        let req = req.timeout(self.config.timeout);
+        match &self.config.api_key {
+            Some(key) => req.header(self.config.api_key_header.as_str(), key),
+            None => req,
+        }
+    }
+
+    fn status_error(status: StatusCode, body: &[u8]) -> ConxianError {
+        ConxianError::Stacks(format!(
+            "Stacks RPC error: status {}: {}",
+            status.as_u16(),
+            String::from_utf8_lossy(body)
+        ))
+    }
+
+    /// Sends a request with retries, returning the body of a successful response.
+    async fn send(&self, build: impl Fn() -> RequestBuilder) -> ConxianResult<Vec<u8>> {
+        let mut attempt = 0;
+        loop {
+            let (error, retryable) = match self.prepare(build()).send().await {
+                Ok(res) if res.status().is_success() => return self.read_body(res).await,
+                Ok(res) => {
+                    let status = res.status();
+                    let body = self.read_body(res).await.unwrap_or_default();
+                    (
+                        Self::status_error(status, &body),
+                        status.is_server_error() || status == StatusCode::TOO_MANY_REQUESTS,
+                    )
+                }
+                Err(e) => {
+                    let retryable = e.is_timeout() || e.is_connect() || e.is_request();
+                    (ConxianError::Stacks(e.to_string()), retryable)
+                }
+            };
+
+            if !retryable || attempt >= self.config.max_retries {
+                return Err(error);
+            }
+            let delay = self.backoff(attempt);
+            warn!(
+                "Stacks RPC attempt {} failed, retrying in {:?}: {}",
+                attempt + 1,
+                delay,
+                error
+            );
+            sleep(delay).await;
+            attempt += 1;
+        }
+    }
+
+    async fn get_json<T: DeserializeOwned>(&self, path: &str) -> ConxianResult<T> {
+        let url = format!("{}{}", self.url, path);
+        let body = self.send(|| self.client.get(&url)).await?;
+        serde_json::from_slice(&body).map_err(|e| ConxianError::Stacks(e.to_string()))
+    }
+}
+
+/// Response of `/v2/accounts/{principal}`; balances are hex-encoded u128s.
+#[derive(Deserialize)]
+struct AccountInfo {
+    balance: String,
+    locked: String,
+    unlock_height: u64,
+    nonce: u64,
+}
+
+/// Whether a rejected broadcast is for a transaction the mempool already
+/// holds, as when an earlier attempt reached the node.
+fn is_duplicate_rejection(body: &[u8]) -> bool {
+    #[derive(Deserialize)]
+    struct Rejection {
+        reason: Option<String>,
+    }
+    serde_json::from_slice::<Rejection>(body)
+        .ok()
+        .and_then(|r| r.reason)
+        .is_some_and(|reason| reason == "ConflictingNonceInMempool")
+}
+
+fn parse_hex_u128(value: &str) -> ConxianResult<u128> {
+    u128::from_str_radix(value.trim_start_matches("0x"), 16)
+        .map_err(|e| ConxianError::Stacks(format!("Invalid amount {}: {}", value, e)))
+}
+
+/// Subset of `/v2/pox` used to map burn heights to reward cycles.
+#[derive(Deserialize)]
+struct PoxInfo {
+    first_burnchain_block_height: u64,
+    reward_cycle_length: u64,
+    current_cycle: PoxCycle,
+}
+
+#[derive(Deserialize)]
+struct PoxCycle {
+    id: u64,
+}
+
+#[derive(Deserialize)]
+struct StackerSetResponse {
+    stacker_set: StackerSet,
+}
+
+#[derive(Deserialize)]
+struct StackerSet {
+    signers: Option<Vec<StacksSigner>>,
+}
+
+/// Page of `/extended/v1/tx/mempool`.
+#[derive(Deserialize)]
+struct MempoolPage {
+    results: Vec<MempoolEntry>,
+}
+
+#[derive(Deserialize)]
+struct MempoolEntry {
+    tx_id: String,
+    sender_address: String,
+    nonce: u64,
+    fee_rate: String,
+    receipt_time: u64,
+}
+
+/// Response of `/v2/contracts/call-read`.
+#[derive(Deserialize)]
+struct ReadOnlyResult {
+    okay: bool,
+    result: Option<String>,
+    cause: Option<String>,
+}
+
+#[derive(Deserialize)]
+struct StacksInfo {
+    stacks_tip_height: u64,
+    mode: String,
+    stacks_tip_epoch: String,
+    burn_block_height: u64,
+}
+
+#[async_trait]
+impl StacksRpc for StacksRpcClient {
+    async fn get_block_count(&self) -> ConxianResult<u64> {
+        self.get_network_info().await.map(|info| info.height)
+    }
+
+    async fn get_network_info(&self) -> ConxianResult<StacksNetworkInfo> {
+        let info: StacksInfo = self.get_json("/v2/info").await?;
+
+        Ok(StacksNetworkInfo {
+            height: info.stacks_tip_height,
+            network: info.mode,
+            epoch: info.stacks_tip_epoch,
+            burn_block_height: info.burn_block_height,
+        })
+    }
+
+    /// Requires the Stacks Blockchain API: a bare node's `/v3/blocks/height`
+    /// does not report the burn block a Stacks block was anchored in.
+    async fn get_block(&self, height: u64) -> ConxianResult<StacksBlockInfo> {
+        self.get_json(&format!("/extended/v2/blocks/{}", height))
+            .await
+    }
+
+    /// Posts the transaction without the retries of `send`: a timed out or
+    /// failed broadcast may still have reached the mempool, so only attempts
+    /// that never connected are repeated.
+    async fn broadcast_transaction(&self, raw: &[u8]) -> ConxianResult<String> {
+        let url = format!("{}/v2/transactions", self.url);
+        let mut attempt = 0;
+        loop {
+            let req = self
+                .client
+                .post(&url)
+                .header("Content-Type", "application/octet-stream")
+                .body(raw.to_vec());
+            match self.prepare(req).send().await {
+                Ok(res) if res.status().is_success() => {
+                    // The node responds with the txid as a JSON string
+                    let body = self.read_body(res).await?;
+                    return serde_json::from_slice::<String>(&body)
+                        .map(|txid| txid.trim_start_matches("0x").to_string())
+                        .map_err(|e| ConxianError::Stacks(e.to_string()));
+                }
+                Ok(res) => {
+                    let status = res.status();
+                    let body = self.read_body(res).await.unwrap_or_default();
+                    if attempt > 0 && is_duplicate_rejection(&body) {
+                        return Ok(sha512_256::Hash::hash(raw)
+                            .to_byte_array()
+                            .to_lower_hex_string());
+                    }
+                    return Err(Self::status_error(status, &body));
+                }
+                Err(e) if e.is_connect() && attempt < self.config.max_retries => {
+                    let delay = self.backoff(attempt);
+                    warn!(
+                        "Stacks broadcast attempt {} could not connect, retrying in {:?}: {}",
+                        attempt + 1,
+                        delay,
+                        e
+                    );
+                    sleep(delay).await;
+                    attempt += 1;
+                }
+                Err(e) => return Err(ConxianError::Stacks(e.to_string())),
+            }
+        }
+    }
+
+    async fn get_transaction_status(&self, txid: &str) -> ConxianResult<StacksTxStatus> {
+        self.get_json(&format!(
+            "/extended/v1/tx/0x{}",
+            txid.trim_start_matches("0x")
+        ))
+        .await
+    }
+
+    async fn get_account(&self, principal: &str) -> ConxianResult<StacksAccount> {
+        let info: AccountInfo = self
+            .get_json(&format!("/v2/accounts/{}?proof=0", principal))
+            .await?;
+
+        Ok(StacksAccount {
+            principal: principal.to_string(),
+            balance: parse_hex_u128(&info.balance)?,
+            locked: parse_hex_u128(&info.locked)?,
+            unlock_height: info.unlock_height,
+            nonce: info.nonce,
+        })
+    }
+
+    async fn get_pox_info(&self) -> ConxianResult<StacksPoxInfo> {
+        let info: PoxInfo = self.get_json("/v2/pox").await?;
+        Ok(StacksPoxInfo {
+            current_cycle: info.current_cycle.id,
+            first_burnchain_block_height: info.first_burnchain_block_height,
+            reward_cycle_length: info.reward_cycle_length,
+        })
+    }
+
+    async fn get_signer_set(&self, cycle: u64) -> ConxianResult<Vec<StacksSigner>> {
+        let res: StackerSetResponse = self.get_json(&format!("/v3/stacker_set/{}", cycle)).await?;
+        Ok(res.stacker_set.signers.unwrap_or_default())
+    }
+
+    async fn get_block_header(&self, height: u64) -> ConxianResult<NakamotoBlockHeader> {
+        let url = format!("{}/v3/blocks/height/{}", self.url, height);
+        let raw = self.send(|| self.client.get(&url)).await?;
+        NakamotoBlockHeader::decode(&raw)
+    }
+
+    async fn call_read_only(
+        &self,
+        contract: &str,
+        function: &str,
+        args: &[ClarityValue],
+    ) -> ConxianResult<ClarityValue> {
+        let (address, name) = contract.split_once('.').ok_or_else(|| {
+            ConxianError::Stacks(format!("Invalid contract identifier: {}", contract))
+        })?;
+        let body = serde_json::json!({
+            "sender": address,
+            "arguments": args
+                .iter()
+                .map(ClarityValue::to_hex)
+                .collect::<ConxianResult<Vec<String>>>()?,
+        });
+        let url = format!(
+            "{}/v2/contracts/call-read/{}/{}/{}",
+            self.url, address, name, function
+        );
+        let raw = self.send(|| self.client.post(&url).json(&body)).await?;
+
+        let res: ReadOnlyResult =
+            serde_json::from_slice(&raw).map_err(|e| ConxianError::Stacks(e.to_string()))?;
+        match (res.okay, res.result) {
+            (true, Some(result)) => ClarityValue::from_hex(&result),
+            _ => Err(ConxianError::Stacks(format!(
+                "Read-only call {}::{} failed: {}",
+                contract,
+                function,
+                res.cause.unwrap_or_default()
+            ))),
+        }
+    }
+
+    async fn get_mempool_transactions(&self, principal: &str) -> ConxianResult<Vec<MempoolTx>> {
+        let page: MempoolPage = self
+            .get_json(&format!(
+                "/extended/v1/tx/mempool?address={}&limit=50",
+                principal
+            ))
+            .await?;
+
+        page.results
+            .into_iter()
+            .map(|entry| {
+                Ok(MempoolTx {
+                    txid: entry.tx_id.trim_start_matches("0x").to_string(),
+                    sender: entry.sender_address,
+                    nonce: entry.nonce,
+                    fee: entry.fee_rate.parse().map_err(|_| {
+                        ConxianError::Stacks(format!("Invalid fee rate: {}", entry.fee_rate))
+                    })?,
+                    received_at: entry.receipt_time,
+                })
+            })
+            .collect()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use axum::http::{HeaderMap, StatusCode as AxumStatus};
+    use axum::routing::{get, post};
+    use axum::{Json, Router};
+    use serde_json::json;
+    use std::sync::atomic::{AtomicU32, Ordering};
+    use std::sync::Arc;
+
+    async fn serve(router: Router) -> String {
+        let listener = tokio::net::TcpListener::bind("127.0.0.1:0").await.unwrap();
+        let addr = listener.local_addr().unwrap();
+        tokio::spawn(async move {
+            axum::serve(listener, router).await.unwrap();
+        });
+        format!("http://{}", addr)
+    }
+
+    fn fast_config() -> StacksClientConfig {
+        StacksClientConfig {
+            timeout: Duration::from_millis(500),
+            initial_backoff: Duration::from_millis(1),
+            max_backoff: Duration::from_millis(5),
+            ..StacksClientConfig::default()
+        }
+    }
+
+    fn info() -> Json<serde_json::Value> {
+        Json(json!({
+            "stacks_tip_height": 150000,
+            "mode": "mainnet",
+            "stacks_tip_epoch": "3.0",
+            "burn_block_height": 850000
+        }))
+    }
+
+    #[tokio::test]
+    async fn test_client_parses_info_and_sends_api_key() {
+        let router = Router::new().route(
+            "/v2/info",
+            get(|headers: HeaderMap| async move {
+                match headers.get("x-api-key") {
+                    Some(key) if key == "secret" => Ok(info()),
+                    _ => Err(AxumStatus::UNAUTHORIZED),
+                }
+            }),
+        );
+        let url = serve(router).await;
+        let config = StacksClientConfig {
+            api_key: Some("secret".to_string()),
+            ..fast_config()
+        };
+        let client = StacksRpcClient::with_config(&url, config).unwrap();
+
+        let info = client.get_network_info().await.unwrap();
+        assert_eq!(info.height, 150000);
+        assert_eq!(info.burn_block_height, 850000);
+
+        let unauthenticated = StacksRpcClient::with_config(&url, fast_config()).unwrap();
+        let err = unauthenticated.get_network_info().await.unwrap_err();
+        assert!(err.to_string().contains("401"));
+    }
+
+    #[tokio::test]
+    async fn test_client_retries_server_errors() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        let router = Router::new().route(
+            "/v2/info",
+            get(move || {
+                let counter = counter.clone();
+                async move {
+                    if counter.fetch_add(1, Ordering::SeqCst) < 2 {
+                        Err(AxumStatus::SERVICE_UNAVAILABLE)
+                    } else {
+                        Ok(info())
+                    }
+                }
+            }),
+        );
+        let client = StacksRpcClient::with_config(&serve(router).await, fast_config()).unwrap();
+
+        assert_eq!(client.get_block_count().await.unwrap(), 150000);
+        assert_eq!(calls.load(Ordering::SeqCst), 3);
+    }
+
+    #[tokio::test]
+    async fn test_client_does_not_retry_client_errors() {
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        let router = Router::new().route(
+            "/v2/transactions",
+            post(move || {
+                let counter = counter.clone();
+                async move {
+                    counter.fetch_add(1, Ordering::SeqCst);
+                    (
+                        AxumStatus::BAD_REQUEST,
+                        Json(json!({ "error": "BadNonce" })),
+                    )
+                }
+            }),
+        );
+        let client = StacksRpcClient::with_config(&serve(router).await, fast_config()).unwrap();
+
+        let err = client.broadcast_transaction(&[0u8; 4]).await.unwrap_err();
+        assert!(err.to_string().contains("BadNonce"));
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+    }
+
+    #[tokio::test]
+    async fn test_client_broadcasts_once() {
+        let rejection = json!({
+            "error": "transaction rejected",
+            "reason": "ConflictingNonceInMempool",
+        });
+        let calls = Arc::new(AtomicU32::new(0));
+        let counter = calls.clone();
+        let body = rejection.clone();
+        let router = Router::new().route(
+            "/v2/transactions",
+            post(move || {
+                let counter = counter.clone();
+                let body = body.clone();
+                async move {
+                    if counter.fetch_add(1, Ordering::SeqCst) == 0 {
+                        (AxumStatus::SERVICE_UNAVAILABLE, Json(json!({})))
+                    } else {
+                        (AxumStatus::BAD_REQUEST, Json(body))
+                    }
+                }
+            }),
+        );
+        let client = StacksRpcClient::with_config(&serve(router).await, fast_config()).unwrap();
+
+        // A failed post may have reached the mempool and is not repeated
+        assert!(client.broadcast_transaction(&[0u8; 4]).await.is_err());
+        assert_eq!(calls.load(Ordering::SeqCst), 1);
+        // A duplicate rejection of the first attempt is still an error
+        let err = client.broadcast_transaction(&[0u8; 4]).await.unwrap_err();
+        assert!(err.to_string().contains("ConflictingNonceInMempool"));
+
+        // Posts that could not connect are retried, and a rejected retry of a
+        // transaction already in the mempool counts as broadcast
+        let listener = std::net::TcpListener::bind("127.0.0.1:0").unwrap();
+        let addr = listener.local_addr().unwrap();
+        drop(listener);
+        let router = Router::new().route(
+            "/v2/transactions",
+            post(move || async move { (AxumStatus::BAD_REQUEST, Json(rejection)) }),
+        );
+        tokio::spawn(async move {
+            sleep(Duration::from_millis(50)).await;
+            let listener = tokio::net::TcpListener::bind(addr).await.unwrap();
+            axum::serve(listener, router).await.unwrap();
+        });
+        let config = StacksClientConfig {
+            initial_backoff: Duration::from_millis(40),
+            max_backoff: Duration::from_millis(80),
+            max_retries: 10,
+            ..fast_config()
+        };
+        let client = StacksRpcClient::with_config(&format!("http://{}", addr), config).unwrap();
+        let txid = client.broadcast_transaction(&[0u8; 4]).await.unwrap();
+        assert_eq!(
+            txid,
+            sha512_256::Hash::hash(&[0u8; 4])
+                .to_byte_array()
+                .to_lower_hex_string()
+        );
+    }
+
+    #[tokio::test]
+    async fn test_client_enforces_timeout_and_size_limit() {
+        let router = Router::new()
+            .route(
+                "/v2/info",
+                get(|| async {
+                    tokio::time::sleep(Duration::from_secs(2)).await;
+                    info()
+                }),
+            )
+            .route(
+                "/v2/accounts/{principal}",
+                get(|| async { "x".repeat(4096) }),
+            );
+        let url = serve(router).await;
+        let config = StacksClientConfig {
+            timeout: Duration::from_millis(50),
+            max_retries: 1,
+            max_response_bytes: 1024,
+            ..fast_config()
+        };
+        let client = StacksRpcClient::with_config(&url, config).unwrap();
+
+        assert!(client.get_network_info().await.is_err());
+        let err = client
+            .get_account("SP000000000000000000002Q6VF78")
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("exceeds 1024 bytes"));
+    }
+
+    #[tokio::test]
+    async fn test_client_parses_account() {
+        let router = Router::new().route(
+            "/v2/accounts/{principal}",
+            get(|| async {
+                Json(json!({
+                    "balance": "0x0000000000000000000000000000000a",
+                    "locked": "0x00000000000000000000000000000002",
+                    "unlock_height": 12,
+                    "nonce": 3
+                }))
+            }),
+        );
+        let client = StacksRpcClient::with_config(&serve(router).await, fast_config()).unwrap();
+
+        let account = client
+            .get_account("SP000000000000000000002Q6VF78")
+            .await
+            .unwrap();
+        assert_eq!(account.balance, 10);
+        assert_eq!(account.locked, 2);
+        assert_eq!(account.unlock_height, 12);
+        assert_eq!(account.nonce, 3);
+    }
+
+    #[tokio::test]
+    async fn test_client_fetches_signer_set_and_block_header() {
+        use crate::stacks::block::tests::signed_header;
+        use bitcoin::secp256k1::SecretKey;
+
+        let key = SecretKey::from_slice(&[0x64; 32]).unwrap();
+        let header = signed_header(9, &[key]);
+        let raw = header.serialize_to_vec();
+
+        let router = Router::new()
+            .route(
+                "/v2/pox",
+                get(|| async {
+                    Json(json!({
+                        "first_burnchain_block_height": 100,
+                        "reward_cycle_length": 20,
+                        "current_cycle": { "id": 5 }
+                    }))
+                }),
+            )
+            .route(
+                "/v3/stacker_set/{cycle}",
+                get(|| async {
+                    Json(json!({
+                        "stacker_set": {
+                            "signers": [
+                                { "signing_key": "0x02aa", "weight": 3, "stacked_amt": 1000 }
+                            ]
+                        }
+                    }))
+                }),
+            )
+            .route(
+                "/v3/blocks/height/{height}",
+                get(move || async move { raw }),
+            );
+        let client = StacksRpcClient::with_config(&serve(router).await, fast_config()).unwrap();
+
+        let pox = client.get_pox_info().await.unwrap();
+        assert_eq!(pox.current_cycle, 5);
+        assert_eq!(pox.cycle_of(145), 2);
+
+        let signers = client.get_signer_set(5).await.unwrap();
+        assert_eq!(signers.len(), 1);
+        assert_eq!(signers[0].weight, 3);
+
+        assert_eq!(client.get_block_header(9).await.unwrap(), header);
+    }
+
+    #[tokio::test]
+    async fn test_client_calls_read_only_functions() {
+        let router = Router::new().route(
+            "/v2/contracts/call-read/{address}/{contract}/{function}",
+            post(|Json(body): Json<serde_json::Value>| async move {
+                // Echo the first argument back inside (some ...)
+                match body["arguments"][0].as_str() {
+                    Some(arg) => Json(json!({
+                        "okay": true,
+                        "result": format!("0x0a{}", arg.trim_start_matches("0x"))
+                    })),
+                    None => Json(json!({ "okay": false, "cause": "missing argument" })),
+                }
+            }),
+        );
+        let client = StacksRpcClient::with_config(&serve(router).await, fast_config()).unwrap();
+
+        let value = client
+            .call_read_only(
+                "SP000000000000000000002Q6VF78.bns",
+                "name-resolve",
+                &[ClarityValue::UInt(7)],
+            )
+            .await
+            .unwrap();
+        assert_eq!(value, ClarityValue::Some(Box::new(ClarityValue::UInt(7))));
+
+        let err = client
+            .call_read_only("SP000000000000000000002Q6VF78.bns", "name-resolve", &[])
+            .await
+            .unwrap_err();
+        assert!(err.to_string().contains("missing argument"));
+        assert!(client
+            .call_read_only("no-contract", "f", &[])
+            .await
+            .is_err());
+    }
+}
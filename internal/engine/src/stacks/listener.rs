@@ -1,5 +1,5 @@
 use crate::stacks::StacksRpc;
-use conxian_core::{ConxianResult, Persistence, PersistentState, SharedState};
+use conxian_core::{ConxianResult, GatewayEvent, Persistence, PersistentState, SharedState};
 use std::sync::Arc;
 use std::time::{SystemTime, UNIX_EPOCH};
 use tokio::time::{sleep, Duration};
@@ -10,6 +10,7 @@ pub struct StacksListener<R: StacksRpc> {
     state: SharedState,
     persistence: Arc<dyn Persistence>,
     last_height: u64,
+    tracked_principals: Vec<String>,
 }
 
 impl<R: StacksRpc> StacksListener<R> {
@@ -20,6 +21,39 @@ impl<R: StacksRpc> StacksListener<R> {
             state,
             persistence,
             last_height,
+            tracked_principals: Vec::new(),
+        }
+    }
+
+    /// Principals whose account changes are recorded on every ingested block.
+    pub fn with_tracked_principals(mut self, principals: Vec<String>) -> Self {
+        self.tracked_principals = principals;
+        self
+    }
+
+    async fn refresh_accounts(&self, height: u64) {
+        for principal in &self.tracked_principals {
+            let account = match self.rpc.get_account(principal).await {
+                Ok(account) => account,
+                Err(e) => {
+                    error!("Failed to fetch Stacks account {}: {}", principal, e);
+                    continue;
+                }
+            };
+
+            let mut state = self.state.write().unwrap();
+            let previous = state.accounts.insert(principal.clone(), account.clone());
+            if previous.as_ref().is_some_and(|p| *p != account) {
+                info!(
+                    "Stacks account {} changed at height {}: balance {}",
+                    principal, height, account.balance
+                );
+                state.emit_event(GatewayEvent::AccountChanged {
+                    stacks_height: height,
+                    previous,
+                    current: account,
+                });
+            }
         }
     }
 
@@ -27,26 +61,29 @@ impl<R: StacksRpc> StacksListener<R> {
         match self.rpc.get_network_info().await {
             Ok(info) => {
                 if info.height > self.last_height || self.last_height == 0 {
-                    let mut state = self.state.write().unwrap();
-                    state.stacks.height = info.height;
-                    state.stacks.status = "synced".to_string();
-                    state.stacks.last_updated = SystemTime::now()
-                        .duration_since(UNIX_EPOCH)
-                        .unwrap()
-                        .as_secs();
-                    state.stacks.network = info.network;
-                    state.stacks.mode = Some("nakamoto".to_string());
-                    state.stacks.epoch = Some(info.epoch);
-                    state.stacks.burn_block_height = Some(info.burn_block_height);
-
-                    // Save persistence
-                    let p_state = PersistentState {
-                        bitcoin_height: state.bitcoin.height,
-                        stacks_height: info.height,
-                    };
-                    let _ = self.persistence.save(&p_state);
+                    {
+                        let mut state = self.state.write().unwrap();
+                        state.stacks.height = info.height;
+                        state.stacks.status = "synced".to_string();
+                        state.stacks.last_updated = SystemTime::now()
+                            .duration_since(UNIX_EPOCH)
+                            .unwrap()
+                            .as_secs();
+                        state.stacks.network = info.network;
+                        state.stacks.mode = Some("nakamoto".to_string());
+                        state.stacks.epoch = Some(info.epoch);
+                        state.stacks.burn_block_height = Some(info.burn_block_height);
+
+                        // Save persistence
+                        let p_state = PersistentState {
+                            bitcoin_height: state.bitcoin.height,
+                            stacks_height: info.height,
+                        };
+                        let _ = self.persistence.save(&p_state);
+                    }
 
                     self.last_height = info.height;
+                    self.refresh_accounts(info.height).await;
                 }
                 Ok(())
             }
@@ -73,9 +110,13 @@ impl<R: StacksRpc> StacksListener<R> {
 #[cfg(test)]
 mod tests {
     use super::*;
-    use crate::stacks::rpc::StacksNetworkInfo;
+    use crate::stacks::block::NakamotoBlockHeader;
+    use crate::stacks::rpc::{
+        StacksBlockInfo, StacksNetworkInfo, StacksPoxInfo, StacksSigner, StacksTxStatus,
+    };
     use async_trait::async_trait;
-    use conxian_core::GatewayState;
+    use conxian_core::clarity::ClarityValue;
+    use conxian_core::{ConxianError, GatewayState, MempoolTx, StacksAccount};
     use std::sync::{Arc, RwLock};
 
     struct MockStacksRpc {
@@ -95,6 +136,57 @@ mod tests {
                 burn_block_height: self.height / 10,
             })
         }
+        async fn get_block(&self, height: u64) -> ConxianResult<StacksBlockInfo> {
+            Ok(StacksBlockInfo {
+                height,
+                hash: format!("0x{:064x}", height),
+                parent_block_hash: format!("0x{:064x}", height.saturating_sub(1)),
+                burn_block_height: height / 10,
+                burn_block_hash: format!("0x{:064x}", height / 10),
+            })
+        }
+        async fn broadcast_transaction(&self, _raw: &[u8]) -> ConxianResult<String> {
+            Ok("txid".to_string())
+        }
+        async fn get_transaction_status(&self, _txid: &str) -> ConxianResult<StacksTxStatus> {
+            Ok(StacksTxStatus {
+                tx_status: "pending".to_string(),
+                block_height: None,
+                block_hash: None,
+            })
+        }
+        async fn get_account(&self, principal: &str) -> ConxianResult<StacksAccount> {
+            Ok(StacksAccount {
+                principal: principal.to_string(),
+                balance: (self.height as u128) * 10,
+                locked: 0,
+                unlock_height: 0,
+                nonce: 1,
+            })
+        }
+        async fn get_pox_info(&self) -> ConxianResult<StacksPoxInfo> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_signer_set(&self, _cycle: u64) -> ConxianResult<Vec<StacksSigner>> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_block_header(&self, _height: u64) -> ConxianResult<NakamotoBlockHeader> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn call_read_only(
+            &self,
+            _contract: &str,
+            _function: &str,
+            _args: &[ClarityValue],
+        ) -> ConxianResult<ClarityValue> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_mempool_transactions(
+            &self,
+            _principal: &str,
+        ) -> ConxianResult<Vec<MempoolTx>> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
     }
 
     struct MockPersistence;
@@ -134,4 +226,40 @@ mod tests {
             assert_eq!(s.stacks.burn_block_height, Some(55)); // Mock int div
         }
     }
+
+    #[tokio::test]
+    async fn test_stacks_listener_tracks_accounts() {
+        let state = Arc::new(RwLock::new(GatewayState::default()));
+        let rpc = MockStacksRpc { height: 10 };
+        let persistence = Arc::new(MockPersistence);
+        let principal = "SP000000000000000000002Q6VF78".to_string();
+        let mut listener = StacksListener::new(rpc, state.clone(), persistence)
+            .with_tracked_principals(vec![principal.clone()]);
+
+        listener.sync_once().await.unwrap();
+        {
+            let s = state.read().unwrap();
+            assert_eq!(s.accounts[&principal].balance, 100);
+            assert!(s.events.is_empty());
+        }
+
+        listener.rpc.height = 11;
+        listener.sync_once().await.unwrap();
+
+        let s = state.read().unwrap();
+        assert_eq!(s.accounts[&principal].balance, 110);
+        assert_eq!(s.events.len(), 1);
+        match &s.events[0] {
+            GatewayEvent::AccountChanged {
+                stacks_height,
+                previous,
+                current,
+            } => {
+                assert_eq!(*stacks_height, 11);
+                assert_eq!(previous.as_ref().unwrap().balance, 100);
+                assert_eq!(current.balance, 110);
+            }
+            other => panic!("unexpected event {:?}", other),
+        }
+    }
 }
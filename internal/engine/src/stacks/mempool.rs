@@ -0,0 +1,459 @@
+use crate::stacks::rpc::StacksRpc;
+use crate::stacks::tx::StacksTransaction;
+use conxian_core::clarity::PrincipalData;
+use conxian_core::{ConxianResult, GatewayEvent, GatewayState, MempoolTx, SharedState};
+use std::collections::HashSet;
+use std::sync::Arc;
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::time::{sleep, Duration};
+use tracing::{error, info, warn};
+
+/// Maximum number of pending transactions tracked at once.
+pub const MAX_MEMPOOL_TXS: usize = 10_000;
+/// Drop reason reported by the node when a transaction is replaced by fee.
+const REPLACE_BY_FEE: &str = "ReplaceByFee";
+
+fn normalize_txid(txid: &str) -> String {
+    txid.trim_start_matches("0x").to_lowercase()
+}
+
+/// Records pending Stacks transactions of watched principals, whether fed by
+/// polling or by event-observer `new_mempool_tx`/`drop_mempool_tx` callbacks.
+/// Clones share the same watch list and gateway state.
+#[derive(Clone)]
+pub struct MempoolTracker {
+    state: SharedState,
+    watched: Arc<HashSet<PrincipalData>>,
+}
+
+impl MempoolTracker {
+    /// Tracks the transactions of `watched`, failing on a malformed principal.
+    pub fn new(state: SharedState, watched: Vec<String>) -> ConxianResult<Self> {
+        let watched = watched
+            .iter()
+            .map(|p| p.parse())
+            .collect::<ConxianResult<HashSet<PrincipalData>>>()?;
+        Ok(Self {
+            state,
+            watched: Arc::new(watched),
+        })
+    }
+
+    pub fn watched(&self) -> impl Iterator<Item = &PrincipalData> {
+        self.watched.iter()
+    }
+
+    /// Whether a principal, or the address of a contract principal, is watched.
+    fn is_watched(&self, principal: &PrincipalData) -> bool {
+        self.watched.contains(principal)
+            || self
+                .watched
+                .contains(&PrincipalData::Standard(*principal.address()))
+    }
+
+    /// Marks a gateway-submitted transaction as dropped, if it is tracked.
+    fn mark_dropped(state: &mut GatewayState, txid: &str) {
+        if let Some(tracked) = state.transactions.get_mut(txid) {
+            if tracked.status == "pending" {
+                tracked.status = "dropped".to_string();
+            }
+        }
+    }
+
+    /// Records a pending transaction, replacing any pending transaction with
+    /// the same sender and nonce. Returns whether the transaction was new.
+    pub fn record(&self, mut tx: MempoolTx) -> bool {
+        tx.txid = normalize_txid(&tx.txid);
+        let mut state = self.state.write().unwrap();
+        if state.mempool.pending.contains_key(&tx.txid) {
+            return false;
+        }
+
+        let replaced: Vec<String> = state
+            .mempool
+            .pending
+            .values()
+            .filter(|p| p.sender == tx.sender && p.nonce == tx.nonce)
+            .map(|p| p.txid.clone())
+            .collect();
+        for txid in replaced {
+            info!(
+                "Stacks transaction {} replaced by {} (sender {}, nonce {})",
+                txid, tx.txid, tx.sender, tx.nonce
+            );
+            state.mempool.pending.remove(&txid);
+            state.mempool.replaced += 1;
+            Self::mark_dropped(&mut state, &txid);
+            state.emit_event(GatewayEvent::MempoolTxReplaced {
+                txid,
+                replaced_by: tx.txid.clone(),
+                sender: tx.sender.clone(),
+                nonce: tx.nonce,
+            });
+        }
+
+        if state.mempool.pending.len() >= MAX_MEMPOOL_TXS {
+            warn!(
+                "Stacks mempool tracking is full, ignoring transaction {}",
+                tx.txid
+            );
+            return false;
+        }
+        state.mempool.seen += 1;
+        state.mempool.pending.insert(tx.txid.clone(), tx);
+        true
+    }
+
+    /// Records a raw transaction from `new_mempool_tx` if it involves a
+    /// watched principal. Returns whether it was recorded.
+    pub fn observe_raw(&self, raw: &[u8]) -> ConxianResult<bool> {
+        let tx = StacksTransaction::decode(raw)?;
+        if !tx.principals().iter().any(|p| self.is_watched(p)) {
+            return Ok(false);
+        }
+        Ok(self.record(MempoolTx {
+            txid: tx.txid.clone(),
+            sender: tx.sender().to_string(),
+            nonce: tx.origin().nonce,
+            fee: tx.fee(),
+            received_at: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+        }))
+    }
+
+    /// Removes dropped transactions; `new_txid` names the replacement of a
+    /// `ReplaceByFee` drop.
+    pub fn drop_txs(&self, txids: &[String], reason: &str, new_txid: Option<&str>) {
+        let mut state = self.state.write().unwrap();
+        for txid in txids {
+            let txid = normalize_txid(txid);
+            let Some(tx) = state.mempool.pending.remove(&txid) else {
+                continue;
+            };
+            Self::mark_dropped(&mut state, &txid);
+
+            match new_txid {
+                Some(new_txid) if reason == REPLACE_BY_FEE => {
+                    state.mempool.replaced += 1;
+                    state.emit_event(GatewayEvent::MempoolTxReplaced {
+                        txid,
+                        replaced_by: normalize_txid(new_txid),
+                        sender: tx.sender,
+                        nonce: tx.nonce,
+                    });
+                }
+                _ => {
+                    info!("Stacks transaction {} dropped: {}", txid, reason);
+                    state.mempool.dropped += 1;
+                    state.emit_event(GatewayEvent::MempoolTxDropped {
+                        txid,
+                        sender: tx.sender,
+                        nonce: tx.nonce,
+                        reason: reason.to_string(),
+                    });
+                }
+            }
+        }
+    }
+
+    /// Removes a transaction that has been included in a block.
+    pub fn confirm(&self, txid: &str) {
+        let mut state = self.state.write().unwrap();
+        if state
+            .mempool
+            .pending
+            .remove(&normalize_txid(txid))
+            .is_some()
+        {
+            state.mempool.confirmed += 1;
+        }
+    }
+}
+
+/// Polls the Stacks API for the mempool of watched principals and resolves
+/// transactions that leave it as confirmed or dropped.
+pub struct MempoolMonitor<R: StacksRpc> {
+    rpc: R,
+    tracker: MempoolTracker,
+    poll_mempool: bool,
+}
+
+impl<R: StacksRpc> MempoolMonitor<R> {
+    pub fn new(rpc: R, tracker: MempoolTracker) -> Self {
+        Self {
+            rpc,
+            tracker,
+            poll_mempool: true,
+        }
+    }
+
+    /// Disables mempool listing when an event observer feeds the tracker;
+    /// pending transactions are still resolved through the API.
+    pub fn with_observer_feed(mut self) -> Self {
+        self.poll_mempool = false;
+        self
+    }
+
+    pub async fn poll_once(&self) -> ConxianResult<()> {
+        let mut listed = HashSet::new();
+        if self.poll_mempool {
+            for principal in self.tracker.watched() {
+                let principal = principal.to_string();
+                for tx in self.rpc.get_mempool_transactions(&principal).await? {
+                    listed.insert(normalize_txid(&tx.txid));
+                    self.tracker.record(tx);
+                }
+            }
+        }
+
+        let unlisted: Vec<String> = self
+            .tracker
+            .state
+            .read()
+            .unwrap()
+            .mempool
+            .pending
+            .keys()
+            .filter(|txid| !listed.contains(*txid))
+            .cloned()
+            .collect();
+        for txid in unlisted {
+            match self.rpc.get_transaction_status(&txid).await {
+                Ok(status) => match status.tx_status.as_str() {
+                    "pending" => {}
+                    s if s.starts_with("dropped") => {
+                        self.tracker.drop_txs(std::slice::from_ref(&txid), s, None)
+                    }
+                    _ => self.tracker.confirm(&txid),
+                },
+                Err(e) => error!("Failed to refresh mempool transaction {}: {}", txid, e),
+            }
+        }
+        Ok(())
+    }
+
+    pub async fn run(&mut self) -> ConxianResult<()> {
+        info!("Starting Stacks mempool monitor...");
+
+        loop {
+            if let Err(e) = self.poll_once().await {
+                error!("Failed to poll Stacks mempool: {}", e);
+            }
+            sleep(Duration::from_secs(30)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stacks::block::NakamotoBlockHeader;
+    use crate::stacks::rpc::{
+        StacksBlockInfo, StacksNetworkInfo, StacksPoxInfo, StacksSigner, StacksTxStatus,
+    };
+    use crate::stacks::tx::tests::signed_transfer;
+    use async_trait::async_trait;
+    use bitcoin::secp256k1::SecretKey;
+    use conxian_core::clarity::ClarityValue;
+    use conxian_core::{ConxianError, StacksAccount, TransactionInfo};
+    use std::sync::{Mutex, RwLock};
+
+    const WATCHED: &str = "ST000000000000000000002AMW42H";
+
+    fn mempool_tx(txid: &str, nonce: u64, fee: u64) -> MempoolTx {
+        MempoolTx {
+            txid: txid.to_string(),
+            sender: WATCHED.to_string(),
+            nonce,
+            fee,
+            received_at: 0,
+        }
+    }
+
+    fn setup() -> (SharedState, MempoolTracker) {
+        let state = Arc::new(RwLock::new(GatewayState::default()));
+        let tracker = MempoolTracker::new(state.clone(), vec![WATCHED.to_string()]).unwrap();
+        (state, tracker)
+    }
+
+    /// Lists the configured mempool and reports "mined" as included,
+    /// "gone" as dropped and anything else as pending.
+    struct MockStacksRpc {
+        mempool: Mutex<Vec<MempoolTx>>,
+    }
+
+    #[async_trait]
+    impl StacksRpc for MockStacksRpc {
+        async fn get_block_count(&self) -> ConxianResult<u64> {
+            Ok(0)
+        }
+        async fn get_network_info(&self) -> ConxianResult<StacksNetworkInfo> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_block(&self, _height: u64) -> ConxianResult<StacksBlockInfo> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn broadcast_transaction(&self, _raw: &[u8]) -> ConxianResult<String> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_transaction_status(&self, txid: &str) -> ConxianResult<StacksTxStatus> {
+            Ok(StacksTxStatus {
+                tx_status: match txid {
+                    "mined" => "success",
+                    "gone" => "dropped_stale_garbage_collect",
+                    _ => "pending",
+                }
+                .to_string(),
+                block_height: None,
+                block_hash: None,
+            })
+        }
+        async fn get_account(&self, _principal: &str) -> ConxianResult<StacksAccount> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_pox_info(&self) -> ConxianResult<StacksPoxInfo> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_signer_set(&self, _cycle: u64) -> ConxianResult<Vec<StacksSigner>> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_block_header(&self, _height: u64) -> ConxianResult<NakamotoBlockHeader> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn call_read_only(
+            &self,
+            _contract: &str,
+            _function: &str,
+            _args: &[ClarityValue],
+        ) -> ConxianResult<ClarityValue> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_mempool_transactions(
+            &self,
+            _principal: &str,
+        ) -> ConxianResult<Vec<MempoolTx>> {
+            Ok(self.mempool.lock().unwrap().clone())
+        }
+    }
+
+    #[test]
+    fn test_record_detects_replace_by_fee() {
+        let (state, tracker) = setup();
+        state
+            .write()
+            .unwrap()
+            .transactions
+            .insert("aa".to_string(), TransactionInfo::pending("stacks", "aa"));
+
+        assert!(tracker.record(mempool_tx("0xAA", 3, 180)));
+        assert!(!tracker.record(mempool_tx("aa", 3, 180)));
+        assert!(tracker.record(mempool_tx("bb", 4, 180)));
+        assert!(tracker.record(mempool_tx("cc", 3, 500)));
+
+        let s = state.read().unwrap();
+        assert_eq!(
+            s.mempool.pending.keys().collect::<Vec<_>>(),
+            vec!["bb", "cc"]
+        );
+        assert_eq!(s.mempool.seen, 3);
+        assert_eq!(s.mempool.replaced, 1);
+        assert_eq!(s.transactions["aa"].status, "dropped");
+        assert!(matches!(
+            &s.events[0],
+            GatewayEvent::MempoolTxReplaced { txid, replaced_by, nonce: 3, .. }
+                if txid == "aa" && replaced_by == "cc"
+        ));
+    }
+
+    #[test]
+    fn test_observer_feed_filters_and_drops() {
+        let (state, tracker) = setup();
+        let sk = SecretKey::from_slice(&[0x71; 32]).unwrap();
+
+        // Matches the watched address through a contract recipient
+        let raw = signed_transfer(&sk, &format!("{}.vault", WATCHED), 10);
+        let other =
+            MempoolTracker::new(state.clone(), vec!["SP000000000000000000002Q6VF78".into()])
+                .unwrap();
+        assert!(!other.observe_raw(&raw).unwrap());
+        assert!(tracker.observe_raw(&raw).unwrap());
+        assert!(tracker.observe_raw(&[0x00, 0x01]).is_err());
+
+        let txid = state
+            .read()
+            .unwrap()
+            .mempool
+            .pending
+            .keys()
+            .next()
+            .unwrap()
+            .clone();
+        tracker.drop_txs(&[format!("0x{}", txid)], "StaleGarbageCollect", None);
+
+        let s = state.read().unwrap();
+        assert!(s.mempool.pending.is_empty());
+        assert_eq!(s.mempool.dropped, 1);
+        assert!(matches!(
+            &s.events[0],
+            GatewayEvent::MempoolTxDropped { reason, nonce: 7, .. } if reason == "StaleGarbageCollect"
+        ));
+    }
+
+    #[test]
+    fn test_observer_feed_rejects_invalid_address_version() {
+        let (state, tracker) = setup();
+        let sk = SecretKey::from_slice(&[0x72; 32]).unwrap();
+        let mut raw = signed_transfer(&sk, WATCHED, 10);
+        // Recipient version byte, just before its hash160
+        let version_offset = raw.len() - 34 - 8 - 20 - 1;
+        raw[version_offset] = 0xff;
+
+        assert!(tracker.observe_raw(&raw).is_err());
+        assert!(state.read().unwrap().mempool.pending.is_empty());
+        assert!(MempoolTracker::new(state, vec!["not-a-principal".into()]).is_err());
+    }
+
+    #[test]
+    fn test_drop_with_replacement_counts_as_replaced() {
+        let (state, tracker) = setup();
+        tracker.record(mempool_tx("aa", 1, 180));
+        tracker.drop_txs(&["aa".to_string()], "ReplaceByFee", Some("0xBB"));
+
+        let s = state.read().unwrap();
+        assert_eq!(s.mempool.replaced, 1);
+        assert_eq!(s.mempool.dropped, 0);
+        assert!(matches!(
+            &s.events[0],
+            GatewayEvent::MempoolTxReplaced { replaced_by, .. } if replaced_by == "bb"
+        ));
+    }
+
+    #[tokio::test]
+    async fn test_poll_resolves_transactions_leaving_mempool() {
+        let (state, tracker) = setup();
+        let rpc = MockStacksRpc {
+            mempool: Mutex::new(vec![
+                mempool_tx("mined", 1, 180),
+                mempool_tx("gone", 2, 180),
+                mempool_tx("waiting", 3, 180),
+            ]),
+        };
+        let monitor = MempoolMonitor::new(rpc, tracker);
+
+        monitor.poll_once().await.unwrap();
+        assert_eq!(state.read().unwrap().mempool.pending.len(), 3);
+
+        monitor.rpc.mempool.lock().unwrap().clear();
+        monitor.poll_once().await.unwrap();
+
+        let s = state.read().unwrap();
+        assert_eq!(
+            s.mempool.pending.keys().collect::<Vec<_>>(),
+            vec!["waiting"]
+        );
+        assert_eq!(s.mempool.confirmed, 1);
+        assert_eq!(s.mempool.dropped, 1);
+    }
+}
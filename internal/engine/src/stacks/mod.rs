@@ -1,5 +1,21 @@
+pub mod block;
+pub mod bns;
+pub mod client;
 pub mod listener;
+pub mod mempool;
+pub mod policy;
 pub mod rpc;
+pub mod signers;
+pub mod tx;
 
+pub use block::NakamotoBlockHeader;
+pub use bns::{BnsName, BnsResolver};
+pub use client::{StacksClientConfig, StacksRpcClient};
 pub use listener::StacksListener;
-pub use rpc::{SimulatedStacksRpc, StacksRpc, StacksRpcClient};
+pub use mempool::{MempoolMonitor, MempoolTracker};
+pub use policy::StacksTxPolicy;
+pub use rpc::{
+    SimulatedStacksRpc, StacksBlockInfo, StacksPoxInfo, StacksRpc, StacksSigner, StacksTxStatus,
+};
+pub use signers::SignerMonitor;
+pub use tx::StacksTransaction;
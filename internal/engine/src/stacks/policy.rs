@@ -0,0 +1,194 @@
+use crate::stacks::tx::{StacksTransaction, TransactionPayload, CHAIN_ID_MAINNET};
+use compliance::Screener;
+use conxian_core::{ConxianError, ConxianResult};
+use std::collections::HashSet;
+use std::sync::Arc;
+
+/// Controls applied to Stacks transactions before the gateway broadcasts them.
+#[derive(Debug, Clone)]
+pub struct StacksTxPolicy {
+    pub chain_id: u32,
+    /// Maximum fee in micro-STX.
+    pub max_fee: u64,
+    /// Maximum STX transfer amount in micro-STX, if limited.
+    pub max_transfer: Option<u64>,
+    pub allow_contract_deploy: bool,
+    pub blocked_principals: HashSet<String>,
+    /// Denylists every principal of the transaction is screened against.
+    pub screener: Option<Arc<Screener>>,
+}
+
+impl Default for StacksTxPolicy {
+    fn default() -> Self {
+        Self {
+            chain_id: CHAIN_ID_MAINNET,
+            max_fee: 1_000_000,
+            max_transfer: None,
+            allow_contract_deploy: false,
+            blocked_principals: HashSet::new(),
+            screener: None,
+        }
+    }
+}
+
+impl StacksTxPolicy {
+    /// Screens a decoded transaction, returning a `Compliance` error naming the
+    /// first control it violates.
+    pub fn check(&self, tx: &StacksTransaction) -> ConxianResult<()> {
+        let deny = |reason: String| Err(ConxianError::Compliance(reason));
+
+        if tx.chain_id != self.chain_id {
+            return deny(format!(
+                "Transaction chain id 0x{:08x} does not match gateway chain id 0x{:08x}",
+                tx.chain_id, self.chain_id
+            ));
+        }
+        if tx.mainnet != (self.chain_id == CHAIN_ID_MAINNET) {
+            return deny("Transaction version does not match gateway network".to_string());
+        }
+        if tx.fee() > self.max_fee {
+            return deny(format!(
+                "Fee {} exceeds maximum of {}",
+                tx.fee(),
+                self.max_fee
+            ));
+        }
+
+        match &tx.payload {
+            TransactionPayload::TokenTransfer { amount, .. } => {
+                if let Some(max) = self.max_transfer {
+                    if *amount > max {
+                        return deny(format!(
+                            "Transfer amount {} exceeds maximum of {}",
+                            amount, max
+                        ));
+                    }
+                }
+            }
+            TransactionPayload::ContractDeploy { .. } if !self.allow_contract_deploy => {
+                return deny("Contract deployments are not permitted".to_string());
+            }
+            _ => {}
+        }
+
+        for principal in tx.principals() {
+            let principal = principal.to_string();
+            let address = principal.split('.').next().unwrap_or_default();
+            if self.blocked_principals.contains(&principal)
+                || self.blocked_principals.contains(address)
+            {
+                return deny(format!("Principal {} is blocked", principal));
+            }
+        }
+        if let Some(screener) = &self.screener {
+            screener.check(tx.principals().iter().map(ToString::to_string))?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stacks::tx::tests::{signed_contract_call, signed_transfer};
+    use crate::stacks::tx::CHAIN_ID_TESTNET;
+    use bitcoin::secp256k1::SecretKey;
+    use conxian_core::clarity::{ClarityValue, PrincipalData};
+
+    fn testnet_policy() -> StacksTxPolicy {
+        StacksTxPolicy {
+            chain_id: CHAIN_ID_TESTNET,
+            ..StacksTxPolicy::default()
+        }
+    }
+
+    #[test]
+    fn test_policy_allows_and_limits() {
+        let sk = SecretKey::from_slice(&[0x44; 32]).unwrap();
+        let tx = StacksTransaction::decode(&signed_transfer(
+            &sk,
+            "ST000000000000000000002AMW42H",
+            5_000,
+        ))
+        .unwrap();
+
+        assert!(testnet_policy().check(&tx).is_ok());
+        assert!(StacksTxPolicy::default().check(&tx).is_err());
+
+        let limited = StacksTxPolicy {
+            max_transfer: Some(1_000),
+            ..testnet_policy()
+        };
+        assert!(limited.check(&tx).is_err());
+    }
+
+    #[test]
+    fn test_policy_blocks_principals() {
+        let sk = SecretKey::from_slice(&[0x55; 32]).unwrap();
+        let tx = StacksTransaction::decode(&signed_transfer(
+            &sk,
+            "ST000000000000000000002AMW42H.some-contract",
+            1,
+        ))
+        .unwrap();
+
+        let mut policy = testnet_policy();
+        policy
+            .blocked_principals
+            .insert("ST000000000000000000002AMW42H".to_string());
+        let err = policy.check(&tx).unwrap_err();
+        assert!(err.to_string().contains("is blocked"));
+    }
+
+    #[test]
+    fn test_policy_blocks_contract_call_recipients() {
+        let sk = SecretKey::from_slice(&[0x56; 32]).unwrap();
+        let blocked = "ST2JHG361ZXG51QTKY2NQCVBPPRRE2KZB1HR05NNC";
+        let tx = StacksTransaction::decode(&signed_contract_call(
+            &sk,
+            "ST000000000000000000002AMW42H.sip-010-token",
+            "transfer",
+            &[
+                ClarityValue::UInt(100),
+                ClarityValue::Principal(PrincipalData::Standard(
+                    StacksTransaction::decode(&signed_transfer(&sk, blocked, 1))
+                        .unwrap()
+                        .sender(),
+                )),
+                ClarityValue::Principal(blocked.parse().unwrap()),
+                ClarityValue::None,
+            ],
+            &[],
+        ))
+        .unwrap();
+
+        let mut policy = testnet_policy();
+        assert!(policy.check(&tx).is_ok());
+        policy.blocked_principals.insert(blocked.to_string());
+        let err = policy.check(&tx).unwrap_err();
+        assert!(err.to_string().contains(blocked), "{}", err);
+    }
+
+    #[test]
+    fn test_policy_screens_principals() {
+        let sk = SecretKey::from_slice(&[0x66; 32]).unwrap();
+        let tx = StacksTransaction::decode(&signed_transfer(
+            &sk,
+            "ST000000000000000000002AMW42H.some-contract",
+            1,
+        ))
+        .unwrap();
+
+        // The mainnet encoding of the recipient's deployer is listed
+        let path = std::env::temp_dir().join(format!("conxian-ofac-{}.csv", rand::random::<u64>()));
+        std::fs::write(&path, "SP000000000000000000002Q6VF78,SDN\n").unwrap();
+        let policy = StacksTxPolicy {
+            screener: Some(Arc::new(Screener::open(&[&path]).unwrap())),
+            ..testnet_policy()
+        };
+        let err = policy.check(&tx).unwrap_err();
+        assert!(err.to_string().contains("derived match"), "{}", err);
+        assert!(testnet_policy().check(&tx).is_ok());
+        std::fs::remove_file(path).unwrap();
+    }
+}
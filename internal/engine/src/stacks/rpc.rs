@@ -1,5 +1,7 @@
+use crate::stacks::block::NakamotoBlockHeader;
 use async_trait::async_trait;
-use conxian_core::{ConxianError, ConxianResult};
+use conxian_core::clarity::ClarityValue;
+use conxian_core::{ConxianResult, MempoolTx, StacksAccount};
 use serde::Deserialize;
 
 #[derive(Debug, Clone)]
@@ -10,10 +12,68 @@ pub struct StacksNetworkInfo {
     pub burn_block_height: u64,
 }
 
+#[derive(Debug, Clone, Deserialize)]
+pub struct StacksBlockInfo {
+    pub height: u64,
+    pub hash: String,
+    pub parent_block_hash: String,
+    pub burn_block_height: u64,
+    pub burn_block_hash: String,
+}
+
+/// Inclusion status of a transaction as reported by the Stacks API.
+#[derive(Debug, Clone, Deserialize)]
+pub struct StacksTxStatus {
+    pub tx_status: String,
+    pub block_height: Option<u64>,
+    pub block_hash: Option<String>,
+}
+
+/// Reward cycle parameters from `/v2/pox`.
+#[derive(Debug, Clone)]
+pub struct StacksPoxInfo {
+    pub current_cycle: u64,
+    pub first_burnchain_block_height: u64,
+    pub reward_cycle_length: u64,
+}
+
+impl StacksPoxInfo {
+    /// Reward cycle containing the given burn block height.
+    pub fn cycle_of(&self, burn_height: u64) -> u64 {
+        burn_height.saturating_sub(self.first_burnchain_block_height)
+            / self.reward_cycle_length.max(1)
+    }
+}
+
+/// Member of a reward cycle's signer set.
+#[derive(Debug, Clone, Deserialize)]
+pub struct StacksSigner {
+    /// Hex-encoded compressed secp256k1 public key.
+    pub signing_key: String,
+    pub weight: u32,
+}
+
 #[async_trait]
 pub trait StacksRpc: Send + Sync {
     async fn get_block_count(&self) -> ConxianResult<u64>;
     async fn get_network_info(&self) -> ConxianResult<StacksNetworkInfo>;
+    async fn get_block(&self, height: u64) -> ConxianResult<StacksBlockInfo>;
+    /// Broadcasts a serialized transaction, returning its txid.
+    async fn broadcast_transaction(&self, raw: &[u8]) -> ConxianResult<String>;
+    async fn get_transaction_status(&self, txid: &str) -> ConxianResult<StacksTxStatus>;
+    async fn get_account(&self, principal: &str) -> ConxianResult<StacksAccount>;
+    async fn get_pox_info(&self) -> ConxianResult<StacksPoxInfo>;
+    async fn get_signer_set(&self, cycle: u64) -> ConxianResult<Vec<StacksSigner>>;
+    async fn get_block_header(&self, height: u64) -> ConxianResult<NakamotoBlockHeader>;
+    /// Evaluates a read-only function of `contract` (`address.name`).
+    async fn call_read_only(
+        &self,
+        contract: &str,
+        function: &str,
+        args: &[ClarityValue],
+    ) -> ConxianResult<ClarityValue>;
+    /// Pending transactions sent or received by `principal`.
+    async fn get_mempool_transactions(&self, principal: &str) -> ConxianResult<Vec<MempoolTx>>;
 }
 
 pub struct SimulatedStacksRpc {
@@ -34,64 +94,82 @@ impl StacksRpc for SimulatedStacksRpc {
             burn_block_height: self.initial_height / 10,
         })
     }
-}
 
-pub struct StacksRpcClient {
-    url: String,
-}
+    async fn get_block(&self, height: u64) -> ConxianResult<StacksBlockInfo> {
+        Ok(StacksBlockInfo {
+            height,
+            hash: format!("0x{:064x}", height),
+            parent_block_hash: format!("0x{:064x}", height.saturating_sub(1)),
+            burn_block_height: height / 10,
+            burn_block_hash: format!("0x{:064x}", height / 10),
+        })
+    }
 
-impl StacksRpcClient {
-    pub fn new(url: &str) -> Self {
-        Self {
-            url: url.to_string(),
-        }
+    async fn broadcast_transaction(&self, raw: &[u8]) -> ConxianResult<String> {
+        use bitcoin::hashes::{sha512_256, Hash};
+        use bitcoin::hex::DisplayHex;
+        Ok(sha512_256::Hash::hash(raw)
+            .to_byte_array()
+            .to_lower_hex_string())
     }
-}
 
-#[derive(Deserialize)]
-struct StacksInfo {
-    stacks_tip_height: u64,
-    mode: String,
-    stacks_tip_epoch: String,
-    burn_block_height: u64,
-}
+    async fn get_transaction_status(&self, _txid: &str) -> ConxianResult<StacksTxStatus> {
+        Ok(StacksTxStatus {
+            tx_status: "success".to_string(),
+            block_height: Some(self.initial_height),
+            block_hash: Some(format!("0x{:064x}", self.initial_height)),
+        })
+    }
 
-#[async_trait]
-impl StacksRpc for StacksRpcClient {
-    async fn get_block_count(&self) -> ConxianResult<u64> {
-        self.get_network_info().await.map(|info| info.height)
+    async fn get_account(&self, principal: &str) -> ConxianResult<StacksAccount> {
+        Ok(StacksAccount {
+            principal: principal.to_string(),
+            balance: 1_000_000,
+            locked: 0,
+            unlock_height: 0,
+            nonce: 0,
+        })
     }
 
-    async fn get_network_info(&self) -> ConxianResult<StacksNetworkInfo> {
-        let url = format!("{}/v2/info", self.url);
-
-        tokio::task::spawn_blocking(move || {
-            let res = minreq::get(&url)
-                .send()
-                .map_err(|e| ConxianError::Stacks(e.to_string()))?;
-
-            if res.status_code != 200 {
-                return Err(ConxianError::Stacks(format!(
-                    "Stacks RPC error: status {}",
-                    res.status_code
-                )));
-            }
-
-            let body = res
-                .as_str()
-                .map_err(|e| ConxianError::Stacks(e.to_string()))?;
-
-            let info: StacksInfo =
-                serde_json::from_str(body).map_err(|e| ConxianError::Stacks(e.to_string()))?;
-
-            Ok(StacksNetworkInfo {
-                height: info.stacks_tip_height,
-                network: info.mode,
-                epoch: info.stacks_tip_epoch,
-                burn_block_height: info.burn_block_height,
-            })
+    async fn get_pox_info(&self) -> ConxianResult<StacksPoxInfo> {
+        Ok(StacksPoxInfo {
+            current_cycle: self.initial_height / 10 / 2100,
+            first_burnchain_block_height: 0,
+            reward_cycle_length: 2100,
         })
-        .await
-        .map_err(|e| ConxianError::Internal(e.to_string()))?
+    }
+
+    async fn get_signer_set(&self, _cycle: u64) -> ConxianResult<Vec<StacksSigner>> {
+        Ok(Vec::new())
+    }
+
+    async fn get_block_header(&self, height: u64) -> ConxianResult<NakamotoBlockHeader> {
+        Ok(NakamotoBlockHeader {
+            version: 0,
+            chain_length: height,
+            burn_spent: 0,
+            consensus_hash: [0u8; 20],
+            parent_block_id: [0u8; 32],
+            tx_merkle_root: [0u8; 32],
+            state_index_root: [0u8; 32],
+            timestamp: 0,
+            miner_signature: [0u8; 65],
+            signer_signature: Vec::new(),
+            pox_treatment_len: 0,
+            pox_treatment: Vec::new(),
+        })
+    }
+
+    async fn call_read_only(
+        &self,
+        _contract: &str,
+        _function: &str,
+        _args: &[ClarityValue],
+    ) -> ConxianResult<ClarityValue> {
+        Ok(ClarityValue::None)
+    }
+
+    async fn get_mempool_transactions(&self, _principal: &str) -> ConxianResult<Vec<MempoolTx>> {
+        Ok(Vec::new())
     }
 }
@@ -0,0 +1,444 @@
+use crate::stacks::rpc::{StacksPoxInfo, StacksRpc, StacksSigner};
+use bitcoin::hex::DisplayHex;
+use conxian_core::{AlertSeverity, ConxianResult, SharedState, SignerParticipation, SignerState};
+use std::collections::{BTreeMap, HashSet};
+use std::time::{SystemTime, UNIX_EPOCH};
+use tokio::time::{sleep, Duration};
+use tracing::{error, info, warn};
+
+/// Number of recent Stacks blocks participation is measured over.
+pub const DEFAULT_SIGNER_WINDOW: u64 = 100;
+/// Percentage points above the 70% threshold below which signing is degraded.
+pub const DEFAULT_SIGNER_WARN_MARGIN: f64 = 5.0;
+/// Number of most recent blocks whose weakest margin sets the status.
+const STATUS_BLOCKS: usize = 10;
+/// Share of total signer weight, in percent, a Nakamoto block needs.
+const SIGNING_THRESHOLD_PCT: f64 = 70.0;
+
+/// Signer set of one reward cycle, with keys normalised to lowercase hex.
+struct SignerSet {
+    signers: Vec<StacksSigner>,
+    total_weight: u64,
+}
+
+impl SignerSet {
+    fn new(mut signers: Vec<StacksSigner>) -> Self {
+        for signer in &mut signers {
+            signer.signing_key = signer.signing_key.trim_start_matches("0x").to_lowercase();
+        }
+        let total_weight = signers.iter().map(|s| s.weight as u64).sum();
+        Self {
+            signers,
+            total_weight,
+        }
+    }
+
+    fn threshold_weight(&self) -> u64 {
+        (self.total_weight * 7).div_ceil(10)
+    }
+}
+
+/// Signers of one block, as indices into its cycle's signer set.
+struct SignedBlock {
+    cycle: u64,
+    signers: HashSet<usize>,
+    signed_weight: u64,
+}
+
+/// Tracks how much of the reward-cycle signer set signs each Nakamoto block
+/// and alerts when signed weight approaches the 70% acceptance threshold.
+pub struct SignerMonitor<R: StacksRpc> {
+    rpc: R,
+    state: SharedState,
+    window: u64,
+    warn_margin: f64,
+    sets: BTreeMap<u64, SignerSet>,
+    blocks: BTreeMap<u64, SignedBlock>,
+}
+
+impl<R: StacksRpc> SignerMonitor<R> {
+    pub fn new(rpc: R, state: SharedState) -> Self {
+        Self {
+            rpc,
+            state,
+            window: DEFAULT_SIGNER_WINDOW,
+            warn_margin: DEFAULT_SIGNER_WARN_MARGIN,
+            sets: BTreeMap::new(),
+            blocks: BTreeMap::new(),
+        }
+    }
+
+    /// Margin, in percentage points of total weight, below which an alert is raised.
+    pub fn with_warn_margin(mut self, warn_margin: f64) -> Self {
+        self.warn_margin = warn_margin;
+        self
+    }
+
+    async fn signer_set(&mut self, cycle: u64) -> ConxianResult<&SignerSet> {
+        if !self.sets.contains_key(&cycle) {
+            let signers = self.rpc.get_signer_set(cycle).await?;
+            info!(
+                "Loaded {} Stacks signers for reward cycle {}",
+                signers.len(),
+                cycle
+            );
+            self.sets.insert(cycle, SignerSet::new(signers));
+            while self.sets.len() > 2 {
+                self.sets.pop_first();
+            }
+        }
+        Ok(&self.sets[&cycle])
+    }
+
+    async fn fetch_block(&mut self, pox: &StacksPoxInfo, height: u64) -> ConxianResult<()> {
+        let block = self.rpc.get_block(height).await?;
+        let header = self.rpc.get_block_header(height).await?;
+        let cycle = pox.cycle_of(block.burn_block_height);
+        let set = self.signer_set(cycle).await?;
+
+        let mut signers = HashSet::new();
+        let mut signed_weight = 0;
+        for key in header.signer_keys()? {
+            let key = key.serialize().to_lower_hex_string();
+            match set.signers.iter().position(|s| s.signing_key == key) {
+                Some(i) if signers.insert(i) => signed_weight += set.signers[i].weight as u64,
+                Some(_) => {}
+                None => warn!(
+                    "Stacks block {} carries a signature from {}, not in cycle {} signer set",
+                    height, key, cycle
+                ),
+            }
+        }
+
+        self.blocks.insert(
+            height,
+            SignedBlock {
+                cycle,
+                signers,
+                signed_weight,
+            },
+        );
+        Ok(())
+    }
+
+    pub async fn check_once(&mut self) -> ConxianResult<()> {
+        let tip = self.state.read().unwrap().stacks.height;
+        if tip == 0 {
+            return Ok(());
+        }
+
+        let pox = self.rpc.get_pox_info().await?;
+        let floor = tip.saturating_sub(self.window - 1).max(1);
+        // Re-read the tip block each pass in case it was replaced
+        self.blocks.retain(|&h, _| h >= floor && h < tip);
+        let start = match self.blocks.keys().next_back() {
+            Some(&last) => last + 1,
+            None => floor,
+        };
+        for h in start..=tip {
+            self.fetch_block(&pox, h).await?;
+        }
+
+        let Some(cycle) = self.blocks.values().next_back().map(|b| b.cycle) else {
+            return Ok(());
+        };
+        let set = &self.sets[&cycle];
+        let cycle_blocks: Vec<(&u64, &SignedBlock)> = self
+            .blocks
+            .iter()
+            .filter(|(_, b)| b.cycle == cycle)
+            .collect();
+
+        let mut signers = SignerState {
+            reward_cycle: cycle,
+            total_weight: set.total_weight,
+            threshold_weight: set.threshold_weight(),
+            blocks_observed: cycle_blocks.len() as u64,
+            last_checked: SystemTime::now()
+                .duration_since(UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+            ..SignerState::default()
+        };
+        signers.signers = set
+            .signers
+            .iter()
+            .enumerate()
+            .map(|(i, signer)| {
+                let blocks_signed = cycle_blocks
+                    .iter()
+                    .filter(|(_, b)| b.signers.contains(&i))
+                    .count() as u64;
+                SignerParticipation {
+                    signing_key: signer.signing_key.clone(),
+                    weight: signer.weight,
+                    blocks_signed,
+                    participation: blocks_signed as f64 / cycle_blocks.len() as f64,
+                }
+            })
+            .collect();
+
+        let mut alert = None;
+        if set.total_weight == 0 {
+            signers.status = "no_signers".to_string();
+        } else {
+            let margin = |b: &SignedBlock| {
+                b.signed_weight as f64 * 100.0 / set.total_weight as f64 - SIGNING_THRESHOLD_PCT
+            };
+            let (&last_height, last) = cycle_blocks[cycle_blocks.len() - 1];
+            signers.last_block_height = last_height;
+            signers.last_signed_weight = last.signed_weight;
+            signers.threshold_margin = margin(last);
+            signers.min_threshold_margin = cycle_blocks
+                .iter()
+                .map(|(_, b)| margin(b))
+                .fold(f64::INFINITY, f64::min);
+
+            // Weakest margin over the most recent blocks, so a single strong
+            // block does not clear an ongoing degradation
+            let recent_margin = cycle_blocks
+                .iter()
+                .rev()
+                .take(STATUS_BLOCKS)
+                .map(|(_, b)| margin(b))
+                .fold(f64::INFINITY, f64::min);
+            signers.status = if recent_margin < 0.0 {
+                "below_threshold"
+            } else if recent_margin < self.warn_margin {
+                "degraded"
+            } else {
+                "healthy"
+            }
+            .to_string();
+
+            let previous = self.state.read().unwrap().signers.status.clone();
+            if signers.status != previous {
+                alert = match signers.status.as_str() {
+                    "below_threshold" => Some((
+                        AlertSeverity::Critical,
+                        format!(
+                            "Stacks signer weight fell below the 70% threshold: {:.1} points under at block {}",
+                            -recent_margin, last_height
+                        ),
+                    )),
+                    "degraded" => Some((
+                        AlertSeverity::Warning,
+                        format!(
+                            "Stacks signer participation is within {:.1} points of the 70% threshold at block {}",
+                            recent_margin, last_height
+                        ),
+                    )),
+                    _ if matches!(previous.as_str(), "degraded" | "below_threshold") => Some((
+                        AlertSeverity::Info,
+                        format!(
+                            "Stacks signer participation recovered to {:.1} points above the 70% threshold",
+                            recent_margin
+                        ),
+                    )),
+                    _ => None,
+                };
+            }
+        }
+
+        let mut state = self.state.write().unwrap();
+        if let Some((severity, message)) = alert {
+            warn!("{}", message);
+            state.raise_alert("signers", severity, message);
+        }
+        state.signers = signers;
+        Ok(())
+    }
+
+    pub async fn run(&mut self) -> ConxianResult<()> {
+        info!("Starting Stacks signer monitor...");
+
+        loop {
+            if let Err(e) = self.check_once().await {
+                error!("Failed to check Stacks signer participation: {}", e);
+                self.state.write().unwrap().signers.status = format!("error: {}", e);
+            }
+            sleep(Duration::from_secs(30)).await;
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::stacks::block::tests::signed_header;
+    use crate::stacks::block::NakamotoBlockHeader;
+    use crate::stacks::rpc::{StacksBlockInfo, StacksNetworkInfo, StacksTxStatus};
+    use async_trait::async_trait;
+    use bitcoin::secp256k1::{SecretKey, SECP256K1};
+    use conxian_core::clarity::ClarityValue;
+    use conxian_core::{ConxianError, GatewayState, MempoolTx, StacksAccount};
+    use std::sync::{Arc, Mutex, RwLock};
+
+    const WEIGHTS: [u32; 4] = [40, 32, 18, 10];
+
+    fn keys() -> Vec<SecretKey> {
+        (1..=4u8)
+            .map(|i| SecretKey::from_slice(&[i; 32]).unwrap())
+            .collect()
+    }
+
+    /// Signs each block with the signers listed for the range containing it.
+    struct MockStacksRpc {
+        schedule: Mutex<Vec<(u64, Vec<usize>)>>,
+    }
+
+    impl MockStacksRpc {
+        fn new(signers: Vec<usize>) -> Self {
+            Self {
+                schedule: Mutex::new(vec![(1, signers)]),
+            }
+        }
+
+        fn sign_from(&self, height: u64, signers: Vec<usize>) {
+            self.schedule.lock().unwrap().push((height, signers));
+        }
+    }
+
+    #[async_trait]
+    impl StacksRpc for Arc<MockStacksRpc> {
+        async fn get_block_count(&self) -> ConxianResult<u64> {
+            Ok(0)
+        }
+        async fn get_network_info(&self) -> ConxianResult<StacksNetworkInfo> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_block(&self, height: u64) -> ConxianResult<StacksBlockInfo> {
+            Ok(StacksBlockInfo {
+                height,
+                hash: format!("0xstx{}", height),
+                parent_block_hash: format!("0xstx{}", height - 1),
+                burn_block_height: 1000 + height / 10,
+                burn_block_hash: format!("0xbtc{}", height / 10),
+            })
+        }
+        async fn broadcast_transaction(&self, _raw: &[u8]) -> ConxianResult<String> {
+            Ok("txid".to_string())
+        }
+        async fn get_transaction_status(&self, _txid: &str) -> ConxianResult<StacksTxStatus> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_account(&self, _principal: &str) -> ConxianResult<StacksAccount> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_pox_info(&self) -> ConxianResult<StacksPoxInfo> {
+            Ok(StacksPoxInfo {
+                current_cycle: 1,
+                first_burnchain_block_height: 0,
+                reward_cycle_length: 1000,
+            })
+        }
+        async fn get_signer_set(&self, _cycle: u64) -> ConxianResult<Vec<StacksSigner>> {
+            Ok(keys()
+                .iter()
+                .zip(WEIGHTS)
+                .map(|(sk, weight)| StacksSigner {
+                    signing_key: format!(
+                        "0x{}",
+                        sk.public_key(SECP256K1).serialize().to_lower_hex_string()
+                    ),
+                    weight,
+                })
+                .collect())
+        }
+        async fn get_block_header(&self, height: u64) -> ConxianResult<NakamotoBlockHeader> {
+            let schedule = self.schedule.lock().unwrap();
+            let (_, signers) = schedule.iter().rev().find(|(h, _)| *h <= height).unwrap();
+            let keys = keys();
+            let signing: Vec<SecretKey> = signers.iter().map(|&i| keys[i]).collect();
+            Ok(signed_header(height, &signing))
+        }
+        async fn call_read_only(
+            &self,
+            _contract: &str,
+            _function: &str,
+            _args: &[ClarityValue],
+        ) -> ConxianResult<ClarityValue> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+        async fn get_mempool_transactions(
+            &self,
+            _principal: &str,
+        ) -> ConxianResult<Vec<MempoolTx>> {
+            Err(ConxianError::Stacks("not used".to_string()))
+        }
+    }
+
+    fn setup(tip: u64) -> SharedState {
+        let state = Arc::new(RwLock::new(GatewayState::default()));
+        state.write().unwrap().stacks.height = tip;
+        state
+    }
+
+    #[tokio::test]
+    async fn test_signer_participation_and_margin() {
+        let rpc = Arc::new(MockStacksRpc::new(vec![0, 1, 2]));
+        let state = setup(20);
+        let mut monitor = SignerMonitor::new(rpc.clone(), state.clone());
+
+        monitor.check_once().await.unwrap();
+
+        let s = state.read().unwrap();
+        assert_eq!(s.signers.status, "healthy");
+        assert_eq!(s.signers.reward_cycle, 1);
+        assert_eq!(s.signers.total_weight, 100);
+        assert_eq!(s.signers.threshold_weight, 70);
+        assert_eq!(s.signers.blocks_observed, 20);
+        assert_eq!(s.signers.last_signed_weight, 90);
+        assert!((s.signers.threshold_margin - 20.0).abs() < 1e-9);
+        assert_eq!(s.signers.signers[0].blocks_signed, 20);
+        assert_eq!(s.signers.signers[3].blocks_signed, 0);
+        assert_eq!(s.signers.signers[3].participation, 0.0);
+        assert!(s.alerts.is_empty());
+    }
+
+    #[tokio::test]
+    async fn test_signer_alerts_near_threshold_and_recovery() {
+        let rpc = Arc::new(MockStacksRpc::new(vec![0, 1, 2, 3]));
+        let state = setup(10);
+        let mut monitor = SignerMonitor::new(rpc.clone(), state.clone());
+        monitor.check_once().await.unwrap();
+        assert_eq!(state.read().unwrap().signers.status, "healthy");
+
+        // 72% of weight signs: inside the 5 point warning margin
+        rpc.sign_from(11, vec![0, 1]);
+        state.write().unwrap().stacks.height = 12;
+        monitor.check_once().await.unwrap();
+        {
+            let s = state.read().unwrap();
+            assert_eq!(s.signers.status, "degraded");
+            assert_eq!(s.alerts.len(), 1);
+            assert_eq!(s.alerts[0].severity, AlertSeverity::Warning);
+            assert_eq!(s.alerts[0].source, "signers");
+            assert_eq!(s.signers.signers[2].blocks_signed, 10);
+        }
+
+        // Unchanged status does not alert again
+        state.write().unwrap().stacks.height = 13;
+        monitor.check_once().await.unwrap();
+        assert_eq!(state.read().unwrap().alerts.len(), 1);
+
+        rpc.sign_from(14, vec![0, 2]);
+        state.write().unwrap().stacks.height = 14;
+        monitor.check_once().await.unwrap();
+        {
+            let s = state.read().unwrap();
+            assert_eq!(s.signers.status, "below_threshold");
+            assert_eq!(s.alerts[1].severity, AlertSeverity::Critical);
+            assert!(s.signers.min_threshold_margin < 0.0);
+        }
+
+        // Recovery once the weak blocks leave the status window
+        rpc.sign_from(15, vec![0, 1, 2, 3]);
+        state.write().unwrap().stacks.height = 24;
+        monitor.check_once().await.unwrap();
+        let s = state.read().unwrap();
+        assert_eq!(s.signers.status, "healthy");
+        assert_eq!(s.alerts.len(), 3);
+        assert_eq!(s.alerts[2].severity, AlertSeverity::Info);
+    }
+}
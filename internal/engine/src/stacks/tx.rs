@@ -0,0 +1,956 @@
+use bitcoin::hashes::{hash160, sha256, sha512_256, Hash};
+use bitcoin::hex::DisplayHex;
+use bitcoin::secp256k1::ecdsa::{RecoverableSignature, RecoveryId};
+use bitcoin::secp256k1::{Message, PublicKey, SECP256K1};
+use conxian_core::c32::{
+    StacksAddress, MAINNET_MULTISIG, MAINNET_SINGLESIG, TESTNET_MULTISIG, TESTNET_SINGLESIG,
+};
+use conxian_core::clarity::{ClarityValue, PrincipalData};
+use conxian_core::codec::Reader;
+use conxian_core::{ConxianError, ConxianResult};
+use serde::{Serialize, Serializer};
+
+pub const CHAIN_ID_MAINNET: u32 = 0x0000_0001;
+pub const CHAIN_ID_TESTNET: u32 = 0x8000_0000;
+
+const VERSION_MAINNET: u8 = 0x00;
+const VERSION_TESTNET: u8 = 0x80;
+const AUTH_STANDARD: u8 = 0x04;
+const AUTH_SPONSORED: u8 = 0x05;
+/// Upper bound on list lengths read from the wire before allocation.
+const MAX_ITEMS: u32 = 1024;
+
+fn as_hex<S: Serializer, T: AsRef<[u8]>>(bytes: &T, serializer: S) -> Result<S::Ok, S::Error> {
+    serializer.serialize_str(&bytes.as_ref().to_lower_hex_string())
+}
+
+fn err(msg: impl Into<String>) -> ConxianError {
+    ConxianError::Stacks(msg.into())
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum HashMode {
+    P2pkh,
+    P2sh,
+    P2wpkhP2sh,
+    P2wshP2sh,
+    P2shNonSequential,
+    P2wshP2shNonSequential,
+}
+
+impl HashMode {
+    fn from_u8(b: u8) -> ConxianResult<Self> {
+        match b {
+            0x00 => Ok(HashMode::P2pkh),
+            0x01 => Ok(HashMode::P2sh),
+            0x02 => Ok(HashMode::P2wpkhP2sh),
+            0x03 => Ok(HashMode::P2wshP2sh),
+            0x05 => Ok(HashMode::P2shNonSequential),
+            0x07 => Ok(HashMode::P2wshP2shNonSequential),
+            other => Err(err(format!("Unknown hash mode: 0x{:02x}", other))),
+        }
+    }
+
+    fn to_u8(self) -> u8 {
+        match self {
+            HashMode::P2pkh => 0x00,
+            HashMode::P2sh => 0x01,
+            HashMode::P2wpkhP2sh => 0x02,
+            HashMode::P2wshP2sh => 0x03,
+            HashMode::P2shNonSequential => 0x05,
+            HashMode::P2wshP2shNonSequential => 0x07,
+        }
+    }
+
+    pub fn is_singlesig(self) -> bool {
+        matches!(self, HashMode::P2pkh | HashMode::P2wpkhP2sh)
+    }
+
+    fn is_segwit(self) -> bool {
+        matches!(
+            self,
+            HashMode::P2wpkhP2sh | HashMode::P2wshP2sh | HashMode::P2wshP2shNonSequential
+        )
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum AuthField {
+    PublicKey {
+        #[serde(serialize_with = "as_hex")]
+        key: [u8; 33],
+        compressed: bool,
+    },
+    Signature {
+        #[serde(serialize_with = "as_hex")]
+        signature: [u8; 65],
+        compressed: bool,
+    },
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum ConditionKind {
+    Singlesig {
+        compressed: bool,
+        #[serde(serialize_with = "as_hex")]
+        signature: [u8; 65],
+    },
+    Multisig {
+        fields: Vec<AuthField>,
+        signatures_required: u16,
+    },
+}
+
+/// Spending condition of an origin or sponsor account.
+#[derive(Debug, Clone, Serialize)]
+pub struct SpendingCondition {
+    pub hash_mode: HashMode,
+    #[serde(serialize_with = "as_hex")]
+    pub signer: [u8; 20],
+    pub nonce: u64,
+    pub fee: u64,
+    #[serde(flatten)]
+    pub kind: ConditionKind,
+}
+
+impl SpendingCondition {
+    fn decode(r: &mut Reader) -> ConxianResult<Self> {
+        let hash_mode = HashMode::from_u8(r.read_u8()?)?;
+        let signer = r.read_array()?;
+        let nonce = r.read_u64()?;
+        let fee = r.read_u64()?;
+
+        let kind = if hash_mode.is_singlesig() {
+            let compressed = match r.read_u8()? {
+                0x00 => true,
+                0x01 => false,
+                other => return Err(err(format!("Unknown key encoding: 0x{:02x}", other))),
+            };
+            ConditionKind::Singlesig {
+                compressed,
+                signature: r.read_array()?,
+            }
+        } else {
+            let count = r.read_u32()?;
+            if count > MAX_ITEMS {
+                return Err(err("Too many auth fields"));
+            }
+            let mut fields = Vec::with_capacity(count as usize);
+            for _ in 0..count {
+                let field = match r.read_u8()? {
+                    id @ (0x00 | 0x01) => AuthField::PublicKey {
+                        key: r.read_array()?,
+                        compressed: id == 0x00,
+                    },
+                    id @ (0x02 | 0x03) => AuthField::Signature {
+                        signature: r.read_array()?,
+                        compressed: id == 0x02,
+                    },
+                    other => return Err(err(format!("Unknown auth field: 0x{:02x}", other))),
+                };
+                fields.push(field);
+            }
+            ConditionKind::Multisig {
+                fields,
+                signatures_required: r.read_u16()?,
+            }
+        };
+
+        Ok(Self {
+            hash_mode,
+            signer,
+            nonce,
+            fee,
+            kind,
+        })
+    }
+
+    /// Serializes the condition with nonce, fee and signatures cleared, as
+    /// used when computing the initial sighash.
+    fn write_cleared(&self, out: &mut Vec<u8>) {
+        out.push(self.hash_mode.to_u8());
+        out.extend_from_slice(&self.signer);
+        out.extend_from_slice(&[0u8; 16]);
+        match &self.kind {
+            ConditionKind::Singlesig { compressed, .. } => {
+                out.push(if *compressed { 0x00 } else { 0x01 });
+                out.extend_from_slice(&[0u8; 65]);
+            }
+            ConditionKind::Multisig {
+                signatures_required,
+                ..
+            } => {
+                out.extend_from_slice(&0u32.to_be_bytes());
+                out.extend_from_slice(&signatures_required.to_be_bytes());
+            }
+        }
+    }
+
+    pub fn address(&self, mainnet: bool) -> StacksAddress {
+        let version = match (mainnet, self.hash_mode.is_singlesig()) {
+            (true, true) => MAINNET_SINGLESIG,
+            (true, false) => MAINNET_MULTISIG,
+            (false, true) => TESTNET_SINGLESIG,
+            (false, false) => TESTNET_MULTISIG,
+        };
+        StacksAddress::new(version, self.signer).expect("standard versions are valid")
+    }
+
+    fn presign_sighash(&self, cur_sighash: &[u8; 32], auth_flag: u8) -> [u8; 32] {
+        let mut data = Vec::with_capacity(49);
+        data.extend_from_slice(cur_sighash);
+        data.push(auth_flag);
+        data.extend_from_slice(&self.fee.to_be_bytes());
+        data.extend_from_slice(&self.nonce.to_be_bytes());
+        sha512_256::Hash::hash(&data).to_byte_array()
+    }
+
+    /// Verifies the condition's signatures and signer hash, returning the
+    /// sighash the next condition is signed over.
+    fn verify(&self, cur_sighash: &[u8; 32], auth_flag: u8) -> ConxianResult<[u8; 32]> {
+        match &self.kind {
+            ConditionKind::Singlesig {
+                compressed,
+                signature,
+            } => {
+                let presign = self.presign_sighash(cur_sighash, auth_flag);
+                let pubkey = recover(&presign, signature)?;
+                if self.hash_mode == HashMode::P2wpkhP2sh && !compressed {
+                    return Err(err("Segwit spending conditions require compressed keys"));
+                }
+                let signer = signer_hash(self.hash_mode, &pubkey_bytes(&pubkey, *compressed));
+                if signer != self.signer {
+                    return Err(err("Signature does not match spending condition signer"));
+                }
+                Ok(next_sighash(&presign, *compressed, signature))
+            }
+            ConditionKind::Multisig {
+                fields,
+                signatures_required,
+            } => {
+                let sequential = matches!(self.hash_mode, HashMode::P2sh | HashMode::P2wshP2sh);
+                let mut sighash = *cur_sighash;
+                let mut presign = self.presign_sighash(cur_sighash, auth_flag);
+                let mut pubkeys = Vec::with_capacity(fields.len());
+                let mut signatures = 0u16;
+
+                for field in fields {
+                    match field {
+                        AuthField::PublicKey { key, compressed } => {
+                            let pk = PublicKey::from_slice(key)
+                                .map_err(|e| err(format!("Invalid public key: {}", e)))?;
+                            pubkeys.push(pubkey_bytes(&pk, *compressed));
+                        }
+                        AuthField::Signature {
+                            signature,
+                            compressed,
+                        } => {
+                            if sequential {
+                                presign = self.presign_sighash(&sighash, auth_flag);
+                            }
+                            let pk = recover(&presign, signature)?;
+                            pubkeys.push(pubkey_bytes(&pk, *compressed));
+                            if sequential {
+                                sighash = next_sighash(&presign, *compressed, signature);
+                            }
+                            signatures += 1;
+                        }
+                    }
+                }
+
+                if signatures < *signatures_required
+                    || (sequential && signatures != *signatures_required)
+                {
+                    return Err(err(format!(
+                        "Multisig requires {} signatures, found {}",
+                        signatures_required, signatures
+                    )));
+                }
+                if self.hash_mode.is_segwit() && pubkeys.iter().any(|k| k.len() != 33) {
+                    return Err(err("Segwit spending conditions require compressed keys"));
+                }
+
+                let script = multisig_script(*signatures_required, &pubkeys)?;
+                if signer_hash(self.hash_mode, &script) != self.signer {
+                    return Err(err("Multisig keys do not match spending condition signer"));
+                }
+                Ok(sighash)
+            }
+        }
+    }
+}
+
+/// Recovers the public key from a Stacks `[recid, r, s]` signature over `presign`.
+pub(crate) fn recover(presign: &[u8; 32], signature: &[u8; 65]) -> ConxianResult<PublicKey> {
+    let recid = RecoveryId::from_i32(signature[0] as i32)
+        .map_err(|e| err(format!("Invalid recovery id: {}", e)))?;
+    let sig = RecoverableSignature::from_compact(&signature[1..], recid)
+        .map_err(|e| err(format!("Invalid signature: {}", e)))?;
+    SECP256K1
+        .recover_ecdsa(&Message::from_digest(*presign), &sig)
+        .map_err(|e| err(format!("Signature recovery failed: {}", e)))
+}
+
+fn pubkey_bytes(pk: &PublicKey, compressed: bool) -> Vec<u8> {
+    if compressed {
+        pk.serialize().to_vec()
+    } else {
+        pk.serialize_uncompressed().to_vec()
+    }
+}
+
+fn next_sighash(presign: &[u8; 32], compressed: bool, signature: &[u8; 65]) -> [u8; 32] {
+    let mut data = Vec::with_capacity(98);
+    data.extend_from_slice(presign);
+    data.push(if compressed { 0x00 } else { 0x01 });
+    data.extend_from_slice(signature);
+    sha512_256::Hash::hash(&data).to_byte_array()
+}
+
+/// Hashes a public key or redeem script the way the given hash mode commits to it.
+fn signer_hash(mode: HashMode, data: &[u8]) -> [u8; 20] {
+    match mode {
+        HashMode::P2pkh | HashMode::P2sh | HashMode::P2shNonSequential => {
+            hash160::Hash::hash(data).to_byte_array()
+        }
+        HashMode::P2wpkhP2sh => {
+            let mut witness = vec![0x00, 0x14];
+            witness.extend_from_slice(&hash160::Hash::hash(data).to_byte_array());
+            hash160::Hash::hash(&witness).to_byte_array()
+        }
+        HashMode::P2wshP2sh | HashMode::P2wshP2shNonSequential => {
+            let mut witness = vec![0x00, 0x20];
+            witness.extend_from_slice(&sha256::Hash::hash(data).to_byte_array());
+            hash160::Hash::hash(&witness).to_byte_array()
+        }
+    }
+}
+
+fn multisig_script(required: u16, pubkeys: &[Vec<u8>]) -> ConxianResult<Vec<u8>> {
+    if required == 0 || required > 16 || pubkeys.is_empty() || pubkeys.len() > 16 {
+        return Err(err("Unsupported multisig size"));
+    }
+    let mut script = vec![0x50 + required as u8];
+    for key in pubkeys {
+        script.push(key.len() as u8);
+        script.extend_from_slice(key);
+    }
+    script.push(0x50 + pubkeys.len() as u8);
+    script.push(0xae);
+    Ok(script)
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TransactionAuth {
+    Standard {
+        origin: SpendingCondition,
+    },
+    Sponsored {
+        origin: SpendingCondition,
+        sponsor: SpendingCondition,
+    },
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum AnchorMode {
+    OnChainOnly,
+    OffChainOnly,
+    Any,
+}
+
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Serialize)]
+#[serde(rename_all = "snake_case")]
+pub enum PostConditionMode {
+    Allow,
+    Deny,
+}
+
+#[derive(Debug, Clone, PartialEq, Eq, Serialize)]
+#[serde(tag = "type", content = "principal", rename_all = "snake_case")]
+pub enum PostConditionPrincipal {
+    Origin,
+    Principal(PrincipalData),
+}
+
+#[derive(Debug, Clone, Serialize)]
+pub struct AssetInfo {
+    pub contract: PrincipalData,
+    pub asset_name: String,
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum PostCondition {
+    Stx {
+        principal: PostConditionPrincipal,
+        condition_code: u8,
+        amount: u64,
+    },
+    FungibleToken {
+        principal: PostConditionPrincipal,
+        asset: AssetInfo,
+        condition_code: u8,
+        amount: u64,
+    },
+    NonFungibleToken {
+        principal: PostConditionPrincipal,
+        asset: AssetInfo,
+        value: ClarityValue,
+        condition_code: u8,
+    },
+}
+
+impl PostCondition {
+    /// The principal the condition constrains, the asset contract and any
+    /// principals in an NFT identifier.
+    pub fn principals(&self) -> Vec<PrincipalData> {
+        let (principal, asset, value) = match self {
+            PostCondition::Stx { principal, .. } => (principal, None, None),
+            PostCondition::FungibleToken {
+                principal, asset, ..
+            } => (principal, Some(asset), None),
+            PostCondition::NonFungibleToken {
+                principal,
+                asset,
+                value,
+                ..
+            } => (principal, Some(asset), Some(value)),
+        };
+        let mut principals = Vec::new();
+        if let PostConditionPrincipal::Principal(p) = principal {
+            principals.push(p.clone());
+        }
+        if let Some(asset) = asset {
+            principals.push(asset.contract.clone());
+        }
+        if let Some(value) = value {
+            principals.extend(value.principals());
+        }
+        principals
+    }
+
+    fn decode(r: &mut Reader) -> ConxianResult<Self> {
+        let asset_id = r.read_u8()?;
+        let principal = match r.read_u8()? {
+            0x01 => PostConditionPrincipal::Origin,
+            0x02 => PostConditionPrincipal::Principal(PrincipalData::Standard(read_address(r)?)),
+            0x03 => {
+                let address = read_address(r)?;
+                PostConditionPrincipal::Principal(PrincipalData::Contract(address, r.read_name()?))
+            }
+            other => {
+                return Err(err(format!(
+                    "Unknown post-condition principal: 0x{:02x}",
+                    other
+                )))
+            }
+        };
+        match asset_id {
+            0x00 => Ok(PostCondition::Stx {
+                principal,
+                condition_code: read_fungible_code(r)?,
+                amount: r.read_u64()?,
+            }),
+            0x01 => Ok(PostCondition::FungibleToken {
+                principal,
+                asset: read_asset_info(r)?,
+                condition_code: read_fungible_code(r)?,
+                amount: r.read_u64()?,
+            }),
+            0x02 => {
+                let asset = read_asset_info(r)?;
+                let value = ClarityValue::deserialize_from(r)?;
+                let condition_code = r.read_u8()?;
+                if !matches!(condition_code, 0x10 | 0x11) {
+                    return Err(err(format!(
+                        "Unknown NFT condition code: 0x{:02x}",
+                        condition_code
+                    )));
+                }
+                Ok(PostCondition::NonFungibleToken {
+                    principal,
+                    asset,
+                    value,
+                    condition_code,
+                })
+            }
+            other => Err(err(format!(
+                "Unknown post-condition asset type: 0x{:02x}",
+                other
+            ))),
+        }
+    }
+}
+
+fn read_address(r: &mut Reader) -> ConxianResult<StacksAddress> {
+    let version = r.read_u8()?;
+    StacksAddress::new(version, r.read_array()?)
+}
+
+fn read_asset_info(r: &mut Reader) -> ConxianResult<AssetInfo> {
+    let address = read_address(r)?;
+    let contract_name = r.read_name()?;
+    Ok(AssetInfo {
+        contract: PrincipalData::Contract(address, contract_name),
+        asset_name: r.read_name()?,
+    })
+}
+
+fn read_fungible_code(r: &mut Reader) -> ConxianResult<u8> {
+    match r.read_u8()? {
+        code @ 0x01..=0x05 => Ok(code),
+        other => Err(err(format!(
+            "Unknown fungible condition code: 0x{:02x}",
+            other
+        ))),
+    }
+}
+
+#[derive(Debug, Clone, Serialize)]
+#[serde(tag = "type", rename_all = "snake_case")]
+pub enum TransactionPayload {
+    TokenTransfer {
+        recipient: PrincipalData,
+        amount: u64,
+        #[serde(serialize_with = "as_hex")]
+        memo: [u8; 34],
+    },
+    ContractCall {
+        contract: PrincipalData,
+        function_name: String,
+        args: Vec<ClarityValue>,
+    },
+    ContractDeploy {
+        name: String,
+        clarity_version: Option<u8>,
+        code: String,
+    },
+}
+
+impl TransactionPayload {
+    fn decode(r: &mut Reader) -> ConxianResult<Self> {
+        match r.read_u8()? {
+            0x00 => {
+                let recipient = match ClarityValue::deserialize_from(r)? {
+                    ClarityValue::Principal(p) => p,
+                    _ => return Err(err("Token transfer recipient must be a principal")),
+                };
+                Ok(TransactionPayload::TokenTransfer {
+                    recipient,
+                    amount: r.read_u64()?,
+                    memo: r.read_array()?,
+                })
+            }
+            0x02 => {
+                let address = read_address(r)?;
+                let contract = PrincipalData::Contract(address, r.read_name()?);
+                let function_name = r.read_name()?;
+                let count = r.read_u32()?;
+                if count > MAX_ITEMS {
+                    return Err(err("Too many contract call arguments"));
+                }
+                let args = (0..count)
+                    .map(|_| ClarityValue::deserialize_from(r))
+                    .collect::<ConxianResult<Vec<_>>>()?;
+                Ok(TransactionPayload::ContractCall {
+                    contract,
+                    function_name,
+                    args,
+                })
+            }
+            id @ (0x01 | 0x06) => {
+                let clarity_version = if id == 0x06 { Some(r.read_u8()?) } else { None };
+                let name = r.read_name()?;
+                let len = r.read_u32()? as usize;
+                let code = String::from_utf8(r.read_bytes(len)?.to_vec())
+                    .map_err(|e| err(format!("Invalid contract code: {}", e)))?;
+                Ok(TransactionPayload::ContractDeploy {
+                    name,
+                    clarity_version,
+                    code,
+                })
+            }
+            other => Err(err(format!("Unsupported payload type: 0x{:02x}", other))),
+        }
+    }
+}
+
+/// A decoded Stacks transaction in SIP-005 wire format.
+#[derive(Debug, Clone, Serialize)]
+pub struct StacksTransaction {
+    pub txid: String,
+    pub mainnet: bool,
+    pub chain_id: u32,
+    pub auth: TransactionAuth,
+    pub anchor_mode: AnchorMode,
+    pub post_condition_mode: PostConditionMode,
+    pub post_conditions: Vec<PostCondition>,
+    pub payload: TransactionPayload,
+    #[serde(skip)]
+    initial_sighash: [u8; 32],
+}
+
+impl StacksTransaction {
+    pub fn decode(raw: &[u8]) -> ConxianResult<Self> {
+        let mut r = Reader::new(raw);
+        let mainnet = match r.read_u8()? {
+            VERSION_MAINNET => true,
+            VERSION_TESTNET => false,
+            other => return Err(err(format!("Unknown transaction version: 0x{:02x}", other))),
+        };
+        let chain_id = r.read_u32()?;
+
+        let auth_start = r.position();
+        let auth = match r.read_u8()? {
+            AUTH_STANDARD => TransactionAuth::Standard {
+                origin: SpendingCondition::decode(&mut r)?,
+            },
+            AUTH_SPONSORED => TransactionAuth::Sponsored {
+                origin: SpendingCondition::decode(&mut r)?,
+                sponsor: SpendingCondition::decode(&mut r)?,
+            },
+            other => return Err(err(format!("Unknown authorization type: 0x{:02x}", other))),
+        };
+        let auth_end = r.position();
+
+        let anchor_mode = match r.read_u8()? {
+            0x01 => AnchorMode::OnChainOnly,
+            0x02 => AnchorMode::OffChainOnly,
+            0x03 => AnchorMode::Any,
+            other => return Err(err(format!("Unknown anchor mode: 0x{:02x}", other))),
+        };
+        let post_condition_mode = match r.read_u8()? {
+            0x01 => PostConditionMode::Allow,
+            0x02 => PostConditionMode::Deny,
+            other => return Err(err(format!("Unknown post-condition mode: 0x{:02x}", other))),
+        };
+        let count = r.read_u32()?;
+        if count > MAX_ITEMS {
+            return Err(err("Too many post-conditions"));
+        }
+        let post_conditions = (0..count)
+            .map(|_| PostCondition::decode(&mut r))
+            .collect::<ConxianResult<Vec<_>>>()?;
+        let payload = TransactionPayload::decode(&mut r)?;
+
+        if !r.is_empty() {
+            return Err(err(format!(
+                "{} trailing bytes after transaction",
+                r.remaining()
+            )));
+        }
+
+        // The initial sighash is the txid of the transaction with its auth cleared
+        let mut cleared = raw[..auth_start].to_vec();
+        match &auth {
+            TransactionAuth::Standard { origin } => {
+                cleared.push(AUTH_STANDARD);
+                origin.write_cleared(&mut cleared);
+            }
+            TransactionAuth::Sponsored { origin, .. } => {
+                cleared.push(AUTH_SPONSORED);
+                origin.write_cleared(&mut cleared);
+                // The sponsor is replaced by an empty P2PKH condition
+                cleared.push(HashMode::P2pkh.to_u8());
+                cleared.extend_from_slice(&[0u8; 20 + 16 + 1 + 65]);
+            }
+        }
+        cleared.extend_from_slice(&raw[auth_end..]);
+
+        Ok(Self {
+            txid: sha512_256::Hash::hash(raw)
+                .to_byte_array()
+                .to_lower_hex_string(),
+            mainnet,
+            chain_id,
+            auth,
+            anchor_mode,
+            post_condition_mode,
+            post_conditions,
+            payload,
+            initial_sighash: sha512_256::Hash::hash(&cleared).to_byte_array(),
+        })
+    }
+
+    pub fn origin(&self) -> &SpendingCondition {
+        match &self.auth {
+            TransactionAuth::Standard { origin } | TransactionAuth::Sponsored { origin, .. } => {
+                origin
+            }
+        }
+    }
+
+    pub fn sponsor(&self) -> Option<&SpendingCondition> {
+        match &self.auth {
+            TransactionAuth::Standard { .. } => None,
+            TransactionAuth::Sponsored { sponsor, .. } => Some(sponsor),
+        }
+    }
+
+    pub fn sender(&self) -> StacksAddress {
+        self.origin().address(self.mainnet)
+    }
+
+    /// Total fee paid, by the sponsor if present.
+    pub fn fee(&self) -> u64 {
+        self.sponsor().unwrap_or(self.origin()).fee
+    }
+
+    /// Every principal the transaction sends from, pays to or calls, passes
+    /// as a contract-call argument or names in a post-condition, once each.
+    pub fn principals(&self) -> Vec<PrincipalData> {
+        let mut principals = vec![PrincipalData::Standard(self.sender())];
+        if let Some(sponsor) = self.sponsor() {
+            principals.push(PrincipalData::Standard(sponsor.address(self.mainnet)));
+        }
+        match &self.payload {
+            TransactionPayload::TokenTransfer { recipient, .. } => {
+                principals.push(recipient.clone())
+            }
+            TransactionPayload::ContractCall { contract, args, .. } => {
+                principals.push(contract.clone());
+                principals.extend(args.iter().flat_map(ClarityValue::principals));
+            }
+            TransactionPayload::ContractDeploy { .. } => {}
+        }
+        principals.extend(
+            self.post_conditions
+                .iter()
+                .flat_map(PostCondition::principals),
+        );
+
+        let mut unique = Vec::with_capacity(principals.len());
+        for principal in principals {
+            if !unique.contains(&principal) {
+                unique.push(principal);
+            }
+        }
+        unique
+    }
+
+    /// Verifies the origin and sponsor signatures against their signer hashes.
+    pub fn verify_signatures(&self) -> ConxianResult<()> {
+        let origin_sighash = self.origin().verify(&self.initial_sighash, AUTH_STANDARD)?;
+        if let Some(sponsor) = self.sponsor() {
+            sponsor.verify(&origin_sighash, AUTH_SPONSORED)?;
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+    use bitcoin::secp256k1::SecretKey;
+
+    /// Builds a signed single-sig STX transfer for tests.
+    pub(crate) fn signed_transfer(sk: &SecretKey, recipient: &str, amount: u64) -> Vec<u8> {
+        let mut payload = vec![0x00];
+        let recipient: PrincipalData = recipient.parse().unwrap();
+        ClarityValue::Principal(recipient)
+            .serialize_into(&mut payload)
+            .unwrap();
+        payload.extend_from_slice(&amount.to_be_bytes());
+        payload.extend_from_slice(&[0u8; 34]);
+        signed_tx(sk, &[], &payload)
+    }
+
+    fn push_address(raw: &mut Vec<u8>, address: &StacksAddress) {
+        raw.push(address.version);
+        raw.extend_from_slice(&address.hash160);
+    }
+
+    fn push_name(raw: &mut Vec<u8>, name: &str) {
+        raw.push(name.len() as u8);
+        raw.extend_from_slice(name.as_bytes());
+    }
+
+    /// Builds a signed single-sig contract call for tests.
+    pub(crate) fn signed_contract_call(
+        sk: &SecretKey,
+        contract: &str,
+        function: &str,
+        args: &[ClarityValue],
+        post_conditions: &[Vec<u8>],
+    ) -> Vec<u8> {
+        let PrincipalData::Contract(address, name) = contract.parse().unwrap() else {
+            panic!("not a contract principal: {}", contract);
+        };
+        let mut payload = vec![0x02];
+        push_address(&mut payload, &address);
+        push_name(&mut payload, &name);
+        push_name(&mut payload, function);
+        payload.extend_from_slice(&(args.len() as u32).to_be_bytes());
+        for arg in args {
+            arg.serialize_into(&mut payload).unwrap();
+        }
+        signed_tx(sk, post_conditions, &payload)
+    }
+
+    /// A fungible token post-condition that `principal` sends at most
+    /// `amount` of `asset` (`contract::name`).
+    pub(crate) fn ft_post_condition(principal: &str, asset: &str, amount: u64) -> Vec<u8> {
+        let PrincipalData::Standard(sender) = principal.parse().unwrap() else {
+            panic!("not a standard principal: {}", principal);
+        };
+        let (contract, asset_name) = asset.split_once("::").unwrap();
+        let PrincipalData::Contract(address, name) = contract.parse().unwrap() else {
+            panic!("not a contract principal: {}", contract);
+        };
+        let mut raw = vec![0x01, 0x02];
+        push_address(&mut raw, &sender);
+        push_address(&mut raw, &address);
+        push_name(&mut raw, &name);
+        push_name(&mut raw, asset_name);
+        raw.push(0x03);
+        raw.extend_from_slice(&amount.to_be_bytes());
+        raw
+    }
+
+    fn signed_tx(sk: &SecretKey, post_conditions: &[Vec<u8>], payload: &[u8]) -> Vec<u8> {
+        let pk = PublicKey::from_secret_key(SECP256K1, sk);
+        let signer = hash160::Hash::hash(&pk.serialize()).to_byte_array();
+        let fee = 180u64;
+        let nonce = 7u64;
+
+        let mut raw = vec![VERSION_TESTNET];
+        raw.extend_from_slice(&CHAIN_ID_TESTNET.to_be_bytes());
+        raw.push(AUTH_STANDARD);
+        raw.push(HashMode::P2pkh.to_u8());
+        raw.extend_from_slice(&signer);
+        raw.extend_from_slice(&nonce.to_be_bytes());
+        raw.extend_from_slice(&fee.to_be_bytes());
+        raw.push(0x00);
+        let sig_offset = raw.len();
+        raw.extend_from_slice(&[0u8; 65]);
+        raw.push(0x03);
+        raw.push(0x02);
+        raw.extend_from_slice(&(post_conditions.len() as u32).to_be_bytes());
+        for condition in post_conditions {
+            raw.extend_from_slice(condition);
+        }
+        raw.extend_from_slice(payload);
+
+        let tx = StacksTransaction::decode(&raw).unwrap();
+        let presign = tx
+            .origin()
+            .presign_sighash(&tx.initial_sighash, AUTH_STANDARD);
+        let sig = SECP256K1.sign_ecdsa_recoverable(&Message::from_digest(presign), sk);
+        let (recid, compact) = sig.serialize_compact();
+        raw[sig_offset] = recid.to_i32() as u8;
+        raw[sig_offset + 1..sig_offset + 65].copy_from_slice(&compact);
+        raw
+    }
+
+    #[test]
+    fn test_decode_and_verify_token_transfer() {
+        let sk = SecretKey::from_slice(&[0x11; 32]).unwrap();
+        let raw = signed_transfer(&sk, "ST000000000000000000002AMW42H", 1_000);
+        let tx = StacksTransaction::decode(&raw).unwrap();
+
+        assert!(!tx.mainnet);
+        assert_eq!(tx.chain_id, CHAIN_ID_TESTNET);
+        assert_eq!(tx.fee(), 180);
+        assert_eq!(tx.origin().nonce, 7);
+        assert_eq!(tx.post_condition_mode, PostConditionMode::Deny);
+        assert!(tx.sender().to_string().starts_with("ST"));
+        match &tx.payload {
+            TransactionPayload::TokenTransfer {
+                recipient, amount, ..
+            } => {
+                assert_eq!(recipient.to_string(), "ST000000000000000000002AMW42H");
+                assert_eq!(*amount, 1_000);
+            }
+            other => panic!("unexpected payload {:?}", other),
+        }
+        tx.verify_signatures().unwrap();
+    }
+
+    #[test]
+    fn test_verify_rejects_tampered_transaction() {
+        let sk = SecretKey::from_slice(&[0x22; 32]).unwrap();
+        let mut raw = signed_transfer(&sk, "ST000000000000000000002AMW42H", 1_000);
+        // Bump the transfer amount after signing
+        let amount_offset = raw.len() - 34 - 8;
+        raw[amount_offset + 7] ^= 0x01;
+
+        let tx = StacksTransaction::decode(&raw).unwrap();
+        assert!(tx.verify_signatures().is_err());
+    }
+
+    #[test]
+    fn test_decode_rejects_malformed() {
+        let sk = SecretKey::from_slice(&[0x33; 32]).unwrap();
+        let raw = signed_transfer(&sk, "ST000000000000000000002AMW42H", 1);
+
+        assert!(StacksTransaction::decode(&raw[..raw.len() - 1]).is_err());
+        let mut trailing = raw.clone();
+        trailing.push(0x00);
+        assert!(StacksTransaction::decode(&trailing).is_err());
+        let mut bad_version = raw;
+        bad_version[0] = 0x42;
+        assert!(StacksTransaction::decode(&bad_version).is_err());
+    }
+
+    #[test]
+    fn test_decode_rejects_invalid_address_version() {
+        let sk = SecretKey::from_slice(&[0x34; 32]).unwrap();
+        let mut raw = signed_transfer(&sk, "ST000000000000000000002AMW42H", 1);
+        // The recipient's version byte follows its 0x05 principal prefix
+        let version_offset = raw.len() - 34 - 8 - 20 - 1;
+        assert_eq!(raw[version_offset - 1], 0x05);
+        raw[version_offset] = 0xff;
+
+        let err = StacksTransaction::decode(&raw).unwrap_err();
+        assert!(
+            err.to_string().contains("Invalid address version"),
+            "{}",
+            err
+        );
+    }
+
+    #[test]
+    fn test_principals_include_call_args_and_post_conditions() {
+        let sk = SecretKey::from_slice(&[0x44; 32]).unwrap();
+        let token = "ST000000000000000000002AMW42H.sip-010-token";
+        let sender = StacksTransaction::decode(&signed_transfer(&sk, token, 1))
+            .unwrap()
+            .sender()
+            .to_string();
+        let recipient: PrincipalData = "ST2JHG361ZXG51QTKY2NQCVBPPRRE2KZB1HR05NNC".parse().unwrap();
+        let raw = signed_contract_call(
+            &sk,
+            token,
+            "transfer",
+            &[
+                ClarityValue::UInt(100),
+                ClarityValue::Principal(sender.parse().unwrap()),
+                ClarityValue::Principal(recipient.clone()),
+                ClarityValue::None,
+            ],
+            &[ft_post_condition(
+                &sender,
+                "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.other-token::coin",
+                100,
+            )],
+        );
+        let tx = StacksTransaction::decode(&raw).unwrap();
+        tx.verify_signatures().unwrap();
+
+        let principals: Vec<String> = tx.principals().iter().map(|p| p.to_string()).collect();
+        assert_eq!(
+            principals,
+            vec![
+                sender,
+                token.to_string(),
+                recipient.to_string(),
+                "ST1PQHQKV0RJXZFY1DGX8MNSNYVE3VGZJSRTPGZGM.other-token".to_string(),
+            ]
+        );
+    }
+}
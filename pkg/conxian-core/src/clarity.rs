@@ -0,0 +1,454 @@
+use crate::c32::StacksAddress;
+use crate::codec::{write_name, Reader};
+use crate::{ConxianError, ConxianResult};
+use bitcoin::hex::{DisplayHex, FromHex};
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use std::collections::BTreeMap;
+use std::fmt;
+use std::str::FromStr;
+
+/// Maximum nesting depth of a Clarity value.
+pub const MAX_VALUE_DEPTH: usize = 32;
+/// Maximum size of a serialized Clarity value.
+pub const MAX_VALUE_SIZE: u32 = 1024 * 1024;
+
+/// A standard or contract principal.
+#[derive(Debug, Clone, PartialEq, Eq, Hash, PartialOrd, Ord)]
+pub enum PrincipalData {
+    Standard(StacksAddress),
+    Contract(StacksAddress, String),
+}
+
+impl PrincipalData {
+    pub fn address(&self) -> &StacksAddress {
+        match self {
+            PrincipalData::Standard(a) | PrincipalData::Contract(a, _) => a,
+        }
+    }
+}
+
+impl fmt::Display for PrincipalData {
+    fn fmt(&self, f: &mut fmt::Formatter<'_>) -> fmt::Result {
+        match self {
+            PrincipalData::Standard(a) => write!(f, "{}", a),
+            PrincipalData::Contract(a, name) => write!(f, "{}.{}", a, name),
+        }
+    }
+}
+
+impl FromStr for PrincipalData {
+    type Err = ConxianError;
+
+    fn from_str(s: &str) -> ConxianResult<Self> {
+        match s.split_once('.') {
+            Some((addr, name)) => {
+                if name.is_empty() || name.len() > 128 {
+                    return Err(ConxianError::Stacks(format!(
+                        "Invalid contract name in {}",
+                        s
+                    )));
+                }
+                Ok(PrincipalData::Contract(addr.parse()?, name.to_string()))
+            }
+            None => Ok(PrincipalData::Standard(s.parse()?)),
+        }
+    }
+}
+
+impl Serialize for PrincipalData {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&self.to_string())
+    }
+}
+
+impl<'de> Deserialize<'de> for PrincipalData {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        s.parse().map_err(serde::de::Error::custom)
+    }
+}
+
+mod hex_bytes {
+    use super::*;
+
+    pub fn serialize<S: Serializer>(bytes: &[u8], serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_str(&bytes.to_lower_hex_string())
+    }
+
+    pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<Vec<u8>, D::Error> {
+        let s = String::deserialize(deserializer)?;
+        Vec::from_hex(s.trim_start_matches("0x")).map_err(serde::de::Error::custom)
+    }
+}
+
+/// 128-bit integers as JSON numbers when they fit in 64 bits and as decimal
+/// strings otherwise. Also keeps them readable from serde's buffered content,
+/// which has no 128-bit support, when the tag follows the value.
+mod wide_int {
+    use serde::de::Error;
+    use serde::{Deserialize, Deserializer, Serializer};
+
+    #[derive(Deserialize)]
+    #[serde(untagged)]
+    enum Repr<T> {
+        Number(T),
+        String(String),
+    }
+
+    pub mod unsigned {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(value: &u128, serializer: S) -> Result<S::Ok, S::Error> {
+            match u64::try_from(*value) {
+                Ok(v) => serializer.serialize_u64(v),
+                Err(_) => serializer.serialize_str(&value.to_string()),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<u128, D::Error> {
+            match Repr::<u64>::deserialize(deserializer)? {
+                Repr::Number(v) => Ok(v as u128),
+                Repr::String(s) => s.parse().map_err(D::Error::custom),
+            }
+        }
+    }
+
+    pub mod signed {
+        use super::*;
+
+        pub fn serialize<S: Serializer>(value: &i128, serializer: S) -> Result<S::Ok, S::Error> {
+            match i64::try_from(*value) {
+                Ok(v) => serializer.serialize_i64(v),
+                Err(_) => serializer.serialize_str(&value.to_string()),
+            }
+        }
+
+        pub fn deserialize<'de, D: Deserializer<'de>>(deserializer: D) -> Result<i128, D::Error> {
+            match Repr::<i64>::deserialize(deserializer)? {
+                Repr::Number(v) => Ok(v as i128),
+                Repr::String(s) => s.parse().map_err(D::Error::custom),
+            }
+        }
+    }
+}
+
+/// A Clarity value in its consensus (SIP-005) serialization.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+#[serde(tag = "type", content = "value", rename_all = "snake_case")]
+pub enum ClarityValue {
+    Int(#[serde(with = "wide_int::signed")] i128),
+    #[serde(rename = "uint")]
+    UInt(#[serde(with = "wide_int::unsigned")] u128),
+    Buffer(#[serde(with = "hex_bytes")] Vec<u8>),
+    Bool(bool),
+    Principal(PrincipalData),
+    Ok(Box<ClarityValue>),
+    Err(Box<ClarityValue>),
+    None,
+    Some(Box<ClarityValue>),
+    List(Vec<ClarityValue>),
+    Tuple(BTreeMap<String, ClarityValue>),
+    StringAscii(String),
+    StringUtf8(String),
+}
+
+impl ClarityValue {
+    /// Every principal in the value, including those nested in optionals,
+    /// responses, lists and tuples.
+    pub fn principals(&self) -> Vec<PrincipalData> {
+        let mut principals = Vec::new();
+        self.collect_principals(&mut principals);
+        principals
+    }
+
+    fn collect_principals(&self, out: &mut Vec<PrincipalData>) {
+        match self {
+            ClarityValue::Principal(p) => out.push(p.clone()),
+            ClarityValue::Ok(v) | ClarityValue::Err(v) | ClarityValue::Some(v) => {
+                v.collect_principals(out)
+            }
+            ClarityValue::List(items) => items.iter().for_each(|v| v.collect_principals(out)),
+            ClarityValue::Tuple(fields) => fields.values().for_each(|v| v.collect_principals(out)),
+            _ => {}
+        }
+    }
+
+    pub fn serialize_to_vec(&self) -> ConxianResult<Vec<u8>> {
+        let mut out = Vec::new();
+        self.serialize_into(&mut out)?;
+        Ok(out)
+    }
+
+    pub fn serialize_into(&self, out: &mut Vec<u8>) -> ConxianResult<()> {
+        match self {
+            ClarityValue::Int(v) => {
+                out.push(0x00);
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+            ClarityValue::UInt(v) => {
+                out.push(0x01);
+                out.extend_from_slice(&v.to_be_bytes());
+            }
+            ClarityValue::Buffer(b) => {
+                out.push(0x02);
+                write_len(out, b.len())?;
+                out.extend_from_slice(b);
+            }
+            ClarityValue::Bool(true) => out.push(0x03),
+            ClarityValue::Bool(false) => out.push(0x04),
+            ClarityValue::Principal(PrincipalData::Standard(a)) => {
+                out.push(0x05);
+                out.push(a.version);
+                out.extend_from_slice(&a.hash160);
+            }
+            ClarityValue::Principal(PrincipalData::Contract(a, name)) => {
+                out.push(0x06);
+                out.push(a.version);
+                out.extend_from_slice(&a.hash160);
+                write_name(out, name)?;
+            }
+            ClarityValue::Ok(v) => {
+                out.push(0x07);
+                v.serialize_into(out)?;
+            }
+            ClarityValue::Err(v) => {
+                out.push(0x08);
+                v.serialize_into(out)?;
+            }
+            ClarityValue::None => out.push(0x09),
+            ClarityValue::Some(v) => {
+                out.push(0x0a);
+                v.serialize_into(out)?;
+            }
+            ClarityValue::List(items) => {
+                out.push(0x0b);
+                write_len(out, items.len())?;
+                for item in items {
+                    item.serialize_into(out)?;
+                }
+            }
+            ClarityValue::Tuple(fields) => {
+                out.push(0x0c);
+                write_len(out, fields.len())?;
+                // BTreeMap iteration order matches Clarity's lexicographic field order
+                for (name, value) in fields {
+                    write_name(out, name)?;
+                    value.serialize_into(out)?;
+                }
+            }
+            ClarityValue::StringAscii(s) => {
+                if !s.is_ascii() {
+                    return Err(ConxianError::Stacks(
+                        "string-ascii contains non-ascii characters".to_string(),
+                    ));
+                }
+                out.push(0x0d);
+                write_len(out, s.len())?;
+                out.extend_from_slice(s.as_bytes());
+            }
+            ClarityValue::StringUtf8(s) => {
+                out.push(0x0e);
+                write_len(out, s.len())?;
+                out.extend_from_slice(s.as_bytes());
+            }
+        }
+        Ok(())
+    }
+
+    pub fn deserialize(bytes: &[u8]) -> ConxianResult<Self> {
+        let mut reader = Reader::new(bytes);
+        let value = Self::deserialize_from(&mut reader)?;
+        if !reader.is_empty() {
+            return Err(ConxianError::Stacks(format!(
+                "{} trailing bytes after Clarity value",
+                reader.remaining()
+            )));
+        }
+        Ok(value)
+    }
+
+    pub fn from_hex(s: &str) -> ConxianResult<Self> {
+        let bytes = Vec::from_hex(s.trim_start_matches("0x"))
+            .map_err(|e| ConxianError::Stacks(format!("Invalid Clarity value hex: {}", e)))?;
+        Self::deserialize(&bytes)
+    }
+
+    pub fn to_hex(&self) -> ConxianResult<String> {
+        Ok(format!(
+            "0x{}",
+            self.serialize_to_vec()?.to_lower_hex_string()
+        ))
+    }
+
+    pub fn deserialize_from(reader: &mut Reader) -> ConxianResult<Self> {
+        Self::deserialize_depth(reader, 0)
+    }
+
+    fn deserialize_depth(reader: &mut Reader, depth: usize) -> ConxianResult<Self> {
+        if depth > MAX_VALUE_DEPTH {
+            return Err(ConxianError::Stacks(
+                "Clarity value exceeds maximum depth".to_string(),
+            ));
+        }
+        let prefix = reader.read_u8()?;
+        let value = match prefix {
+            0x00 => ClarityValue::Int(i128::from_be_bytes(reader.read_array()?)),
+            0x01 => ClarityValue::UInt(u128::from_be_bytes(reader.read_array()?)),
+            0x02 => {
+                let len = read_len(reader)?;
+                ClarityValue::Buffer(reader.read_bytes(len)?.to_vec())
+            }
+            0x03 => ClarityValue::Bool(true),
+            0x04 => ClarityValue::Bool(false),
+            0x05 | 0x06 => {
+                let version = reader.read_u8()?;
+                let address = StacksAddress::new(version, reader.read_array()?)?;
+                if prefix == 0x05 {
+                    ClarityValue::Principal(PrincipalData::Standard(address))
+                } else {
+                    ClarityValue::Principal(PrincipalData::Contract(address, reader.read_name()?))
+                }
+            }
+            0x07 => ClarityValue::Ok(Box::new(Self::deserialize_depth(reader, depth + 1)?)),
+            0x08 => ClarityValue::Err(Box::new(Self::deserialize_depth(reader, depth + 1)?)),
+            0x09 => ClarityValue::None,
+            0x0a => ClarityValue::Some(Box::new(Self::deserialize_depth(reader, depth + 1)?)),
+            0x0b => {
+                let len = read_len(reader)?;
+                let mut items = Vec::with_capacity(len.min(reader.remaining()));
+                for _ in 0..len {
+                    items.push(Self::deserialize_depth(reader, depth + 1)?);
+                }
+                ClarityValue::List(items)
+            }
+            0x0c => {
+                let len = read_len(reader)?;
+                let mut fields = BTreeMap::new();
+                for _ in 0..len {
+                    let name = reader.read_name()?;
+                    let value = Self::deserialize_depth(reader, depth + 1)?;
+                    if fields.insert(name.clone(), value).is_some() {
+                        return Err(ConxianError::Stacks(format!(
+                            "Duplicate tuple field: {}",
+                            name
+                        )));
+                    }
+                }
+                ClarityValue::Tuple(fields)
+            }
+            0x0d | 0x0e => {
+                let len = read_len(reader)?;
+                let bytes = reader.read_bytes(len)?.to_vec();
+                let s = String::from_utf8(bytes)
+                    .map_err(|e| ConxianError::Stacks(format!("Invalid string: {}", e)))?;
+                if prefix == 0x0d {
+                    if !s.is_ascii() {
+                        return Err(ConxianError::Stacks(
+                            "string-ascii contains non-ascii characters".to_string(),
+                        ));
+                    }
+                    ClarityValue::StringAscii(s)
+                } else {
+                    ClarityValue::StringUtf8(s)
+                }
+            }
+            other => {
+                return Err(ConxianError::Stacks(format!(
+                    "Unknown Clarity type prefix: 0x{:02x}",
+                    other
+                )))
+            }
+        };
+        Ok(value)
+    }
+}
+
+fn read_len(reader: &mut Reader) -> ConxianResult<usize> {
+    let len = reader.read_u32()?;
+    if len > MAX_VALUE_SIZE {
+        return Err(ConxianError::Stacks(format!(
+            "Clarity length {} exceeds maximum",
+            len
+        )));
+    }
+    Ok(len as usize)
+}
+
+fn write_len(out: &mut Vec<u8>, len: usize) -> ConxianResult<()> {
+    let len = u32::try_from(len)
+        .ok()
+        .filter(|l| *l <= MAX_VALUE_SIZE)
+        .ok_or_else(|| ConxianError::Stacks("Clarity value too large".to_string()))?;
+    out.extend_from_slice(&len.to_be_bytes());
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn test_clarity_known_encodings() {
+        assert_eq!(
+            ClarityValue::UInt(1).to_hex().unwrap(),
+            "0x0100000000000000000000000000000001"
+        );
+        assert_eq!(
+            ClarityValue::StringAscii("hi".to_string())
+                .to_hex()
+                .unwrap(),
+            "0x0d000000026869"
+        );
+        let principal: PrincipalData = "SP000000000000000000002Q6VF78.bns".parse().unwrap();
+        assert_eq!(
+            ClarityValue::Principal(principal).to_hex().unwrap(),
+            "0x0616000000000000000000000000000000000000000003626e73"
+        );
+    }
+
+    #[test]
+    fn test_clarity_roundtrip() {
+        let mut fields = BTreeMap::new();
+        fields.insert("name".to_string(), ClarityValue::Buffer(b"alice".to_vec()));
+        fields.insert(
+            "owner".to_string(),
+            ClarityValue::Some(Box::new(ClarityValue::Principal(
+                "ST000000000000000000002AMW42H".parse().unwrap(),
+            ))),
+        );
+        let value = ClarityValue::Ok(Box::new(ClarityValue::List(vec![
+            ClarityValue::Tuple(fields),
+            ClarityValue::Int(-5),
+            ClarityValue::Bool(false),
+            ClarityValue::None,
+            ClarityValue::StringUtf8("é".to_string()),
+        ])));
+
+        let hex = value.to_hex().unwrap();
+        assert_eq!(ClarityValue::from_hex(&hex).unwrap(), value);
+    }
+
+    #[test]
+    fn test_clarity_json_wide_integers() {
+        let value = ClarityValue::List(vec![
+            ClarityValue::UInt(7),
+            ClarityValue::UInt(u128::MAX),
+            ClarityValue::Int(i128::MIN),
+        ]);
+        let json = serde_json::to_string(&value).unwrap();
+        assert!(json.contains(r#"{"type":"uint","value":7}"#));
+        assert!(json.contains(&format!(r#""{}""#, u128::MAX)));
+        assert_eq!(serde_json::from_str::<ClarityValue>(&json).unwrap(), value);
+
+        // Value before tag forces serde to buffer the content
+        let reordered: ClarityValue = serde_json::from_str(r#"{"value":1,"type":"uint"}"#).unwrap();
+        assert_eq!(reordered, ClarityValue::UInt(1));
+    }
+
+    #[test]
+    fn test_clarity_rejects_malformed() {
+        assert!(ClarityValue::from_hex("0x01").is_err());
+        assert!(ClarityValue::from_hex("0x0304").is_err());
+        assert!(ClarityValue::from_hex("0xff").is_err());
+    }
+}
@@ -0,0 +1,78 @@
+use crate::{ConxianError, ConxianResult};
+
+/// Cursor over a byte slice for decoding Stacks consensus-serialized data.
+pub struct Reader<'a> {
+    data: &'a [u8],
+    pos: usize,
+}
+
+impl<'a> Reader<'a> {
+    pub fn new(data: &'a [u8]) -> Self {
+        Self { data, pos: 0 }
+    }
+
+    pub fn position(&self) -> usize {
+        self.pos
+    }
+
+    pub fn remaining(&self) -> usize {
+        self.data.len() - self.pos
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.remaining() == 0
+    }
+
+    pub fn read_bytes(&mut self, len: usize) -> ConxianResult<&'a [u8]> {
+        if len > self.remaining() {
+            return Err(ConxianError::Stacks(format!(
+                "Unexpected end of data: wanted {} bytes at offset {}, {} left",
+                len,
+                self.pos,
+                self.remaining()
+            )));
+        }
+        let bytes = &self.data[self.pos..self.pos + len];
+        self.pos += len;
+        Ok(bytes)
+    }
+
+    pub fn read_array<const N: usize>(&mut self) -> ConxianResult<[u8; N]> {
+        let mut out = [0u8; N];
+        out.copy_from_slice(self.read_bytes(N)?);
+        Ok(out)
+    }
+
+    pub fn read_u8(&mut self) -> ConxianResult<u8> {
+        Ok(self.read_bytes(1)?[0])
+    }
+
+    pub fn read_u16(&mut self) -> ConxianResult<u16> {
+        Ok(u16::from_be_bytes(self.read_array()?))
+    }
+
+    pub fn read_u32(&mut self) -> ConxianResult<u32> {
+        Ok(u32::from_be_bytes(self.read_array()?))
+    }
+
+    pub fn read_u64(&mut self) -> ConxianResult<u64> {
+        Ok(u64::from_be_bytes(self.read_array()?))
+    }
+
+    /// Reads a string prefixed with a one-byte length, as used for contract and asset names.
+    pub fn read_name(&mut self) -> ConxianResult<String> {
+        let len = self.read_u8()? as usize;
+        let bytes = self.read_bytes(len)?;
+        String::from_utf8(bytes.to_vec())
+            .map_err(|e| ConxianError::Stacks(format!("Invalid name: {}", e)))
+    }
+}
+
+/// Writes a string prefixed with a one-byte length.
+pub fn write_name(out: &mut Vec<u8>, name: &str) -> ConxianResult<()> {
+    let len = u8::try_from(name.len())
+        .map_err(|_| ConxianError::Stacks(format!("Name too long: {}", name)))?;
+    out.push(len);
+    out.extend_from_slice(name.as_bytes());
+    Ok(())
+}
@@ -0,0 +1,728 @@
+//! InterVASP Messaging Standard (IVMS101) identity data for the Travel Rule.
+//!
+//! The types follow the standard's JSON encoding (camelCase fields and
+//! four-letter codes) for the elements VASPs exchange about a transfer: the
+//! originator, the beneficiary and the two VASPs. `validate` applies the
+//! standard's constraints and returns a `Compliance` error naming the path
+//! of the first element that breaks one.
+
+use crate::{ConxianError, ConxianResult};
+use serde::{Deserialize, Serialize};
+
+const MAX_NAME: usize = 100;
+const MAX_IDENTIFIER: usize = 35;
+const MAX_ADDRESS_LINE: usize = 70;
+const MAX_ADDRESS_LINES: usize = 7;
+
+/// Originator, beneficiary and VASP data for one transfer.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct IdentityPayload {
+    pub originator: Originator,
+    pub beneficiary: Beneficiary,
+    #[serde(rename = "originatingVASP", default)]
+    pub originating_vasp: Option<OriginatingVasp>,
+    #[serde(rename = "beneficiaryVASP", default)]
+    pub beneficiary_vasp: Option<BeneficiaryVasp>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Originator {
+    pub originator_persons: Vec<Person>,
+    #[serde(default)]
+    pub account_number: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Beneficiary {
+    pub beneficiary_persons: Vec<Person>,
+    #[serde(default)]
+    pub account_number: Vec<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct OriginatingVasp {
+    #[serde(rename = "originatingVASP")]
+    pub originating_vasp: Person,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct BeneficiaryVasp {
+    #[serde(rename = "beneficiaryVASP")]
+    pub beneficiary_vasp: Person,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum Person {
+    NaturalPerson(NaturalPerson),
+    LegalPerson(LegalPerson),
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NaturalPerson {
+    pub name: NaturalPersonName,
+    #[serde(default)]
+    pub geographic_address: Vec<GeographicAddress>,
+    #[serde(default)]
+    pub national_identification: Option<NationalIdentification>,
+    #[serde(default)]
+    pub customer_identification: Option<String>,
+    #[serde(default)]
+    pub date_and_place_of_birth: Option<DateAndPlaceOfBirth>,
+    /// ISO 3166-1 alpha-2 country code.
+    #[serde(default)]
+    pub country_of_residence: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NaturalPersonName {
+    pub name_identifier: Vec<NaturalPersonNameId>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NaturalPersonNameId {
+    /// Surname, or the full name where it cannot be split.
+    pub primary_identifier: String,
+    #[serde(default)]
+    pub secondary_identifier: Option<String>,
+    pub name_identifier_type: NaturalPersonNameType,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum NaturalPersonNameType {
+    /// Alias name.
+    #[serde(rename = "ALIA")]
+    Alias,
+    /// Name at birth.
+    #[serde(rename = "BIRT")]
+    Birth,
+    /// Maiden name.
+    #[serde(rename = "MAID")]
+    Maiden,
+    /// Legal name.
+    #[serde(rename = "LEGL")]
+    Legal,
+    /// Unspecified.
+    #[serde(rename = "MISC")]
+    Misc,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LegalPerson {
+    pub name: LegalPersonName,
+    #[serde(default)]
+    pub geographic_address: Vec<GeographicAddress>,
+    #[serde(default)]
+    pub customer_identification: Option<String>,
+    #[serde(default)]
+    pub national_identification: Option<NationalIdentification>,
+    /// ISO 3166-1 alpha-2 country code.
+    #[serde(default)]
+    pub country_of_registration: Option<String>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LegalPersonName {
+    pub name_identifier: Vec<LegalPersonNameId>,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LegalPersonNameId {
+    pub legal_person_name: String,
+    pub legal_person_name_identifier_type: LegalPersonNameType,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum LegalPersonNameType {
+    #[serde(rename = "LEGL")]
+    Legal,
+    #[serde(rename = "SHRT")]
+    Short,
+    #[serde(rename = "TRAD")]
+    Trading,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct GeographicAddress {
+    pub address_type: AddressType,
+    #[serde(default)]
+    pub street_name: Option<String>,
+    #[serde(default)]
+    pub building_number: Option<String>,
+    #[serde(default)]
+    pub building_name: Option<String>,
+    #[serde(default)]
+    pub post_code: Option<String>,
+    pub town_name: String,
+    #[serde(default)]
+    pub country_sub_division: Option<String>,
+    #[serde(default)]
+    pub address_line: Vec<String>,
+    /// ISO 3166-1 alpha-2 country code.
+    pub country: String,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AddressType {
+    #[serde(rename = "HOME")]
+    Residential,
+    #[serde(rename = "BIZZ")]
+    Business,
+    #[serde(rename = "GEOG")]
+    Geographic,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct NationalIdentification {
+    pub national_identifier: String,
+    pub national_identifier_type: NationalIdentifierType,
+    #[serde(default)]
+    pub country_of_issue: Option<String>,
+    /// GLEIF Registration Authority code, for legal persons.
+    #[serde(default)]
+    pub registration_authority: Option<String>,
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum NationalIdentifierType {
+    /// Alien registration number.
+    #[serde(rename = "ARNU")]
+    AlienRegistration,
+    /// Passport number.
+    #[serde(rename = "CCPT")]
+    Passport,
+    /// Registration authority identifier.
+    #[serde(rename = "RAID")]
+    RegistrationAuthority,
+    /// Driver's licence number.
+    #[serde(rename = "DRLC")]
+    DriversLicence,
+    /// Foreign investment identity number.
+    #[serde(rename = "FIIN")]
+    ForeignInvestment,
+    /// Tax identification number.
+    #[serde(rename = "TXID")]
+    Tax,
+    /// Social security number.
+    #[serde(rename = "SOCS")]
+    SocialSecurity,
+    /// Identity card number.
+    #[serde(rename = "IDCD")]
+    IdentityCard,
+    /// Legal Entity Identifier.
+    #[serde(rename = "LEIX")]
+    Lei,
+    /// Unspecified.
+    #[serde(rename = "MISC")]
+    Misc,
+}
+
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct DateAndPlaceOfBirth {
+    /// `YYYY-MM-DD`.
+    pub date_of_birth: String,
+    pub place_of_birth: String,
+}
+
+fn invalid<T>(path: &str, reason: impl std::fmt::Display) -> ConxianResult<T> {
+    Err(ConxianError::Compliance(format!(
+        "Invalid IVMS101 {}: {}",
+        path, reason
+    )))
+}
+
+fn text(path: &str, value: &str, max: usize) -> ConxianResult<()> {
+    if value.trim().is_empty() {
+        return invalid(path, "must not be empty");
+    }
+    if value.chars().count() > max {
+        return invalid(path, format!("exceeds {} characters", max));
+    }
+    Ok(())
+}
+
+fn country(path: &str, code: &str) -> ConxianResult<()> {
+    if code.len() != 2 || !code.bytes().all(|b| b.is_ascii_uppercase()) {
+        return invalid(path, format!("{} is not an ISO 3166-1 alpha-2 code", code));
+    }
+    Ok(())
+}
+
+/// The UTC calendar date of a Unix timestamp as `YYYY-MM-DD`.
+pub fn date(unix_secs: u64) -> String {
+    // Days to civil date, after Howard Hinnant's algorithm
+    let z = (unix_secs / 86_400) as i64 + 719_468;
+    let era = z.div_euclid(146_097);
+    let doe = z - era * 146_097;
+    let yoe = (doe - doe / 1_460 + doe / 36_524 - doe / 146_096) / 365;
+    let doy = doe - (365 * yoe + yoe / 4 - yoe / 100);
+    let mp = (5 * doy + 2) / 153;
+    let day = doy - (153 * mp + 2) / 5 + 1;
+    let month = if mp < 10 { mp + 3 } else { mp - 9 };
+    let year = yoe + era * 400 + (month <= 2) as i64;
+    format!("{:04}-{:02}-{:02}", year, month, day)
+}
+
+/// Whether `date` is a `YYYY-MM-DD` calendar date before `today`, both
+/// compared as strings of that form.
+fn date_before(date: &str, today: &str) -> bool {
+    let bytes = date.as_bytes();
+    let shape = bytes.len() == 10
+        && bytes.iter().enumerate().all(|(i, b)| match i {
+            4 | 7 => *b == b'-',
+            _ => b.is_ascii_digit(),
+        });
+    if !shape {
+        return false;
+    }
+    let month: u32 = date[5..7].parse().unwrap_or(0);
+    let day: u32 = date[8..10].parse().unwrap_or(0);
+    (1..=12).contains(&month) && (1..=31).contains(&day) && date < today
+}
+
+/// Checks an ISO 17442 Legal Entity Identifier: 18 alphanumerics followed
+/// by two check digits, valid under ISO 7064 MOD 97-10.
+fn valid_lei(lei: &str) -> bool {
+    if lei.len() != 20
+        || !lei
+            .bytes()
+            .all(|b| b.is_ascii_digit() || b.is_ascii_uppercase())
+        || !lei[18..].bytes().all(|b| b.is_ascii_digit())
+    {
+        return false;
+    }
+    let remainder = lei.bytes().fold(0u32, |acc, b| {
+        let value = if b.is_ascii_digit() {
+            (b - b'0') as u32
+        } else {
+            (b - b'A') as u32 + 10
+        };
+        let shift = if value >= 10 { 100 } else { 10 };
+        (acc * shift + value) % 97
+    });
+    remainder == 1
+}
+
+impl GeographicAddress {
+    fn validate(&self, path: &str) -> ConxianResult<()> {
+        text(
+            &format!("{}.townName", path),
+            &self.town_name,
+            MAX_IDENTIFIER,
+        )?;
+        country(&format!("{}.country", path), &self.country)?;
+        if self.address_line.len() > MAX_ADDRESS_LINES {
+            return invalid(
+                &format!("{}.addressLine", path),
+                format!("exceeds {} lines", MAX_ADDRESS_LINES),
+            );
+        }
+        for (i, line) in self.address_line.iter().enumerate() {
+            text(
+                &format!("{}.addressLine[{}]", path, i),
+                line,
+                MAX_ADDRESS_LINE,
+            )?;
+        }
+        // An address is either free-form lines or a street and building
+        let structured = self.street_name.is_some()
+            && (self.building_number.is_some() || self.building_name.is_some());
+        if self.address_line.is_empty() && !structured {
+            return invalid(
+                path,
+                "requires addressLine, or streetName with buildingName or buildingNumber",
+            );
+        }
+        Ok(())
+    }
+}
+
+impl NationalIdentification {
+    fn validate(&self, path: &str, legal: bool) -> ConxianResult<()> {
+        let id_path = format!("{}.nationalIdentifier", path);
+        text(&id_path, &self.national_identifier, MAX_IDENTIFIER)?;
+        if let Some(code) = &self.country_of_issue {
+            country(&format!("{}.countryOfIssue", path), code)?;
+        }
+        use NationalIdentifierType::*;
+        match self.national_identifier_type {
+            Lei if !valid_lei(&self.national_identifier) => invalid(&id_path, "is not a valid LEI"),
+            RegistrationAuthority | Misc | Lei | Tax => Ok(()),
+            other if legal => invalid(
+                &format!("{}.nationalIdentifierType", path),
+                format!(
+                    "{} does not identify a legal person",
+                    serde_json::to_string(&other).unwrap_or_default()
+                ),
+            ),
+            _ => Ok(()),
+        }
+    }
+}
+
+impl NaturalPerson {
+    fn validate(&self, path: &str, originator: bool, today: &str) -> ConxianResult<()> {
+        let names = &self.name.name_identifier;
+        for (i, name) in names.iter().enumerate() {
+            let name_path = format!("{}.name.nameIdentifier[{}]", path, i);
+            text(
+                &format!("{}.primaryIdentifier", name_path),
+                &name.primary_identifier,
+                MAX_NAME,
+            )?;
+            if let Some(secondary) = &name.secondary_identifier {
+                text(
+                    &format!("{}.secondaryIdentifier", name_path),
+                    secondary,
+                    MAX_NAME,
+                )?;
+            }
+        }
+        if !names
+            .iter()
+            .any(|n| n.name_identifier_type == NaturalPersonNameType::Legal)
+        {
+            return invalid(&format!("{}.name", path), "requires a LEGL name identifier");
+        }
+        for (i, address) in self.geographic_address.iter().enumerate() {
+            address.validate(&format!("{}.geographicAddress[{}]", path, i))?;
+        }
+        if let Some(id) = &self.national_identification {
+            id.validate(&format!("{}.nationalIdentification", path), false)?;
+        }
+        if let Some(id) = &self.customer_identification {
+            text(
+                &format!("{}.customerIdentification", path),
+                id,
+                MAX_ADDRESS_LINE,
+            )?;
+        }
+        if let Some(birth) = &self.date_and_place_of_birth {
+            if !date_before(&birth.date_of_birth, today) {
+                return invalid(
+                    &format!("{}.dateAndPlaceOfBirth.dateOfBirth", path),
+                    "must be a YYYY-MM-DD date in the past",
+                );
+            }
+            text(
+                &format!("{}.dateAndPlaceOfBirth.placeOfBirth", path),
+                &birth.place_of_birth,
+                MAX_NAME,
+            )?;
+        }
+        if let Some(code) = &self.country_of_residence {
+            country(&format!("{}.countryOfResidence", path), code)?;
+        }
+        // An originating natural person must be identifiable beyond a name
+        if originator
+            && self.geographic_address.is_empty()
+            && self.customer_identification.is_none()
+            && self.national_identification.is_none()
+            && self.date_and_place_of_birth.is_none()
+        {
+            return invalid(
+                path,
+                "originator requires a geographicAddress, customerIdentification, \
+                 nationalIdentification or dateAndPlaceOfBirth",
+            );
+        }
+        Ok(())
+    }
+}
+
+impl LegalPerson {
+    fn validate(&self, path: &str, originator: bool) -> ConxianResult<()> {
+        let names = &self.name.name_identifier;
+        for (i, name) in names.iter().enumerate() {
+            text(
+                &format!("{}.name.nameIdentifier[{}].legalPersonName", path, i),
+                &name.legal_person_name,
+                MAX_NAME,
+            )?;
+        }
+        if !names
+            .iter()
+            .any(|n| n.legal_person_name_identifier_type == LegalPersonNameType::Legal)
+        {
+            return invalid(&format!("{}.name", path), "requires a LEGL name identifier");
+        }
+        for (i, address) in self.geographic_address.iter().enumerate() {
+            address.validate(&format!("{}.geographicAddress[{}]", path, i))?;
+        }
+        if let Some(id) = &self.national_identification {
+            id.validate(&format!("{}.nationalIdentification", path), true)?;
+        }
+        if let Some(code) = &self.country_of_registration {
+            country(&format!("{}.countryOfRegistration", path), code)?;
+        }
+        if originator
+            && self.geographic_address.is_empty()
+            && self.customer_identification.is_none()
+            && self.national_identification.is_none()
+        {
+            return invalid(
+                path,
+                "originator requires a geographicAddress, customerIdentification or \
+                 nationalIdentification",
+            );
+        }
+        Ok(())
+    }
+}
+
+impl Person {
+    fn validate(&self, path: &str, originator: bool, today: &str) -> ConxianResult<()> {
+        match self {
+            Person::NaturalPerson(person) => {
+                person.validate(&format!("{}.naturalPerson", path), originator, today)
+            }
+            Person::LegalPerson(person) => {
+                person.validate(&format!("{}.legalPerson", path), originator)
+            }
+        }
+    }
+}
+
+impl IdentityPayload {
+    /// Checks the payload against the IVMS101 constraints, with dates of
+    /// birth required to fall before `today` (`YYYY-MM-DD`).
+    pub fn validate(&self, today: &str) -> ConxianResult<()> {
+        let persons = &self.originator.originator_persons;
+        if persons.is_empty() {
+            return invalid("originator.originatorPersons", "must not be empty");
+        }
+        for (i, person) in persons.iter().enumerate() {
+            person.validate(&format!("originator.originatorPersons[{}]", i), true, today)?;
+        }
+        for (i, account) in self.originator.account_number.iter().enumerate() {
+            text(
+                &format!("originator.accountNumber[{}]", i),
+                account,
+                MAX_NAME,
+            )?;
+        }
+
+        let persons = &self.beneficiary.beneficiary_persons;
+        if persons.is_empty() {
+            return invalid("beneficiary.beneficiaryPersons", "must not be empty");
+        }
+        for (i, person) in persons.iter().enumerate() {
+            person.validate(
+                &format!("beneficiary.beneficiaryPersons[{}]", i),
+                false,
+                today,
+            )?;
+        }
+        for (i, account) in self.beneficiary.account_number.iter().enumerate() {
+            text(
+                &format!("beneficiary.accountNumber[{}]", i),
+                account,
+                MAX_NAME,
+            )?;
+        }
+
+        // VASPs are institutions, never natural persons
+        let vasps = [
+            self.originating_vasp
+                .as_ref()
+                .map(|v| ("originatingVASP.originatingVASP", &v.originating_vasp)),
+            self.beneficiary_vasp
+                .as_ref()
+                .map(|v| ("beneficiaryVASP.beneficiaryVASP", &v.beneficiary_vasp)),
+        ];
+        for (path, vasp) in vasps.into_iter().flatten() {
+            match vasp {
+                Person::LegalPerson(person) => {
+                    person.validate(&format!("{}.legalPerson", path), false)?
+                }
+                Person::NaturalPerson(_) => {
+                    return invalid(path, "must be a legal person");
+                }
+            }
+        }
+        Ok(())
+    }
+}
+
+#[cfg(test)]
+pub(crate) mod tests {
+    use super::*;
+
+    pub(crate) fn payload() -> IdentityPayload {
+        serde_json::from_value(serde_json::json!({
+            "originator": {
+                "originatorPersons": [{
+                    "naturalPerson": {
+                        "name": {
+                            "nameIdentifier": [{
+                                "primaryIdentifier": "Nakamoto",
+                                "secondaryIdentifier": "Satoshi",
+                                "nameIdentifierType": "LEGL"
+                            }]
+                        },
+                        "geographicAddress": [{
+                            "addressType": "HOME",
+                            "streetName": "Main Street",
+                            "buildingNumber": "1",
+                            "townName": "Zug",
+                            "country": "CH"
+                        }],
+                        "dateAndPlaceOfBirth": {
+                            "dateOfBirth": "1975-04-05",
+                            "placeOfBirth": "Tokyo"
+                        }
+                    }
+                }],
+                "accountNumber": ["bc1q9vza2e8x573nczrlzms0wvx3gsqjx7vavgkx0l"]
+            },
+            "beneficiary": {
+                "beneficiaryPersons": [{
+                    "legalPerson": {
+                        "name": {
+                            "nameIdentifier": [{
+                                "legalPersonName": "Example Custody AG",
+                                "legalPersonNameIdentifierType": "LEGL"
+                            }]
+                        }
+                    }
+                }],
+                "accountNumber": ["SP000000000000000000002Q6VF78"]
+            },
+            "originatingVASP": {
+                "originatingVASP": {
+                    "legalPerson": {
+                        "name": {
+                            "nameIdentifier": [{
+                                "legalPersonName": "Conxian Custody Ltd",
+                                "legalPersonNameIdentifierType": "LEGL"
+                            }]
+                        },
+                        "nationalIdentification": {
+                            "nationalIdentifier": "5493001KJTIIGC8Y1R12",
+                            "nationalIdentifierType": "LEIX"
+                        },
+                        "countryOfRegistration": "CH"
+                    }
+                }
+            }
+        }))
+        .unwrap()
+    }
+
+    #[test]
+    fn test_date() {
+        assert_eq!(date(0), "1970-01-01");
+        assert_eq!(date(951_782_400), "2000-02-29");
+        assert_eq!(date(1_767_225_599), "2025-12-31");
+    }
+
+    #[test]
+    fn test_ivms101_round_trip() {
+        let payload = payload();
+        payload.validate("2026-01-01").unwrap();
+        let json = serde_json::to_value(&payload).unwrap();
+        assert_eq!(
+            json["originatingVASP"]["originatingVASP"]["legalPerson"]["nationalIdentification"]
+                ["nationalIdentifierType"],
+            "LEIX"
+        );
+        assert_eq!(
+            serde_json::from_value::<IdentityPayload>(json).unwrap(),
+            payload
+        );
+    }
+
+    fn originator(payload: &mut IdentityPayload) -> &mut NaturalPerson {
+        match &mut payload.originator.originator_persons[0] {
+            Person::NaturalPerson(person) => person,
+            Person::LegalPerson(_) => unreachable!(),
+        }
+    }
+
+    fn vasp_id(payload: &mut IdentityPayload) -> &mut NationalIdentification {
+        match &mut payload.originating_vasp.as_mut().unwrap().originating_vasp {
+            Person::LegalPerson(vasp) => vasp.national_identification.as_mut().unwrap(),
+            Person::NaturalPerson(_) => unreachable!(),
+        }
+    }
+
+    #[test]
+    fn test_ivms101_constraints() {
+        let rejects = |edit: fn(&mut IdentityPayload), expected: &str| {
+            let mut payload = payload();
+            edit(&mut payload);
+            let err = payload.validate("2026-01-01").unwrap_err().to_string();
+            assert!(err.contains(expected), "{}", err);
+        };
+
+        rejects(
+            |p| p.originator.originator_persons.clear(),
+            "originator.originatorPersons: must not be empty",
+        );
+        rejects(
+            |p| {
+                originator(p).name.name_identifier[0].name_identifier_type =
+                    NaturalPersonNameType::Alias
+            },
+            "requires a LEGL name identifier",
+        );
+        rejects(
+            |p| {
+                let person = originator(p);
+                person.geographic_address.clear();
+                person.date_and_place_of_birth = None;
+            },
+            "originator requires",
+        );
+        rejects(
+            |p| originator(p).geographic_address[0].building_number = None,
+            "geographicAddress[0]: requires addressLine",
+        );
+        rejects(
+            |p| originator(p).geographic_address[0].country = "Switzerland".to_string(),
+            "ISO 3166-1",
+        );
+        rejects(
+            |p| {
+                originator(p)
+                    .date_and_place_of_birth
+                    .as_mut()
+                    .unwrap()
+                    .date_of_birth = "2030-01-01".to_string()
+            },
+            "dateOfBirth: must be a YYYY-MM-DD date in the past",
+        );
+        rejects(
+            |p| vasp_id(p).national_identifier = "5493001KJTIIGC8Y1R13".to_string(),
+            "is not a valid LEI",
+        );
+        rejects(
+            |p| vasp_id(p).national_identifier_type = NationalIdentifierType::Passport,
+            "does not identify a legal person",
+        );
+
+        // A beneficiary needs no more than a name
+        let mut payload = payload();
+        payload.beneficiary.beneficiary_persons[0] =
+            payload.originator.originator_persons[0].clone();
+        let person = match &mut payload.beneficiary.beneficiary_persons[0] {
+            Person::NaturalPerson(person) => person,
+            Person::LegalPerson(_) => unreachable!(),
+        };
+        person.geographic_address.clear();
+        person.date_and_place_of_birth = None;
+        payload.validate("2026-01-01").unwrap();
+    }
+}
@@ -1,5 +1,14 @@
+pub mod c32;
+pub mod clarity;
+pub mod codec;
+pub mod ivms101;
+pub mod merkle;
 pub mod persistence;
+pub mod receipt;
+pub mod ring;
+pub mod travel_rule;
 use serde::{Deserialize, Serialize};
+use std::collections::BTreeMap;
 use std::sync::{Arc, RwLock};
 use thiserror::Error;
 
@@ -19,6 +28,40 @@ pub struct TransactionInfo {
     pub confirmations: u32,
     pub block_hash: Option<String>,
     pub block_height: Option<u64>,
+    /// Chain the transaction was submitted to ("bitcoin" or "stacks").
+    #[serde(default)]
+    pub chain: String,
+    /// Lifecycle status: pending, confirmed, anchored, failed, dropped or orphaned.
+    #[serde(default)]
+    pub status: String,
+    #[serde(default)]
+    pub burn_block_height: Option<u64>,
+    #[serde(default)]
+    pub burn_block_hash: Option<String>,
+    /// Where the transfer is in the Travel Rule exchange, if it needs one.
+    #[serde(default)]
+    pub travel_rule: Option<TravelRuleStatus>,
+}
+
+impl TransactionInfo {
+    pub fn pending(chain: &str, txid: &str) -> Self {
+        Self {
+            txid: txid.to_string(),
+            confirmations: 0,
+            block_hash: None,
+            block_height: None,
+            chain: chain.to_string(),
+            status: "pending".to_string(),
+            burn_block_height: None,
+            burn_block_hash: None,
+            travel_rule: None,
+        }
+    }
+
+    /// Whether the transaction has reached a state that no longer needs polling.
+    pub fn is_final(&self) -> bool {
+        matches!(self.status.as_str(), "failed" | "dropped")
+    }
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
@@ -38,21 +81,236 @@ pub struct ChainState {
 pub struct Metrics {
     pub total_requests: u64,
     pub verification_count: u64,
+    pub verifications_valid: u64,
+    pub verifications_failed: u64,
+    pub alerts_raised: u64,
+    pub transactions_broadcast: u64,
+    pub transactions_rejected: u64,
+    pub events_emitted: u64,
+}
+
+/// Maximum number of alerts retained in the gateway state.
+pub const MAX_ALERTS: usize = 100;
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum AlertSeverity {
+    Info,
+    Warning,
+    Critical,
+}
+
+/// Operational alert raised by an engine component.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct Alert {
+    pub source: String,
+    pub severity: AlertSeverity,
+    pub message: String,
+    pub raised_at: u64,
+}
+
+/// Result of checking Stacks blocks against the Bitcoin header index.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct AnchorState {
+    pub status: String,
+    pub stacks_height: u64,
+    pub burn_block_height: u64,
+    pub burn_block_hash: String,
+    /// Bitcoin tip height minus the burn height of the latest Stacks block.
+    pub lag_blocks: u64,
+    pub anchored_blocks: u64,
+    pub unknown_anchors: u64,
+    pub mismatched_anchors: u64,
+    pub orphaned_anchors: u64,
+    pub last_checked: u64,
+}
+
+impl Default for AnchorState {
+    fn default() -> Self {
+        Self {
+            status: "initializing".to_string(),
+            stacks_height: 0,
+            burn_block_height: 0,
+            burn_block_hash: "".to_string(),
+            lag_blocks: 0,
+            anchored_blocks: 0,
+            unknown_anchors: 0,
+            mismatched_anchors: 0,
+            orphaned_anchors: 0,
+            last_checked: 0,
+        }
+    }
+}
+
+/// Signing record of one member of the current reward-cycle signer set.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignerParticipation {
+    pub signing_key: String,
+    pub weight: u32,
+    pub blocks_signed: u64,
+    /// Share of the observed blocks this signer signed, from 0 to 1.
+    pub participation: f64,
+}
+
+/// Nakamoto signer participation over recent Stacks blocks.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+pub struct SignerState {
+    pub status: String,
+    pub reward_cycle: u64,
+    pub total_weight: u64,
+    /// Weight a block needs to be accepted: 70% of the total, rounded up.
+    pub threshold_weight: u64,
+    pub blocks_observed: u64,
+    pub last_block_height: u64,
+    pub last_signed_weight: u64,
+    /// Percentage points of total weight by which the last block cleared the threshold.
+    pub threshold_margin: f64,
+    /// Lowest margin over the observed blocks.
+    pub min_threshold_margin: f64,
+    pub signers: Vec<SignerParticipation>,
+    pub last_checked: u64,
+}
+
+impl Default for SignerState {
+    fn default() -> Self {
+        Self {
+            status: "initializing".to_string(),
+            reward_cycle: 0,
+            total_weight: 0,
+            threshold_weight: 0,
+            blocks_observed: 0,
+            last_block_height: 0,
+            last_signed_weight: 0,
+            threshold_margin: 0.0,
+            min_threshold_margin: 0.0,
+            signers: Vec::new(),
+            last_checked: 0,
+        }
+    }
+}
+
+/// Maximum number of events retained in the gateway state.
+pub const MAX_EVENTS: usize = 500;
+
+/// Balance, lock and nonce information of a Stacks principal, in micro-STX.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct StacksAccount {
+    pub principal: String,
+    pub balance: u128,
+    pub locked: u128,
+    pub unlock_height: u64,
+    pub nonce: u64,
+}
+
+/// Pending Stacks transaction involving a watched principal.
+#[derive(Debug, Clone, PartialEq, Eq, Serialize, Deserialize)]
+pub struct MempoolTx {
+    pub txid: String,
+    /// Origin (sending) principal; replacements share its nonce.
+    pub sender: String,
+    pub nonce: u64,
+    /// Fee in micro-STX.
+    pub fee: u64,
+    pub received_at: u64,
+}
+
+/// Watched Stacks mempool transactions and lifecycle counters.
+#[derive(Debug, Clone, Default, Serialize, Deserialize)]
+pub struct MempoolState {
+    /// Pending transactions keyed by txid.
+    pub pending: BTreeMap<String, MempoolTx>,
+    pub seen: u64,
+    pub replaced: u64,
+    pub dropped: u64,
+    pub confirmed: u64,
+}
+
+/// Notable state change observed by an engine component.
+#[derive(Debug, Clone, Serialize, Deserialize)]
+#[serde(tag = "type")]
+pub enum GatewayEvent {
+    AccountChanged {
+        stacks_height: u64,
+        previous: Option<StacksAccount>,
+        current: StacksAccount,
+    },
+    /// A pending transaction was replaced by one with the same sender and nonce.
+    MempoolTxReplaced {
+        txid: String,
+        replaced_by: String,
+        sender: String,
+        nonce: u64,
+    },
+    MempoolTxDropped {
+        txid: String,
+        sender: String,
+        nonce: u64,
+        reason: String,
+    },
+    /// The counterparty VASP accepted or rejected a Travel Rule payload.
+    TravelRuleAcknowledged {
+        txid: String,
+        accepted: bool,
+        reason: Option<String>,
+    },
 }
 
 #[derive(Debug, Clone, Serialize, Deserialize)]
 pub struct GatewayState {
     pub bitcoin: ChainState,
     pub stacks: ChainState,
+    pub anchor: AnchorState,
+    pub signers: SignerState,
+    pub mempool: MempoolState,
+    pub alerts: Vec<Alert>,
+    /// Transactions submitted through the gateway, keyed by txid.
+    pub transactions: BTreeMap<String, TransactionInfo>,
+    /// Latest snapshot of each tracked Stacks principal.
+    pub accounts: BTreeMap<String, StacksAccount>,
+    pub events: Vec<GatewayEvent>,
     pub metrics: Metrics,
     pub start_time: u64,
 }
 
+impl GatewayState {
+    /// Records an alert, dropping the oldest once `MAX_ALERTS` is reached.
+    pub fn raise_alert(&mut self, source: &str, severity: AlertSeverity, message: String) {
+        if self.alerts.len() >= MAX_ALERTS {
+            self.alerts.remove(0);
+        }
+        self.alerts.push(Alert {
+            source: source.to_string(),
+            severity,
+            message,
+            raised_at: std::time::SystemTime::now()
+                .duration_since(std::time::UNIX_EPOCH)
+                .unwrap()
+                .as_secs(),
+        });
+        self.metrics.alerts_raised += 1;
+    }
+
+    /// Records an event, dropping the oldest once `MAX_EVENTS` is reached.
+    pub fn emit_event(&mut self, event: GatewayEvent) {
+        if self.events.len() >= MAX_EVENTS {
+            self.events.remove(0);
+        }
+        self.events.push(event);
+        self.metrics.events_emitted += 1;
+    }
+}
+
 impl Default for GatewayState {
     fn default() -> Self {
         Self {
             bitcoin: ChainState::default(),
             stacks: ChainState::default(),
+            anchor: AnchorState::default(),
+            signers: SignerState::default(),
+            mempool: MempoolState::default(),
+            alerts: Vec::new(),
+            transactions: BTreeMap::new(),
+            accounts: BTreeMap::new(),
+            events: Vec::new(),
             metrics: Metrics::default(),
             start_time: std::time::SystemTime::now()
                 .duration_since(std::time::UNIX_EPOCH)
@@ -89,6 +347,74 @@ pub struct Attestation {
     pub public_key: String, // Hex encoded
 }
 
+/// Lifecycle status of an enrolled Conxius device.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum DeviceStatus {
+    Active,
+    Suspended,
+    /// Terminal: a revoked device cannot be reactivated or re-enrolled.
+    Revoked,
+}
+
+/// A change to a device's key or status.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DeviceChange {
+    /// enrolled, rotated, suspended, reactivated or revoked.
+    pub action: String,
+    /// Key bound by this change, for enrollment and rotation.
+    pub public_key: Option<String>,
+    pub reason: Option<String>,
+    pub at: u64,
+}
+
+/// A Conxius device and the secp256k1 key bound to it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DeviceRecord {
+    pub device_id: String,
+    /// Compressed public key in hex; Schnorr attestations use its x-only form.
+    pub public_key: String,
+    pub status: DeviceStatus,
+    pub enrolled_at: u64,
+    pub updated_at: u64,
+    pub history: Vec<DeviceChange>,
+    /// Enclave key attested by the manufacturer, if the device has been attested.
+    #[serde(default)]
+    pub hardware: Option<HardwareAttestation>,
+}
+
+/// A P-256 enclave key whose certificate chain was validated against a
+/// configured manufacturer root.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct HardwareAttestation {
+    /// android, apple-production or apple-development.
+    pub platform: String,
+    /// Compressed SEC1 P-256 public key in hex.
+    pub public_key: String,
+    /// strongbox, trusted_environment or secure_enclave.
+    pub security_level: String,
+    /// Bootloader lock state, where the platform reports it.
+    pub device_locked: Option<bool>,
+    /// Verified boot state, where the platform reports it.
+    pub verified_boot: Option<bool>,
+    /// SHA-256 fingerprint of the root certificate anchoring the chain.
+    pub root_fingerprint: String,
+    pub attested_at: u64,
+}
+
+/// Structured attestation payload, signed as JSON in the `payload` field.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct AttestationClaims {
+    /// Unique per device; a nonce is accepted once within its validity window.
+    pub nonce: String,
+    /// Unix seconds.
+    pub issued_at: u64,
+    /// Unix seconds.
+    pub expires_at: u64,
+    /// Gateway the attestation is intended for.
+    pub audience: String,
+}
+
 /// Research enhancement: Schnorr signature support for Taproot-compatible attestations.
 #[derive(Debug, Serialize, Deserialize, Clone)]
 pub struct SchnorrAttestation {
@@ -96,6 +422,150 @@ pub struct SchnorrAttestation {
     pub signature: String, // 64-byte Schnorr signature in hex
     pub payload: String,
     pub x_only_public_key: String, // 32-byte X-only public key in hex
+    /// MuSig2 participant keys (x-only or compressed hex); when present,
+    /// `x_only_public_key` must be their BIP-327 aggregate over sorted keys.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub participants: Option<Vec<String>>,
+}
+
+/// SIP-018 signed structured Clarity data from a Stacks wallet.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Sip018Attestation {
+    pub device_id: String,
+    /// Tuple of `name` and `version` (string-ascii) and `chain-id` (uint).
+    pub domain: clarity::ClarityValue,
+    pub message: clarity::ClarityValue,
+    pub signature: String, // 65-byte recoverable signature (r, s, recovery id) in hex
+    /// Address the recovered signing key must correspond to.
+    pub expected_address: c32::StacksAddress,
+}
+
+/// BIP-322 generic signed message proving control of a Bitcoin address.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Bip322Attestation {
+    pub device_id: String,
+    pub address: String,
+    pub message: String,
+    pub signature: String, // Base64 witness stack (simple) or to_sign transaction (full)
+}
+
+/// Legacy "Bitcoin Signed Message" (BIP-137) signature from a hardware wallet.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct Bip137Attestation {
+    pub device_id: String,
+    /// P2PKH, P2SH-P2WPKH or P2WPKH address of the signing key.
+    pub address: String,
+    pub message: String,
+    pub signature: String, // Base64 65-byte header || r || s
+}
+
+/// ECDSA P-256 signature from a hardware-attested enclave key.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct P256Attestation {
+    pub device_id: String,
+    pub signature: String, // DER or 64-byte r || s in hex
+    pub payload: String,
+}
+
+/// COSE algorithm of a registered passkey.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+pub enum PasskeyAlgorithm {
+    /// ECDSA P-256 with SHA-256 (COSE -7).
+    #[serde(rename = "ES256")]
+    Es256,
+    /// Ed25519 (COSE -8).
+    #[serde(rename = "EdDSA")]
+    EdDsa,
+}
+
+/// A WebAuthn credential registered for an operator.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PasskeyCredential {
+    /// Base64url credential id, as reported by the authenticator.
+    pub credential_id: String,
+    pub operator_id: String,
+    pub algorithm: PasskeyAlgorithm,
+    /// Compressed SEC1 P-256 key or 32-byte Ed25519 key, in hex.
+    pub public_key: String,
+    /// Highest signature counter seen; zero if the authenticator has none.
+    pub sign_count: u32,
+    pub registered_at: u64,
+    pub last_used_at: Option<u64>,
+}
+
+/// WebAuthn assertion from an operator's passkey approving `payload`. The
+/// challenge must be the base64url SHA-256 of `payload`.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct WebAuthnAssertion {
+    pub credential_id: String,      // Base64url
+    pub authenticator_data: String, // Base64url
+    pub client_data_json: String,   // Base64url
+    pub signature: String,          // Base64url; DER for ES256
+    pub payload: String,
+}
+
+/// Signature scheme of a threshold co-signature.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum SignatureScheme {
+    /// DER or 64-byte compact ECDSA over SHA-256 of the payload.
+    Ecdsa,
+    /// BIP-340 Schnorr over SHA-256 of the payload, by the x-only key.
+    Schnorr,
+}
+
+/// M-of-N approval policy over enrolled devices.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ThresholdPolicy {
+    pub threshold: usize,
+    /// Device ids whose bound keys may co-sign.
+    pub signers: Vec<String>,
+}
+
+/// One co-signature of a threshold attestation.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ThresholdSignature {
+    pub device_id: String,
+    pub scheme: SignatureScheme,
+    pub signature: String, // Hex encoded
+}
+
+/// Several devices' signatures over the same payload, approved once at least
+/// `policy.threshold` distinct signers verify.
+#[derive(Debug, Serialize, Deserialize, Clone)]
+pub struct ThresholdAttestation {
+    pub policy: ThresholdPolicy,
+    pub payload: String,
+    pub signatures: Vec<ThresholdSignature>,
+}
+
+/// Proof that the payload was signed by one of the active registered
+/// devices, without revealing which; see [`ring`].
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct RingAttestation {
+    /// Registry root of the device ring the signature was made under, in hex.
+    pub root: String,
+    pub payload: String,
+    /// Challenge of the first ring member, as a 32-byte hex scalar.
+    pub challenge: String,
+    /// One 32-byte hex scalar per ring member, in ring order.
+    pub responses: Vec<String>,
+}
+
+/// A co-signature that was not counted towards the threshold.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct RejectedSignature {
+    pub device_id: String,
+    pub reason: String,
+}
+
+/// Outcome of a threshold attestation that met its policy.
+#[derive(Debug, Serialize, Deserialize, Clone, PartialEq, Eq)]
+pub struct ThresholdReport {
+    pub threshold: usize,
+    /// Devices counted towards the threshold, in signature order.
+    pub counted: Vec<String>,
+    pub rejected: Vec<RejectedSignature>,
 }
 
 /// Unified request for attestation verification.
@@ -104,6 +574,429 @@ pub struct SchnorrAttestation {
 pub enum AttestationRequest {
     Ecdsa(Attestation),
     Schnorr(SchnorrAttestation),
+    Sip018(Sip018Attestation),
+    Bip322(Bip322Attestation),
+    Bip137(Bip137Attestation),
+    P256(P256Attestation),
+    WebAuthn(WebAuthnAssertion),
+    Threshold(ThresholdAttestation),
+    Ring(RingAttestation),
+}
+
+impl AttestationRequest {
+    /// Name of the attestation type, as in its `type` tag.
+    pub fn kind(&self) -> &'static str {
+        match self {
+            AttestationRequest::Ecdsa(_) => "Ecdsa",
+            AttestationRequest::Schnorr(_) => "Schnorr",
+            AttestationRequest::Sip018(_) => "Sip018",
+            AttestationRequest::Bip322(_) => "Bip322",
+            AttestationRequest::Bip137(_) => "Bip137",
+            AttestationRequest::P256(_) => "P256",
+            AttestationRequest::WebAuthn(_) => "WebAuthn",
+            AttestationRequest::Threshold(_) => "Threshold",
+            AttestationRequest::Ring(_) => "Ring",
+        }
+    }
+
+    /// Device the attestation claims to be made by, if it names one.
+    pub fn device_id(&self) -> Option<&str> {
+        match self {
+            AttestationRequest::Ecdsa(a) => Some(&a.device_id),
+            AttestationRequest::Schnorr(a) => Some(&a.device_id),
+            AttestationRequest::Sip018(a) => Some(&a.device_id),
+            AttestationRequest::Bip322(a) => Some(&a.device_id),
+            AttestationRequest::Bip137(a) => Some(&a.device_id),
+            AttestationRequest::P256(a) => Some(&a.device_id),
+            AttestationRequest::WebAuthn(_)
+            | AttestationRequest::Threshold(_)
+            | AttestationRequest::Ring(_) => None,
+        }
+    }
+
+    /// Keys and addresses the attestation is made by, for screening.
+    pub fn subjects(&self) -> Vec<String> {
+        match self {
+            AttestationRequest::Ecdsa(a) => vec![a.public_key.clone()],
+            AttestationRequest::Schnorr(a) => {
+                let mut subjects = vec![a.x_only_public_key.clone()];
+                subjects.extend(a.participants.iter().flatten().cloned());
+                subjects
+            }
+            AttestationRequest::Sip018(a) => vec![a.expected_address.to_string()],
+            AttestationRequest::Bip322(a) => vec![a.address.clone()],
+            AttestationRequest::Bip137(a) => vec![a.address.clone()],
+            AttestationRequest::P256(_)
+            | AttestationRequest::WebAuthn(_)
+            | AttestationRequest::Threshold(_)
+            | AttestationRequest::Ring(_) => Vec::new(),
+        }
+    }
+}
+
+/// Category of an audited action.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum AuditKind {
+    Verification,
+    AuthFailure,
+    Admin,
+    /// A compliance policy decision.
+    Policy,
+    /// A new log segment was started.
+    Rotation,
+}
+
+/// What happened, as recorded in an audit entry.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AuditEvent {
+    pub kind: AuditKind,
+    /// Dotted action name, e.g. `device.revoke`.
+    pub action: String,
+    /// Device, credential, request hash or route the action concerned.
+    pub subject: Option<String>,
+    pub success: bool,
+    pub detail: Option<String>,
+}
+
+/// An audit log entry. `hash` commits to the event, its position and time,
+/// and `prev_hash`, the hash of the entry before it.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AuditEntry {
+    pub seq: u64,
+    pub at: u64,
+    #[serde(flatten)]
+    pub event: AuditEvent,
+    pub prev_hash: String,
+    pub hash: String,
+}
+
+/// Result of checking an audit log's hash chain.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct AuditReport {
+    pub valid: bool,
+    pub segments: usize,
+    pub entries: u64,
+    pub first_seq: Option<u64>,
+    pub last_seq: Option<u64>,
+    pub head_hash: String,
+    /// Whether the chain starts at the genesis entry rather than after
+    /// archived segments.
+    pub from_genesis: bool,
+    /// First integrity failure, if any.
+    pub error: Option<String>,
+}
+
+/// What a committed merkle leaf stands for.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitmentLeafKind {
+    AuditEntry,
+    Receipt,
+}
+
+/// A leaf of a Bitcoin commitment: an audit entry hash or receipt hash.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CommitmentLeaf {
+    pub kind: CommitmentLeafKind,
+    /// 32-byte hash in hex.
+    pub id: String,
+}
+
+/// Progress of a commitment transaction.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum CommitmentStatus {
+    /// Sealed, waiting for a funded or signed transaction.
+    Pending,
+    /// Funded PSBT waiting for an external signature.
+    Unsigned,
+    /// Signed transaction waiting to be broadcast.
+    Signed,
+    Broadcast,
+    Confirmed,
+}
+
+/// A batch of leaves whose merkle root is committed to Bitcoin in an
+/// OP_RETURN output.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct CommitmentBatch {
+    pub id: u64,
+    pub root: String,
+    pub leaves: Vec<CommitmentLeaf>,
+    pub created_at: u64,
+    pub status: CommitmentStatus,
+    /// Base64 funded PSBT, when signed externally.
+    pub psbt: Option<String>,
+    pub raw_tx: Option<String>,
+    pub txid: Option<String>,
+    pub block_hash: Option<String>,
+    pub block_height: Option<u64>,
+    /// Hex `gettxoutproof` merkle block proving the transaction is in the block.
+    pub tx_proof: Option<String>,
+}
+
+/// One step from a merkle leaf towards the root: the sibling hash and the
+/// side it is on.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum MerkleStep {
+    Left(String),
+    Right(String),
+}
+
+/// Ties a leaf to its batch root, the commitment transaction and the block
+/// that confirmed it. Checked offline with `merkle::verify_inclusion`.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct InclusionProof {
+    pub leaf: CommitmentLeaf,
+    pub batch: u64,
+    pub root: String,
+    pub path: Vec<MerkleStep>,
+    pub status: CommitmentStatus,
+    pub raw_tx: Option<String>,
+    pub txid: Option<String>,
+    pub block_hash: Option<String>,
+    pub block_height: Option<u64>,
+    pub tx_proof: Option<String>,
+}
+
+/// How a screened subject matched a denylist entry.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreeningMatch {
+    /// The same script, principal or key.
+    Exact,
+    /// A different encoding of the same key or script hash, or a contract
+    /// deployed by a listed address.
+    Derived,
+}
+
+/// A screened subject that matched a denylist entry.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ScreeningHit {
+    /// The subject as submitted.
+    pub subject: String,
+    /// The entry as listed.
+    pub entry: String,
+    /// Name of the denylist, from its file name.
+    pub list: String,
+    #[serde(rename = "match")]
+    pub matched: ScreeningMatch,
+    /// The normalised form the subject and entry share, e.g. `pkh:<hex>`.
+    pub key: String,
+    pub reason: Option<String>,
+}
+
+/// A loaded denylist.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct DenylistSummary {
+    pub name: String,
+    pub path: String,
+    pub entries: usize,
+}
+
+/// How much of a transaction's value can be traced to denylisted outputs.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum RiskLevel {
+    Low,
+    /// At or above the review threshold.
+    Medium,
+    /// At or above the blocking threshold.
+    High,
+}
+
+/// A transaction output on the path to a denylisted output.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct RiskHop {
+    pub txid: String,
+    pub vout: u32,
+    pub address: Option<String>,
+    /// Value of the output in satoshis.
+    pub value: u64,
+}
+
+/// Exposure of a scored transaction to one denylisted output.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RiskExposure {
+    /// 0 for the transaction's own outputs, 1 for the outputs it spends,
+    /// and so on back through its ancestors.
+    pub hops: u32,
+    /// Share of the transaction's inputs traced to the output, before decay.
+    pub share: f64,
+    /// What the exposure adds to the score, after decay.
+    pub contribution: f64,
+    /// Outputs from the scored transaction back to the listed one.
+    pub path: Vec<RiskHop>,
+    /// Entries the listed output matches.
+    pub hits: Vec<ScreeningHit>,
+}
+
+/// Risk score of a Bitcoin transaction from its exposure to denylisted outputs.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq)]
+pub struct RiskAssessment {
+    pub txid: String,
+    /// Sum of the exposures' contributions, capped at 1.
+    pub score: f64,
+    pub level: RiskLevel,
+    /// Largest contribution first.
+    pub exposures: Vec<RiskExposure>,
+    /// Hops the inputs were walked back.
+    pub max_hops: u32,
+    /// Ancestor transactions inspected.
+    pub transactions: usize,
+    /// Whether the walk stopped at its transaction limit before reaching
+    /// `max_hops` on every path.
+    pub truncated: bool,
+}
+
+/// Progress of a transfer's Travel Rule exchange with the counterparty VASP.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum TravelRuleStatus {
+    /// The transfer is at or above the threshold and has no payload yet.
+    Required,
+    /// A payload is sealed to the counterparty and awaits acknowledgement.
+    Sent,
+    Acknowledged,
+    /// The counterparty refused the payload; a corrected one may be attached.
+    Rejected,
+}
+
+/// The counterparty's signed response to a Travel Rule payload.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TravelRuleAck {
+    pub accepted: bool,
+    pub reason: Option<String>,
+    /// BIP-340 signature by the counterparty key, in hex.
+    pub signature: String,
+    pub received_at: u64,
+}
+
+/// A Travel Rule payload attached to a tracked transaction.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct TravelRuleRecord {
+    pub txid: String,
+    /// Key of the counterparty VASP the payload is sealed to, in hex.
+    pub counterparty_key: String,
+    /// SHA-256 hex of the sealed IVMS101 JSON.
+    pub payload_hash: String,
+    pub sealed: travel_rule::SealedPayload,
+    pub status: TravelRuleStatus,
+    pub attached_at: u64,
+    pub acknowledgement: Option<TravelRuleAck>,
+}
+
+/// What a compliance policy decision is made about.
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyTarget {
+    Attestation,
+    Transaction,
+    Account,
+}
+
+/// Whether the subjects of a decision were screened against the denylists.
+#[derive(Debug, Clone, Copy, Default, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum ScreeningStatus {
+    /// No denylists are configured.
+    #[default]
+    NotScreened,
+    Clear,
+    Hit,
+}
+
+/// Facts about an attestation, transaction or account that policy rules
+/// are evaluated over.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PolicyInput {
+    pub target: PolicyTarget,
+    #[serde(default)]
+    pub device_id: Option<String>,
+    /// Attestation type, as in its `type` tag, e.g. `Ecdsa`.
+    #[serde(default)]
+    pub scheme: Option<String>,
+    /// Keys, addresses and principals involved.
+    #[serde(default)]
+    pub principals: Vec<String>,
+    /// Transfer amount in the chain's base unit.
+    #[serde(default)]
+    pub amount: Option<u64>,
+    #[serde(default)]
+    pub screening: ScreeningStatus,
+    /// Tenant the subject belongs to; resolved from the policy's tenants
+    /// when unset.
+    #[serde(default)]
+    pub tenant: Option<String>,
+}
+
+impl PolicyInput {
+    pub fn new(target: PolicyTarget) -> Self {
+        Self {
+            target,
+            device_id: None,
+            scheme: None,
+            principals: Vec::new(),
+            amount: None,
+            screening: ScreeningStatus::NotScreened,
+            tenant: None,
+        }
+    }
+}
+
+#[derive(Debug, Clone, Copy, Serialize, Deserialize, PartialEq, Eq)]
+#[serde(rename_all = "snake_case")]
+pub enum PolicyEffect {
+    Allow,
+    Deny,
+}
+
+/// A policy rule whose condition held for a decision.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct MatchedRule {
+    pub id: String,
+    pub effect: PolicyEffect,
+    pub reason: Option<String>,
+}
+
+/// Outcome of evaluating the policy over a [`PolicyInput`]. A matching deny
+/// rule denies; otherwise a matching allow rule allows, and the policy's
+/// default applies when no rule matches.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct PolicyDecision {
+    pub allowed: bool,
+    pub target: PolicyTarget,
+    pub tenant: Option<String>,
+    /// Every rule that matched, in policy order.
+    pub matched: Vec<MatchedRule>,
+    /// SHA-256 hex of the policy the decision was made under.
+    pub policy_hash: String,
+    pub evaluated_at: u64,
+}
+
+impl PolicyDecision {
+    /// A `Compliance` error naming the deny rules, if the decision denies.
+    pub fn check(&self) -> ConxianResult<()> {
+        if self.allowed {
+            return Ok(());
+        }
+        let denied: Vec<String> = self
+            .matched
+            .iter()
+            .filter(|rule| rule.effect == PolicyEffect::Deny)
+            .map(|rule| match &rule.reason {
+                Some(reason) => format!("{} ({})", rule.id, reason),
+                None => rule.id.clone(),
+            })
+            .collect();
+        Err(ConxianError::Compliance(if denied.is_empty() {
+            "Denied by default policy".to_string()
+        } else {
+            format!("Denied by policy rule {}", denied.join(", "))
+        }))
+    }
 }
 
 /// Persistent data that needs to be saved across restarts.
@@ -137,4 +1030,19 @@ pub enum ConxianError {
     Io(String),
 }
 
+impl ConxianError {
+    /// Stable machine-readable code for the error category.
+    pub fn code(&self) -> &'static str {
+        match self {
+            ConxianError::Bitcoin(_) => "bitcoin",
+            ConxianError::Stacks(_) => "stacks",
+            ConxianError::Api(_) => "api",
+            ConxianError::Compliance(_) => "compliance",
+            ConxianError::Internal(_) => "internal",
+            ConxianError::Security(_) => "security",
+            ConxianError::Io(_) => "io",
+        }
+    }
+}
+
 pub type ConxianResult<T> = Result<T, ConxianError>;
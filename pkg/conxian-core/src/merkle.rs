@@ -0,0 +1,270 @@
+//! Merkle commitments of audit entries and receipts to Bitcoin.
+//!
+//! Leaves and inner nodes are domain separated (`0x00` and `0x01` prefixes)
+//! and an unpaired node is carried up unchanged, so no two leaf sets share a
+//! root. The root is committed in an OP_RETURN output as `CXN1 || root`.
+
+use crate::{ConxianError, ConxianResult, InclusionProof, MerkleStep};
+use bitcoin::blockdata::script::{Instruction, PushBytesBuf};
+use bitcoin::consensus::encode::deserialize_hex;
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+use bitcoin::{MerkleBlock, ScriptBuf, Transaction, Txid};
+
+/// Marker of the gateway's OP_RETURN commitments.
+pub const COMMITMENT_MAGIC: &[u8; 4] = b"CXN1";
+
+fn tagged(prefix: u8, chunks: &[&[u8]]) -> [u8; 32] {
+    let mut engine = sha256::Hash::engine();
+    engine.input(&[prefix]);
+    for chunk in chunks {
+        engine.input(chunk);
+    }
+    sha256::Hash::from_engine(engine).to_byte_array()
+}
+
+/// Hash of a leaf with the 32-byte id `id`.
+pub fn leaf_hash(id: &[u8; 32]) -> [u8; 32] {
+    tagged(0x00, &[id])
+}
+
+fn node_hash(left: &[u8; 32], right: &[u8; 32]) -> [u8; 32] {
+    tagged(0x01, &[left, right])
+}
+
+/// Decodes a 32-byte hex leaf id.
+pub fn parse_id(id: &str) -> ConxianResult<[u8; 32]> {
+    let bytes = decode_hex(id)?;
+    bytes
+        .try_into()
+        .map_err(|_| ConxianError::Compliance(format!("Leaf id is not 32 bytes: {}", id)))
+}
+
+fn decode_hex(s: &str) -> ConxianResult<Vec<u8>> {
+    use bitcoin::hex::FromHex;
+    Vec::<u8>::from_hex(s).map_err(|e| ConxianError::Compliance(format!("Invalid hex: {}", e)))
+}
+
+fn levels(leaves: &[[u8; 32]]) -> Vec<Vec<[u8; 32]>> {
+    let mut levels = vec![leaves.to_vec()];
+    while levels.last().is_some_and(|level| level.len() > 1) {
+        let next = levels
+            .last()
+            .unwrap()
+            .chunks(2)
+            .map(|pair| match pair {
+                [left, right] => node_hash(left, right),
+                [single] => *single,
+                _ => unreachable!(),
+            })
+            .collect();
+        levels.push(next);
+    }
+    levels
+}
+
+/// Root over leaf hashes, or `None` for no leaves.
+pub fn merkle_root(leaves: &[[u8; 32]]) -> Option<[u8; 32]> {
+    levels(leaves).last()?.first().copied()
+}
+
+/// Sibling hashes from leaf `index` up to the root.
+pub fn merkle_path(leaves: &[[u8; 32]], index: usize) -> Option<Vec<MerkleStep>> {
+    if index >= leaves.len() {
+        return None;
+    }
+    let mut path = Vec::new();
+    let mut i = index;
+    for level in levels(leaves).iter().take_while(|level| level.len() > 1) {
+        if let Some(sibling) = level.get(i ^ 1) {
+            let sibling = sha256::Hash::from_byte_array(*sibling).to_string();
+            path.push(if i.is_multiple_of(2) {
+                MerkleStep::Right(sibling)
+            } else {
+                MerkleStep::Left(sibling)
+            });
+        }
+        i /= 2;
+    }
+    Some(path)
+}
+
+/// Folds `path` over a leaf hash and compares the result with `root`.
+pub fn verify_merkle_path(leaf: [u8; 32], path: &[MerkleStep], root: &[u8; 32]) -> bool {
+    let folded = path.iter().try_fold(leaf, |node, step| {
+        Some(match step {
+            MerkleStep::Left(sibling) => node_hash(&parse_id(sibling).ok()?, &node),
+            MerkleStep::Right(sibling) => node_hash(&node, &parse_id(sibling).ok()?),
+        })
+    });
+    folded.as_ref() == Some(root)
+}
+
+/// OP_RETURN data committing to `root`.
+pub fn commitment_data(root: &[u8; 32]) -> Vec<u8> {
+    [COMMITMENT_MAGIC.as_slice(), root].concat()
+}
+
+/// OP_RETURN output script committing to `root`.
+pub fn commitment_script(root: &[u8; 32]) -> ScriptBuf {
+    let push = PushBytesBuf::try_from(commitment_data(root)).expect("36 bytes fit in a push");
+    ScriptBuf::new_op_return(push)
+}
+
+/// The root committed to by `tx`, if it has a commitment output.
+pub fn find_commitment(tx: &Transaction) -> Option<[u8; 32]> {
+    tx.output.iter().find_map(|output| {
+        let script = &output.script_pubkey;
+        if !script.is_op_return() {
+            return None;
+        }
+        match script.instructions().nth(1)? {
+            Ok(Instruction::PushBytes(bytes)) => {
+                let data = bytes.as_bytes().strip_prefix(COMMITMENT_MAGIC.as_slice())?;
+                data.try_into().ok()
+            }
+            _ => None,
+        }
+    })
+}
+
+/// Checks an inclusion proof offline: the leaf folds to the root, the
+/// transaction commits to the root, and the merkle block proves the
+/// transaction is in the stated block. Whether that block is on the best
+/// chain is for the verifier to check against their own node.
+pub fn verify_inclusion(proof: &InclusionProof) -> ConxianResult<()> {
+    let root = parse_id(&proof.root)?;
+    if !verify_merkle_path(leaf_hash(&parse_id(&proof.leaf.id)?), &proof.path, &root) {
+        return Err(ConxianError::Security(
+            "Leaf does not fold to the batch root".to_string(),
+        ));
+    }
+
+    let (Some(raw_tx), Some(block_hash), Some(tx_proof)) =
+        (&proof.raw_tx, &proof.block_hash, &proof.tx_proof)
+    else {
+        return Err(ConxianError::Compliance(
+            "Commitment is not confirmed yet".to_string(),
+        ));
+    };
+    let tx: Transaction = deserialize_hex(raw_tx)
+        .map_err(|e| ConxianError::Security(format!("Invalid commitment transaction: {}", e)))?;
+    if find_commitment(&tx) != Some(root) {
+        return Err(ConxianError::Security(
+            "Transaction does not commit to the batch root".to_string(),
+        ));
+    }
+
+    let merkle_block: MerkleBlock = deserialize_hex(tx_proof)
+        .map_err(|e| ConxianError::Security(format!("Invalid transaction proof: {}", e)))?;
+    let mut matches: Vec<Txid> = Vec::new();
+    let mut indexes = Vec::new();
+    merkle_block
+        .extract_matches(&mut matches, &mut indexes)
+        .map_err(|e| ConxianError::Security(format!("Invalid transaction proof: {}", e)))?;
+    if !matches.contains(&tx.compute_txid()) {
+        return Err(ConxianError::Security(
+            "Transaction proof does not include the commitment".to_string(),
+        ));
+    }
+    if merkle_block.header.block_hash().to_string() != *block_hash {
+        return Err(ConxianError::Security(
+            "Transaction proof is for a different block".to_string(),
+        ));
+    }
+    Ok(())
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::{CommitmentLeaf, CommitmentLeafKind, CommitmentStatus};
+    use bitcoin::block::{Header, Version};
+    use bitcoin::consensus::encode::serialize_hex;
+    use bitcoin::{absolute, transaction, Amount, Block, BlockHash, CompactTarget, TxOut};
+
+    fn leaves(n: u8) -> Vec<[u8; 32]> {
+        (0..n).map(|i| leaf_hash(&[i; 32])).collect()
+    }
+
+    #[test]
+    fn test_merkle_paths_fold_to_root() {
+        for n in 1..=9 {
+            let leaves = leaves(n);
+            let root = merkle_root(&leaves).unwrap();
+            for (i, leaf) in leaves.iter().enumerate() {
+                let path = merkle_path(&leaves, i).unwrap();
+                assert!(verify_merkle_path(*leaf, &path, &root), "{} of {}", i, n);
+                assert!(!verify_merkle_path(leaf_hash(&[0xff; 32]), &path, &root));
+            }
+        }
+        assert_eq!(merkle_root(&[]), None);
+        // A leaf cannot pose as an inner node
+        let two = leaves(2);
+        assert_ne!(merkle_root(&two).unwrap(), leaf_hash(&[0; 32]));
+    }
+
+    fn commitment_tx(root: &[u8; 32]) -> Transaction {
+        Transaction {
+            version: transaction::Version::TWO,
+            lock_time: absolute::LockTime::ZERO,
+            input: vec![Default::default()],
+            output: vec![TxOut {
+                value: Amount::ZERO,
+                script_pubkey: commitment_script(root),
+            }],
+        }
+    }
+
+    #[test]
+    fn test_inclusion_proof_ties_leaf_to_block() {
+        let ids: Vec<[u8; 32]> = (0..3u8).map(|i| [i; 32]).collect();
+        let hashes: Vec<[u8; 32]> = ids.iter().map(leaf_hash).collect();
+        let root = merkle_root(&hashes).unwrap();
+        let tx = commitment_tx(&root);
+        assert_eq!(find_commitment(&tx), Some(root));
+
+        let other = commitment_tx(&[9; 32]);
+        let mut block = Block {
+            header: Header {
+                version: Version::TWO,
+                prev_blockhash: BlockHash::all_zeros(),
+                merkle_root: bitcoin::TxMerkleNode::all_zeros(),
+                time: 1_700_000_000,
+                bits: CompactTarget::from_consensus(0x207fffff),
+                nonce: 0,
+            },
+            txdata: vec![other, tx.clone()],
+        };
+        block.header.merkle_root = block.compute_merkle_root().unwrap();
+        let txid = tx.compute_txid();
+        let merkle_block = MerkleBlock::from_block_with_predicate(&block, |t| *t == txid);
+
+        let mut proof = InclusionProof {
+            leaf: CommitmentLeaf {
+                kind: CommitmentLeafKind::AuditEntry,
+                id: sha256::Hash::from_byte_array(ids[2]).to_string(),
+            },
+            batch: 1,
+            root: sha256::Hash::from_byte_array(root).to_string(),
+            path: merkle_path(&hashes, 2).unwrap(),
+            status: CommitmentStatus::Confirmed,
+            raw_tx: Some(serialize_hex(&tx)),
+            txid: Some(txid.to_string()),
+            block_hash: Some(block.block_hash().to_string()),
+            block_height: Some(100),
+            tx_proof: Some(serialize_hex(&merkle_block)),
+        };
+        verify_inclusion(&proof).unwrap();
+
+        let mut wrong_block = proof.clone();
+        wrong_block.block_hash = Some(BlockHash::all_zeros().to_string());
+        assert!(verify_inclusion(&wrong_block).is_err());
+
+        let mut wrong_leaf = proof.clone();
+        wrong_leaf.leaf.id = "ff".repeat(32);
+        assert!(verify_inclusion(&wrong_leaf).is_err());
+
+        proof.tx_proof = None;
+        assert!(verify_inclusion(&proof).is_err());
+    }
+}
@@ -1,6 +1,16 @@
 use crate::{ConxianError, ConxianResult, Persistence, PersistentState};
 use std::fs;
-use std::path::PathBuf;
+use std::path::{Path, PathBuf};
+
+/// Writes `contents` to a temporary sibling of `path` and renames it into place.
+pub fn write_atomic(path: &Path, contents: &str) -> ConxianResult<()> {
+    let mut temp_path = path.to_path_buf();
+    temp_path.set_extension("tmp");
+
+    fs::write(&temp_path, contents).map_err(|e| ConxianError::Io(e.to_string()))?;
+    fs::rename(&temp_path, path).map_err(|e| ConxianError::Io(e.to_string()))?;
+    Ok(())
+}
 
 pub struct FilePersistence {
     path: PathBuf,
@@ -18,13 +28,7 @@ impl Persistence for FilePersistence {
     fn save(&self, state: &PersistentState) -> ConxianResult<()> {
         let json = serde_json::to_string_pretty(state)
             .map_err(|e| ConxianError::Internal(e.to_string()))?;
-
-        let mut temp_path = self.path.clone();
-        temp_path.set_extension("tmp");
-
-        fs::write(&temp_path, json).map_err(|e| ConxianError::Io(e.to_string()))?;
-        fs::rename(&temp_path, &self.path).map_err(|e| ConxianError::Io(e.to_string()))?;
-        Ok(())
+        write_atomic(&self.path, &json)
     }
 
     fn load(&self) -> ConxianResult<PersistentState> {
@@ -0,0 +1,174 @@
+//! Verification receipts: the gateway's signed statement of a verdict.
+//!
+//! A receipt carries its claims as a JSON string and a 64-byte BIP-340
+//! Schnorr signature over `tagged_hash("conxian/receipt", payload)`, so it can
+//! be checked offline with nothing but the gateway's x-only public key.
+
+use crate::{ConxianError, ConxianResult};
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+use bitcoin::secp256k1::{schnorr, Keypair, Message, Secp256k1, SecretKey, XOnlyPublicKey};
+use serde::{Deserialize, Serialize};
+
+const RECEIPT_TAG: &[u8] = b"conxian/receipt";
+
+/// What the gateway attests to in a receipt.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct ReceiptClaims {
+    /// SHA-256 hex of the attestation request as serialized by the gateway.
+    pub attestation_hash: String,
+    pub valid: bool,
+    /// Unix seconds.
+    pub issued_at: u64,
+    pub bitcoin_height: u64,
+    pub bitcoin_tip: String,
+    pub stacks_height: u64,
+    pub stacks_tip: String,
+    /// X-only public key of the issuing gateway, in hex.
+    pub issuer: String,
+}
+
+/// A signed receipt. `payload` is the exact JSON of the claims that was signed.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct Receipt {
+    pub payload: String,
+    pub signature: String, // 64-byte BIP-340 signature in hex
+}
+
+fn receipt_message(payload: &str) -> Message {
+    let tag = sha256::Hash::hash(RECEIPT_TAG);
+    let mut engine = sha256::Hash::engine();
+    engine.input(tag.as_byte_array());
+    engine.input(tag.as_byte_array());
+    engine.input(payload.as_bytes());
+    Message::from_digest(sha256::Hash::from_engine(engine).to_byte_array())
+}
+
+/// SHA-256 hex of `data`, as used for `attestation_hash`.
+pub fn attestation_hash(data: &[u8]) -> String {
+    sha256::Hash::hash(data).to_string()
+}
+
+/// SHA-256 hex over a receipt's payload and signature, identifying it in
+/// Bitcoin commitments.
+pub fn receipt_hash(receipt: &Receipt) -> String {
+    let mut engine = sha256::Hash::engine();
+    engine.input(receipt.payload.as_bytes());
+    engine.input(receipt.signature.as_bytes());
+    sha256::Hash::from_engine(engine).to_string()
+}
+
+/// Issues receipts with the gateway's key.
+pub struct ReceiptSigner {
+    secp: Secp256k1<bitcoin::secp256k1::All>,
+    keypair: Keypair,
+}
+
+impl ReceiptSigner {
+    /// Loads the signer from a 32-byte secret key in hex.
+    pub fn from_secret_hex(secret: &str) -> ConxianResult<Self> {
+        let bytes = hex_decode(secret.trim())
+            .ok_or_else(|| ConxianError::Security("Invalid receipt key hex".to_string()))?;
+        let secret = SecretKey::from_slice(&bytes)
+            .map_err(|e| ConxianError::Security(format!("Invalid receipt key: {}", e)))?;
+        let secp = Secp256k1::new();
+        let keypair = Keypair::from_secret_key(&secp, &secret);
+        Ok(Self { secp, keypair })
+    }
+
+    /// The issuer key receipts verify against, in hex.
+    pub fn public_key(&self) -> String {
+        self.keypair.x_only_public_key().0.to_string()
+    }
+
+    /// Signs `claims`, setting their issuer to this signer.
+    pub fn issue(&self, mut claims: ReceiptClaims) -> ConxianResult<Receipt> {
+        claims.issuer = self.public_key();
+        let payload =
+            serde_json::to_string(&claims).map_err(|e| ConxianError::Internal(e.to_string()))?;
+        let signature = self
+            .secp
+            .sign_schnorr_no_aux_rand(&receipt_message(&payload), &self.keypair);
+        Ok(Receipt {
+            payload,
+            signature: signature.to_string(),
+        })
+    }
+}
+
+/// Verifies a receipt offline against the gateway key `issuer` (x-only hex),
+/// returning its claims.
+pub fn verify_receipt(receipt: &Receipt, issuer: &str) -> ConxianResult<ReceiptClaims> {
+    let key: XOnlyPublicKey = issuer
+        .parse()
+        .map_err(|e| ConxianError::Security(format!("Invalid issuer key: {}", e)))?;
+    let signature: schnorr::Signature = receipt
+        .signature
+        .parse()
+        .map_err(|e| ConxianError::Security(format!("Invalid receipt signature: {}", e)))?;
+    Secp256k1::verification_only()
+        .verify_schnorr(&signature, &receipt_message(&receipt.payload), &key)
+        .map_err(|e| {
+            ConxianError::Security(format!("Receipt signature verification failed: {}", e))
+        })?;
+
+    let claims: ReceiptClaims = serde_json::from_str(&receipt.payload)
+        .map_err(|e| ConxianError::Security(format!("Invalid receipt payload: {}", e)))?;
+    if claims.issuer != issuer.to_lowercase() {
+        return Err(ConxianError::Security(
+            "Receipt was issued by a different gateway".to_string(),
+        ));
+    }
+    Ok(claims)
+}
+
+fn hex_decode(s: &str) -> Option<Vec<u8>> {
+    use bitcoin::hex::FromHex;
+    Vec::<u8>::from_hex(s).ok()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn claims(valid: bool) -> ReceiptClaims {
+        ReceiptClaims {
+            attestation_hash: attestation_hash(b"{}"),
+            valid,
+            issued_at: 1_700_000_000,
+            bitcoin_height: 850_000,
+            bitcoin_tip: "00".repeat(32),
+            stacks_height: 170_000,
+            stacks_tip: "11".repeat(32),
+            issuer: String::new(),
+        }
+    }
+
+    #[test]
+    fn test_receipt_round_trip() {
+        let signer = ReceiptSigner::from_secret_hex(&"01".repeat(32)).unwrap();
+        let receipt = signer.issue(claims(true)).unwrap();
+
+        let verified = verify_receipt(&receipt, &signer.public_key()).unwrap();
+        assert!(verified.valid);
+        assert_eq!(verified.issuer, signer.public_key());
+        assert_eq!(verified.bitcoin_height, 850_000);
+
+        // Receipts survive a JSON round trip
+        let json = serde_json::to_string(&receipt).unwrap();
+        let parsed: Receipt = serde_json::from_str(&json).unwrap();
+        assert!(verify_receipt(&parsed, &signer.public_key()).is_ok());
+    }
+
+    #[test]
+    fn test_receipt_rejects_tampering_and_other_issuers() {
+        let signer = ReceiptSigner::from_secret_hex(&"01".repeat(32)).unwrap();
+        let other = ReceiptSigner::from_secret_hex(&"02".repeat(32)).unwrap();
+        let receipt = signer.issue(claims(false)).unwrap();
+
+        let mut tampered = receipt.clone();
+        tampered.payload = tampered.payload.replace("false", "true");
+        assert!(verify_receipt(&tampered, &signer.public_key()).is_err());
+        assert!(verify_receipt(&receipt, &other.public_key()).is_err());
+        assert!(ReceiptSigner::from_secret_hex("zz").is_err());
+    }
+}
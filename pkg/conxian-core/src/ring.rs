@@ -0,0 +1,102 @@
+//! Ring signatures proving that an attestation was made by one of the
+//! registered devices, without revealing which.
+//!
+//! The ring is the set of active device keys in canonical order (sorted by
+//! compressed encoding), committed to by a merkle root over their hashes.
+//! A [`RingAttestation`] is an AOS (Abe-Ohkubo-Suzuki) Schnorr ring signature
+//! over secp256k1: starting from the challenge `e_0`, each member `i` gives
+//! `R_i = s_i·G + e_i·P_i` and `e_{i+1} = H(root || payload || R_i)`, and the
+//! signature is valid when the chain closes at `e_n = e_0`. Only the holder
+//! of one member's secret key can close it, and every member is equally
+//! likely to have.
+
+use crate::merkle::{leaf_hash, merkle_root};
+use crate::{ConxianError, ConxianResult, RingAttestation};
+use bitcoin::hashes::{sha256, Hash, HashEngine};
+use bitcoin::hex::{DisplayHex, FromHex};
+use bitcoin::secp256k1::{PublicKey, Scalar, Secp256k1, SecretKey};
+
+const CHALLENGE_TAG: &[u8] = b"conxian/zkc-ring";
+
+/// Sorts `keys` by their compressed encoding and drops duplicates.
+pub fn canonical_ring(keys: impl IntoIterator<Item = PublicKey>) -> Vec<PublicKey> {
+    let mut ring: Vec<PublicKey> = keys.into_iter().collect();
+    ring.sort_by_key(|key| key.serialize());
+    ring.dedup();
+    ring
+}
+
+/// Merkle root in hex over the SHA-256 of each key of a canonical ring, or
+/// `None` for an empty ring.
+pub fn ring_root(ring: &[PublicKey]) -> Option<String> {
+    let leaves: Vec<[u8; 32]> = ring
+        .iter()
+        .map(|key| leaf_hash(&sha256::Hash::hash(&key.serialize()).to_byte_array()))
+        .collect();
+    merkle_root(&leaves).map(|root| root.to_lower_hex_string())
+}
+
+/// `tagged_hash("conxian/zkc-ring", root || payload || point)` as a scalar,
+/// or `None` in the negligible case that it is not below the curve order.
+pub fn challenge(root: &str, payload: &str, point: &PublicKey) -> Option<Scalar> {
+    let tag = sha256::Hash::hash(CHALLENGE_TAG);
+    let mut engine = sha256::Hash::engine();
+    engine.input(tag.as_byte_array());
+    engine.input(tag.as_byte_array());
+    engine.input(root.as_bytes());
+    engine.input(payload.as_bytes());
+    engine.input(&point.serialize());
+    Scalar::from_be_bytes(sha256::Hash::from_engine(engine).to_byte_array()).ok()
+}
+
+fn invalid(reason: &str) -> ConxianError {
+    ConxianError::Security(format!("Invalid ring signature: {}", reason))
+}
+
+fn scalar(hex: &str) -> ConxianResult<Scalar> {
+    <[u8; 32]>::from_hex(hex)
+        .ok()
+        .and_then(|bytes| Scalar::from_be_bytes(bytes).ok())
+        .ok_or_else(|| invalid("malformed scalar"))
+}
+
+/// Checks a ring signature against the canonical `ring` it claims to be
+/// made under.
+pub fn verify_ring(ring: &[PublicKey], attestation: &RingAttestation) -> ConxianResult<()> {
+    let root = ring_root(ring).ok_or_else(|| invalid("the device ring is empty"))?;
+    if attestation.root != root {
+        return Err(ConxianError::Security(format!(
+            "Ring root {} is not the current registry root {}",
+            attestation.root, root
+        )));
+    }
+    if attestation.responses.len() != ring.len() {
+        return Err(invalid(&format!(
+            "{} responses for a ring of {}",
+            attestation.responses.len(),
+            ring.len()
+        )));
+    }
+
+    let secp = Secp256k1::new();
+    let first = scalar(&attestation.challenge)?;
+    let mut e = first;
+    for (key, response) in ring.iter().zip(&attestation.responses) {
+        let s = SecretKey::from_slice(&scalar(response)?.to_be_bytes())
+            .map_err(|_| invalid("zero response"))?;
+        let point = PublicKey::from_secret_key(&secp, &s)
+            .combine(
+                &key.mul_tweak(&secp, &e)
+                    .map_err(|_| invalid("zero challenge"))?,
+            )
+            .map_err(|_| invalid("point at infinity"))?;
+        e = challenge(&root, &attestation.payload, &point)
+            .ok_or_else(|| invalid("challenge out of range"))?;
+    }
+    if e != first {
+        return Err(ConxianError::Security(
+            "Ring signature verification failed".to_string(),
+        ));
+    }
+    Ok(())
+}
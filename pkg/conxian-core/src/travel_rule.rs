@@ -0,0 +1,222 @@
+//! Travel Rule payloads sealed to a counterparty VASP, and its acknowledgements.
+//!
+//! An IVMS101 payload is encrypted to the counterparty's secp256k1 key with
+//! an ephemeral ECDH key: the shared secret is expanded with HKDF-SHA256 into
+//! a ChaCha20-Poly1305 key, with the ephemeral key as associated data. The
+//! counterparty acknowledges with a BIP-340 signature by the same key over
+//! `tagged_hash("conxian/travel-rule-ack", txid || payload_hash || accepted)`,
+//! so both steps can be carried out offline with this crate.
+
+use crate::ivms101::IdentityPayload;
+use crate::{ConxianError, ConxianResult};
+use bitcoin::hashes::{hmac, sha256, Hash, HashEngine};
+use bitcoin::hex::{DisplayHex, FromHex};
+use bitcoin::secp256k1::{
+    ecdh::SharedSecret, schnorr, Keypair, Message, PublicKey, Secp256k1, SecretKey,
+};
+use chacha20poly1305::aead::{Aead, AeadCore, KeyInit, OsRng, Payload};
+use chacha20poly1305::{ChaCha20Poly1305, Key, Nonce};
+use serde::{Deserialize, Serialize};
+
+const KEY_SALT: &[u8] = b"conxian/travel-rule";
+const ACK_TAG: &[u8] = b"conxian/travel-rule-ack";
+
+/// An IVMS101 payload encrypted to a counterparty key; fields are hex.
+#[derive(Debug, Clone, Serialize, Deserialize, PartialEq, Eq)]
+pub struct SealedPayload {
+    /// Compressed ephemeral public key.
+    pub ephemeral_key: String,
+    pub nonce: String,
+    /// Ciphertext of the payload's JSON with the Poly1305 tag appended.
+    pub ciphertext: String,
+}
+
+fn security<E: std::fmt::Display>(context: &str) -> impl Fn(E) -> ConxianError + '_ {
+    move |e| ConxianError::Security(format!("{}: {}", context, e))
+}
+
+fn public_key(key: &str) -> ConxianResult<PublicKey> {
+    key.trim()
+        .parse()
+        .map_err(security("Invalid counterparty key"))
+}
+
+/// HKDF-SHA256 of the ECDH secret into a 32-byte cipher key, bound to both keys.
+fn cipher_key(shared: &SharedSecret, ephemeral: &PublicKey, counterparty: &PublicKey) -> Key {
+    let mut extract = hmac::HmacEngine::<sha256::Hash>::new(KEY_SALT);
+    extract.input(&shared.secret_bytes());
+    let prk = hmac::Hmac::<sha256::Hash>::from_engine(extract);
+
+    let mut expand = hmac::HmacEngine::<sha256::Hash>::new(prk.as_byte_array());
+    expand.input(&ephemeral.serialize());
+    expand.input(&counterparty.serialize());
+    expand.input(&[1]);
+    let okm = hmac::Hmac::<sha256::Hash>::from_engine(expand);
+    Key::from(okm.to_byte_array())
+}
+
+/// SHA-256 hex of a payload's JSON, as sealed and acknowledged.
+pub fn payload_hash(json: &[u8]) -> String {
+    sha256::Hash::hash(json).to_string()
+}
+
+/// Encrypts `payload` to the compressed or uncompressed hex `counterparty_key`,
+/// returning the sealed payload and the hash of the JSON it contains.
+pub fn seal(
+    payload: &IdentityPayload,
+    counterparty_key: &str,
+) -> ConxianResult<(SealedPayload, String)> {
+    let counterparty = public_key(counterparty_key)?;
+    let json = serde_json::to_vec(payload).map_err(|e| ConxianError::Internal(e.to_string()))?;
+
+    let secret = SecretKey::from_slice(&ChaCha20Poly1305::generate_key(&mut OsRng))
+        .map_err(security("Invalid ephemeral key"))?;
+    let ephemeral = PublicKey::from_secret_key(&Secp256k1::signing_only(), &secret);
+    let shared = SharedSecret::new(&counterparty, &secret);
+    let cipher = ChaCha20Poly1305::new(&cipher_key(&shared, &ephemeral, &counterparty));
+    let nonce = ChaCha20Poly1305::generate_nonce(&mut OsRng);
+    let ciphertext = cipher
+        .encrypt(
+            &nonce,
+            Payload {
+                msg: &json,
+                aad: &ephemeral.serialize(),
+            },
+        )
+        .map_err(security("Encryption failed"))?;
+
+    Ok((
+        SealedPayload {
+            ephemeral_key: ephemeral.to_string(),
+            nonce: nonce.to_lower_hex_string(),
+            ciphertext: ciphertext.to_lower_hex_string(),
+        },
+        payload_hash(&json),
+    ))
+}
+
+/// Decrypts a sealed payload with the counterparty's 32-byte secret key in
+/// hex, returning the payload and the hash of its JSON.
+pub fn open(sealed: &SealedPayload, secret: &str) -> ConxianResult<(IdentityPayload, String)> {
+    let secret = Vec::<u8>::from_hex(secret.trim())
+        .map_err(security("Invalid secret key hex"))
+        .and_then(|bytes| SecretKey::from_slice(&bytes).map_err(security("Invalid secret key")))?;
+    let counterparty = PublicKey::from_secret_key(&Secp256k1::signing_only(), &secret);
+    let ephemeral = public_key(&sealed.ephemeral_key)?;
+    let nonce = <[u8; 12]>::from_hex(&sealed.nonce).map_err(security("Invalid nonce"))?;
+    let ciphertext =
+        Vec::<u8>::from_hex(&sealed.ciphertext).map_err(security("Invalid ciphertext hex"))?;
+
+    let shared = SharedSecret::new(&ephemeral, &secret);
+    let cipher = ChaCha20Poly1305::new(&cipher_key(&shared, &ephemeral, &counterparty));
+    let json = cipher
+        .decrypt(
+            &Nonce::from(nonce),
+            Payload {
+                msg: &ciphertext,
+                aad: &ephemeral.serialize(),
+            },
+        )
+        .map_err(|_| ConxianError::Security("Payload decryption failed".to_string()))?;
+    let payload = serde_json::from_slice(&json).map_err(security("Invalid payload"))?;
+    Ok((payload, payload_hash(&json)))
+}
+
+fn acknowledgement_message(txid: &str, payload_hash: &str, accepted: bool) -> Message {
+    let tag = sha256::Hash::hash(ACK_TAG);
+    let mut engine = sha256::Hash::engine();
+    engine.input(tag.as_byte_array());
+    engine.input(tag.as_byte_array());
+    engine.input(txid.as_bytes());
+    engine.input(payload_hash.as_bytes());
+    engine.input(&[accepted as u8]);
+    Message::from_digest(sha256::Hash::from_engine(engine).to_byte_array())
+}
+
+/// Signs the counterparty's acknowledgement of a payload with its 32-byte
+/// secret key in hex, returning the BIP-340 signature in hex.
+pub fn sign_acknowledgement(
+    secret: &str,
+    txid: &str,
+    payload_hash: &str,
+    accepted: bool,
+) -> ConxianResult<String> {
+    let secp = Secp256k1::new();
+    let keypair =
+        Keypair::from_seckey_str(&secp, secret.trim()).map_err(security("Invalid secret key"))?;
+    let message = acknowledgement_message(txid, payload_hash, accepted);
+    Ok(secp
+        .sign_schnorr_no_aux_rand(&message, &keypair)
+        .to_string())
+}
+
+/// Checks an acknowledgement signature against the key the payload was sealed to.
+pub fn verify_acknowledgement(
+    counterparty_key: &str,
+    txid: &str,
+    payload_hash: &str,
+    accepted: bool,
+    signature: &str,
+) -> ConxianResult<()> {
+    let key = public_key(counterparty_key)?.x_only_public_key().0;
+    let signature: schnorr::Signature = signature
+        .trim()
+        .parse()
+        .map_err(security("Invalid acknowledgement signature"))?;
+    Secp256k1::verification_only()
+        .verify_schnorr(
+            &signature,
+            &acknowledgement_message(txid, payload_hash, accepted),
+            &key,
+        )
+        .map_err(security("Acknowledgement signature verification failed"))
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::ivms101::tests::payload;
+
+    const SECRET: &str = "0202020202020202020202020202020202020202020202020202020202020202";
+
+    fn counterparty_key() -> String {
+        let secret = SecretKey::from_slice(&[2; 32]).unwrap();
+        PublicKey::from_secret_key(&Secp256k1::new(), &secret).to_string()
+    }
+
+    #[test]
+    fn test_sealed_payload_round_trip() {
+        let (sealed, hash) = seal(&payload(), &counterparty_key()).unwrap();
+        let (opened, opened_hash) = open(&sealed, SECRET).unwrap();
+        assert_eq!(opened, payload());
+        assert_eq!(opened_hash, hash);
+
+        // Only the counterparty can open it, and it cannot be altered
+        assert!(open(&sealed, &"03".repeat(32)).is_err());
+        let mut tampered = sealed.clone();
+        let flipped = if &sealed.ciphertext[..2] == "00" {
+            "01"
+        } else {
+            "00"
+        };
+        tampered.ciphertext.replace_range(0..2, flipped);
+        assert!(open(&tampered, SECRET).is_err());
+        let (other, _) = seal(&payload(), &counterparty_key()).unwrap();
+        tampered = sealed.clone();
+        tampered.ephemeral_key = other.ephemeral_key;
+        assert!(open(&tampered, SECRET).is_err());
+    }
+
+    #[test]
+    fn test_acknowledgement_signature() {
+        let hash = payload_hash(b"{}");
+        let signature = sign_acknowledgement(SECRET, "ab", &hash, true).unwrap();
+        verify_acknowledgement(&counterparty_key(), "ab", &hash, true, &signature).unwrap();
+        assert!(
+            verify_acknowledgement(&counterparty_key(), "ab", &hash, false, &signature).is_err()
+        );
+        assert!(
+            verify_acknowledgement(&counterparty_key(), "cd", &hash, true, &signature).is_err()
+        );
+    }
+}
@@ -0,0 +1,128 @@
+//! Device-side prover for ZKC ring membership attestations.
+//!
+//! A device fetches the registry's device ring from `GET /api/v1/zkc/ring`
+//! and signs its payload with [`prove_membership`]; the gateway checks the
+//! result with [`conxian_core::ring::verify_ring`] against the same ring.
+
+use bitcoin::hex::DisplayHex;
+use bitcoin::secp256k1::{PublicKey, Scalar, Secp256k1, SecretKey};
+use conxian_core::ring::{canonical_ring, challenge, ring_root};
+use conxian_core::{ConxianError, ConxianResult, RingAttestation};
+
+/// Signs `payload` as an anonymous member of `ring`, the registry's active
+/// device keys, with the secret key of one of them.
+pub fn prove_membership(
+    ring: &[PublicKey],
+    secret: &SecretKey,
+    payload: &str,
+) -> ConxianResult<RingAttestation> {
+    let secp = Secp256k1::new();
+    let ring = canonical_ring(ring.iter().copied());
+    let root = ring_root(&ring)
+        .ok_or_else(|| ConxianError::Compliance("The device ring is empty".to_string()))?;
+    let signer = ring
+        .iter()
+        .position(|key| *key == PublicKey::from_secret_key(&secp, secret))
+        .ok_or_else(|| ConxianError::Compliance("Key is not a member of the ring".to_string()))?;
+
+    // A challenge outside the curve order ends an attempt with negligible
+    // probability; the next one draws fresh nonces
+    loop {
+        if let Some(attestation) = try_prove(&secp, &ring, &root, signer, secret, payload) {
+            return Ok(attestation);
+        }
+    }
+}
+
+fn try_prove(
+    secp: &Secp256k1<bitcoin::secp256k1::All>,
+    ring: &[PublicKey],
+    root: &str,
+    signer: usize,
+    secret: &SecretKey,
+    payload: &str,
+) -> Option<RingAttestation> {
+    let n = ring.len();
+    let mut rng = rand::thread_rng();
+    let mut challenges = vec![Scalar::ZERO; n];
+    let mut responses: Vec<SecretKey> = (0..n).map(|_| SecretKey::new(&mut rng)).collect();
+
+    // Close the ring from the signer's nonce round to the signer again
+    let nonce = SecretKey::new(&mut rng);
+    let mut i = (signer + 1) % n;
+    challenges[i] = challenge(root, payload, &PublicKey::from_secret_key(secp, &nonce))?;
+    while i != signer {
+        let point = PublicKey::from_secret_key(secp, &responses[i])
+            .combine(&ring[i].mul_tweak(secp, &challenges[i]).ok()?)
+            .ok()?;
+        let next = (i + 1) % n;
+        challenges[next] = challenge(root, payload, &point)?;
+        i = next;
+    }
+    let product = secret.mul_tweak(&challenges[signer]).ok()?;
+    responses[signer] = nonce.add_tweak(&Scalar::from(product.negate())).ok()?;
+
+    Some(RingAttestation {
+        root: root.to_string(),
+        payload: payload.to_string(),
+        challenge: challenges[0].to_be_bytes().to_lower_hex_string(),
+        responses: responses
+            .iter()
+            .map(|s| s.secret_bytes().to_lower_hex_string())
+            .collect(),
+    })
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use conxian_core::ring::verify_ring;
+
+    fn keys(n: u8) -> Vec<(SecretKey, PublicKey)> {
+        let secp = Secp256k1::new();
+        (1..=n)
+            .map(|i| {
+                let secret = SecretKey::from_slice(&[i; 32]).unwrap();
+                (secret, PublicKey::from_secret_key(&secp, &secret))
+            })
+            .collect()
+    }
+
+    #[test]
+    fn test_every_member_can_prove() {
+        let keys = keys(5);
+        let ring = canonical_ring(keys.iter().map(|(_, key)| *key));
+        for (secret, _) in &keys {
+            let attestation = prove_membership(&ring, secret, "payload").unwrap();
+            assert_eq!(attestation.responses.len(), 5);
+            verify_ring(&ring, &attestation).unwrap();
+        }
+
+        // A ring of one is a plain Schnorr signature
+        let single = canonical_ring([keys[0].1]);
+        let attestation = prove_membership(&single, &keys[0].0, "payload").unwrap();
+        verify_ring(&single, &attestation).unwrap();
+    }
+
+    #[test]
+    fn test_proofs_are_bound_to_ring_and_payload() {
+        let keys = keys(4);
+        let ring = canonical_ring(keys.iter().map(|(_, key)| *key));
+        let attestation = prove_membership(&ring, &keys[2].0, "payload").unwrap();
+
+        let mut tampered = attestation.clone();
+        tampered.payload = "other".to_string();
+        assert!(verify_ring(&ring, &tampered).is_err());
+        tampered = attestation.clone();
+        tampered.responses.swap(0, 1);
+        assert!(verify_ring(&ring, &tampered).is_err());
+
+        // Removing a member changes the root the proof was made under
+        let smaller = canonical_ring(keys[..3].iter().map(|(_, key)| *key));
+        let err = verify_ring(&smaller, &attestation).unwrap_err();
+        assert!(err.to_string().contains("not the current registry root"));
+
+        let outsider = SecretKey::from_slice(&[9; 32]).unwrap();
+        assert!(prove_membership(&ring, &outsider, "payload").is_err());
+    }
+}